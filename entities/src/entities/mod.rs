@@ -2,5 +2,25 @@
 
 pub mod prelude;
 
+pub mod applications;
+pub mod bookmarks;
+pub mod channel_mutes;
+pub mod drip_queue;
+pub mod event_attendance;
+pub mod incident_notes;
+pub mod incidents;
+pub mod kudos;
+pub mod kv_store;
+pub mod member_snapshots;
 pub mod messages;
 pub mod pending_flushes;
+pub mod posted_embeds;
+pub mod quota_usage;
+pub mod reports;
+pub mod suggestion_votes;
+pub mod suggestions;
+pub mod todos;
+pub mod tree_hole_deletions;
+pub mod trivia_scores;
+pub mod user_prefs;
+pub mod watch_state;
@@ -1,3 +1,15 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.13
 
-pub use super::{messages::Entity as Messages, pending_flushes::Entity as PendingFlushes};
+pub use super::{
+    applications::Entity as Applications, bookmarks::Entity as Bookmarks,
+    channel_mutes::Entity as ChannelMutes, drip_queue::Entity as DripQueue,
+    event_attendance::Entity as EventAttendance, incident_notes::Entity as IncidentNotes,
+    incidents::Entity as Incidents, kudos::Entity as Kudos, kv_store::Entity as KvStore,
+    member_snapshots::Entity as MemberSnapshots, messages::Entity as Messages,
+    pending_flushes::Entity as PendingFlushes, posted_embeds::Entity as PostedEmbeds,
+    quota_usage::Entity as QuotaUsage, reports::Entity as Reports,
+    suggestion_votes::Entity as SuggestionVotes, suggestions::Entity as Suggestions,
+    todos::Entity as Todos, tree_hole_deletions::Entity as TreeHoleDeletions,
+    trivia_scores::Entity as TriviaScores, user_prefs::Entity as UserPrefs,
+    watch_state::Entity as WatchState,
+};
@@ -1,6 +1,126 @@
+use std::collections::HashMap;
+
 use sea_orm::sqlx::types::chrono::{DateTime, Utc};
 use serenity::all::*;
 
+use crate::applications::Model as Applications;
+impl Applications {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn user_id(&self) -> UserId {
+        UserId::new(self.user_id as u64)
+    }
+    pub fn reviewer_id(&self) -> Option<UserId> {
+        self.reviewer_id.map(|id| UserId::new(id as u64))
+    }
+    /// The `[question, answer]` pairs of the application
+    pub fn answers(&self) -> Vec<(String, String)> {
+        serenity::json::from_str(&self.answers).unwrap_or_default()
+    }
+}
+
+use crate::bookmarks::Model as Bookmarks;
+impl Bookmarks {
+    pub fn message_id(&self) -> MessageId {
+        MessageId::new(self.message_id as u64)
+    }
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel_id as u64)
+    }
+    pub fn guild_id(&self) -> Option<GuildId> {
+        self.guild_id.map(|id| GuildId::new(id as u64))
+    }
+    pub fn author_id(&self) -> UserId {
+        UserId::new(self.author_id as u64)
+    }
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at.into()
+    }
+}
+
+use crate::channel_mutes::Model as ChannelMutes;
+impl ChannelMutes {
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel_id as u64)
+    }
+    pub fn user_id(&self) -> UserId {
+        UserId::new(self.user_id as u64)
+    }
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn moderator_id(&self) -> UserId {
+        UserId::new(self.moderator_id as u64)
+    }
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at.into()
+    }
+}
+
+use crate::drip_queue::Model as DripQueue;
+impl DripQueue {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn user_id(&self) -> UserId {
+        UserId::new(self.user_id as u64)
+    }
+}
+
+use crate::incidents::Model as Incidents;
+impl Incidents {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn thread_id(&self) -> ChannelId {
+        ChannelId::new(self.thread_id as u64)
+    }
+    pub fn status_message_id(&self) -> Option<MessageId> {
+        self.status_message_id.map(|id| MessageId::new(id as u64))
+    }
+    pub fn opened_by(&self) -> UserId {
+        UserId::new(self.opened_by as u64)
+    }
+}
+
+use crate::incident_notes::Model as IncidentNotes;
+impl IncidentNotes {
+    pub fn author_id(&self) -> UserId {
+        UserId::new(self.author_id as u64)
+    }
+}
+
+use crate::kudos::Model as Kudos;
+impl Kudos {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn user_id(&self) -> UserId {
+        UserId::new(self.user_id as u64)
+    }
+    pub fn count(&self) -> u64 {
+        self.count as u64
+    }
+}
+
+use crate::member_snapshots::Model as MemberSnapshots;
+impl MemberSnapshots {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn member_count(&self) -> Option<u64> {
+        self.member_count.map(|c| c as u64)
+    }
+    /// Members holding each role at the time of the snapshot
+    pub fn roles(&self) -> HashMap<RoleId, u64> {
+        self.roles
+            .as_deref()
+            .and_then(|roles| serenity::json::from_str(roles).ok())
+            .unwrap_or_default()
+    }
+}
+
 use crate::messages::Model as Messages;
 impl Messages {
     pub fn timestamp(&self) -> DateTime<Utc> {
@@ -35,3 +155,100 @@ impl PendingFlushes {
         self.created_at.into()
     }
 }
+
+use crate::posted_embeds::Model as PostedEmbeds;
+impl PostedEmbeds {
+    pub fn message_id(&self) -> MessageId {
+        MessageId::new(self.message_id as u64)
+    }
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel_id as u64)
+    }
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn author_id(&self) -> UserId {
+        UserId::new(self.author_id as u64)
+    }
+}
+
+use crate::reports::Model as Reports;
+impl Reports {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel_id as u64)
+    }
+    pub fn message_id(&self) -> MessageId {
+        MessageId::new(self.message_id as u64)
+    }
+    pub fn author_id(&self) -> UserId {
+        UserId::new(self.author_id as u64)
+    }
+    pub fn handler_id(&self) -> Option<UserId> {
+        self.handler_id.map(|id| UserId::new(id as u64))
+    }
+}
+
+use crate::suggestions::Model as Suggestions;
+impl Suggestions {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel_id as u64)
+    }
+    pub fn message_id(&self) -> Option<MessageId> {
+        self.message_id.map(|id| MessageId::new(id as u64))
+    }
+    pub fn author_id(&self) -> UserId {
+        UserId::new(self.author_id as u64)
+    }
+    pub fn handler_id(&self) -> Option<UserId> {
+        self.handler_id.map(|id| UserId::new(id as u64))
+    }
+}
+
+use crate::todos::Model as Todos;
+impl Todos {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn assignee_id(&self) -> Option<UserId> {
+        self.assignee_id.map(|id| UserId::new(id as u64))
+    }
+    pub fn created_by(&self) -> UserId {
+        UserId::new(self.created_by as u64)
+    }
+}
+
+use crate::tree_hole_deletions::Model as TreeHoleDeletions;
+impl TreeHoleDeletions {
+    pub fn message_id(&self) -> MessageId {
+        MessageId::new(self.message_id as u64)
+    }
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel_id as u64)
+    }
+}
+
+use crate::trivia_scores::Model as TriviaScores;
+impl TriviaScores {
+    pub fn guild_id(&self) -> GuildId {
+        GuildId::new(self.guild_id as u64)
+    }
+    pub fn user_id(&self) -> UserId {
+        UserId::new(self.user_id as u64)
+    }
+    pub fn score(&self) -> u64 {
+        self.score as u64
+    }
+}
+
+use crate::user_prefs::Model as UserPrefs;
+impl UserPrefs {
+    pub fn user_id(&self) -> UserId {
+        UserId::new(self.user_id as u64)
+    }
+}
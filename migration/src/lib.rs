@@ -3,6 +3,27 @@ pub use sea_orm_migration::prelude::*;
 mod m20220101_000001_create_table;
 mod m20250704_012322_add_flush_reason;
 mod m20250710_000001_optimize_channel_stats;
+mod m20261014_000001_create_trivia_scores;
+mod m20261014_000002_create_user_prefs;
+mod m20261014_000003_add_weather_location;
+mod m20261014_000004_create_watch_state;
+mod m20261014_000005_create_quota_usage;
+mod m20261014_000006_create_posted_embeds;
+mod m20261014_000007_add_embed_board;
+mod m20261014_000008_create_kudos;
+mod m20261014_000009_create_applications;
+mod m20261014_000010_create_channel_mutes;
+mod m20261014_000011_create_bookmarks;
+mod m20261014_000012_create_reports;
+mod m20261014_000013_create_event_attendance;
+mod m20261014_000014_create_drip_queue;
+mod m20261014_000015_create_suggestions;
+mod m20261014_000016_add_muted_dms;
+mod m20261014_000017_create_todos;
+mod m20261014_000018_create_tree_hole_deletions;
+mod m20261014_000019_create_incidents;
+mod m20261014_000020_create_kv_store;
+mod m20261014_000021_create_member_snapshots;
 
 pub struct Migrator;
 
@@ -13,6 +34,27 @@ impl MigratorTrait for Migrator {
             Box::new(m20220101_000001_create_table::Migration),
             Box::new(m20250704_012322_add_flush_reason::Migration),
             Box::new(m20250710_000001_optimize_channel_stats::Migration),
+            Box::new(m20261014_000001_create_trivia_scores::Migration),
+            Box::new(m20261014_000002_create_user_prefs::Migration),
+            Box::new(m20261014_000003_add_weather_location::Migration),
+            Box::new(m20261014_000004_create_watch_state::Migration),
+            Box::new(m20261014_000005_create_quota_usage::Migration),
+            Box::new(m20261014_000006_create_posted_embeds::Migration),
+            Box::new(m20261014_000007_add_embed_board::Migration),
+            Box::new(m20261014_000008_create_kudos::Migration),
+            Box::new(m20261014_000009_create_applications::Migration),
+            Box::new(m20261014_000010_create_channel_mutes::Migration),
+            Box::new(m20261014_000011_create_bookmarks::Migration),
+            Box::new(m20261014_000012_create_reports::Migration),
+            Box::new(m20261014_000013_create_event_attendance::Migration),
+            Box::new(m20261014_000014_create_drip_queue::Migration),
+            Box::new(m20261014_000015_create_suggestions::Migration),
+            Box::new(m20261014_000016_add_muted_dms::Migration),
+            Box::new(m20261014_000017_create_todos::Migration),
+            Box::new(m20261014_000018_create_tree_hole_deletions::Migration),
+            Box::new(m20261014_000019_create_incidents::Migration),
+            Box::new(m20261014_000020_create_kv_store::Migration),
+            Box::new(m20261014_000021_create_member_snapshots::Migration),
         ]
     }
 }
@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TriviaScores::Table)
+                    .if_not_exists()
+                    .col(big_unsigned(TriviaScores::GuildId))
+                    .col(big_unsigned(TriviaScores::UserId))
+                    .col(big_unsigned(TriviaScores::Score).default(Expr::value(0)))
+                    .col(
+                        timestamp_with_time_zone(TriviaScores::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(TriviaScores::GuildId)
+                            .col(TriviaScores::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TriviaScores::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TriviaScores {
+    Table,
+    GuildId,
+    UserId,
+    Score,
+    UpdatedAt,
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(QuotaUsage::Table)
+                    .if_not_exists()
+                    .col(text(QuotaUsage::Module))
+                    .col(text(QuotaUsage::Subject))
+                    .col(big_integer(QuotaUsage::WindowStart))
+                    .col(big_unsigned(QuotaUsage::Count).default(Expr::value(0)))
+                    .primary_key(
+                        Index::create()
+                            .col(QuotaUsage::Module)
+                            .col(QuotaUsage::Subject)
+                            .col(QuotaUsage::WindowStart),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QuotaUsage::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum QuotaUsage {
+    Table,
+    Module,
+    Subject,
+    WindowStart,
+    Count,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostedEmbeds::Table)
+                    .if_not_exists()
+                    .col(big_unsigned_uniq(PostedEmbeds::MessageId).primary_key())
+                    .col(big_unsigned(PostedEmbeds::ChannelId))
+                    .col(big_unsigned(PostedEmbeds::GuildId))
+                    .col(big_unsigned(PostedEmbeds::AuthorId))
+                    .col(text(PostedEmbeds::Source))
+                    .col(
+                        timestamp_with_time_zone(PostedEmbeds::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostedEmbeds::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum PostedEmbeds {
+    Table,
+    MessageId,
+    ChannelId,
+    GuildId,
+    AuthorId,
+    Source,
+    UpdatedAt,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Kudos::Table)
+                    .if_not_exists()
+                    .col(big_unsigned(Kudos::GuildId))
+                    .col(big_unsigned(Kudos::UserId))
+                    .col(string(Kudos::Month))
+                    .col(big_unsigned(Kudos::Count).default(Expr::value(0)))
+                    .primary_key(
+                        Index::create()
+                            .col(Kudos::GuildId)
+                            .col(Kudos::UserId)
+                            .col(Kudos::Month),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Kudos::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Kudos {
+    Table,
+    GuildId,
+    UserId,
+    /// `YYYY-MM` in the configured time offset
+    Month,
+    Count,
+}
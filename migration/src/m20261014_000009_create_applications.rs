@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Applications::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Applications::Id))
+                    .col(big_unsigned(Applications::GuildId))
+                    .col(big_unsigned(Applications::UserId))
+                    .col(text(Applications::Answers))
+                    .col(string(Applications::Status))
+                    .col(big_unsigned_null(Applications::ReviewerId))
+                    .col(
+                        timestamp_with_time_zone(Applications::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_with_time_zone_null(Applications::ReviewedAt))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_applications_guild_user")
+                    .table(Applications::Table)
+                    .col(Applications::GuildId)
+                    .col(Applications::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Applications::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Applications {
+    Table,
+    Id,
+    GuildId,
+    UserId,
+    /// JSON array of `[question, answer]` pairs
+    Answers,
+    /// `pending`, `approved` or `denied`
+    Status,
+    ReviewerId,
+    CreatedAt,
+    ReviewedAt,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChannelMutes::Table)
+                    .if_not_exists()
+                    .col(big_unsigned(ChannelMutes::ChannelId))
+                    .col(big_unsigned(ChannelMutes::UserId))
+                    .col(big_unsigned(ChannelMutes::GuildId))
+                    .col(big_unsigned(ChannelMutes::ModeratorId))
+                    .col(timestamp_with_time_zone(ChannelMutes::ExpiresAt))
+                    .primary_key(
+                        Index::create()
+                            .col(ChannelMutes::ChannelId)
+                            .col(ChannelMutes::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChannelMutes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChannelMutes {
+    Table,
+    ChannelId,
+    UserId,
+    GuildId,
+    ModeratorId,
+    ExpiresAt,
+}
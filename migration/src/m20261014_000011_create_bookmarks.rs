@@ -0,0 +1,60 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Bookmarks::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Bookmarks::Id))
+                    .col(big_unsigned(Bookmarks::UserId))
+                    .col(big_unsigned(Bookmarks::MessageId))
+                    .col(big_unsigned(Bookmarks::ChannelId))
+                    .col(big_unsigned_null(Bookmarks::GuildId))
+                    .col(big_unsigned(Bookmarks::AuthorId))
+                    .col(text(Bookmarks::Content))
+                    .col(
+                        timestamp_with_time_zone(Bookmarks::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_bookmarks_user_message")
+                    .table(Bookmarks::Table)
+                    .col(Bookmarks::UserId)
+                    .col(Bookmarks::MessageId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Bookmarks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Bookmarks {
+    Table,
+    Id,
+    UserId,
+    MessageId,
+    ChannelId,
+    GuildId,
+    AuthorId,
+    /// Copy of the message, kept after tree holes delete the original
+    Content,
+    CreatedAt,
+}
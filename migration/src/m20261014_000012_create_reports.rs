@@ -0,0 +1,66 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Reports::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Reports::Id))
+                    .col(big_unsigned(Reports::GuildId))
+                    .col(big_unsigned(Reports::ChannelId))
+                    .col(big_unsigned(Reports::MessageId))
+                    .col(big_unsigned(Reports::AuthorId))
+                    .col(big_unsigned(Reports::ReporterId))
+                    .col(text(Reports::Reason))
+                    .col(string(Reports::Status))
+                    .col(big_unsigned_null(Reports::HandlerId))
+                    .col(
+                        timestamp_with_time_zone(Reports::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_with_time_zone_null(Reports::HandledAt))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_reports_message_reporter")
+                    .table(Reports::Table)
+                    .col(Reports::MessageId)
+                    .col(Reports::ReporterId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Reports::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Reports {
+    Table,
+    Id,
+    GuildId,
+    ChannelId,
+    MessageId,
+    AuthorId,
+    /// Kept for abuse investigations, never shown to moderators
+    ReporterId,
+    Reason,
+    Status,
+    HandlerId,
+    CreatedAt,
+    HandledAt,
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EventAttendance::Table)
+                    .if_not_exists()
+                    .col(big_unsigned(EventAttendance::EventId))
+                    .col(big_unsigned(EventAttendance::UserId))
+                    .col(big_unsigned(EventAttendance::GuildId))
+                    .col(
+                        timestamp_with_time_zone(EventAttendance::AttendedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(EventAttendance::EventId)
+                            .col(EventAttendance::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EventAttendance::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EventAttendance {
+    Table,
+    EventId,
+    UserId,
+    GuildId,
+    AttendedAt,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DripQueue::Table)
+                    .if_not_exists()
+                    .col(big_unsigned(DripQueue::GuildId))
+                    .col(big_unsigned(DripQueue::UserId))
+                    .col(integer(DripQueue::Step))
+                    .col(timestamp_with_time_zone(DripQueue::DueAt))
+                    .primary_key(
+                        Index::create()
+                            .col(DripQueue::GuildId)
+                            .col(DripQueue::UserId)
+                            .col(DripQueue::Step),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DripQueue::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DripQueue {
+    Table,
+    GuildId,
+    UserId,
+    /// Index into the guild's configured drip steps
+    Step,
+    DueAt,
+}
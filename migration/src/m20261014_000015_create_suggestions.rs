@@ -0,0 +1,81 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Suggestions::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Suggestions::Id))
+                    .col(big_unsigned(Suggestions::GuildId))
+                    .col(big_unsigned(Suggestions::ChannelId))
+                    .col(big_unsigned_null(Suggestions::MessageId))
+                    .col(big_unsigned(Suggestions::AuthorId))
+                    .col(text(Suggestions::Content))
+                    .col(string(Suggestions::Status))
+                    .col(text_null(Suggestions::Note))
+                    .col(big_unsigned_null(Suggestions::HandlerId))
+                    .col(
+                        timestamp_with_time_zone(Suggestions::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(SuggestionVotes::Table)
+                    .if_not_exists()
+                    .col(integer(SuggestionVotes::SuggestionId))
+                    .col(big_unsigned(SuggestionVotes::UserId))
+                    .col(boolean(SuggestionVotes::Up))
+                    .primary_key(
+                        Index::create()
+                            .col(SuggestionVotes::SuggestionId)
+                            .col(SuggestionVotes::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SuggestionVotes::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Suggestions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Suggestions {
+    Table,
+    Id,
+    GuildId,
+    ChannelId,
+    /// The bot's embed, set once posted
+    MessageId,
+    AuthorId,
+    Content,
+    Status,
+    /// Moderator's reason for the latest status change
+    Note,
+    HandlerId,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum SuggestionVotes {
+    Table,
+    SuggestionId,
+    UserId,
+    Up,
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Todos::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Todos::Id))
+                    .col(big_unsigned(Todos::GuildId))
+                    .col(text(Todos::Content))
+                    .col(big_unsigned_null(Todos::AssigneeId))
+                    .col(big_unsigned(Todos::CreatedBy))
+                    .col(timestamp_with_time_zone_null(Todos::DueAt))
+                    .col(boolean(Todos::Reminded).default(false))
+                    .col(
+                        timestamp_with_time_zone(Todos::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_with_time_zone_null(Todos::DoneAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Todos::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Todos {
+    Table,
+    Id,
+    GuildId,
+    Content,
+    AssigneeId,
+    CreatedBy,
+    DueAt,
+    /// Whether the assignee was pinged about the due date
+    Reminded,
+    CreatedAt,
+    DoneAt,
+}
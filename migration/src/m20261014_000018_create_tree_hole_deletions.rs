@@ -0,0 +1,35 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TreeHoleDeletions::Table)
+                    .if_not_exists()
+                    .col(big_unsigned(TreeHoleDeletions::MessageId).primary_key())
+                    .col(big_unsigned(TreeHoleDeletions::ChannelId))
+                    .col(timestamp_with_time_zone(TreeHoleDeletions::DeleteAt))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TreeHoleDeletions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TreeHoleDeletions {
+    Table,
+    MessageId,
+    ChannelId,
+    DeleteAt,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Incidents::Table)
+                    .if_not_exists()
+                    .col(pk_auto(Incidents::Id))
+                    .col(big_unsigned(Incidents::GuildId))
+                    .col(big_unsigned_uniq(Incidents::ThreadId))
+                    .col(big_unsigned_null(Incidents::StatusMessageId))
+                    .col(text(Incidents::Title))
+                    .col(big_unsigned(Incidents::OpenedBy))
+                    .col(
+                        timestamp_with_time_zone(Incidents::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(timestamp_with_time_zone_null(Incidents::ClosedAt))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(IncidentNotes::Table)
+                    .if_not_exists()
+                    .col(pk_auto(IncidentNotes::Id))
+                    .col(integer(IncidentNotes::IncidentId))
+                    .col(big_unsigned(IncidentNotes::AuthorId))
+                    .col(text(IncidentNotes::Content))
+                    .col(
+                        timestamp_with_time_zone(IncidentNotes::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_incident_notes_incident")
+                    .table(IncidentNotes::Table)
+                    .col(IncidentNotes::IncidentId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(IncidentNotes::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Incidents::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Incidents {
+    Table,
+    Id,
+    GuildId,
+    /// The private thread the incident is handled in
+    ThreadId,
+    /// The pinned status embed, set once posted
+    StatusMessageId,
+    Title,
+    OpenedBy,
+    CreatedAt,
+    ClosedAt,
+}
+
+#[derive(DeriveIden)]
+enum IncidentNotes {
+    Table,
+    Id,
+    IncidentId,
+    AuthorId,
+    Content,
+    CreatedAt,
+}
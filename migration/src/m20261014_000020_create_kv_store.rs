@@ -0,0 +1,40 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(KvStore::Table)
+                    .if_not_exists()
+                    .col(text(KvStore::Namespace))
+                    .col(text(KvStore::Key))
+                    .col(text(KvStore::Value))
+                    .col(timestamp_with_time_zone_null(KvStore::ExpiresAt))
+                    .primary_key(Index::create().col(KvStore::Namespace).col(KvStore::Key))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(KvStore::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum KvStore {
+    Table,
+    /// The module owning the entry
+    Namespace,
+    Key,
+    /// JSON encoded
+    Value,
+    ExpiresAt,
+}
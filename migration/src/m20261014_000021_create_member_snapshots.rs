@@ -0,0 +1,49 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(MemberSnapshots::Table)
+                    .if_not_exists()
+                    .col(big_unsigned(MemberSnapshots::GuildId))
+                    .col(string(MemberSnapshots::Day))
+                    .col(big_unsigned(MemberSnapshots::Joins).default(Expr::value(0)))
+                    .col(big_unsigned(MemberSnapshots::Leaves).default(Expr::value(0)))
+                    .col(big_unsigned_null(MemberSnapshots::MemberCount))
+                    .col(text_null(MemberSnapshots::Roles))
+                    .primary_key(
+                        Index::create()
+                            .col(MemberSnapshots::GuildId)
+                            .col(MemberSnapshots::Day),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MemberSnapshots::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MemberSnapshots {
+    Table,
+    GuildId,
+    /// Local date, `YYYY-MM-DD`
+    Day,
+    Joins,
+    Leaves,
+    /// Taken by the nightly snapshot, missing for days the bot was offline at night
+    MemberCount,
+    /// JSON object of role id to member count
+    Roles,
+}
@@ -0,0 +1,95 @@
+use poise::{CreateReply, Modal, command};
+use serenity::all::{colours::branding::BLURPLE, *};
+
+use super::Context;
+use crate::{config::PermissionLevel, error::BotError, utils::template::Template};
+
+const ANNOUNCE_VARS: &[&str] = &["guild", "channel", "count"];
+
+#[derive(Debug, Modal)]
+#[name = "发布公告"]
+struct AnnouncementModal {
+    #[name = "标题"]
+    #[max_length = 256]
+    title: String,
+    #[name = "内容"]
+    #[placeholder = "支持 Markdown 以及 {guild}、{channel}、{count} 变量"]
+    #[paragraph]
+    #[max_length = 4000]
+    content: String,
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "公告"),
+    description_localized("zh-CN", "在频道中发布一条公告")
+)]
+/// Posts an announcement, written in a form, to a channel.
+pub async fn announce(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "频道")]
+    #[description_localized("zh-CN", "发布公告的频道")]
+    #[description = "Channel to post the announcement in"]
+    #[channel_types("Text", "News")]
+    channel: GuildChannel,
+    #[name_localized("zh-CN", "提及角色")]
+    #[description_localized("zh-CN", "随公告提及的角色")]
+    #[description = "Role mentioned with the announcement"]
+    role: Option<Role>,
+) -> Result<(), BotError> {
+    let Context::Application(app_ctx) = ctx else {
+        unreachable!("announce is a slash command");
+    };
+    let Some(modal) = AnnouncementModal::execute(app_ctx).await? else {
+        return Ok(()); // The user dismissed the modal
+    };
+    let content = match modal
+        .content
+        .parse::<Template>()
+        .and_then(|t| t.validate(ANNOUNCE_VARS).map(|()| t))
+    {
+        Ok(template) => {
+            let (guild_name, count) = ctx
+                .guild()
+                .map(|g| (g.name.to_owned(), g.member_count))
+                .unwrap_or_default();
+            template.render(&[
+                ("guild", &guild_name),
+                ("channel", &channel.mention()),
+                ("count", &count),
+            ])
+        }
+        Err(why) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!("❌ **错误**\n\n无效的公告模板: {why}"))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    let mut msg = CreateMessage::new().embed(
+        CreateEmbed::new()
+            .title(modal.title)
+            .description(content)
+            .author(CreateEmbedAuthor::new(&ctx.author().name).icon_url(ctx.author().face()))
+            .color(BLURPLE)
+            .timestamp(Timestamp::now()),
+    );
+    if let Some(role) = role {
+        msg = msg
+            .content(role.mention().to_string())
+            .allowed_mentions(CreateAllowedMentions::new().roles([role.id]));
+    }
+    let posted = channel.send_message(ctx, msg).await?;
+    ctx.send(
+        CreateReply::default()
+            .content(format!("✅ **成功**\n\n公告已发布: {}", posted.link()))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use poise::{CreateReply, command};
+use serenity::all::{colours::branding::BLURPLE, *};
+
+use super::Context;
+use crate::error::BotError;
+
+/// Custom ID prefixes of the review buttons, followed by the application ID
+pub const APPROVE_PREFIX: &str = "application:approve:";
+pub const DENY_PREFIX: &str = "application:deny:";
+
+const FORM_TIMEOUT: Duration = Duration::from_secs(900);
+
+#[command(
+    slash_command,
+    guild_only,
+    name_localized("zh-CN", "申请加入"),
+    description_localized("zh-CN", "填写入服申请, 由管理员审核")
+)]
+/// Applies for membership by answering the server's questionnaire.
+pub async fn apply(ctx: Context<'_>) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let gate = ctx
+        .data()
+        .cfg
+        .load()
+        .guilds
+        .get(&guild_id)
+        .and_then(|g| g.join_gate.to_owned());
+    let reply = |content: &str| CreateReply::default().content(content).ephemeral(true);
+    let Some(gate) = gate else {
+        ctx.send(reply("❌ **错误**\n\n本服务器未开放入服申请。"))
+            .await?;
+        return Ok(());
+    };
+    if ctx
+        .author_member()
+        .await
+        .is_some_and(|m| m.roles.contains(&gate.member_role_id))
+    {
+        ctx.send(reply("❌ **错误**\n\n你已经是正式成员了。"))
+            .await?;
+        return Ok(());
+    }
+    let db = &ctx.data().db;
+    if db
+        .applications()
+        .pending(guild_id, ctx.author().id)
+        .await?
+        .is_some()
+    {
+        ctx.send(reply("⏳ 你的申请正在审核中, 请耐心等待。"))
+            .await?;
+        return Ok(());
+    }
+    let Context::Application(app_ctx) = ctx else {
+        unreachable!("apply is a slash command");
+    };
+    let modal = gate
+        .questions
+        .iter()
+        .fold(CreateQuickModal::new("入服申请"), |modal, q| {
+            modal.paragraph_field(q)
+        })
+        .timeout(FORM_TIMEOUT);
+    let Some(response) = app_ctx
+        .interaction
+        .quick_modal(ctx.serenity_context(), modal)
+        .await?
+    else {
+        return Ok(()); // The user dismissed the form
+    };
+    let answers = gate
+        .questions
+        .iter()
+        .cloned()
+        .zip(response.inputs)
+        .collect::<Vec<_>>();
+    let id = db
+        .applications()
+        .create(guild_id, ctx.author().id, &answers)
+        .await?;
+    let embed = answers
+        .iter()
+        .fold(
+            CreateEmbed::new()
+                .title(format!("📝 入服申请 #{id}"))
+                .author(CreateEmbedAuthor::from(ctx.author().to_owned()))
+                .description(ctx.author().mention().to_string())
+                .color(BLURPLE)
+                .timestamp(Timestamp::now()),
+            |embed, (q, a)| embed.field(q, a, false),
+        )
+        .footer(CreateEmbedFooter::new(format!(
+            "用户 ID: {}",
+            ctx.author().id
+        )));
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{APPROVE_PREFIX}{id}"))
+            .label("通过")
+            .style(ButtonStyle::Success),
+        CreateButton::new(format!("{DENY_PREFIX}{id}"))
+            .label("拒绝并踢出")
+            .style(ButtonStyle::Danger),
+    ]);
+    gate.review_channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new().embed(embed).components(vec![buttons]),
+        )
+        .await?;
+    response
+        .interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content("✅ **成功**\n\n申请已提交, 管理员审核后会通知你。")
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//! Autocomplete providers shared by commands, so users pick from what exists instead of typing
+//! names and IDs by hand.
+
+use chrono_tz::TZ_VARIANTS;
+use serenity::all::{AutocompleteChoice, ChannelId};
+
+use super::Context;
+use crate::utils::parse_duration;
+
+/// Discord shows at most this many choices
+const MAX_CHOICES: usize = 25;
+
+/// IANA timezone names
+pub async fn timezone_choices<'a>(
+    _ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = AutocompleteChoice> + 'a {
+    let partial = partial.to_lowercase();
+    TZ_VARIANTS
+        .iter()
+        .map(|tz| tz.name())
+        .filter(move |name| name.to_lowercase().contains(&partial))
+        .take(MAX_CHOICES)
+        .map(|name| AutocompleteChoice::new(name, name))
+}
+
+/// Names of the configured uptime monitors
+pub async fn monitor_choices<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = AutocompleteChoice> + 'a {
+    ctx.data()
+        .cfg
+        .load()
+        .uptime_monitors
+        .iter()
+        .map(|m| m.name.to_owned())
+        .filter(|name| name.contains(partial))
+        .take(MAX_CHOICES)
+        .map(|name| AutocompleteChoice::new(name.to_owned(), name))
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Tree hole channels of the invoking guild, by ID
+pub async fn tree_hole_choices<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = AutocompleteChoice> + 'a {
+    let partial = partial.to_lowercase();
+    let names = ctx
+        .guild()
+        .map(|g| {
+            g.channels
+                .values()
+                .map(|c| (c.id, c.name.to_owned()))
+                .collect::<Vec<(ChannelId, String)>>()
+        })
+        .unwrap_or_default();
+    let tree_holes = ctx
+        .guild_id()
+        .and_then(|guild_id| {
+            Some(
+                ctx.data()
+                    .cfg
+                    .load()
+                    .guilds
+                    .get(&guild_id)?
+                    .tree_holes
+                    .to_owned(),
+            )
+        })
+        .unwrap_or_default();
+    names
+        .into_iter()
+        .filter_map(|(id, name)| Some((id, name, tree_holes.get(&id)?.duration)))
+        .filter(|(_, name, _)| name.to_lowercase().contains(&partial))
+        .take(MAX_CHOICES)
+        .map(|(id, name, duration)| {
+            AutocompleteChoice::new(format!("#{name} ({}s)", duration.as_secs()), id.to_string())
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Open todos of the invoking guild, by number
+pub async fn todo_choices<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = AutocompleteChoice> + 'a {
+    let partial = partial.to_lowercase();
+    let todos = match ctx.guild_id() {
+        Some(guild_id) => ctx
+            .data()
+            .db
+            .todos()
+            .open(guild_id)
+            .await
+            .unwrap_or_default(),
+        None => vec![],
+    };
+    todos
+        .into_iter()
+        .filter(|t| {
+            t.id.to_string().starts_with(&partial) || t.content.to_lowercase().contains(&partial)
+        })
+        .take(MAX_CHOICES)
+        .map(|t| {
+            let label = format!("#{} {}", t.id, t.content);
+            // Choice names are capped at 100 characters
+            AutocompleteChoice::new(label.chars().take(100).collect::<String>(), t.id)
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+/// Common durations, led by what is typed so far if it already parses
+pub async fn duration_choices<'a>(
+    _ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = AutocompleteChoice> + 'a {
+    const COMMON: [(&str, &str); 6] = [
+        ("30m", "30 分钟"),
+        ("1h", "1 小时"),
+        ("6h", "6 小时"),
+        ("1d", "1 天"),
+        ("3d", "3 天"),
+        ("7d", "7 天"),
+    ];
+    let typed = parse_duration(partial)
+        .is_some()
+        .then(|| AutocompleteChoice::new(partial, partial));
+    typed.into_iter().chain(
+        COMMON
+            .into_iter()
+            .filter(move |(value, _)| *value != partial && value.starts_with(partial))
+            .map(|(value, name)| AutocompleteChoice::new(format!("{value} ({name})"), value)),
+    )
+}
@@ -0,0 +1,96 @@
+use chrono::Utc;
+use poise::command;
+use tracing::info;
+
+use super::Context;
+use crate::{
+    config::{BackupCfg, BackupTarget, PermissionLevel},
+    database::BotDatabase,
+    error::BotError,
+    utils::s3::Bucket,
+};
+
+const BACKUP_PREFIX: &str = "dc-bot-";
+
+/// Snapshot the database to the configured target and prune old backups, returning the new name.
+pub async fn run_backup(db: &BotDatabase, cfg: &BackupCfg) -> Result<String, BotError> {
+    // Timestamps sort lexicographically, which rotation relies on
+    let name = format!(
+        "{BACKUP_PREFIX}{}.sqlite",
+        Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    match &cfg.target {
+        BackupTarget::Local { path } => {
+            tokio::fs::create_dir_all(path).await?;
+            db.snapshot(path.join(&name)).await?;
+            let mut backups = vec![];
+            let mut dir = tokio::fs::read_dir(path).await?;
+            while let Some(entry) = dir.next_entry().await? {
+                let file = entry.file_name().to_string_lossy().into_owned();
+                if file.starts_with(BACKUP_PREFIX) {
+                    backups.push(file);
+                }
+            }
+            backups.sort();
+            for old in backups.iter().rev().skip(cfg.keep) {
+                info!("Removing old backup {old}");
+                tokio::fs::remove_file(path.join(old)).await?;
+            }
+        }
+        BackupTarget::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key,
+            secret_key,
+            prefix,
+        } => {
+            let bucket = Bucket {
+                endpoint,
+                name: bucket,
+                region,
+                access_key,
+                secret_key,
+            };
+            let tmp = std::env::temp_dir().join(&name);
+            db.snapshot(&tmp).await?;
+            let data = tokio::fs::read(&tmp).await;
+            tokio::fs::remove_file(&tmp).await?;
+            bucket.put(&format!("{prefix}{name}"), data?).await?;
+            let mut backups = bucket.list(&format!("{prefix}{BACKUP_PREFIX}")).await?;
+            backups.sort();
+            for old in backups.iter().rev().skip(cfg.keep) {
+                info!("Removing old backup {old}");
+                bucket.delete(old).await?;
+            }
+        }
+    }
+    Ok(name)
+}
+
+#[command(
+    slash_command,
+    custom_data = "PermissionLevel::Owner",
+    name_localized("zh-CN", "备份"),
+    description_localized("zh-CN", "立即备份数据库"),
+    ephemeral
+)]
+/// Backs up the database right now.
+pub async fn backup(ctx: Context<'_>) -> Result<(), BotError> {
+    let Some(cfg) = ctx.data().cfg.load().backup.to_owned() else {
+        ctx.say("❌ **错误**\n\n未配置备份目标。").await?;
+        return Ok(());
+    };
+    ctx.defer_ephemeral().await?;
+    match run_backup(&ctx.data().db, &cfg).await {
+        Ok(name) => {
+            ctx.say(format!("✅ **成功**\n\n已备份为 `{name}`。"))
+                .await?
+        }
+        Err(why) => {
+            ctx.say(format!("❌ **错误**\n\n备份失败: {why}")).await?;
+            return Err(why);
+        }
+    };
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use poise::command;
+use serenity::all::*;
+use similar::TextDiff;
+
+use super::{Context, embed::EmbedSpec, say_long};
+use crate::{
+    config::{BoardCfg, PermissionLevel},
+    error::BotError,
+};
+
+async fn board_choices<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = AutocompleteChoice> + 'a {
+    let mut names = ctx
+        .data()
+        .cfg
+        .load()
+        .boards
+        .keys()
+        .filter(|name| name.starts_with(partial))
+        .cloned()
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| AutocompleteChoice::new(name.clone(), name))
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    subcommands("board_sync"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "公告板"),
+    description_localized("zh-CN", "管理由机器人维护的规则与信息消息")
+)]
+/// Manages bot-maintained info messages.
+pub async fn board(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+/// Bring one board's live message up to date with its source file, describing what changed.
+async fn sync_one(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+    name: &str,
+    board: &BoardCfg,
+) -> Result<String, BotError> {
+    let source = match tokio::fs::read_to_string(&board.path).await {
+        Ok(source) => source,
+        Err(why) => {
+            return Ok(format!(
+                "❌ **{name}**: 无法读取 `{}`: {why}",
+                board.path.display()
+            ));
+        }
+    };
+    let spec = match EmbedSpec::parse(&source) {
+        Ok(spec) => spec,
+        Err(why) => return Ok(format!("❌ **{name}**: 无效的嵌入: {why}")),
+    };
+    let db = &ctx.data().db;
+    let tracked = db
+        .embeds()
+        .board(name)
+        .await?
+        .filter(|t| t.channel_id() == board.channel_id);
+    let message = match &tracked {
+        Some(tracked) if tracked.source == source => {
+            return Ok(format!("➖ **{name}**: 无变化"));
+        }
+        Some(tracked) => {
+            let edit = EditMessage::new().embed(spec.to_embed());
+            match board
+                .channel_id
+                .edit_message(ctx, tracked.message_id(), edit)
+                .await
+            {
+                Ok(message) => Some(message),
+                // The message was deleted, post it again below
+                Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(res)))
+                    if res.status_code == StatusCode::NOT_FOUND =>
+                {
+                    None
+                }
+                Err(why) => return Err(why.into()),
+            }
+        }
+        None => None,
+    };
+    let (message, status) = match message {
+        Some(message) => (message, "已更新"),
+        None => {
+            let message = board
+                .channel_id
+                .send_message(ctx, CreateMessage::new().embed(spec.to_embed()))
+                .await?;
+            (message, "已发布")
+        }
+    };
+    db.embeds()
+        .track_board(&message, guild_id, ctx.author().id, name, source.to_owned())
+        .await?;
+    let mut report = format!("✅ **{name}**: {status} {}", message.link());
+    if let Some(tracked) = tracked {
+        let diff = TextDiff::from_lines(&tracked.source, &source)
+            .unified_diff()
+            .context_radius(1)
+            .to_string();
+        report.push_str(&format!(
+            "\n```diff\n{}```",
+            diff.replace("```", "`\u{200b}``")
+        ));
+    }
+    Ok(report)
+}
+
+#[command(
+    slash_command,
+    rename = "sync",
+    name_localized("zh-CN", "同步"),
+    description_localized("zh-CN", "按源文件更新公告板消息并报告变化"),
+    ephemeral
+)]
+/// Edits board messages whose source file changed and reports a diff.
+pub async fn board_sync(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "名称")]
+    #[description_localized("zh-CN", "要同步的公告板, 默认为本服务器所有公告板")]
+    #[description = "Board to sync, defaults to every board of this server"]
+    #[autocomplete = "board_choices"]
+    name: Option<String>,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let mut boards = ctx
+        .data()
+        .cfg
+        .load()
+        .boards
+        .iter()
+        .filter(|(n, _)| name.as_ref().is_none_or(|name| name == *n))
+        .map(|(n, b)| (n.to_owned(), b.to_owned()))
+        .collect::<Vec<_>>();
+    boards.sort_by(|(a, _), (b, _)| a.cmp(b));
+    ctx.defer_ephemeral().await?;
+    let mut reports = Vec::new();
+    for (name, board) in boards {
+        // One broken board must not keep the others from syncing
+        let channel = match board.channel_id.to_channel(ctx).await {
+            Ok(channel) => channel.guild(),
+            Err(why) => {
+                reports.push(format!("❌ **{name}**: 无法获取频道: {why}"));
+                continue;
+            }
+        };
+        // Only boards living in this server, so guild admins cannot touch other servers
+        if channel.is_none_or(|c| c.guild_id != guild_id) {
+            continue;
+        }
+        reports.push(match sync_one(ctx, guild_id, &name, &board).await {
+            Ok(report) => report,
+            Err(why) => format!("❌ **{name}**: 同步失败: {why}"),
+        });
+    }
+    if reports.is_empty() {
+        ctx.say("❌ **错误**\n\n本服务器没有匹配的公告板。").await?;
+        return Ok(());
+    }
+    say_long(ctx, reports.join("\n\n")).await
+}
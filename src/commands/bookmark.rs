@@ -0,0 +1,188 @@
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use super::Context;
+use crate::error::BotError;
+
+/// Bookmarks shown per page of `/bookmarks list`
+const PAGE_SIZE: u64 = 10;
+
+fn truncate(content: &str, max: usize) -> String {
+    if content.chars().count() <= max {
+        content.to_string()
+    } else {
+        format!("{}…", content.chars().take(max - 1).collect::<String>())
+    }
+}
+
+/// A copy of `message` that survives the original being deleted
+fn copy_embed(message: &Message) -> CreateEmbed {
+    let mut embed = CreateEmbed::new()
+        .author(
+            CreateEmbedAuthor::new(message.author.display_name()).icon_url(message.author.face()),
+        )
+        .description(truncate(&message.content, 4096))
+        .field("原消息", message.link(), false)
+        .timestamp(message.timestamp)
+        .color(0x5865F2);
+    if !message.attachments.is_empty() {
+        embed = embed.field(
+            "附件",
+            truncate(
+                &message
+                    .attachments
+                    .iter()
+                    .map(|a| format!("[{}]({})", a.filename, a.url))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                1024,
+            ),
+            false,
+        );
+    }
+    if let Some(image) = message.attachments.iter().find(|a| {
+        a.content_type
+            .as_deref()
+            .is_some_and(|t| t.starts_with("image/"))
+    }) {
+        embed = embed.image(&image.url);
+    }
+    embed
+}
+
+#[command(
+    context_menu_command = "Bookmark",
+    name_localized("zh-CN", "收藏"),
+    ephemeral
+)]
+/// Bookmarks a message and sends you a copy by DM.
+pub async fn bookmark(ctx: Context<'_>, message: Message) -> Result<(), BotError> {
+    let dm = CreateMessage::new()
+        .content("🔖 已收藏消息:")
+        .embed(copy_embed(&message));
+    if ctx.author().direct_message(ctx, dm).await.is_err() {
+        ctx.say("❌ **错误**\n\n无法私信你, 请检查你的隐私设置。")
+            .await?;
+        return Ok(());
+    }
+    let added = ctx
+        .data()
+        .db
+        .bookmarks()
+        .add(ctx.author().id, &message)
+        .await?;
+    ctx.say(if added {
+        "✅ **成功**\n\n已收藏, 副本已私信发送给你。"
+    } else {
+        "✅ **成功**\n\n该消息已在收藏中, 已重新私信发送副本。"
+    })
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    subcommands("bookmarks_list", "bookmarks_remove"),
+    subcommand_required,
+    name_localized("zh-CN", "收藏夹"),
+    description_localized("zh-CN", "管理你收藏的消息")
+)]
+/// Manages your bookmarked messages.
+pub async fn bookmarks(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "list",
+    name_localized("zh-CN", "列表"),
+    description_localized("zh-CN", "列出你收藏的消息"),
+    ephemeral
+)]
+/// Lists your bookmarked messages.
+async fn bookmarks_list(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "页码")]
+    #[description_localized("zh-CN", "页码, 默认为第 1 页")]
+    #[description = "Page number, defaults to 1"]
+    #[min = 1]
+    page: Option<u64>,
+) -> Result<(), BotError> {
+    let repo = ctx.data().db.bookmarks();
+    let total = repo.count(ctx.author().id).await?;
+    if total == 0 {
+        ctx.say("🔖 你还没有收藏任何消息。").await?;
+        return Ok(());
+    }
+    let pages = total.div_ceil(PAGE_SIZE);
+    let page = page.unwrap_or(1).min(pages);
+    let bookmarks = repo
+        .list(ctx.author().id, PAGE_SIZE, (page - 1) * PAGE_SIZE)
+        .await?;
+    let description = bookmarks
+        .iter()
+        .map(|b| {
+            let preview = b.content.replace('\n', " ");
+            format!(
+                "`#{}` {} {} [跳转]({})\n> {}",
+                b.id,
+                b.author_id().mention(),
+                FormattedTimestamp::new(
+                    b.created_at().into(),
+                    Some(FormattedTimestampStyle::ShortDate)
+                ),
+                b.message_id().link(b.channel_id(), b.guild_id()),
+                if preview.is_empty() {
+                    "*(无文字内容)*".to_string()
+                } else {
+                    truncate(&preview, 100)
+                }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("🔖 我的收藏")
+                .description(description)
+                .footer(CreateEmbedFooter::new(format!(
+                    "第 {page}/{pages} 页, 共 {total} 条"
+                )))
+                .color(0x5865F2),
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "remove",
+    name_localized("zh-CN", "删除"),
+    description_localized("zh-CN", "删除一条收藏"),
+    ephemeral
+)]
+/// Removes one of your bookmarks.
+async fn bookmarks_remove(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "编号")]
+    #[description_localized("zh-CN", "收藏列表中的编号")]
+    #[description = "Bookmark number from the list"]
+    id: i32,
+) -> Result<(), BotError> {
+    if ctx
+        .data()
+        .db
+        .bookmarks()
+        .remove(ctx.author().id, id)
+        .await?
+    {
+        ctx.say(format!("✅ **成功**\n\n已删除收藏 `#{id}`。"))
+            .await?;
+    } else {
+        ctx.say(format!("❌ **错误**\n\n没有编号为 `#{id}` 的收藏。"))
+            .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::{StreamExt, TryStreamExt};
+use poise::{CreateReply, command};
+use serenity::all::*;
+use snafu::OptionExt;
+
+use super::{Context, autocomplete::duration_choices, utils::user_tz};
+use crate::{error::BotError, handlers::extract_urls, utils::parse_duration};
+
+/// Messages read at most, so a very busy channel cannot stall the command
+const MAX_MESSAGES: usize = 1000;
+
+/// The `n` most frequent items, most frequent first and ties in order of appearance
+fn rank<T: Eq + Hash + Clone>(items: impl IntoIterator<Item = T>, n: usize) -> Vec<(T, usize)> {
+    let mut counts = HashMap::<T, (usize, usize)>::new();
+    for (i, item) in items.into_iter().enumerate() {
+        counts.entry(item).or_insert((0, i)).0 += 1;
+    }
+    let mut ranked = counts.into_iter().collect::<Vec<_>>();
+    ranked.sort_by_key(|(_, (count, first))| (std::cmp::Reverse(*count), *first));
+    ranked
+        .into_iter()
+        .take(n)
+        .map(|(item, (count, _))| (item, count))
+        .collect()
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    name_localized("zh-CN", "速览"),
+    description_localized("zh-CN", "汇总频道中你错过的消息"),
+    ephemeral
+)]
+/// Summarizes what happened in a channel since a point in time.
+pub async fn catchup(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "频道")]
+    #[description_localized("zh-CN", "要汇总的频道")]
+    #[description = "Channel to summarize"]
+    #[channel_types("Text", "News", "PublicThread", "PrivateThread")]
+    channel: GuildChannel,
+    #[name_localized("zh-CN", "起始")]
+    #[description_localized("zh-CN", "时长如 3h, 或时间如 今天 08:00, 默认为 24 小时内")]
+    #[description = "A duration like 3h or a time like today 08:00, the last 24 hours by default"]
+    #[autocomplete = "duration_choices"]
+    since: Option<String>,
+) -> Result<(), BotError> {
+    let since = match since.as_deref() {
+        None => Utc::now() - TimeDelta::days(1),
+        Some(input) => match parse_duration(input) {
+            Some(duration) => Utc::now() - duration,
+            None => match user_tz(ctx).await?.parse(input) {
+                Some(at) => at.to_utc(),
+                None => {
+                    ctx.say("❌ **错误**\n\n无法解析起始时间, 例如 `3h`、`今天 08:00`。")
+                        .await?;
+                    return Ok(());
+                }
+            },
+        },
+    };
+    let member = ctx
+        .author_member()
+        .await
+        .whatever_context::<&str, BotError>("Failed to get the invoking member")?;
+    let permissions = ctx
+        .guild()
+        .map(|g| g.user_permissions_in(&channel, &member))
+        .unwrap_or_default();
+    if !permissions.view_channel() || !permissions.read_message_history() {
+        ctx.say("❌ **错误**\n\n你无法查看该频道的消息记录。")
+            .await?;
+        return Ok(());
+    }
+    ctx.defer_ephemeral().await?;
+
+    // Newest first
+    let messages = channel
+        .id
+        .messages_iter(ctx)
+        .take(MAX_MESSAGES)
+        .try_take_while(|m| std::future::ready(Ok(m.timestamp.to_utc() >= since)))
+        .try_collect::<Vec<_>>()
+        .await?;
+    let at = |t: DateTime<Utc>| {
+        FormattedTimestamp::new(t.into(), Some(FormattedTimestampStyle::RelativeTime))
+    };
+    let Some(first) = messages.last() else {
+        ctx.say(format!(
+            "📭 {} 自 {} 以来没有新消息。",
+            channel.mention(),
+            at(since)
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let humans = messages.iter().filter(|m| !m.author.bot);
+    let posters = rank(humans.clone().map(|m| m.author.id), 5);
+    let active = humans.map(|m| m.author.id).collect::<HashSet<_>>().len();
+    let links = rank(
+        messages.iter().rev().flat_map(|m| extract_urls(&m.content)),
+        5,
+    );
+    let attachments = messages.iter().map(|m| m.attachments.len()).sum::<usize>();
+    let truncated = if messages.len() == MAX_MESSAGES {
+        " (已达上限)"
+    } else {
+        ""
+    };
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("📰 #{} 速览", channel.name))
+        .description(format!(
+            "自 {} 以来, [从这里开始阅读]({})",
+            at(since),
+            first.link()
+        ))
+        .field("消息", format!("{}{truncated}", messages.len()), true)
+        .field("活跃成员", active.to_string(), true)
+        .field("附件", attachments.to_string(), true)
+        .color(0x5865F2)
+        .timestamp(Timestamp::now());
+    if !posters.is_empty() {
+        embed = embed.field(
+            "发言最多",
+            posters
+                .iter()
+                .map(|(user_id, count)| format!("{} - {count} 条", user_id.mention()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            false,
+        );
+    }
+    if !links.is_empty() {
+        embed = embed.field(
+            "热门链接",
+            links
+                .iter()
+                .map(|(url, count)| format!("<{url}> ×{count}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            false,
+        );
+    }
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rank() {
+        let ranked = rank(["b", "a", "c", "a", "c", "d"], 3);
+        assert_eq!(ranked, [("a", 2), ("c", 2), ("b", 1)]);
+    }
+}
@@ -0,0 +1,337 @@
+use poise::{CreateReply, Modal, command};
+use serde::Deserialize;
+use serenity::all::*;
+
+use super::Context;
+use crate::{config::PermissionLevel, error::BotError};
+
+/// Largest attachment accepted as embed source
+const MAX_SOURCE_SIZE: u32 = 64 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ColorSpec {
+    Int(u32),
+    /// `#RRGGBB`
+    Hex(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct FieldSpec {
+    name: String,
+    value: String,
+    #[serde(default)]
+    inline: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AuthorSpec {
+    name: String,
+    url: Option<String>,
+    icon_url: Option<String>,
+}
+
+/// An embed as written by an admin in JSON or YAML
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EmbedSpec {
+    title: Option<String>,
+    description: Option<String>,
+    url: Option<String>,
+    color: Option<ColorSpec>,
+    #[serde(default)]
+    fields: Vec<FieldSpec>,
+    footer: Option<String>,
+    image: Option<String>,
+    thumbnail: Option<String>,
+    author: Option<AuthorSpec>,
+    /// Show the time of posting
+    #[serde(default)]
+    timestamp: bool,
+}
+
+fn check_len(what: &str, text: &str, max: usize) -> Result<usize, String> {
+    let len = text.chars().count();
+    if len > max {
+        return Err(format!("{what}过长 ({len}/{max})"));
+    }
+    Ok(len)
+}
+
+impl EmbedSpec {
+    /// Parse JSON, or YAML if the source does not look like a JSON object.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let spec = if source.trim_start().starts_with('{') {
+            serenity::json::from_str::<Self>(source).map_err(|e| format!("JSON 解析失败: {e}"))?
+        } else {
+            serde_yaml_ng::from_str::<Self>(source).map_err(|e| format!("YAML 解析失败: {e}"))?
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Check Discord's embed limits up front for readable errors.
+    fn validate(&self) -> Result<(), String> {
+        let mut total = 0;
+        if let Some(title) = &self.title {
+            total += check_len("标题", title, 256)?;
+        }
+        if let Some(description) = &self.description {
+            total += check_len("描述", description, 4096)?;
+        }
+        if self.fields.len() > 25 {
+            return Err(format!("字段过多 ({}/25)", self.fields.len()));
+        }
+        for field in &self.fields {
+            total += check_len("字段名", &field.name, 256)?;
+            total += check_len("字段内容", &field.value, 1024)?;
+        }
+        if let Some(footer) = &self.footer {
+            total += check_len("页脚", footer, 2048)?;
+        }
+        if let Some(author) = &self.author {
+            total += check_len("作者", &author.name, 256)?;
+        }
+        if total == 0 && self.image.is_none() && self.thumbnail.is_none() {
+            return Err("嵌入内容为空".into());
+        }
+        if total > 6000 {
+            return Err(format!("嵌入总长度过长 ({total}/6000)"));
+        }
+        if let Some(ColorSpec::Hex(hex)) = &self.color {
+            Self::parse_hex(hex)?;
+        }
+        Ok(())
+    }
+
+    fn parse_hex(hex: &str) -> Result<u32, String> {
+        u32::from_str_radix(hex.trim_start_matches('#'), 16)
+            .ok()
+            .filter(|&c| c <= 0xFFFFFF)
+            .ok_or_else(|| format!("无效的颜色 `{hex}`"))
+    }
+
+    pub fn to_embed(&self) -> CreateEmbed {
+        let mut embed = CreateEmbed::new();
+        if let Some(title) = &self.title {
+            embed = embed.title(title);
+        }
+        if let Some(description) = &self.description {
+            embed = embed.description(description);
+        }
+        if let Some(url) = &self.url {
+            embed = embed.url(url);
+        }
+        match &self.color {
+            Some(ColorSpec::Int(color)) => embed = embed.color(*color),
+            Some(ColorSpec::Hex(hex)) => {
+                embed = embed.color(Self::parse_hex(hex).unwrap_or_default())
+            }
+            None => {}
+        }
+        embed = embed.fields(
+            self.fields
+                .iter()
+                .map(|f| (f.name.to_owned(), f.value.to_owned(), f.inline)),
+        );
+        if let Some(footer) = &self.footer {
+            embed = embed.footer(CreateEmbedFooter::new(footer));
+        }
+        if let Some(image) = &self.image {
+            embed = embed.image(image);
+        }
+        if let Some(thumbnail) = &self.thumbnail {
+            embed = embed.thumbnail(thumbnail);
+        }
+        if let Some(author) = &self.author {
+            let mut builder = CreateEmbedAuthor::new(&author.name);
+            if let Some(url) = &author.url {
+                builder = builder.url(url);
+            }
+            if let Some(icon_url) = &author.icon_url {
+                builder = builder.icon_url(icon_url);
+            }
+            embed = embed.author(builder);
+        }
+        if self.timestamp {
+            embed = embed.timestamp(Timestamp::now());
+        }
+        embed
+    }
+}
+
+#[derive(Debug, Modal)]
+#[name = "嵌入内容"]
+struct EmbedModal {
+    #[name = "JSON 或 YAML"]
+    #[placeholder = "{\"title\": \"规则\", \"description\": \"...\"}"]
+    #[paragraph]
+    #[max_length = 4000]
+    source: String,
+}
+
+/// Read embed source from `file`, or ask for it in a modal prefilled with `defaults`.
+async fn read_source(
+    ctx: Context<'_>,
+    file: Option<Attachment>,
+    defaults: Option<String>,
+) -> Result<Option<String>, BotError> {
+    if let Some(file) = file {
+        if file.size > MAX_SOURCE_SIZE {
+            ctx.say("❌ **错误**\n\n附件过大, 最大为 64 KiB。").await?;
+            return Ok(None);
+        }
+        ctx.defer_ephemeral().await?;
+        let Ok(source) = String::from_utf8(file.download().await?) else {
+            ctx.say("❌ **错误**\n\n附件不是 UTF-8 文本。").await?;
+            return Ok(None);
+        };
+        return Ok(Some(source));
+    }
+    let Context::Application(app_ctx) = ctx else {
+        unreachable!("embed is a slash command");
+    };
+    let defaults = defaults.map(|source| EmbedModal { source });
+    Ok(poise::execute_modal(app_ctx, defaults, None)
+        .await?
+        .map(|m| m.source))
+}
+
+/// Parse `source`, replying with the reason if it is not a valid embed.
+async fn parse_source(ctx: Context<'_>, source: &str) -> Result<Option<EmbedSpec>, BotError> {
+    match EmbedSpec::parse(source) {
+        Ok(spec) => Ok(Some(spec)),
+        Err(why) => {
+            ctx.say(format!("❌ **错误**\n\n无效的嵌入: {why}")).await?;
+            Ok(None)
+        }
+    }
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    subcommands("embed_post", "embed_edit"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "嵌入"),
+    description_localized("zh-CN", "以机器人身份发布或编辑嵌入消息")
+)]
+/// Posts or edits rich embeds as the bot.
+pub async fn embed(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "post",
+    name_localized("zh-CN", "发布"),
+    description_localized("zh-CN", "根据 JSON 或 YAML 发布嵌入消息"),
+    ephemeral
+)]
+/// Posts an embed described in JSON or YAML.
+pub async fn embed_post(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "频道")]
+    #[description_localized("zh-CN", "发布嵌入的频道")]
+    #[description = "Channel to post the embed in"]
+    #[channel_types("Text", "News")]
+    channel: GuildChannel,
+    #[name_localized("zh-CN", "文件")]
+    #[description_localized("zh-CN", "包含 JSON 或 YAML 的附件, 不提供则弹出表单")]
+    #[description = "Attachment with the JSON or YAML, a form opens if omitted"]
+    file: Option<Attachment>,
+) -> Result<(), BotError> {
+    let Some(source) = read_source(ctx, file, None).await? else {
+        return Ok(());
+    };
+    let Some(spec) = parse_source(ctx, &source).await? else {
+        return Ok(());
+    };
+    let message = channel
+        .send_message(ctx, CreateMessage::new().embed(spec.to_embed()))
+        .await?;
+    ctx.data()
+        .db
+        .embeds()
+        .track(&message, channel.guild_id, ctx.author().id, source)
+        .await?;
+    ctx.say(format!("✅ **成功**\n\n嵌入已发布: {}", message.link()))
+        .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "edit",
+    name_localized("zh-CN", "编辑"),
+    description_localized("zh-CN", "编辑之前发布的嵌入消息"),
+    ephemeral
+)]
+/// Edits an embed previously posted with `/embed post`.
+pub async fn embed_edit(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "消息")]
+    #[description_localized("zh-CN", "要编辑的消息链接或 ID")]
+    #[description = "Link or ID of the message to edit"]
+    message: Message,
+    #[name_localized("zh-CN", "文件")]
+    #[description_localized("zh-CN", "包含 JSON 或 YAML 的附件, 不提供则弹出表单")]
+    #[description = "Attachment with the JSON or YAML, a form opens if omitted"]
+    file: Option<Attachment>,
+) -> Result<(), BotError> {
+    let tracked = ctx.data().db.embeds().get(message.id).await?;
+    let Some(tracked) = tracked.filter(|t| Some(t.guild_id()) == ctx.guild_id()) else {
+        ctx.send(
+            CreateReply::default()
+                .content("❌ **错误**\n\n只能编辑本服务器中由 `/embed post` 发布的消息。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+    let (channel_id, guild_id) = (tracked.channel_id(), tracked.guild_id());
+    let Some(source) = read_source(ctx, file, Some(tracked.source)).await? else {
+        return Ok(());
+    };
+    let Some(spec) = parse_source(ctx, &source).await? else {
+        return Ok(());
+    };
+    let message = channel_id
+        .edit_message(ctx, message.id, EditMessage::new().embed(spec.to_embed()))
+        .await?;
+    ctx.data()
+        .db
+        .embeds()
+        .track(&message, guild_id, ctx.author().id, source)
+        .await?;
+    ctx.say(format!("✅ **成功**\n\n嵌入已更新: {}", message.link()))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_embed() {
+        let spec = EmbedSpec::parse(
+            r##"{"title": "规则", "color": "#57F287", "fields": [{"name": "1", "value": "友善"}]}"##,
+        )
+        .unwrap();
+        assert_eq!(spec.title.as_deref(), Some("规则"));
+        assert_eq!(spec.fields.len(), 1);
+
+        let spec = EmbedSpec::parse("title: FAQ\ncolor: 5793266\ntimestamp: true\n").unwrap();
+        assert!(matches!(spec.color, Some(ColorSpec::Int(5793266))));
+        assert!(spec.timestamp);
+
+        assert!(EmbedSpec::parse("{}").is_err());
+        assert!(EmbedSpec::parse(r#"{"title": "x", "colour": 1}"#).is_err());
+        assert!(EmbedSpec::parse(r##"{"title": "x", "color": "#GGGGGG"}"##).is_err());
+        assert!(EmbedSpec::parse(&format!(r#"{{"title": "{}"}}"#, "x".repeat(257))).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+use dashmap::DashMap;
+use futures::TryStreamExt;
+use poise::command;
+use serenity::all::*;
+
+use super::Context;
+use crate::{config::PermissionLevel, error::BotError};
+
+/// One message of an FAQ channel
+#[derive(Debug, Clone)]
+pub struct FaqEntry {
+    pub title: String,
+    pub link: String,
+    keywords: HashSet<String>,
+}
+
+/// FAQ entries per guild, built on startup and by `/faq rebuild`
+pub(crate) static INDEX: LazyLock<DashMap<GuildId, Vec<FaqEntry>>> = LazyLock::new(DashMap::new);
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}' // Kana
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}' // Hangul
+    )
+}
+
+/// Keywords of a text: lowercase words of two or more letters or digits, and pairs of adjacent
+/// CJK characters since those languages put no spaces between words.
+pub fn keywords(text: &str) -> HashSet<String> {
+    fn flush(keywords: &mut HashSet<String>, word: &mut String) {
+        if word.chars().count() >= 2 {
+            keywords.insert(word.to_owned());
+        }
+        word.clear();
+    }
+    let mut keywords = HashSet::new();
+    let (mut word, mut previous) = (String::new(), None);
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush(&mut keywords, &mut word);
+            if let Some(p) = previous {
+                keywords.insert(format!("{p}{c}"));
+            }
+            previous = Some(c);
+            continue;
+        }
+        previous = None;
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        } else {
+            flush(&mut keywords, &mut word);
+        }
+    }
+    flush(&mut keywords, &mut word);
+    keywords
+}
+
+/// Share of the question's keywords found in an entry
+fn score(question: &HashSet<String>, entry: &FaqEntry) -> f64 {
+    if question.is_empty() {
+        return 0.0;
+    }
+    question.intersection(&entry.keywords).count() as f64 / question.len() as f64
+}
+
+/// The entry of a guild's FAQ closest to a question, if it scores at least `min_score`
+pub fn best_match(
+    guild_id: GuildId,
+    question: &HashSet<String>,
+    min_score: f64,
+) -> Option<FaqEntry> {
+    let index = INDEX.get(&guild_id)?;
+    index
+        .iter()
+        .map(|entry| (entry, score(question, entry)))
+        .filter(|(_, score)| *score >= min_score)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entry, _)| entry.to_owned())
+}
+
+fn entry(msg: &Message) -> Option<FaqEntry> {
+    let mut text = msg.content.to_owned();
+    for embed in &msg.embeds {
+        for part in [&embed.title, &embed.description].into_iter().flatten() {
+            text.push('\n');
+            text.push_str(part);
+        }
+    }
+    let title = text
+        .lines()
+        .map(|line| {
+            line.trim_start_matches(['#', '*', '>', ' '])
+                .trim_end_matches(['*', ' '])
+        })
+        .find(|line| !line.is_empty())?
+        .chars()
+        .take(80)
+        .collect();
+    Some(FaqEntry {
+        title,
+        link: msg.link(),
+        keywords: keywords(&text),
+    })
+}
+
+/// Index the messages of a guild's FAQ channel, returning how many entries there are.
+pub async fn rebuild(
+    http: impl AsRef<Http>,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<usize, BotError> {
+    let entries = channel_id
+        .messages_iter(http)
+        .try_collect::<Vec<_>>()
+        .await?
+        .iter()
+        .filter_map(entry)
+        .collect::<Vec<_>>();
+    let count = entries.len();
+    INDEX.insert(guild_id, entries);
+    Ok(count)
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    subcommands("faq_rebuild"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "常见问题"),
+    description_localized("zh-CN", "管理常见问题自动回复")
+)]
+/// Manages automatic replies from the FAQ.
+pub async fn faq(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "rebuild",
+    name_localized("zh-CN", "重建索引"),
+    description_localized("zh-CN", "重新读取常见问题频道"),
+    ephemeral
+)]
+/// Reads the FAQ channel again, e.g. after editing entries.
+async fn faq_rebuild(ctx: Context<'_>) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let Some(channel_id) = ctx
+        .data()
+        .cfg
+        .load()
+        .guilds
+        .get(&guild_id)
+        .and_then(|g| g.faq.as_ref())
+        .map(|faq| faq.channel_id)
+    else {
+        ctx.say("❌ **错误**\n\n本服务器未配置常见问题频道。")
+            .await?;
+        return Ok(());
+    };
+    ctx.defer_ephemeral().await?;
+    let count = rebuild(ctx, guild_id, channel_id).await?;
+    ctx.say(format!("✅ **成功**\n\n已索引 {count} 条常见问题。"))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_keywords() {
+        let words = keywords("How do I reset my **password**? 忘记密码");
+        let expected = [
+            "how", "do", "reset", "my", "password", "忘记", "记密", "密码",
+        ];
+        assert_eq!(words, expected.map(str::to_owned).into());
+    }
+
+    #[test]
+    fn test_score() {
+        let entry = FaqEntry {
+            title: "Resetting your password".to_owned(),
+            link: String::new(),
+            keywords: keywords("Resetting your password: open settings, 忘记密码"),
+        };
+        assert_eq!(score(&keywords("settings password"), &entry), 1.0);
+        assert_eq!(score(&keywords("密码错误"), &entry), 1.0 / 3.0);
+        assert_eq!(score(&HashSet::new(), &entry), 0.0);
+    }
+}
@@ -0,0 +1,322 @@
+use itertools::Itertools;
+use poise::command;
+use rand::{Rng, seq::IndexedRandom};
+use serenity::all::MessageBuilder;
+
+use super::Context;
+use crate::error::BotError;
+
+const MAX_DICE: u32 = 100;
+const MAX_SIDES: u32 = 1000;
+/// Longest per-die breakdown shown, so big rolls stay within Discord's message limit
+const MAX_BREAKDOWN: usize = 1500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keep {
+    All,
+    Highest(u32),
+    Lowest(u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    Dice { count: u32, sides: u32, keep: Keep },
+    Constant(i64),
+}
+
+/// A parsed dice expression such as `2d6+3`, `4d6kh3` or `d20adv`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiceExpr(Vec<(i64, Term)>);
+
+fn parse_term(term: &str) -> Result<Term, String> {
+    let Some((count, rest)) = term.split_once('d') else {
+        return term
+            .parse()
+            .map(Term::Constant)
+            .map_err(|_| format!("无法解析 `{term}`"));
+    };
+    let count = if count.is_empty() {
+        1
+    } else {
+        count
+            .parse()
+            .map_err(|_| format!("无法解析骰子数量 `{count}`"))?
+    };
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '%')
+        .unwrap_or(rest.len());
+    let (sides, modifier) = rest.split_at(digits);
+    let sides = match sides {
+        "%" => 100,
+        s => s.parse().map_err(|_| format!("无法解析骰子面数 `{s}`"))?,
+    };
+    let (count, keep) = match modifier {
+        "" => (count, Keep::All),
+        "adv" => (count.max(2), Keep::Highest(1)),
+        "dis" => (count.max(2), Keep::Lowest(1)),
+        m => {
+            let (keep, n) = if let Some(n) = m.strip_prefix("kh") {
+                (Keep::Highest as fn(u32) -> Keep, n)
+            } else if let Some(n) = m.strip_prefix("kl") {
+                (Keep::Lowest as fn(u32) -> Keep, n)
+            } else {
+                return Err(format!("未知的修饰符 `{m}`"));
+            };
+            let n = n.parse().map_err(|_| format!("无法解析保留数量 `{n}`"))?;
+            if n == 0 || n > count {
+                return Err(format!("保留数量必须在 1 到 {count} 之间"));
+            }
+            (count, keep(n))
+        }
+    };
+    if count == 0 || count > MAX_DICE {
+        return Err(format!("骰子数量必须在 1 到 {MAX_DICE} 之间"));
+    }
+    if !(2..=MAX_SIDES).contains(&sides) {
+        return Err(format!("骰子面数必须在 2 到 {MAX_SIDES} 之间"));
+    }
+    Ok(Term::Dice { count, sides, keep })
+}
+
+impl std::str::FromStr for DiceExpr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase();
+        if s.is_empty() {
+            return Err("表达式为空".into());
+        }
+        let mut terms = vec![];
+        let mut sign = 1;
+        let mut start = 0;
+        for (i, c) in s.char_indices().chain([(s.len(), '+')]) {
+            if c != '+' && c != '-' {
+                continue;
+            }
+            let term = &s[start..i];
+            if term.is_empty() {
+                if i != 0 {
+                    return Err("运算符之间缺少数值".into());
+                }
+            } else {
+                terms.push((sign, parse_term(term)?));
+            }
+            sign = if c == '-' { -1 } else { 1 };
+            start = i + 1;
+        }
+        if terms.len() > 20 {
+            return Err("表达式项数过多".into());
+        }
+        Ok(DiceExpr(terms))
+    }
+}
+
+impl DiceExpr {
+    /// Roll the expression, returning the total and a breakdown of each term, or `None` if the
+    /// total overflows.
+    fn roll(&self, rng: &mut impl Rng) -> Option<(i64, String)> {
+        let mut total = 0i64;
+        let mut breakdown = String::new();
+        for (i, (sign, term)) in self.0.iter().enumerate() {
+            let (value, text) = match term {
+                Term::Constant(c) => (*c, c.to_string()),
+                Term::Dice { count, sides, keep } => {
+                    let rolls = (0..*count)
+                        .map(|_| rng.random_range(1..=*sides as i64))
+                        .collect::<Vec<_>>();
+                    let kept = match keep {
+                        Keep::All => rolls.to_owned(),
+                        Keep::Highest(n) => rolls.iter().copied().k_largest(*n as usize).collect(),
+                        Keep::Lowest(n) => rolls.iter().copied().k_smallest(*n as usize).collect(),
+                    };
+                    let text = if *keep == Keep::All {
+                        format!("[{}]", rolls.iter().join(", "))
+                    } else {
+                        format!(
+                            "[{}] → [{}]",
+                            rolls.iter().join(", "),
+                            kept.iter().join(", ")
+                        )
+                    };
+                    (kept.iter().sum(), text)
+                }
+            };
+            total = sign.checked_mul(value).and_then(|v| total.checked_add(v))?;
+            breakdown.push_str(&match (i, sign) {
+                (0, 1) => text,
+                (0, _) => format!("-{text}"),
+                (_, 1) => format!(" + {text}"),
+                _ => format!(" - {text}"),
+            });
+        }
+        Some((total, breakdown))
+    }
+}
+
+#[command(
+    slash_command,
+    name_localized("zh-CN", "掷骰"),
+    description_localized("zh-CN", "掷骰子, 支持 2d6+3、4d6kh3、d20adv 等表达式")
+)]
+/// Rolls dice such as `2d6+3`, `4d6kh3` or `d20adv`.
+pub async fn roll(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "表达式")]
+    #[description_localized("zh-CN", "骰子表达式, 默认为 d20")]
+    #[description = "Dice expression, d20 by default"]
+    expr: Option<String>,
+) -> Result<(), BotError> {
+    let expr = expr.unwrap_or_else(|| "d20".into());
+    let dice = match expr.parse::<DiceExpr>() {
+        Ok(dice) => dice,
+        Err(why) => {
+            ctx.say(format!("❌ **错误**\n\n无效的骰子表达式: {why}"))
+                .await?;
+            return Ok(());
+        }
+    };
+    let Some((total, mut breakdown)) = dice.roll(&mut rand::rng()) else {
+        ctx.say("❌ **错误**\n\n无效的骰子表达式: 结果超出范围")
+            .await?;
+        return Ok(());
+    };
+    if let Some((cut, _)) = breakdown.char_indices().nth(MAX_BREAKDOWN) {
+        breakdown.truncate(cut);
+        breakdown.push('…');
+    }
+    ctx.say(
+        MessageBuilder::new()
+            .push("🎲 ")
+            .push_bold_safe(&expr)
+            .push(format!(" = **{total}**\n"))
+            .push_mono_safe(breakdown)
+            .build(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    name_localized("zh-CN", "选择"),
+    description_localized("zh-CN", "从用 | 分隔的选项中随机选择一个")
+)]
+/// Picks one of the options separated by `|`.
+pub async fn choose(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "选项")]
+    #[description_localized("zh-CN", "用 | 分隔的选项, 例如 火锅|烧烤|寿司")]
+    #[description = "Options separated by |, e.g. pizza|sushi|tacos"]
+    options: String,
+) -> Result<(), BotError> {
+    let options = options
+        .split('|')
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+        .collect::<Vec<_>>();
+    let Some(choice) = options.choose(&mut rand::rng()) else {
+        ctx.say("❌ **错误**\n\n请至少提供一个选项。").await?;
+        return Ok(());
+    };
+    ctx.say(
+        MessageBuilder::new()
+            .push("🤔 我选择: ")
+            .push_bold_safe(*choice)
+            .build(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    name_localized("zh-CN", "抛硬币"),
+    description_localized("zh-CN", "抛一枚硬币")
+)]
+/// Flips a coin.
+pub async fn coinflip(ctx: Context<'_>) -> Result<(), BotError> {
+    let side = if rand::rng().random_bool(0.5) {
+        "正面"
+    } else {
+        "反面"
+    };
+    ctx.say(format!("🪙 **{side}**")).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_dice() {
+        assert_eq!(
+            "2d6+3".parse(),
+            Ok(DiceExpr(vec![
+                (
+                    1,
+                    Term::Dice {
+                        count: 2,
+                        sides: 6,
+                        keep: Keep::All
+                    }
+                ),
+                (1, Term::Constant(3)),
+            ]))
+        );
+        assert_eq!(
+            "d20 adv".parse(),
+            Ok(DiceExpr(vec![(
+                1,
+                Term::Dice {
+                    count: 2,
+                    sides: 20,
+                    keep: Keep::Highest(1)
+                }
+            )]))
+        );
+        assert_eq!(
+            "-1+4d6kh3".parse(),
+            Ok(DiceExpr(vec![
+                (-1, Term::Constant(1)),
+                (
+                    1,
+                    Term::Dice {
+                        count: 4,
+                        sides: 6,
+                        keep: Keep::Highest(3)
+                    }
+                ),
+            ]))
+        );
+        assert!("".parse::<DiceExpr>().is_err());
+        assert!("2d6++3".parse::<DiceExpr>().is_err());
+        assert!("0d6".parse::<DiceExpr>().is_err());
+        assert!("2d1".parse::<DiceExpr>().is_err());
+        assert!("2d6kh3".parse::<DiceExpr>().is_err());
+        assert!("1000d6".parse::<DiceExpr>().is_err());
+    }
+
+    #[test]
+    fn test_roll_bounds() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let dice = "4d6kh3+2".parse::<DiceExpr>().unwrap();
+        for _ in 0..100 {
+            let (total, _) = dice.roll(&mut rng).unwrap();
+            assert!((5..=20).contains(&total));
+        }
+        let dice = "d%-100".parse::<DiceExpr>().unwrap();
+        for _ in 0..100 {
+            let (total, _) = dice.roll(&mut rng).unwrap();
+            assert!((-99..=0).contains(&total));
+        }
+        let dice = "9223372036854775807+1".parse::<DiceExpr>().unwrap();
+        assert_eq!(dice.roll(&mut rng), None);
+    }
+}
@@ -0,0 +1,162 @@
+use poise::{CreateReply, command};
+use serde::Deserialize;
+use serenity::all::{colours::branding::BLURPLE, *};
+
+use super::Context;
+use crate::error::BotError;
+
+const STEAM_API: &str = "https://api.steampowered.com";
+const STEAM_STORE: &str = "https://store.steampowered.com";
+
+/// A Steam app together with its live player count
+pub struct GameStats {
+    pub app_id: u32,
+    pub name: String,
+    pub players: u64,
+    pub release_date: Option<String>,
+    pub coming_soon: bool,
+    pub header_image: Option<String>,
+}
+
+/// Find a Steam app by id or by searching the store for its name.
+async fn resolve_app(client: &reqwest::Client, query: &str) -> Result<Option<u32>, BotError> {
+    #[derive(Deserialize)]
+    struct Search {
+        items: Vec<Item>,
+    }
+    #[derive(Deserialize)]
+    struct Item {
+        id: u32,
+    }
+
+    if let Ok(app_id) = query.trim().parse() {
+        return Ok(Some(app_id));
+    }
+    let search = client
+        .get(format!("{STEAM_STORE}/api/storesearch/"))
+        .query(&[("term", query), ("l", "schinese"), ("cc", "us")])
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Search>()
+        .await?;
+    Ok(search.items.first().map(|i| i.id))
+}
+
+/// Look up `query` (an app id or name) and its current player count on Steam.
+pub async fn game_stats(api_key: Option<&str>, query: &str) -> Result<Option<GameStats>, BotError> {
+    #[derive(Deserialize)]
+    struct Details {
+        success: bool,
+        data: Option<AppData>,
+    }
+    #[derive(Deserialize)]
+    struct AppData {
+        name: String,
+        header_image: Option<String>,
+        release_date: Option<ReleaseDate>,
+    }
+    #[derive(Deserialize)]
+    struct ReleaseDate {
+        coming_soon: bool,
+        date: String,
+    }
+    #[derive(Deserialize)]
+    struct Players {
+        response: PlayersResponse,
+    }
+    #[derive(Deserialize)]
+    struct PlayersResponse {
+        player_count: Option<u64>,
+    }
+
+    let client = reqwest::Client::new();
+    let Some(app_id) = resolve_app(&client, query).await? else {
+        return Ok(None);
+    };
+    let app_id_str = app_id.to_string();
+    let details = client
+        .get(format!("{STEAM_STORE}/api/appdetails"))
+        .query(&[("appids", app_id_str.as_str()), ("l", "schinese")])
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<std::collections::HashMap<String, Details>>()
+        .await?
+        .remove(&app_id_str)
+        .filter(|d| d.success)
+        .and_then(|d| d.data);
+    let Some(details) = details else {
+        return Ok(None);
+    };
+    let mut req = client
+        .get(format!(
+            "{STEAM_API}/ISteamUserStats/GetNumberOfCurrentPlayers/v1/"
+        ))
+        .query(&[("appid", app_id_str.as_str())]);
+    if let Some(key) = api_key {
+        req = req.query(&[("key", key)]);
+    }
+    // Unreleased apps answer with a 404 and no player count
+    let players = match req.send().await?.error_for_status() {
+        Ok(res) => res.json::<Players>().await?.response.player_count,
+        Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => None,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(Some(GameStats {
+        app_id,
+        name: details.name,
+        players: players.unwrap_or_default(),
+        coming_soon: details.release_date.as_ref().is_some_and(|r| r.coming_soon),
+        release_date: details.release_date.map(|r| r.date),
+        header_image: details.header_image,
+    }))
+}
+
+#[command(
+    slash_command,
+    user_cooldown = 5,
+    name_localized("zh-CN", "游戏状态"),
+    description_localized("zh-CN", "查询 Steam 游戏的在线人数")
+)]
+/// Shows the live player count of a Steam game.
+pub async fn gamestats(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "游戏")]
+    #[description_localized("zh-CN", "游戏名称或 Steam App ID")]
+    #[description = "Game name or Steam app id"]
+    game: String,
+) -> Result<(), BotError> {
+    ctx.defer().await?;
+    let api_key = ctx.data().cfg.load().steam_api_key.to_owned();
+    let Some(stats) = game_stats(api_key.as_deref(), &game).await? else {
+        ctx.say(
+            MessageBuilder::new()
+                .push("❌ **错误**\n\n在 Steam 上找不到游戏 ")
+                .push_bold_safe(&game)
+                .build(),
+        )
+        .await?;
+        return Ok(());
+    };
+    let status = if stats.coming_soon {
+        "🕒 即将推出"
+    } else {
+        "✅ 已发售"
+    };
+    let mut embed = CreateEmbed::new()
+        .title(format!("🎮 {}", stats.name))
+        .url(format!("{STEAM_STORE}/app/{}", stats.app_id))
+        .field("当前在线", stats.players.to_string(), true)
+        .field("状态", status, true)
+        .color(BLURPLE)
+        .timestamp(Timestamp::now());
+    if let Some(date) = stats.release_date.filter(|d| !d.is_empty()) {
+        embed = embed.field("发售日期", date, true);
+    }
+    if let Some(image) = stats.header_image {
+        embed = embed.image(image);
+    }
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
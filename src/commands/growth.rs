@@ -0,0 +1,168 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeDelta, Utc};
+use poise::{ChoiceParameter, CreateReply, command};
+use serenity::all::*;
+
+use super::{Context, send_output};
+use crate::{
+    config::PermissionLevel, error::BotError, repo::members::MemberSnapshot, utils::chart,
+};
+
+/// The snapshot day `at` falls on, as `YYYY-MM-DD` in `offset`
+pub fn day(at: DateTime<Utc>, offset: FixedOffset) -> String {
+    at.with_timezone(&offset).format("%Y-%m-%d").to_string()
+}
+
+#[derive(Debug, Clone, Copy, ChoiceParameter)]
+pub enum GrowthRange {
+    #[name = "Last 7 days"]
+    #[name_localized("zh-CN", "近 7 天")]
+    Week,
+    #[name = "Last 30 days"]
+    #[name_localized("zh-CN", "近 30 天")]
+    Month,
+    #[name = "Last 90 days"]
+    #[name_localized("zh-CN", "近 90 天")]
+    Quarter,
+    #[name = "Last 365 days"]
+    #[name_localized("zh-CN", "近 365 天")]
+    Year,
+}
+
+impl GrowthRange {
+    fn days(self) -> u32 {
+        match self {
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::Quarter => 90,
+            Self::Year => 365,
+        }
+    }
+}
+
+/// Role member counts at the end of the range, with how they changed since its start
+fn role_changes(first: &MemberSnapshot, last: &MemberSnapshot) -> String {
+    let before = first.roles();
+    let mut roles = last.roles().into_iter().collect::<Vec<_>>();
+    roles.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    roles
+        .into_iter()
+        .take(10)
+        .map(|(role_id, count)| {
+            let change = count as i64 - before.get(&role_id).copied().unwrap_or(0) as i64;
+            format!("{} {count} ({change:+})", role_id.mention())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "增长"),
+    description_localized("zh-CN", "查看成员加入和离开的趋势")
+)]
+/// Shows how membership changed over a range of days.
+pub async fn growth(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "范围")]
+    #[description_localized("zh-CN", "统计的天数, 默认近 30 天")]
+    #[description = "Days to cover, the last 30 by default"]
+    range: Option<GrowthRange>,
+) -> Result<(), BotError> {
+    let range = range.unwrap_or(GrowthRange::Month);
+    let offset = FixedOffset::east_opt(ctx.data().cfg.load().time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    let today = Utc::now().with_timezone(&offset).date_naive();
+    let start = today - TimeDelta::days(range.days() as i64 - 1);
+    let format = |date: NaiveDate| date.format("%Y-%m-%d").to_string();
+    let days = ctx
+        .data()
+        .db
+        .members()
+        .since(ctx.guild_id().unwrap(), &format(start))
+        .await?;
+    if days.is_empty() {
+        ctx.say("❌ **错误**\n\n暂无成员数据, 成员变动从机器人加入后开始记录。")
+            .await?;
+        return Ok(());
+    }
+    // Days without joins or leaves have no row
+    let series = start
+        .iter_days()
+        .take(range.days() as usize)
+        .map(|date| {
+            let date = format(date);
+            days.iter()
+                .find(|d| d.day == date)
+                .map_or((0, 0), |d| (d.joins as u64, d.leaves as u64))
+        })
+        .collect::<Vec<_>>();
+    let labels = start
+        .iter_days()
+        .take(series.len())
+        .map(|date| date.format("%m-%d").to_string())
+        .collect::<Vec<_>>();
+    let joins = series.iter().map(|d| d.0).sum::<u64>();
+    let leaves = series.iter().map(|d| d.1).sum::<u64>();
+    let name = range.localized_name("zh-CN").unwrap_or(range.name());
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("📈 成员增长 · {name}"))
+        .field("🟢 加入", joins.to_string(), true)
+        .field("🔴 离开", leaves.to_string(), true)
+        .field(
+            "🔵 净变化",
+            format!("{:+}", joins as i64 - leaves as i64),
+            true,
+        )
+        .image("attachment://growth.png")
+        .footer(CreateEmbedFooter::new(format!(
+            "{} 至 {}",
+            format(start),
+            format(today)
+        )))
+        .color(0x5865F2);
+    let snapshots = days
+        .iter()
+        .filter(|d| d.member_count.is_some())
+        .collect::<Vec<_>>();
+    if let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) {
+        embed = embed.field(
+            "成员数",
+            format!(
+                "{} → {}",
+                first.member_count().unwrap_or_default(),
+                last.member_count().unwrap_or_default()
+            ),
+            false,
+        );
+        let roles = role_changes(first, last);
+        if !roles.is_empty() {
+            embed = embed.field("身份组分布", roles, false);
+        }
+    }
+    send_output(
+        ctx,
+        CreateReply::default()
+            .embed(embed)
+            .attachment(CreateAttachment::bytes(
+                chart::diverging_bars(&series, &labels, ["JOIN", "LEAVE", "NET"]),
+                "growth.png",
+            )),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_day() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let at = "2026-10-13T17:30:00Z".parse().unwrap();
+        assert_eq!(day(at, offset), "2026-10-14");
+    }
+}
@@ -0,0 +1,318 @@
+use chrono::{TimeDelta, Utc};
+use poise::{CreateReply, command};
+use serenity::all::{
+    colours::css::{DANGER, POSITIVE},
+    *,
+};
+
+use super::Context;
+use crate::{
+    config::PermissionLevel,
+    error::BotError,
+    repo::incidents::{Incident, IncidentNote},
+    utils::{
+        alert::{Severity, guild_log},
+        format,
+    },
+};
+
+/// Length of an incident, e.g. `2 小时 5 分钟`
+fn elapsed(delta: TimeDelta) -> String {
+    format::duration(delta.to_std().unwrap_or_default())
+}
+
+fn timeline_entry(note: &IncidentNote) -> String {
+    format!(
+        "{} {}: {}",
+        FormattedTimestamp::new(
+            note.created_at.into(),
+            Some(FormattedTimestampStyle::ShortTime)
+        ),
+        note.author_id().mention(),
+        note.content
+    )
+}
+
+/// The pinned embed summarizing where an incident stands
+fn status_embed(incident: &Incident, notes: &[IncidentNote]) -> CreateEmbed {
+    let started = FormattedTimestamp::new(
+        incident.created_at.into(),
+        Some(FormattedTimestampStyle::RelativeTime),
+    );
+    let (status, colour) = match incident.closed_at {
+        Some(closed_at) => (
+            format!(
+                "✅ 已结束, 历时 {}",
+                elapsed(closed_at - incident.created_at)
+            ),
+            POSITIVE,
+        ),
+        None => ("🔴 处理中".to_owned(), DANGER),
+    };
+    CreateEmbed::new()
+        .title(format!("🚨 事件 #{}: {}", incident.id, incident.title))
+        .field("状态", status, true)
+        .field("发起者", incident.opened_by().mention().to_string(), true)
+        .field("开始于", started.to_string(), true)
+        .field(
+            "最新进展",
+            notes
+                .last()
+                .map(timeline_entry)
+                .unwrap_or_else(|| "暂无".to_owned()),
+            false,
+        )
+        .footer(CreateEmbedFooter::new(format!(
+            "时间线共 {} 条",
+            notes.len()
+        )))
+        .colour(colour)
+        .timestamp(Timestamp::now())
+}
+
+/// Rewrite the pinned status embed of an incident.
+async fn refresh_status(ctx: Context<'_>, incident: &Incident) -> Result<(), BotError> {
+    let Some(message_id) = incident.status_message_id() else {
+        return Ok(());
+    };
+    let notes = ctx.data().db.incidents().notes(incident.id).await?;
+    incident
+        .thread_id()
+        .edit_message(
+            ctx,
+            message_id,
+            EditMessage::new().embed(status_embed(incident, &notes)),
+        )
+        .await?;
+    Ok(())
+}
+
+/// The open incident of the thread the command is used in, telling the user when there is none.
+async fn current_incident(ctx: Context<'_>) -> Result<Option<Incident>, BotError> {
+    let incident = ctx
+        .data()
+        .db
+        .incidents()
+        .by_thread(ctx.channel_id())
+        .await?
+        .filter(|i| i.closed_at.is_none());
+    if incident.is_none() {
+        ctx.send(
+            CreateReply::default()
+                .content("❌ **错误**\n\n请在进行中的事件讨论串内使用此命令。")
+                .ephemeral(true),
+        )
+        .await?;
+    }
+    Ok(incident)
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    subcommands("incident_start", "incident_note", "incident_close"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "事件"),
+    description_localized("zh-CN", "记录和处理突发事件")
+)]
+/// Coordinates the handling of an incident in a private thread.
+pub async fn incident(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "start",
+    name_localized("zh-CN", "开始"),
+    description_localized("zh-CN", "开启事件并创建私密讨论串"),
+    ephemeral
+)]
+/// Opens an incident in a new private thread of this channel.
+async fn incident_start(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "标题")]
+    #[description_localized("zh-CN", "事件简述")]
+    #[description = "Short description of the incident"]
+    #[max_length = 90]
+    title: String,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let db = &ctx.data().db;
+    let thread = ctx
+        .channel_id()
+        .create_thread(
+            ctx,
+            CreateThread::new(format!("🚨 {title}"))
+                .kind(ChannelType::PrivateThread)
+                .invitable(false),
+        )
+        .await?;
+    thread.id.add_thread_member(ctx, ctx.author().id).await?;
+    let id = db
+        .incidents()
+        .create(guild_id, thread.id, &title, ctx.author().id)
+        .await?;
+    let incident = db
+        .incidents()
+        .by_thread(thread.id)
+        .await?
+        .expect("The incident was just created");
+    let status = thread
+        .id
+        .send_message(
+            ctx,
+            CreateMessage::new().embed(status_embed(&incident, &[])),
+        )
+        .await?;
+    status.pin(ctx).await?;
+    db.incidents().set_status_message(id, status.id).await?;
+    guild_log(
+        ctx.serenity_context(),
+        guild_id,
+        Severity::Warning,
+        format!("事件 #{id} 已开启"),
+        format!(
+            "**{title}**\n{} 开启于 {}",
+            ctx.author().mention(),
+            thread.mention()
+        ),
+    )
+    .await?;
+    ctx.say(format!(
+        "✅ **成功**\n\n已开启事件 `#{id}`: {}",
+        thread.mention()
+    ))
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "note",
+    name_localized("zh-CN", "记录"),
+    description_localized("zh-CN", "在事件时间线中添加一条记录")
+)]
+/// Adds a timestamped entry to the timeline of this incident.
+async fn incident_note(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "内容")]
+    #[description_localized("zh-CN", "发生了什么或做了什么")]
+    #[description = "What happened or was done"]
+    #[max_length = 1000]
+    text: String,
+) -> Result<(), BotError> {
+    let Some(incident) = current_incident(ctx).await? else {
+        return Ok(());
+    };
+    let incidents = ctx.data().db.incidents();
+    incidents
+        .add_note(incident.id, ctx.author().id, &text)
+        .await?;
+    ctx.say(format!(
+        "🕒 {} {}: {text}",
+        FormattedTimestamp::new(Timestamp::now(), Some(FormattedTimestampStyle::ShortTime)),
+        ctx.author().mention()
+    ))
+    .await?;
+    refresh_status(ctx, &incident).await
+}
+
+#[command(
+    slash_command,
+    rename = "close",
+    name_localized("zh-CN", "结束"),
+    description_localized("zh-CN", "结束事件并生成事后总结")
+)]
+/// Closes this incident, posting a postmortem and archiving the thread.
+async fn incident_close(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "总结")]
+    #[description_localized("zh-CN", "原因与后续措施")]
+    #[description = "Cause and follow-ups"]
+    #[max_length = 1000]
+    summary: Option<String>,
+) -> Result<(), BotError> {
+    let Some(incident) = current_incident(ctx).await? else {
+        return Ok(());
+    };
+    let db = &ctx.data().db;
+    if !db.incidents().close(incident.id).await? {
+        return Ok(()); // Closed concurrently
+    }
+    let incident = db
+        .incidents()
+        .by_thread(incident.thread_id())
+        .await?
+        .expect("The incident was just closed");
+    let notes = db.incidents().notes(incident.id).await?;
+    let duration = elapsed(Utc::now() - incident.created_at.to_utc());
+    let mut participants = notes.iter().map(|n| n.author_id()).collect::<Vec<_>>();
+    participants.push(incident.opened_by());
+    participants.sort();
+    participants.dedup();
+    let mut timeline = String::new();
+    for entry in notes.iter().map(timeline_entry) {
+        // Leave room for the summary within the 4096 characters of a description
+        if timeline.chars().count() + entry.chars().count() > 2800 {
+            timeline += "…\n";
+            break;
+        }
+        timeline += &entry;
+        timeline.push('\n');
+    }
+    let postmortem = CreateEmbed::new()
+        .title(format!("📝 事后总结 #{}: {}", incident.id, incident.title))
+        .description(format!(
+            "{}\n\n**时间线**\n{}",
+            summary.as_deref().unwrap_or("*未填写总结*"),
+            if timeline.is_empty() {
+                "暂无记录"
+            } else {
+                &timeline
+            }
+        ))
+        .field("历时", &duration, true)
+        .field(
+            "参与者",
+            participants
+                .iter()
+                .map(|id| id.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            true,
+        )
+        .colour(POSITIVE)
+        .timestamp(Timestamp::now());
+    ctx.send(CreateReply::default().embed(postmortem)).await?;
+    refresh_status(ctx, &incident).await?;
+    guild_log(
+        ctx.serenity_context(),
+        incident.guild_id(),
+        Severity::Resolved,
+        format!("事件 #{} 已结束", incident.id),
+        format!(
+            "**{}**\n历时 {duration}, 详见 {}\n\n{}",
+            incident.title,
+            incident.thread_id().mention(),
+            summary.as_deref().unwrap_or_default()
+        ),
+    )
+    .await?;
+    incident
+        .thread_id()
+        .edit_thread(ctx, EditThread::new().archived(true).locked(true))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_elapsed() {
+        assert_eq!(elapsed(TimeDelta::minutes(42)), "42 分钟");
+        assert_eq!(elapsed(TimeDelta::minutes(125)), "2 小时 5 分钟");
+    }
+}
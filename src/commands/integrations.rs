@@ -0,0 +1,126 @@
+use std::{sync::LazyLock, time::Instant};
+
+use dashmap::DashMap;
+use poise::{CreateReply, command};
+use serenity::all::{
+    colours::css::{DANGER, POSITIVE},
+    *,
+};
+
+use super::{
+    Context,
+    uptime::{EndpointStatus, STATUS},
+};
+use crate::{config::PermissionLevel, error::BotError};
+
+/// Latest query of each game server by name, updated by the game server handler
+pub static GAME_SERVERS: LazyLock<DashMap<String, EndpointStatus>> = LazyLock::new(DashMap::new);
+
+/// One line per service, from its latest check
+fn status_line(name: &str, status: Option<&EndpointStatus>) -> String {
+    match status {
+        None => format!("⚪ **{name}** 尚未检查"),
+        Some(s) => {
+            let checked =
+                FormattedTimestamp::new(s.checked_at, Some(FormattedTimestampStyle::RelativeTime));
+            if s.up {
+                format!(
+                    "🟢 **{name}** {} ms · {checked}",
+                    s.response_time.as_millis()
+                )
+            } else {
+                let reason = s.reason.as_deref().unwrap_or("无法访问");
+                format!("🔴 **{name}** {reason} · {checked}")
+            }
+        }
+    }
+}
+
+#[command(
+    slash_command,
+    subcommands("integrations_status"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "外部服务"),
+    description_localized("zh-CN", "查看外部服务的连接状态")
+)]
+/// Shows how the bot's external services are doing.
+pub async fn integrations(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "status",
+    name_localized("zh-CN", "状态"),
+    description_localized("zh-CN", "查看各外部服务最近一次检查的结果"),
+    ephemeral
+)]
+/// Reports the reachability and latency of external services from their latest checks.
+async fn integrations_status(ctx: Context<'_>) -> Result<(), BotError> {
+    let cfg = ctx.data().cfg.load_full();
+    // The database is local, so it is cheap enough to check on the spot
+    let started = Instant::now();
+    let db = ctx.data().db.size().await;
+    let db = EndpointStatus {
+        up: db.is_ok(),
+        response_time: started.elapsed(),
+        reason: db.err().map(|e| e.to_string()),
+        checked_at: Timestamp::now(),
+    };
+    let mut lines = vec![status_line("数据库", Some(&db))];
+    let mut all_up = db.up;
+    for name in cfg
+        .uptime_monitors
+        .iter()
+        .map(|m| &m.name)
+        .chain(cfg.game_servers.iter().map(|s| &s.name))
+    {
+        let status = STATUS
+            .get(name)
+            .or_else(|| GAME_SERVERS.get(name))
+            .map(|s| s.to_owned());
+        all_up &= status.as_ref().is_none_or(|s| s.up);
+        lines.push(status_line(name, status.as_ref()));
+    }
+    // Endpoints only used on demand have no checks of their own
+    for (name, configured) in [
+        ("代码运行", cfg.sandbox_endpoint.is_some()),
+        ("粘贴服务", cfg.paste_endpoint.is_some()),
+    ] {
+        if configured {
+            lines.push(format!(
+                "⚪ **{name}** 按需使用, 可添加为可用性监控以持续检查"
+            ));
+        }
+    }
+    let embed = CreateEmbed::new()
+        .title("🔌 外部服务状态")
+        .description(lines.join("\n"))
+        .color(if all_up { POSITIVE } else { DANGER })
+        .timestamp(Timestamp::now());
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_status_line() {
+        assert_eq!(status_line("API", None), "⚪ **API** 尚未检查");
+        let status = EndpointStatus {
+            up: true,
+            response_time: Duration::from_millis(42),
+            reason: None,
+            checked_at: Timestamp::from_unix_timestamp(1_800_000_000).unwrap(),
+        };
+        assert_eq!(
+            status_line("API", Some(&status)),
+            "🟢 **API** 42 ms · <t:1800000000:R>"
+        );
+    }
+}
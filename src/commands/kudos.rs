@@ -0,0 +1,148 @@
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use super::Context;
+use crate::{database::BotDatabase, error::BotError};
+
+/// The kudos month `at` falls in, as `YYYY-MM` in `offset`
+pub fn month(at: DateTime<Utc>, offset: FixedOffset) -> String {
+    at.with_timezone(&offset).format("%Y-%m").to_string()
+}
+
+/// The month before the one `at` falls in
+pub fn previous_month(at: DateTime<Utc>, offset: FixedOffset) -> String {
+    let local = at.with_timezone(&offset).date_naive();
+    let first = local.with_day(1).expect("Every month has a first day");
+    (first - chrono::Duration::days(1))
+        .format("%Y-%m")
+        .to_string()
+}
+
+/// Render the kudos leaderboard of a guild for `month`, if anyone received kudos.
+pub async fn leaderboard_embed(
+    db: &BotDatabase,
+    guild_id: GuildId,
+    month: &str,
+) -> Result<Option<CreateEmbed>, BotError> {
+    let board = db.kudos().leaderboard(guild_id, month, 10).await?;
+    if board.is_empty() {
+        return Ok(None);
+    }
+    let medals = ["🥇", "🥈", "🥉"];
+    let description = board
+        .iter()
+        .enumerate()
+        .map(|(i, k)| {
+            let rank = medals
+                .get(i)
+                .map_or_else(|| format!("{}.", i + 1), |m| m.to_string());
+            format!("{rank} {} - {} 次", k.user_id().mention(), k.count())
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(Some(
+        CreateEmbed::new()
+            .title(format!("🙌 {month} 点赞排行榜"))
+            .description(description)
+            .color(0xF1C40F),
+    ))
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    name_localized("zh-CN", "点赞"),
+    description_localized("zh-CN", "为帮助过你的成员点赞")
+)]
+/// Gives a member kudos for helping out.
+pub async fn kudos(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "用户")]
+    #[description_localized("zh-CN", "要点赞的成员")]
+    #[description = "Member to give kudos to"]
+    user: User,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let cfg = ctx.data().cfg.load();
+    let Some(kudos) = cfg.guilds.get(&guild_id).and_then(|g| g.kudos.to_owned()) else {
+        ctx.send(
+            CreateReply::default()
+                .content("❌ **错误**\n\n本服务器未启用点赞。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+    if user.id == ctx.author().id || user.bot {
+        ctx.send(
+            CreateReply::default()
+                .content("❌ **错误**\n\n不能为自己或机器人点赞。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let offset = FixedOffset::east_opt(cfg.time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    let now = Utc::now();
+    let today = now
+        .with_timezone(&offset)
+        .date_naive()
+        .and_time(Default::default())
+        .and_local_timezone(offset)
+        .single()
+        .expect("Fixed offsets have no ambiguous local times")
+        .timestamp();
+    let db = &ctx.data().db;
+    let giver = format!("{guild_id}:{}", ctx.author().id);
+    if !db
+        .quota()
+        .consume("kudos", &giver, today, kudos.daily_limit)
+        .await?
+    {
+        ctx.send(
+            CreateReply::default()
+                .content(format!(
+                    "❌ **错误**\n\n你今天已经点赞 {} 次了, 明天再来吧。",
+                    kudos.daily_limit
+                ))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    let count = db
+        .kudos()
+        .give(guild_id, user.id, &month(now, offset))
+        .await?;
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "🙌 {} 为 {} 点赞! 本月已收到 {count} 次点赞。",
+                ctx.author().mention(),
+                user.mention()
+            ))
+            .allowed_mentions(CreateAllowedMentions::new().users([user.id])),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_month() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        // 16:30 UTC on Oct 31 is already November in UTC+8
+        let at = Utc.with_ymd_and_hms(2026, 10, 31, 16, 30, 0).unwrap();
+        assert_eq!(month(at, offset), "2026-11");
+        assert_eq!(previous_month(at, offset), "2026-10");
+        let at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(previous_month(at, offset), "2025-12");
+    }
+}
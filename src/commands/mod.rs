@@ -1,44 +1,202 @@
+mod announce;
+pub mod apply;
+mod autocomplete;
+pub mod backup;
+mod board;
+mod bookmark;
+mod catchup;
 mod cookie;
+mod embed;
+pub mod faq;
 pub mod flush;
+mod fun;
+pub mod gamestats;
+pub mod growth;
+mod incident;
+pub mod integrations;
+pub mod kudos;
+pub mod moderation;
+mod modules;
+mod permission;
+pub mod price;
+#[cfg(feature = "profiling")]
+pub mod profile;
+mod quota;
+pub mod ratelimits;
+pub mod report;
+mod run;
+mod setup;
 mod stats;
+pub mod suggestion;
+pub mod todo;
 mod tree_hole;
+pub mod trivia;
+pub mod uptime;
 mod utils;
+mod weather;
+mod welcome;
 
 use std::sync::Arc;
 
+use announce::*;
+use apply::*;
 use arc_swap::ArcSwap;
+use backup::*;
+use board::*;
+use bookmark::*;
+use catchup::*;
 use cookie::*;
+use embed::*;
+use faq::*;
 use flush::*;
+use fun::*;
+use gamestats::*;
+use growth::*;
+use incident::*;
+use integrations::*;
+use kudos::*;
+use moderation::*;
+use modules::*;
 use owo_colors::OwoColorize;
-use poise::{PrefixFrameworkOptions, command};
-use snafu::OptionExt;
+use permission::*;
+use poise::{CreateReply, PrefixFrameworkOptions, command};
+use price::*;
+#[cfg(feature = "profiling")]
+use profile::*;
+use quota::*;
+use ratelimits::*;
+use report::*;
+use run::*;
+use serenity::all::{ChannelId, CreateAllowedMentions, CreateAttachment, CreateMessage};
+use setup::*;
 use stats::*;
-use tracing::{error, info};
+use suggestion::*;
+use todo::*;
+use tracing::{error, info, warn};
 use tree_hole::*;
+use trivia::*;
+use uptime::*;
 use utils::*;
+use weather::*;
+use welcome::*;
 
-use crate::{config::BotCfg, database::BotDatabase, error::BotError};
+use crate::{
+    config::{BotCfg, PermissionLevel},
+    database::BotDatabase,
+    error::BotError,
+    utils::{InvalidDuration, paste},
+};
 
 pub type Context<'a> = poise::Context<'a, Data, BotError>;
 
 pub async fn check_admin(ctx: Context<'_>) -> Result<bool, BotError> {
-    let user_id = ctx.author().id;
-    if ctx
+    Ok(user_level(ctx).await? >= PermissionLevel::Admin)
+}
+
+/// Discord's message length limit
+pub const MESSAGE_LIMIT: usize = 2000;
+
+/// Reply with `content`, falling back to the paste service, or a file attachment if none is
+/// configured, when it does not fit in a single message.
+pub async fn say_long(ctx: Context<'_>, content: String) -> Result<(), BotError> {
+    if content.chars().count() <= MESSAGE_LIMIT {
+        ctx.say(content).await?;
+        return Ok(());
+    }
+    let endpoint = ctx.data().cfg.load().paste_endpoint.to_owned();
+    if let Some(endpoint) = endpoint {
+        match paste::upload(&endpoint, content.to_owned()).await {
+            Ok(url) => {
+                ctx.say(format!("📄 内容过长, 已上传至: {url}")).await?;
+                return Ok(());
+            }
+            Err(why) => warn!("Failed to upload long output to paste service: {why}"),
+        }
+    }
+    ctx.send(
+        CreateReply::default()
+            .content("📄 内容过长, 已作为附件发送。")
+            .attachment(CreateAttachment::bytes(content, "output.txt")),
+    )
+    .await?;
+    Ok(())
+}
+
+/// The channel configured in the invoking guild's `commandOutput` for this command, unless the
+/// command runs there already
+fn output_channel(ctx: Context<'_>) -> Option<ChannelId> {
+    let guild_id = ctx.guild_id()?;
+    let channel_id = *ctx
         .data()
         .cfg
         .load()
-        .extra_admin_user_ids
-        .contains(&user_id)
-    {
-        return Ok(true);
+        .guilds
+        .get(&guild_id)?
+        .command_output
+        .get(&ctx.command().qualified_name)?;
+    // Never post the data of one guild into a channel of another
+    let in_guild = ctx.guild()?.channels.contains_key(&channel_id);
+    (in_guild && channel_id != ctx.channel_id()).then_some(channel_id)
+}
+
+/// Defer the response of a command whose output goes through `send_output`. It is deferred
+/// privately if the output is posted elsewhere, as only the link to it is replied then.
+pub async fn defer_output(ctx: Context<'_>, ephemeral: bool) -> Result<(), BotError> {
+    if ephemeral || output_channel(ctx).is_some() {
+        ctx.defer_ephemeral().await?;
+    } else {
+        ctx.defer().await?;
+    }
+    Ok(())
+}
+
+/// Send the output of a command, or post it to the channel configured in the guild's
+/// `commandOutput` and reply privately with a link to it. Commands deferring their response must
+/// do so through `defer_output`, or the link would replace a public deferred response.
+pub async fn send_output(ctx: Context<'_>, reply: CreateReply) -> Result<(), BotError> {
+    let Some(channel_id) = output_channel(ctx) else {
+        ctx.send(reply).await?;
+        return Ok(());
+    };
+    let mut msg = CreateMessage::new()
+        .embeds(reply.embeds)
+        .add_files(reply.attachments)
+        .allowed_mentions(CreateAllowedMentions::new());
+    if let Some(content) = reply.content {
+        msg = msg.content(content);
     }
-    Ok(ctx
-        .author_member()
-        .await
-        .whatever_context::<&str, BotError>("Failed to get member information")?
-        .roles
-        .iter()
-        .any(|&id| ctx.data().cfg.load().admin_role_ids.contains(&id)))
+    let msg = channel_id.send_message(ctx, msg).await?;
+    ctx.send(
+        CreateReply::default()
+            .content(format!("📤 结果已发送至 {}", msg.link()))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Make a reply ephemeral as configured in `commandEphemeral` or the guild's `ephemeral`. The
+/// policy only adds privacy: replies that are ephemeral anyway, as declared by the command or
+/// set where they are sent, may reveal who ran it and are never made public. Deferred responses
+/// keep their visibility too, which Discord fixes when deferring.
+fn ephemeral_policy(ctx: Context<'_>, mut reply: CreateReply) -> CreateReply {
+    let command = ctx.command();
+    if reply.ephemeral == Some(true) {
+        return reply;
+    }
+    let cfg = ctx.data().cfg.load();
+    let policy = cfg
+        .command_ephemeral
+        .get(&command.qualified_name)
+        .copied()
+        .or_else(|| {
+            let guild_id = ctx.guild_id()?;
+            cfg.guilds.get(&guild_id)?.ephemeral
+        });
+    if policy == Some(true) {
+        reply.ephemeral = policy;
+    }
+    reply
 }
 
 #[derive(Debug)]
@@ -47,6 +205,12 @@ pub struct Data {
     cfg: Arc<ArcSwap<BotCfg>>,
 }
 
+async fn report_check(ctx: Context<'_>, failure: CheckFailure) {
+    if let Err(e) = failure.reply(ctx).await {
+        error!("Failed to report a failed check: {e}");
+    }
+}
+
 async fn on_error(error: poise::FrameworkError<'_, Data, BotError>) {
     // This is our custom error handler
     // They are many errors that can occur, so we only handle the ones we want to customize
@@ -56,6 +220,38 @@ async fn on_error(error: poise::FrameworkError<'_, Data, BotError>) {
         poise::FrameworkError::Command { error, ctx, .. } => {
             error!("Error in command `{}`: {}", ctx.command().name, error);
         }
+        // Spell out the accepted forms rather than poise's generic usage hint
+        poise::FrameworkError::ArgumentParse { error, ctx, .. }
+            if error.is::<InvalidDuration>() =>
+        {
+            let reply = poise::CreateReply::default()
+                .content(format!("❌ **错误**\n\n{error}。"))
+                .ephemeral(true);
+            if let Err(e) = ctx.send(reply).await {
+                error!("Failed to report an argument error: {e}");
+            }
+        }
+        poise::FrameworkError::CooldownHit {
+            remaining_cooldown,
+            ctx,
+            ..
+        } => report_check(ctx, CheckFailure::Cooldown(remaining_cooldown)).await,
+        poise::FrameworkError::MissingUserPermissions {
+            missing_permissions,
+            ctx,
+            ..
+        } => report_check(ctx, CheckFailure::UserPermissions(missing_permissions)).await,
+        poise::FrameworkError::MissingBotPermissions {
+            missing_permissions,
+            ctx,
+            ..
+        } => report_check(ctx, CheckFailure::BotPermissions(missing_permissions)).await,
+        poise::FrameworkError::NotAnOwner { ctx, .. } => {
+            report_check(ctx, CheckFailure::OwnerOnly).await
+        }
+        poise::FrameworkError::GuildOnly { ctx, .. } => {
+            report_check(ctx, CheckFailure::GuildOnly).await
+        }
         error => {
             if let Err(e) = poise::builtins::on_error(error).await {
                 error!("Error while handling error: {}", e)
@@ -64,7 +260,7 @@ async fn on_error(error: poise::FrameworkError<'_, Data, BotError>) {
     }
 }
 
-#[command(prefix_command, owners_only)]
+#[command(prefix_command, custom_data = "PermissionLevel::Owner")]
 async fn register(ctx: Context<'_>) -> Result<(), BotError> {
     Ok(poise::builtins::register_application_commands_buttons(ctx).await?)
 }
@@ -75,13 +271,54 @@ fn option(cfg: &ArcSwap<BotCfg>) -> poise::FrameworkOptions<Data, BotError> {
             guilds_info(),
             register(),
             system_info(),
+            services(),
+            ratelimits(),
+            module(),
+            #[cfg(feature = "profiling")]
+            profile(),
             submit_cookie(),
             register_tree_hole(),
             unregister_tree_hole(),
+            ttl(),
             list_tree_holes(),
+            tree_hole(),
+            trivia(),
             flush_message(),
             channel_stats(),
             user_stats(),
+            roll(),
+            choose(),
+            coinflip(),
+            run(),
+            timestamp(),
+            timezone(),
+            notifications(),
+            weather(),
+            price(),
+            gamestats(),
+            uptime_monitor(),
+            backup(),
+            setup(),
+            welcome(),
+            announce(),
+            embed(),
+            board(),
+            kudos(),
+            apply(),
+            softban(),
+            massban(),
+            channelmute(),
+            channelunmute(),
+            bookmark(),
+            bookmarks(),
+            catchup(),
+            report_message(),
+            suggestion(),
+            todo(),
+            incident(),
+            integrations(),
+            faq(),
+            growth(),
             ping(),
             help(),
         ],
@@ -101,6 +338,14 @@ fn option(cfg: &ArcSwap<BotCfg>) -> poise::FrameworkOptions<Data, BotError> {
             .map(|id| id.to_owned())
             .collect(),
         skip_checks_for_owners: true,
+        reply_callback: Some(ephemeral_policy),
+        command_check: Some(|ctx| {
+            Box::pin(async move {
+                Ok(check_permission(ctx).await?
+                    && check_channel(ctx).await?
+                    && check_quota(ctx).await?)
+            })
+        }),
         pre_command: |ctx| {
             Box::pin(async move {
                 info!(
@@ -0,0 +1,165 @@
+use chrono::Utc;
+use poise::command;
+use serenity::all::*;
+
+use super::{
+    super::{Context, autocomplete::duration_choices},
+    check_hierarchy,
+};
+use crate::{
+    config::PermissionLevel,
+    error::BotError,
+    utils::{
+        DurationArg,
+        alert::{Severity, guild_log},
+    },
+};
+
+const MUTED: Permissions = Permissions::SEND_MESSAGES
+    .union(Permissions::SEND_MESSAGES_IN_THREADS)
+    .union(Permissions::CREATE_PUBLIC_THREADS)
+    .union(Permissions::ADD_REACTIONS)
+    .union(Permissions::SPEAK);
+
+/// Add or lift the mute overwrite of a member, keeping any other overwritten permissions.
+pub async fn set_channel_muted(
+    http: impl CacheHttp,
+    channel_id: ChannelId,
+    user_id: UserId,
+    muted: bool,
+) -> Result<(), BotError> {
+    let Some(channel) = channel_id.to_channel(&http).await?.guild() else {
+        snafu::whatever!("Channel {channel_id} is not a guild channel");
+    };
+    let kind = PermissionOverwriteType::Member(user_id);
+    let (mut allow, mut deny) = channel
+        .permission_overwrites
+        .iter()
+        .find(|o| o.kind == kind)
+        .map_or((Permissions::empty(), Permissions::empty()), |o| {
+            (o.allow, o.deny)
+        });
+    if muted {
+        allow.remove(MUTED);
+        deny.insert(MUTED);
+    } else {
+        deny.remove(MUTED);
+    }
+    if allow.is_empty() && deny.is_empty() {
+        channel.delete_permission(http.http(), kind).await?;
+    } else {
+        channel
+            .create_permission(http.http(), PermissionOverwrite { allow, deny, kind })
+            .await?;
+    }
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "频道禁言"),
+    description_localized("zh-CN", "在单个频道内临时禁言用户"),
+    ephemeral
+)]
+/// Temporarily mutes a member in one channel, a lighter alternative to a timeout.
+pub async fn channelmute(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "用户")]
+    #[description_localized("zh-CN", "要禁言的成员")]
+    #[description = "Member to mute"]
+    user: User,
+    #[name_localized("zh-CN", "频道")]
+    #[description_localized("zh-CN", "禁言的频道")]
+    #[description = "Channel to mute them in"]
+    channel: GuildChannel,
+    #[name_localized("zh-CN", "时长")]
+    #[description_localized("zh-CN", "禁言时长, 例如 30m, 2h, 1d")]
+    #[description = "How long, e.g. 30m, 2h, 1d"]
+    #[autocomplete = "duration_choices"]
+    duration: DurationArg,
+    #[name_localized("zh-CN", "原因")]
+    #[description_localized("zh-CN", "禁言原因")]
+    #[description = "Reason for the mute"]
+    reason: Option<String>,
+) -> Result<(), BotError> {
+    let DurationArg(duration) = duration;
+    let guild_id = ctx.guild_id().unwrap();
+    let Some(expires_at) = Utc::now().checked_add_signed(duration) else {
+        ctx.say("❌ **错误**\n\n禁言时长过长。").await?;
+        return Ok(());
+    };
+    if !check_hierarchy(ctx, user.id).await? {
+        return Ok(());
+    }
+    set_channel_muted(ctx, channel.id, user.id, true).await?;
+    ctx.data()
+        .db
+        .channel_mutes()
+        .add(guild_id, channel.id, user.id, ctx.author().id, expires_at)
+        .await?;
+    let until = FormattedTimestamp::new(
+        expires_at.into(),
+        Some(FormattedTimestampStyle::RelativeTime),
+    );
+    ctx.say(format!(
+        "✅ **成功**\n\n已在 {} 禁言 {}, 将于 {until} 解除。",
+        channel.mention(),
+        user.mention()
+    ))
+    .await?;
+    guild_log(
+        ctx.serenity_context(),
+        guild_id,
+        Severity::Warning,
+        "频道禁言",
+        format!(
+            "{} 在 {} 禁言了 {}, 将于 {until} 解除\n原因: {}",
+            ctx.author().mention(),
+            channel.mention(),
+            user.mention(),
+            reason.as_deref().unwrap_or("无")
+        ),
+    )
+    .await
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES",
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "解除频道禁言"),
+    description_localized("zh-CN", "提前解除用户的频道禁言"),
+    ephemeral
+)]
+/// Lifts a channel mute before it expires.
+pub async fn channelunmute(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "用户")]
+    #[description_localized("zh-CN", "要解除禁言的成员")]
+    #[description = "Member to unmute"]
+    user: User,
+    #[name_localized("zh-CN", "频道")]
+    #[description_localized("zh-CN", "禁言的频道")]
+    #[description = "Channel they are muted in"]
+    channel: GuildChannel,
+) -> Result<(), BotError> {
+    set_channel_muted(ctx, channel.id, user.id, false).await?;
+    ctx.data()
+        .db
+        .channel_mutes()
+        .remove(channel.id, user.id)
+        .await?;
+    ctx.say(format!(
+        "✅ **成功**\n\n已解除 {} 在 {} 的禁言。",
+        user.mention(),
+        channel.mention()
+    ))
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeSet;
+
+use poise::{CreateReply, command};
+use serenity::all::*;
+use tracing::warn;
+
+use super::{super::Context, outranks};
+use crate::{
+    config::PermissionLevel,
+    error::BotError,
+    utils::alert::{Severity, guild_log},
+};
+
+/// Discord's limit of users per bulk ban request
+const BULK_BAN_LIMIT: usize = 200;
+/// Largest ID list attachment accepted
+const MAX_LIST_SIZE: u32 = 256 * 1024;
+
+/// Extract user IDs from free text, accepting mentions and any separators.
+fn parse_ids(text: &str) -> BTreeSet<UserId> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .filter(|s| (17..=20).contains(&s.len()))
+        .filter_map(|s| s.parse::<u64>().ok())
+        .filter(|&id| id != 0)
+        .map(UserId::new)
+        .collect()
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "BAN_MEMBERS",
+    required_bot_permissions = "BAN_MEMBERS",
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "批量封禁"),
+    description_localized("zh-CN", "按 ID 列表批量封禁用户, 用于清理突袭"),
+    ephemeral
+)]
+/// Bans a list of user IDs at once, e.g. to clean up after a raid.
+pub async fn massban(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "用户id")]
+    #[description_localized("zh-CN", "以空格或逗号分隔的用户 ID")]
+    #[description = "User IDs separated by spaces or commas"]
+    ids: Option<String>,
+    #[name_localized("zh-CN", "文件")]
+    #[description_localized("zh-CN", "包含用户 ID 的文本文件")]
+    #[description = "Text file listing user IDs"]
+    file: Option<Attachment>,
+    #[name_localized("zh-CN", "原因")]
+    #[description_localized("zh-CN", "记录在审计日志中的原因")]
+    #[description = "Reason recorded in the audit log"]
+    reason: Option<String>,
+    #[name_localized("zh-CN", "预览")]
+    #[description_localized("zh-CN", "只列出将被封禁的用户, 不执行封禁")]
+    #[description = "Only list who would be banned without banning"]
+    dry_run: Option<bool>,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let mut text = ids.unwrap_or_default();
+    if let Some(file) = file {
+        if file.size > MAX_LIST_SIZE {
+            ctx.say("❌ **错误**\n\n附件过大, 最大为 256 KiB。").await?;
+            return Ok(());
+        }
+        ctx.defer_ephemeral().await?;
+        text.push('\n');
+        text.push_str(&String::from_utf8_lossy(&file.download().await?));
+    }
+    let mut ids = parse_ids(&text);
+    // Never ban the moderator or the bot by accident
+    ids.remove(&ctx.author().id);
+    ids.remove(&ctx.framework().bot_id);
+    // Nor anyone the moderator does not outrank, as far as the cached members tell
+    let Some(author) = ctx.author_member().await else {
+        snafu::whatever!("Failed to get the member of {}", ctx.author().name);
+    };
+    let protected = ctx.guild().map_or_else(BTreeSet::new, |guild| {
+        ids.iter()
+            .filter(|id| {
+                guild
+                    .members
+                    .get(id)
+                    .is_some_and(|member| !outranks(&guild, &author, member))
+            })
+            .copied()
+            .collect()
+    });
+    ids.retain(|id| !protected.contains(id));
+    let skipped = if protected.is_empty() {
+        String::new()
+    } else {
+        format!("\n已跳过 {} 个身份组不低于你的成员。", protected.len())
+    };
+    if ids.is_empty() {
+        ctx.say(format!("❌ **错误**\n\n没有找到有效的用户 ID。{skipped}"))
+            .await?;
+        return Ok(());
+    }
+    let ids = ids.into_iter().collect::<Vec<_>>();
+    let members = ctx
+        .guild()
+        .map(|g| ids.iter().filter(|id| g.members.contains_key(id)).count())
+        .unwrap_or_default();
+    if dry_run.unwrap_or(false) {
+        let preview = ids
+            .iter()
+            .take(30)
+            .map(|id| format!("- {} (`{id}`)", id.mention()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let more = ids.len().saturating_sub(30);
+        ctx.say(format!(
+            "🔍 **预览**\n\n将封禁 {} 个用户, 其中 {members} 个在服务器中:\n{preview}{}{skipped}",
+            ids.len(),
+            if more > 0 {
+                format!("\n…以及另外 {more} 个")
+            } else {
+                String::new()
+            }
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let reason = reason.unwrap_or_else(|| "无".into());
+    let audit = format!("批量封禁, 由 {} 执行: {reason}", ctx.author().name);
+    let progress = |done: usize| format!("⏳ 正在封禁… {done}/{}", ids.len());
+    let handle = ctx
+        .send(CreateReply::default().content(progress(0)))
+        .await?;
+    let (mut banned, mut failed) = (0, 0);
+    for chunk in ids.chunks(BULK_BAN_LIMIT) {
+        match guild_id.bulk_ban(ctx.http(), chunk, 0, Some(&audit)).await {
+            Ok(res) => {
+                banned += res.banned_users.len();
+                failed += res.failed_users.len();
+            }
+            Err(why) => {
+                failed += chunk.len();
+                warn!("Bulk ban in {guild_id} failed: {why}");
+            }
+        }
+        handle
+            .edit(
+                ctx,
+                CreateReply::default().content(progress(banned + failed)),
+            )
+            .await?;
+    }
+    handle
+        .edit(
+            ctx,
+            CreateReply::default().content(format!(
+                "✅ **完成**\n\n已封禁 {banned} 个用户, {failed} 个失败。{skipped}"
+            )),
+        )
+        .await?;
+    guild_log(
+        ctx.serenity_context(),
+        guild_id,
+        Severity::Warning,
+        "批量封禁",
+        format!(
+            "{} 批量封禁了 {banned} 个用户 ({failed} 个失败)\n原因: {reason}",
+            ctx.author().mention()
+        ),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_ids() {
+        let ids = parse_ids("123456789012345678, <@234567890123456789>\n123456789012345678 42 abc");
+        assert_eq!(
+            ids.into_iter().collect::<Vec<_>>(),
+            [
+                UserId::new(123456789012345678),
+                UserId::new(234567890123456789)
+            ]
+        );
+    }
+}
@@ -0,0 +1,82 @@
+mod channel_mute;
+mod massban;
+mod softban;
+pub use channel_mute::*;
+pub use massban::*;
+pub use softban::*;
+
+use serenity::all::*;
+
+use super::Context;
+use crate::error::BotError;
+
+/// Whether `author` may moderate `target` through the bot's role. Like Discord, the guild owner
+/// outranks everyone, anyone else needs a highest role above the target's.
+fn outranks(guild: &Guild, author: &Member, target: &Member) -> bool {
+    if author.user.id == guild.owner_id {
+        return true;
+    }
+    let top = |member| guild.member_highest_role(member).map_or(0, |r| r.position);
+    target.user.id != guild.owner_id && top(author) > top(target)
+}
+
+/// Check that the author outranks a user before acting on them, replying with an error if not.
+/// Users who are not in the guild have no roles to outrank.
+async fn check_hierarchy(ctx: Context<'_>, target: UserId) -> Result<bool, BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let target = match guild_id.member(ctx, target).await {
+        Ok(member) => member,
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(res)))
+            if res.status_code == StatusCode::NOT_FOUND =>
+        {
+            return Ok(true);
+        }
+        Err(why) => return Err(why.into()),
+    };
+    let Some(author) = ctx.author_member().await else {
+        snafu::whatever!("Failed to get the member of {}", ctx.author().name);
+    };
+    let allowed = ctx
+        .guild()
+        .is_some_and(|guild| outranks(&guild, &author, &target));
+    if !allowed {
+        ctx.say("❌ **错误**\n\n你的最高身份组必须高于对方的最高身份组。")
+            .await?;
+    }
+    Ok(allowed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_outranks() {
+        let mut guild = Guild::default();
+        guild.owner_id = UserId::new(1);
+        for (id, position) in [(10, 1), (20, 5)] {
+            let mut role = Role::default();
+            role.id = RoleId::new(id);
+            role.position = position;
+            guild.roles.insert(role.id, role);
+        }
+        let member = |id, roles: &[u64]| {
+            let mut member = Member::default();
+            member.user.id = UserId::new(id);
+            member.roles = roles.iter().map(|r| RoleId::new(*r)).collect();
+            member
+        };
+        let (owner, moderator, helper, nobody) = (
+            member(1, &[]),
+            member(2, &[10, 20]),
+            member(3, &[10]),
+            member(4, &[]),
+        );
+        assert!(outranks(&guild, &moderator, &helper));
+        assert!(outranks(&guild, &helper, &nobody));
+        assert!(!outranks(&guild, &helper, &moderator));
+        assert!(!outranks(&guild, &helper, &member(5, &[10])));
+        assert!(!outranks(&guild, &moderator, &owner));
+        assert!(outranks(&guild, &owner, &moderator));
+    }
+}
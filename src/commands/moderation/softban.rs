@@ -0,0 +1,89 @@
+use poise::command;
+use serenity::all::*;
+
+use super::{super::Context, check_hierarchy};
+use crate::{
+    config::PermissionLevel,
+    error::BotError,
+    handlers::expect_softban,
+    utils::alert::{Severity, guild_log},
+};
+
+#[command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "BAN_MEMBERS",
+    required_bot_permissions = "BAN_MEMBERS",
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "软封禁"),
+    description_localized("zh-CN", "封禁后立即解封, 以清除用户最近的消息"),
+    ephemeral
+)]
+/// Bans and immediately unbans a user to purge their recent messages.
+pub async fn softban(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "用户")]
+    #[description_localized("zh-CN", "要软封禁的用户")]
+    #[description = "User to softban"]
+    user: User,
+    #[name_localized("zh-CN", "原因")]
+    #[description_localized("zh-CN", "记录在审计日志中的原因")]
+    #[description = "Reason recorded in the audit log"]
+    reason: Option<String>,
+    #[name_localized("zh-CN", "天数")]
+    #[description_localized("zh-CN", "清除最近几天的消息, 默认为 1")]
+    #[description = "Days of messages to purge, defaults to 1"]
+    #[min = 1]
+    #[max = 7]
+    days: Option<u8>,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    if !check_hierarchy(ctx, user.id).await? {
+        return Ok(());
+    }
+    let reason = reason.unwrap_or_else(|| "无".into());
+    let audit = format!("软封禁, 由 {} 执行: {reason}", ctx.author().name);
+    // Not a ban to copy to the rest of a ban sync group
+    expect_softban(guild_id, user.id);
+    guild_id
+        .ban_with_reason(ctx, user.id, days.unwrap_or(1), &audit)
+        .await?;
+    if let Err(why) = guild_id.unban(ctx, user.id).await {
+        ctx.say(format!(
+            "❌ **错误**\n\n已封禁 {} 并清除其消息, 但解封失败: {why}\n请手动解封。",
+            user.mention()
+        ))
+        .await?;
+        return guild_log(
+            ctx.serenity_context(),
+            guild_id,
+            Severity::Warning,
+            "软封禁: 解封失败",
+            format!(
+                "{} 软封禁了 {} ({}), 但解封失败, 需要手动解封: {why}\n原因: {reason}",
+                ctx.author().mention(),
+                user.mention(),
+                user.id
+            ),
+        )
+        .await;
+    }
+    ctx.say(format!(
+        "✅ **成功**\n\n已软封禁 {} 并清除其消息。",
+        user.mention()
+    ))
+    .await?;
+    guild_log(
+        ctx.serenity_context(),
+        guild_id,
+        Severity::Warning,
+        "软封禁",
+        format!(
+            "{} 软封禁了 {} ({})\n原因: {reason}",
+            ctx.author().mention(),
+            user.mention(),
+            user.id
+        ),
+    )
+    .await
+}
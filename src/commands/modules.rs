@@ -0,0 +1,76 @@
+use poise::{ChoiceParameter, command};
+
+use super::Context;
+use crate::{
+    config::PermissionLevel,
+    error::BotError,
+    handlers::{MODULES, Module},
+};
+
+#[command(
+    slash_command,
+    subcommands("module_disable", "module_enable"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Owner",
+    name_localized("zh-CN", "模块"),
+    description_localized("zh-CN", "在运行时停用或启用整个模块"),
+    ephemeral
+)]
+/// Switches whole modules off and on at runtime.
+pub async fn module(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "disable",
+    custom_data = "PermissionLevel::Owner",
+    name_localized("zh-CN", "停用"),
+    description_localized("zh-CN", "停用模块, 中止其后台任务并清空其状态, 直到重新启用或重启"),
+    ephemeral
+)]
+/// Disables a module, aborting its jobs and dropping its state until re-enabled or restarted.
+pub async fn module_disable(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "模块")]
+    #[description_localized("zh-CN", "要停用的模块")]
+    #[description = "The module to disable"]
+    module: Module,
+) -> Result<(), BotError> {
+    let name = module.localized_name("zh-CN").unwrap_or(module.name());
+    if MODULES.disable(module) {
+        ctx.say(format!("✅ **成功**\n\n模块 **{name}** 已停用。"))
+            .await?;
+    } else {
+        ctx.say(format!("❌ **错误**\n\n模块 **{name}** 已经停用。"))
+            .await?;
+    }
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "enable",
+    custom_data = "PermissionLevel::Owner",
+    name_localized("zh-CN", "启用"),
+    description_localized("zh-CN", "重新启用模块并重启其后台任务"),
+    ephemeral
+)]
+/// Re-enables a module, restarting its jobs.
+pub async fn module_enable(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "模块")]
+    #[description_localized("zh-CN", "要启用的模块")]
+    #[description = "The module to enable"]
+    module: Module,
+) -> Result<(), BotError> {
+    let name = module.localized_name("zh-CN").unwrap_or(module.name());
+    if MODULES.enable(module).await {
+        ctx.say(format!("✅ **成功**\n\n模块 **{name}** 已启用。"))
+            .await?;
+    } else {
+        ctx.say(format!("❌ **错误**\n\n模块 **{name}** 未被停用。"))
+            .await?;
+    }
+    Ok(())
+}
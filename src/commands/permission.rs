@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use poise::CreateReply;
+use serenity::all::{ChannelId, Mentionable, Permissions};
+
+use super::Context;
+use crate::{config::PermissionLevel, error::BotError};
+
+/// Why a command may not run here or for this user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckFailure {
+    /// Below the bot permission level the command requires
+    Level(PermissionLevel),
+    /// Lacking every role allowed to use the command
+    MissingRole,
+    /// Discord permissions the user lacks, `None` if they could not be fetched
+    UserPermissions(Option<Permissions>),
+    /// Discord permissions the bot lacks
+    BotPermissions(Permissions),
+    /// Not allowed in this channel, with an allowed one to point to
+    WrongChannel(Option<ChannelId>),
+    Cooldown(Duration),
+    /// The command's quota is used up until the Unix timestamp `reset`
+    Quota {
+        limit: u64,
+        reset: i64,
+    },
+    OwnerOnly,
+    GuildOnly,
+}
+
+impl CheckFailure {
+    fn message(self, zh: bool) -> String {
+        match (self, zh) {
+            (Self::Level(level), true) => format!("此命令需要{level}权限。"),
+            (Self::Level(level), false) => format!("This command requires the {level:?} level."),
+            (Self::MissingRole, true) => "你没有使用此命令所需的身份组。".to_string(),
+            (Self::MissingRole, false) => "You lack the roles this command requires.".to_string(),
+            (Self::UserPermissions(Some(missing)), true) => {
+                format!("你缺少以下权限: {missing}。")
+            }
+            (Self::UserPermissions(Some(missing)), false) => {
+                format!("You are missing these permissions: {missing}.")
+            }
+            (Self::UserPermissions(None), true) => {
+                "无法确认你的权限, 为安全起见未执行。".to_string()
+            }
+            (Self::UserPermissions(None), false) => {
+                "Your permissions could not be checked, so nothing was done to be safe.".to_string()
+            }
+            (Self::BotPermissions(missing), true) => format!("机器人缺少以下权限: {missing}。"),
+            (Self::BotPermissions(missing), false) => {
+                format!("The bot is missing these permissions: {missing}.")
+            }
+            (Self::WrongChannel(Some(allowed)), true) => {
+                format!("此命令不能在这里使用, 请前往 {}。", allowed.mention())
+            }
+            (Self::WrongChannel(Some(allowed)), false) => format!(
+                "This command cannot be used here, please head to {}.",
+                allowed.mention()
+            ),
+            (Self::WrongChannel(None), true) => "此命令不能在这里使用。".to_string(),
+            (Self::WrongChannel(None), false) => "This command cannot be used here.".to_string(),
+            (Self::Cooldown(remaining), true) => {
+                format!(
+                    "操作过于频繁, 请在 {} 秒后重试。",
+                    remaining.as_secs().max(1)
+                )
+            }
+            (Self::Cooldown(remaining), false) => format!(
+                "You're too fast, please retry in {} seconds.",
+                remaining.as_secs().max(1)
+            ),
+            (Self::Quota { limit, reset }, true) => {
+                format!("此命令的使用次数已达上限 ({limit} 次), 将于 <t:{reset}:R> 重置。")
+            }
+            (Self::Quota { limit, reset }, false) => {
+                format!(
+                    "This command has reached its limit of {limit} uses, it resets <t:{reset}:R>."
+                )
+            }
+            (Self::OwnerOnly, true) => "此命令仅限机器人所有者使用。".to_string(),
+            (Self::OwnerOnly, false) => "Only bot owners can use this command.".to_string(),
+            (Self::GuildOnly, true) => "此命令不能在私信中使用。".to_string(),
+            (Self::GuildOnly, false) => "This command cannot be used in DMs.".to_string(),
+        }
+    }
+
+    /// Tell the invoking user, in their language, why the command did not run.
+    pub async fn reply(self, ctx: Context<'_>) -> Result<(), BotError> {
+        let zh = ctx.locale().is_none_or(|l| l.starts_with("zh"));
+        let heading = if zh {
+            "❌ **错误**"
+        } else {
+            "❌ **Error**"
+        };
+        ctx.send(
+            CreateReply::default()
+                .content(format!("{heading}\n\n{}", self.message(zh)))
+                .ephemeral(true),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// The bot permission level of the invoking user.
+pub async fn user_level(ctx: Context<'_>) -> Result<PermissionLevel, BotError> {
+    let user_id = ctx.author().id;
+    // Application owners are fetched by poise on startup, next to `extraOwners`
+    if ctx.framework().options().owners.contains(&user_id) {
+        return Ok(PermissionLevel::Owner);
+    }
+    let member = ctx.author_member().await;
+    Ok(
+        if ctx.data().cfg.load().is_admin(user_id, member.as_deref()) {
+            PermissionLevel::Admin
+        } else {
+            PermissionLevel::Everyone
+        },
+    )
+}
+
+/// The level required by the invoked command, the strictest of it and its parents. A level
+/// configured for the invoked command itself is final, so operators can open up a subcommand of
+/// a restricted parent.
+fn required_level(ctx: Context<'_>) -> PermissionLevel {
+    let cfg = ctx.data().cfg.load();
+    if let Some(level) = cfg.command_levels.get(&ctx.command().qualified_name) {
+        return *level;
+    }
+    ctx.parent_commands()
+        .iter()
+        .copied()
+        .chain([ctx.command()])
+        .map(|cmd| {
+            cfg.command_levels
+                .get(&cmd.qualified_name)
+                .or_else(|| cmd.custom_data.downcast_ref())
+                .copied()
+                .unwrap_or_default()
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// Central permission check, replying when the user lacks the required level.
+pub async fn check_permission(ctx: Context<'_>) -> Result<bool, BotError> {
+    let required = required_level(ctx);
+    if required == PermissionLevel::Everyone || user_level(ctx).await? >= required {
+        return Ok(true);
+    }
+    CheckFailure::Level(required).reply(ctx).await?;
+    Ok(false)
+}
+
+/// Enforce `commandChannels` for the invoked command or its parents, pointing the user to an
+/// allowed channel when it may not run here.
+pub async fn check_channel(ctx: Context<'_>) -> Result<bool, BotError> {
+    let cfg = ctx.data().cfg.load();
+    let channel_id = ctx.channel_id();
+    // Threads follow the rules of the channel they were created in
+    let parent_id = ctx.guild_id().and_then(|guild_id| {
+        ctx.cache()
+            .guild(guild_id)?
+            .threads
+            .iter()
+            .find(|t| t.id == channel_id)?
+            .parent_id
+    });
+    let matches = |channels: &[ChannelId]| {
+        channels.contains(&channel_id) || parent_id.is_some_and(|p| channels.contains(&p))
+    };
+    let Some(rule) = ctx
+        .parent_commands()
+        .iter()
+        .copied()
+        .chain([ctx.command()])
+        .filter_map(|cmd| cfg.command_channels.get(&cmd.qualified_name))
+        .find(|rule| matches(&rule.deny) || !(rule.allow.is_empty() || matches(&rule.allow)))
+    else {
+        return Ok(true);
+    };
+    CheckFailure::WrongChannel(rule.allow.first().copied())
+        .reply(ctx)
+        .await?;
+    Ok(false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check_failure_message() {
+        let cooldown = CheckFailure::Cooldown(Duration::from_millis(2500));
+        assert_eq!(cooldown.message(true), "操作过于频繁, 请在 2 秒后重试。");
+        assert_eq!(
+            cooldown.message(false),
+            "You're too fast, please retry in 2 seconds."
+        );
+        assert_eq!(
+            CheckFailure::Level(PermissionLevel::Admin).message(true),
+            "此命令需要管理员权限。"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::{sync::LazyLock, time::Duration};
+
+use poise::{CreateReply, command};
+use serde::Deserialize;
+use serenity::all::{
+    colours::branding::{GREEN, RED},
+    *,
+};
+use snafu::OptionExt;
+
+use super::Context;
+use crate::{config::Market, error::BotError, utils::RateLimiter};
+
+static BINANCE_LIMITER: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::new(Duration::from_millis(250)));
+static YAHOO_LIMITER: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::new(Duration::from_secs(1)));
+
+pub struct Quote {
+    pub symbol: String,
+    pub price: f64,
+    pub currency: Option<String>,
+    /// Change since the previous close or over the last 24 hours, in percent
+    pub change: Option<f64>,
+}
+
+impl Market {
+    /// Fetch the latest quote of `symbol`, `None` if the provider does not know it.
+    pub async fn quote(self, symbol: &str) -> Result<Option<Quote>, BotError> {
+        let client = reqwest::Client::new();
+        let symbol = symbol.to_uppercase();
+        match self {
+            Self::Crypto => {
+                #[derive(Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct Ticker {
+                    symbol: String,
+                    last_price: String,
+                    price_change_percent: String,
+                }
+
+                BINANCE_LIMITER.acquire().await;
+                let res = client
+                    .get("https://api.binance.com/api/v3/ticker/24hr")
+                    .query(&[("symbol", &symbol)])
+                    .send()
+                    .await?;
+                if res.status() == reqwest::StatusCode::BAD_REQUEST {
+                    return Ok(None); // Binance answers unknown symbols with 400
+                }
+                let ticker = res.error_for_status()?.json::<Ticker>().await?;
+                // A made up price of 0 would cross every threshold below it
+                let Ok(price) = ticker.last_price.parse() else {
+                    snafu::whatever!("Binance returned an invalid price {:?}", ticker.last_price);
+                };
+                Ok(Some(Quote {
+                    price,
+                    change: ticker.price_change_percent.parse().ok(),
+                    currency: ["USDT", "USDC", "BTC", "ETH"]
+                        .into_iter()
+                        .find(|q| ticker.symbol.ends_with(q))
+                        .map(str::to_string),
+                    symbol: ticker.symbol,
+                }))
+            }
+            Self::Stock => {
+                #[derive(Deserialize)]
+                struct Response {
+                    chart: Chart,
+                }
+                #[derive(Deserialize)]
+                struct Chart {
+                    result: Option<Vec<ChartResult>>,
+                }
+                #[derive(Deserialize)]
+                struct ChartResult {
+                    meta: Meta,
+                }
+                #[derive(Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct Meta {
+                    symbol: String,
+                    currency: Option<String>,
+                    regular_market_price: f64,
+                    chart_previous_close: Option<f64>,
+                }
+
+                YAHOO_LIMITER.acquire().await;
+                let res = client
+                    .get(format!(
+                        "https://query1.finance.yahoo.com/v8/finance/chart/{symbol}"
+                    ))
+                    .query(&[("range", "1d"), ("interval", "1d")])
+                    // Yahoo rejects requests without a browser-like user agent
+                    .header(reqwest::header::USER_AGENT, "Mozilla/5.0")
+                    .send()
+                    .await?;
+                if res.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+                let res = res.error_for_status()?.json::<Response>().await?;
+                let meta = res
+                    .chart
+                    .result
+                    .and_then(|r| r.into_iter().next())
+                    .whatever_context::<&str, BotError>("Yahoo Finance returned no result")?
+                    .meta;
+                Ok(Some(Quote {
+                    change: meta
+                        .chart_previous_close
+                        .map(|prev| (meta.regular_market_price - prev) / prev * 100.0),
+                    price: meta.regular_market_price,
+                    currency: meta.currency,
+                    symbol: meta.symbol,
+                }))
+            }
+        }
+    }
+}
+
+pub fn format_price(quote: &Quote) -> String {
+    match &quote.currency {
+        Some(currency) => format!("{} {}", quote.price, currency),
+        None => quote.price.to_string(),
+    }
+}
+
+#[command(
+    slash_command,
+    user_cooldown = 5,
+    name_localized("zh-CN", "价格"),
+    description_localized("zh-CN", "查询加密货币或股票的最新价格")
+)]
+/// Looks up the latest price of a crypto pair or stock.
+pub async fn price(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "代码")]
+    #[description_localized("zh-CN", "交易代码, 例如 BTCUSDT 或 AAPL")]
+    #[description = "Ticker symbol, e.g. BTCUSDT or AAPL"]
+    symbol: String,
+    #[name_localized("zh-CN", "市场")]
+    #[description_localized("zh-CN", "市场类型, 默认为加密货币")]
+    #[description = "Market, crypto by default"]
+    market: Option<Market>,
+) -> Result<(), BotError> {
+    ctx.defer().await?;
+    let Some(quote) = market.unwrap_or(Market::Crypto).quote(&symbol).await? else {
+        ctx.say(
+            MessageBuilder::new()
+                .push("❌ **错误**\n\n找不到交易代码 ")
+                .push_bold_safe(&symbol)
+                .build(),
+        )
+        .await?;
+        return Ok(());
+    };
+    let mut embed = CreateEmbed::new()
+        .title(format!("💹 {}", quote.symbol))
+        .field("价格", format_price(&quote), true)
+        .timestamp(Timestamp::now());
+    if let Some(change) = quote.change {
+        embed = embed
+            .field("涨跌幅", format!("{change:+.2}%"), true)
+            .color(if change >= 0.0 { GREEN } else { RED });
+    }
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
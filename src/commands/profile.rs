@@ -0,0 +1,99 @@
+use std::sync::LazyLock;
+
+use dashmap::DashMap;
+use poise::{CreateReply, command};
+use serenity::all::{colours::branding::BLURPLE, *};
+
+use super::{Context, faq, ratelimits, uptime};
+use crate::{
+    config::PermissionLevel,
+    error::BotError,
+    utils::{chart, format},
+};
+
+/// Length of a collection owned by a handler
+type Len = Box<dyn Fn() -> usize + Send + Sync>;
+
+/// Maps and queues shown by `/profile memory`
+static TRACKED: LazyLock<DashMap<&'static str, Len>> = LazyLock::new(DashMap::new);
+/// Internal collections listed, largest first
+const MAX_COLLECTIONS: usize = 15;
+
+/// Show the length of a collection owned by a handler in `/profile memory`.
+pub fn track(name: &'static str, len: impl Fn() -> usize + Send + Sync + 'static) {
+    TRACKED.insert(name, Box::new(len));
+}
+
+/// Entries of the bot's collections that can grow over time, largest first
+fn collections(ctx: Context<'_>) -> Vec<(&'static str, usize)> {
+    let cache = ctx.cache();
+    let mut collections = vec![
+        ("缓存: 用户", cache.user_count()),
+        ("缓存: 服务器", cache.guild_count()),
+        ("缓存: 频道", cache.guild_channel_count()),
+        ("图表缓存", chart::CACHE.len()),
+        ("常见问题索引", faq::INDEX.iter().map(|e| e.len()).sum()),
+        ("可用性历史", uptime::HISTORY.iter().map(|h| h.len()).sum()),
+        ("速率限制记录", ratelimits::HITS.lock().unwrap().len()),
+    ];
+    collections.extend(TRACKED.iter().map(|entry| (*entry.key(), entry.value()())));
+    collections.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    collections.truncate(MAX_COLLECTIONS);
+    collections
+}
+
+#[command(
+    slash_command,
+    subcommands("profile_memory"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Owner",
+    name_localized("zh-CN", "性能分析"),
+    description_localized("zh-CN", "查看机器人运行时的资源占用")
+)]
+/// Inspects the bot's resource usage, for hunting leaks in long-running deployments.
+pub async fn profile(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "memory",
+    name_localized("zh-CN", "内存"),
+    description_localized("zh-CN", "查看分配器统计与最大的内部集合"),
+    ephemeral
+)]
+/// Shows allocator statistics and the largest internal collections.
+async fn profile_memory(ctx: Context<'_>) -> Result<(), BotError> {
+    use tikv_jemalloc_ctl::{epoch, stats};
+    epoch::advance()?;
+    let allocated = stats::allocated::read()? as u64;
+    let active = stats::active::read()? as u64;
+    let resident = stats::resident::read()? as u64;
+    let mapped = stats::mapped::read()? as u64;
+    let retained = stats::retained::read()? as u64;
+    let metadata = stats::metadata::read()? as u64;
+    let collections = collections(ctx)
+        .into_iter()
+        .map(|(name, len)| format!("- {name}: {}", format::number(len as i64)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let embed = CreateEmbed::new()
+        .title("🧮 内存分析")
+        .colour(BLURPLE)
+        .field("已分配", format::bytes(allocated), true)
+        .field("活跃页", format::bytes(active), true)
+        .field("常驻", format::bytes(resident), true)
+        .field("已映射", format::bytes(mapped), true)
+        .field("保留", format::bytes(retained), true)
+        .field("元数据", format::bytes(metadata), true)
+        // Memory held in active pages but not handed out
+        .field(
+            "碎片率",
+            format::percent((active - allocated) as f64, active as f64),
+            true,
+        )
+        .field("内部集合", collections, false)
+        .timestamp(Timestamp::now());
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use chrono::Utc;
+
+use super::{Context, permission::CheckFailure};
+use crate::{config::QuotaScope, error::BotError};
+
+/// Count a use of the invoked command against its configured quota, replying and returning
+/// `false` once the quota is exhausted.
+pub async fn check_quota(ctx: Context<'_>) -> Result<bool, BotError> {
+    let name = ctx.command().qualified_name.to_owned();
+    let Some(quota) = ctx.data().cfg.load().quotas.get(&name).cloned() else {
+        return Ok(true);
+    };
+    let subject = match (quota.scope, ctx.guild_id()) {
+        (QuotaScope::Guild, Some(guild_id)) => format!("guild:{guild_id}"),
+        // Guild quotas fall back to the user in DMs
+        _ => format!("user:{}", ctx.author().id),
+    };
+    let period = quota.period.as_secs().max(1) as i64;
+    let window_start = Utc::now().timestamp() / period * period;
+    if ctx
+        .data()
+        .db
+        .quota()
+        .consume(&name, &subject, window_start, quota.limit)
+        .await?
+    {
+        return Ok(true);
+    }
+    CheckFailure::Quota {
+        limit: quota.limit,
+        reset: window_start + period,
+    }
+    .reply(ctx)
+    .await?;
+    Ok(false)
+}
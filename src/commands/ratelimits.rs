@@ -0,0 +1,182 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{LazyLock, Mutex},
+    time::{Duration, SystemTime},
+};
+
+use poise::{CreateReply, command};
+use serenity::all::{
+    colours::branding::{GREEN, YELLOW},
+    *,
+};
+
+use super::Context;
+use crate::{config::PermissionLevel, error::BotError};
+
+/// A request that had to wait for a rate limit, either ahead of time or after a 429
+#[derive(Debug, Clone)]
+pub struct RatelimitHit {
+    pub at: Timestamp,
+    /// Method and route, with IDs after the major parameter left out
+    pub route: String,
+    pub timeout: Duration,
+    pub global: bool,
+}
+
+/// Hits kept for `/ratelimits`
+const HITS_LEN: usize = 500;
+/// Hits older than this are left out of `/ratelimits`
+const HITS_WINDOW: Duration = Duration::from_secs(3600);
+
+pub(crate) static HITS: LazyLock<Mutex<VecDeque<RatelimitHit>>> = LazyLock::new(Mutex::default);
+
+/// Store a rate limit reported by serenity's ratelimiter.
+pub fn record_ratelimit(info: &RatelimitInfo) {
+    let mut hits = HITS.lock().unwrap();
+    if hits.len() == HITS_LEN {
+        hits.pop_front();
+    }
+    hits.push_back(RatelimitHit {
+        at: Timestamp::now(),
+        route: format!(
+            "{} /{}",
+            format!("{:?}", info.method).to_uppercase(),
+            route(&info.path)
+        ),
+        timeout: info.timeout,
+        global: info.global,
+    });
+}
+
+/// A route path with every ID but the first, which Discord limits by, replaced
+fn route(path: &str) -> String {
+    let mut major = true;
+    path.split('/')
+        .map(|segment| {
+            let id = !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit());
+            if id && !std::mem::take(&mut major) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Hits per route as `(route, hits, longest wait)`, most hit first
+fn by_route(hits: &[RatelimitHit]) -> Vec<(String, usize, Duration)> {
+    let mut routes = HashMap::<_, (usize, Duration)>::new();
+    for hit in hits {
+        let (count, longest) = routes.entry(hit.route.as_str()).or_default();
+        *count += 1;
+        *longest = (*longest).max(hit.timeout);
+    }
+    let mut routes = routes
+        .into_iter()
+        .map(|(route, (count, longest))| (route.to_owned(), count, longest))
+        .collect::<Vec<_>>();
+    routes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    routes
+}
+
+#[command(
+    slash_command,
+    custom_data = "PermissionLevel::Owner",
+    name_localized("zh-CN", "速率限制"),
+    description_localized("zh-CN", "查看 Discord 速率限制桶与最近的限流情况"),
+    ephemeral
+)]
+/// Shows Discord rate limit buckets and the requests held back by them recently.
+pub async fn ratelimits(ctx: Context<'_>) -> Result<(), BotError> {
+    let mut buckets = vec![];
+    if let Some(ratelimiter) = &ctx.serenity_context().http.ratelimiter {
+        for bucket in ratelimiter.routes().read().await.values() {
+            let bucket = bucket.lock().await;
+            buckets.push((bucket.remaining(), bucket.limit(), bucket.reset()));
+        }
+    }
+    let now = SystemTime::now();
+    // Buckets whose window is over start afresh on the next request
+    buckets.retain(|(_, _, reset)| reset.is_some_and(|r| r > now));
+    buckets.sort_by_key(|(remaining, limit, _)| (*remaining, *limit));
+    let exhausted = buckets.iter().filter(|(r, _, _)| *r == 0).count();
+    let mut lines = vec![format!(
+        "{} 个活跃桶, 其中 {exhausted} 个已用尽",
+        buckets.len()
+    )];
+    lines.extend(buckets.iter().take(5).map(|(remaining, limit, reset)| {
+        let reset = reset.map_or(0, |r| {
+            r.duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        });
+        format!("剩余 {remaining}/{limit} · <t:{reset}:R> 重置")
+    }));
+
+    let since = Timestamp::now().unix_timestamp() - HITS_WINDOW.as_secs() as i64;
+    let hits = HITS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|hit| hit.at.unix_timestamp() >= since)
+        .cloned()
+        .collect::<Vec<_>>();
+    let global = hits.iter().filter(|hit| hit.global).count();
+    let routes = by_route(&hits);
+    let mut recent = vec![format!("共 {} 次, 其中全局限流 {global} 次", hits.len())];
+    recent.extend(routes.iter().take(10).map(|(route, count, longest)| {
+        format!(
+            "`{route}` {count} 次 · 最长等待 {:.1}s",
+            longest.as_secs_f64()
+        )
+    }));
+
+    let embed = CreateEmbed::new()
+        .title("🚦 速率限制")
+        .field("当前桶", lines.join("\n"), false)
+        .field("最近一小时", recent.join("\n"), false)
+        .color(if hits.is_empty() { GREEN } else { YELLOW })
+        .timestamp(Timestamp::now());
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_by_route() {
+        assert_eq!(
+            route("channels/123/messages/456"),
+            "channels/123/messages/{id}"
+        );
+        let hit = |route: &str, secs| RatelimitHit {
+            at: Timestamp::now(),
+            route: route.to_owned(),
+            timeout: Duration::from_secs(secs),
+            global: false,
+        };
+        let hits = [
+            hit("DELETE /channels/1/messages/{id}", 1),
+            hit("GET /channels/1/messages", 2),
+            hit("DELETE /channels/1/messages/{id}", 3),
+        ];
+        assert_eq!(
+            by_route(&hits),
+            [
+                (
+                    "DELETE /channels/1/messages/{id}".to_owned(),
+                    2,
+                    Duration::from_secs(3)
+                ),
+                (
+                    "GET /channels/1/messages".to_owned(),
+                    1,
+                    Duration::from_secs(2)
+                ),
+            ]
+        );
+    }
+}
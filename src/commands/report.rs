@@ -0,0 +1,106 @@
+use poise::{CreateReply, Modal, command};
+use serenity::all::{colours::css::WARNING, *};
+
+use super::Context;
+use crate::error::BotError;
+
+/// Custom ID prefixes of the moderator buttons, followed by the report ID
+pub const DELETE_PREFIX: &str = "report:delete:";
+pub const WARN_PREFIX: &str = "report:warn:";
+pub const DISMISS_PREFIX: &str = "report:dismiss:";
+
+#[derive(Debug, Modal)]
+#[name = "举报消息"]
+struct ReportModal {
+    #[name = "举报理由"]
+    #[placeholder = "请说明该消息违反了哪条规则"]
+    #[paragraph]
+    #[max_length = 500]
+    reason: String,
+}
+
+#[command(
+    context_menu_command = "Report message",
+    guild_only,
+    name_localized("zh-CN", "举报消息"),
+    ephemeral
+)]
+/// Reports a message to the moderators anonymously.
+pub async fn report_message(ctx: Context<'_>, message: Message) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let report_channel_id = ctx
+        .data()
+        .cfg
+        .load()
+        .guilds
+        .get(&guild_id)
+        .and_then(|g| g.report_channel_id);
+    let reply = |content: &str| CreateReply::default().content(content).ephemeral(true);
+    let Some(report_channel_id) = report_channel_id else {
+        ctx.send(reply("❌ **错误**\n\n本服务器未启用消息举报。"))
+            .await?;
+        return Ok(());
+    };
+    if message.author.id == ctx.author().id || message.author.bot {
+        ctx.send(reply("❌ **错误**\n\n不能举报自己或机器人的消息。"))
+            .await?;
+        return Ok(());
+    }
+    let Context::Application(app_ctx) = ctx else {
+        unreachable!("report_message is a context menu command");
+    };
+    let Some(ReportModal { reason }) = ReportModal::execute(app_ctx).await? else {
+        return Ok(());
+    };
+    let Some(id) = ctx
+        .data()
+        .db
+        .reports()
+        .create(guild_id, &message, ctx.author().id, &reason)
+        .await?
+    else {
+        ctx.send(reply("❌ **错误**\n\n你已经举报过这条消息了。"))
+            .await?;
+        return Ok(());
+    };
+    let content = if message.content.is_empty() {
+        "*(无文字内容)*".to_string()
+    } else {
+        message.content.chars().take(1024).collect()
+    };
+    // The reporter is deliberately left out so moderators judge the message on its own
+    let embed = CreateEmbed::new()
+        .title(format!("🚩 消息举报 #{id}"))
+        .field("消息作者", message.author.mention().to_string(), true)
+        .field("频道", message.channel_id.mention().to_string(), true)
+        .field("消息", message.link(), false)
+        .field("内容", content, false)
+        .field("举报理由", reason, false)
+        .color(WARNING)
+        .timestamp(Timestamp::now());
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{DELETE_PREFIX}{id}"))
+            .label("删除消息")
+            .style(ButtonStyle::Danger),
+        CreateButton::new(format!("{WARN_PREFIX}{id}"))
+            .label("警告作者")
+            .style(ButtonStyle::Primary),
+        CreateButton::new(format!("{DISMISS_PREFIX}{id}"))
+            .label("驳回")
+            .style(ButtonStyle::Secondary),
+    ]);
+    report_channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new()
+                .embed(embed)
+                .components(vec![buttons])
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+    ctx.send(reply(
+        "✅ **成功**\n\n举报已匿名提交给管理员, 感谢你的反馈。",
+    ))
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use poise::{CreateReply, Modal, command};
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    colours::branding::{GREEN, RED},
+    *,
+};
+use snafu::ResultExt;
+use tracing::warn;
+
+use super::{Context, check_admin, permission::CheckFailure};
+use crate::{error::BotError, utils::paste};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const RUN_TIMEOUT_MS: u64 = 5000;
+const COMPILE_TIMEOUT_MS: u64 = 10000;
+/// Size of a single output field, leaving room for the code fence
+const MAX_OUTPUT: usize = 1000;
+const LANGUAGES: &[&str] = &[
+    "bash",
+    "c",
+    "c++",
+    "csharp",
+    "go",
+    "java",
+    "javascript",
+    "kotlin",
+    "lua",
+    "python",
+    "ruby",
+    "rust",
+    "typescript",
+];
+
+#[derive(Debug, Modal)]
+#[name = "运行代码"]
+struct CodeModal {
+    #[name = "代码"]
+    #[placeholder = "要运行的代码"]
+    #[paragraph]
+    code: String,
+}
+
+#[derive(Serialize)]
+struct ExecuteRequest<'a> {
+    language: &'a str,
+    version: &'a str,
+    files: [File<'a>; 1],
+    run_timeout: u64,
+    compile_timeout: u64,
+}
+
+#[derive(Serialize)]
+struct File<'a> {
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ExecuteResponse {
+    language: String,
+    version: String,
+    run: Stage,
+    compile: Option<Stage>,
+}
+
+#[derive(Deserialize)]
+struct Stage {
+    stdout: String,
+    stderr: String,
+    code: Option<i32>,
+    signal: Option<String>,
+}
+
+/// Bot admins and roles from `trustedRoleIds` may run code, owners skip the check entirely.
+pub async fn check_trusted(ctx: Context<'_>) -> Result<bool, BotError> {
+    if check_admin(ctx).await? {
+        return Ok(true);
+    }
+    let trusted = ctx.data().cfg.load().trusted_role_ids.to_owned();
+    if ctx
+        .author_member()
+        .await
+        .is_some_and(|m| m.roles.iter().any(|r| trusted.contains(r)))
+    {
+        return Ok(true);
+    }
+    CheckFailure::MissingRole.reply(ctx).await?;
+    Ok(false)
+}
+
+async fn language_choices<'a>(
+    _ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Iterator<Item = AutocompleteChoice> + 'a {
+    LANGUAGES
+        .iter()
+        .filter(move |l| l.starts_with(&partial.to_lowercase()))
+        .map(|l| AutocompleteChoice::new(*l, *l))
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_owned();
+    }
+    let mut s = s.chars().take(max).collect::<String>();
+    s.push_str("\n…(已截断)");
+    s
+}
+
+fn code_block(s: &str) -> String {
+    let s = s.replace("```", "`\u{200b}``");
+    format!("```\n{}\n```", truncate(&s, MAX_OUTPUT))
+}
+
+#[command(
+    slash_command,
+    check = "check_trusted",
+    user_cooldown = 10,
+    name_localized("zh-CN", "运行"),
+    description_localized("zh-CN", "在沙箱中运行一段代码")
+)]
+/// Runs a code snippet in the configured sandbox.
+pub async fn run(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "语言")]
+    #[description_localized("zh-CN", "编程语言")]
+    #[description = "Programming language"]
+    #[autocomplete = "language_choices"]
+    language: String,
+    #[name_localized("zh-CN", "代码")]
+    #[description_localized("zh-CN", "要运行的代码, 留空则弹出多行输入框")]
+    #[description = "The code to run, leave empty to open a multi-line editor"]
+    code: Option<String>,
+) -> Result<(), BotError> {
+    let endpoint = ctx.data().cfg.load().sandbox_endpoint.to_owned();
+    let Some(endpoint) = endpoint else {
+        ctx.say("❌ **错误**\n\n沙箱服务未配置。").await?;
+        return Ok(());
+    };
+    let code = match code {
+        Some(code) => code,
+        None => {
+            let Context::Application(app_ctx) = ctx else {
+                unreachable!("run is a slash command");
+            };
+            let Some(modal) = CodeModal::execute(app_ctx).await? else {
+                return Ok(()); // The user dismissed the modal
+            };
+            modal.code
+        }
+    };
+    ctx.defer().await?;
+
+    let res = reqwest::Client::new()
+        .post(
+            endpoint
+                .join("execute")
+                .whatever_context::<&str, BotError>("Failed to construct sandbox URL")?,
+        )
+        .timeout(REQUEST_TIMEOUT)
+        .json(&ExecuteRequest {
+            language: &language,
+            version: "*",
+            files: [File { content: &code }],
+            run_timeout: RUN_TIMEOUT_MS,
+            compile_timeout: COMPILE_TIMEOUT_MS,
+        })
+        .send()
+        .await?;
+    if res.status().is_client_error() {
+        // Piston rejects unknown languages with a message body
+        let message = res.text().await.unwrap_or_default();
+        ctx.say(
+            MessageBuilder::new()
+                .push("❌ **错误**\n\n沙箱拒绝了请求: ")
+                .push_mono_safe(truncate(&message, 200))
+                .build(),
+        )
+        .await?;
+        return Ok(());
+    }
+    let res = res.error_for_status()?.json::<ExecuteResponse>().await?;
+
+    let failed_stage = res
+        .compile
+        .as_ref()
+        .filter(|c| c.code.is_some_and(|c| c != 0))
+        .map(|c| ("编译", c));
+    let (stage_name, stage) = failed_stage.unwrap_or(("运行", &res.run));
+    let status = match (&stage.signal, stage.code) {
+        (Some(signal), _) => format!("被信号 {signal} 终止"),
+        (None, Some(code)) => format!("退出码 {code}"),
+        (None, None) => "未知".to_string(),
+    };
+    let success = failed_stage.is_none() && stage.code == Some(0);
+    let mut embed = CreateEmbed::new()
+        .title(format!(
+            "{} {} {}",
+            if success { "✅" } else { "❌" },
+            res.language,
+            res.version
+        ))
+        .field(format!("{stage_name}状态"), status, false)
+        .color(if success { GREEN } else { RED });
+    if !stage.stdout.is_empty() {
+        embed = embed.field("stdout", code_block(&stage.stdout), false);
+    }
+    if !stage.stderr.is_empty() {
+        embed = embed.field("stderr", code_block(&stage.stderr), false);
+    }
+    if stage.stdout.is_empty() && stage.stderr.is_empty() {
+        embed = embed.description("(无输出)");
+    }
+    let truncated = [&stage.stdout, &stage.stderr]
+        .iter()
+        .any(|s| s.chars().count() > MAX_OUTPUT);
+    let paste_endpoint = ctx.data().cfg.load().paste_endpoint.to_owned();
+    if truncated && let Some(endpoint) = paste_endpoint {
+        let full = format!("stdout:\n{}\n\nstderr:\n{}", stage.stdout, stage.stderr);
+        match paste::upload(&endpoint, full).await {
+            Ok(url) => embed = embed.field("完整输出", url.to_string(), false),
+            Err(why) => warn!("Failed to upload sandbox output: {why}"),
+        }
+    }
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
@@ -0,0 +1,349 @@
+use std::{collections::HashMap, time::Duration};
+
+use poise::{CreateReply, Modal, ReplyHandle, command};
+use serenity::all::{colours::branding::BLURPLE, *};
+
+use super::{Context, welcome::WelcomeModal};
+use crate::{
+    config::{BotCfg, GuildCfg, PermissionLevel, TreeHoleCfg},
+    error::BotError,
+    utils::{InvalidDuration, parse_duration},
+};
+
+/// How long each step waits for the admin before the wizard gives up
+const STEP_TIMEOUT: Duration = Duration::from_secs(180);
+
+#[derive(Debug, Modal)]
+#[name = "树洞清理时间"]
+struct TreeHoleModal {
+    #[name = "清理时间"]
+    #[placeholder = "例如 30m, 12h, 3天"]
+    duration: String,
+}
+
+/// Show one wizard step and wait for the admin to pick from `menu` or skip.
+async fn ask(
+    ctx: Context<'_>,
+    reply: &ReplyHandle<'_>,
+    (step, total): (usize, usize),
+    title: &str,
+    description: &str,
+    menu: CreateSelectMenuKind,
+    max: u8,
+) -> Result<Option<ComponentInteraction>, BotError> {
+    let select_id = format!("{}_setup_select", ctx.id());
+    let skip_id = format!("{}_setup_skip", ctx.id());
+    let embed = CreateEmbed::new()
+        .title(format!("⚙️ 设置向导 ({step}/{total}) - {title}"))
+        .description(description)
+        .color(BLURPLE);
+    let components = vec![
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(&select_id, menu)
+                .min_values(1)
+                .max_values(max),
+        ),
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(&skip_id)
+                .label("跳过")
+                .style(ButtonStyle::Secondary),
+        ]),
+    ];
+    reply
+        .edit(
+            ctx,
+            CreateReply::default().embed(embed).components(components),
+        )
+        .await?;
+    Ok(ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .custom_ids(vec![select_id, skip_id])
+        .timeout(STEP_TIMEOUT)
+        .await)
+}
+
+fn selected_channels(interaction: &ComponentInteraction) -> Vec<ChannelId> {
+    match &interaction.data.kind {
+        ComponentInteractionDataKind::ChannelSelect { values } => values.to_owned(),
+        _ => vec![],
+    }
+}
+
+async fn acknowledge(ctx: Context<'_>, interaction: &ComponentInteraction) -> Result<(), BotError> {
+    interaction
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "设置"),
+    description_localized("zh-CN", "通过向导设置本服务器的日志频道、树洞、管理角色和欢迎消息"),
+    ephemeral
+)]
+/// Walks through setting up logging, tree holes, admin roles and welcome messages.
+pub async fn setup(ctx: Context<'_>) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().expect("setup is guild only");
+    let mut guild = ctx
+        .data()
+        .cfg
+        .load()
+        .guilds
+        .get(&guild_id)
+        .cloned()
+        .unwrap_or_default();
+    let mut tree_holes = HashMap::new();
+    let text_channels = || CreateSelectMenuKind::Channel {
+        channel_types: Some(vec![ChannelType::Text]),
+        default_channels: None,
+    };
+    let reply = ctx
+        .send(CreateReply::default().embed(CreateEmbed::new().title("⚙️ 设置向导").color(BLURPLE)))
+        .await?;
+    // Replace the wizard with a final message, dropping its embed and components
+    let finish = async |content: &str| -> Result<(), BotError> {
+        reply
+            .edit(
+                ctx,
+                CreateReply::default().content(content).components(vec![]),
+            )
+            .await?;
+        Ok(())
+    };
+    const TIMED_OUT: &str = "⌛ 设置向导已超时, 未保存任何更改。";
+
+    // 1. Log channel
+    let Some(interaction) = ask(
+        ctx,
+        &reply,
+        (1, 4),
+        "日志频道",
+        "选择接收本服务器机器人日志的频道。",
+        text_channels(),
+        1,
+    )
+    .await?
+    else {
+        return finish(TIMED_OUT).await;
+    };
+    acknowledge(ctx, &interaction).await?;
+    if let Some(&channel_id) = selected_channels(&interaction).first() {
+        guild.log_channel_id = Some(channel_id);
+    }
+
+    // 2. Tree holes, with their cleanup time asked through a modal
+    let Some(interaction) = ask(
+        ctx,
+        &reply,
+        (2, 4),
+        "树洞频道",
+        "选择要自动清理消息的树洞频道, 之后会询问清理时间。",
+        CreateSelectMenuKind::Channel {
+            channel_types: Some(vec![
+                ChannelType::Text,
+                ChannelType::News,
+                ChannelType::Forum,
+            ]),
+            default_channels: None,
+        },
+        10,
+    )
+    .await?
+    else {
+        return finish(TIMED_OUT).await;
+    };
+    let channels = selected_channels(&interaction);
+    if channels.is_empty() {
+        acknowledge(ctx, &interaction).await?;
+    } else {
+        let modal = poise::execute_modal_on_component_interaction::<TreeHoleModal>(
+            ctx,
+            interaction,
+            None,
+            Some(STEP_TIMEOUT),
+        )
+        .await?;
+        // The same rules as `/register-tree-hole`, as the config is not validated again until
+        // the next load
+        let Some(duration) = modal.and_then(|m| parse_duration(&m.duration)) else {
+            return finish(&format!(
+                "❌ **错误**\n\n{InvalidDuration}, 设置向导已取消。"
+            ))
+            .await;
+        };
+        let duration = duration.to_std().expect("Parsed durations are positive");
+        tree_holes.extend(channels.into_iter().map(|c| {
+            let hole = TreeHoleCfg {
+                duration,
+                ..Default::default()
+            };
+            (c, hole)
+        }));
+    }
+
+    // 3. Admin roles
+    let Some(interaction) = ask(
+        ctx,
+        &reply,
+        (3, 4),
+        "管理角色",
+        "选择可以使用机器人管理命令的角色。",
+        CreateSelectMenuKind::Role {
+            default_roles: Some(guild.admin_role_ids.to_owned()).filter(|r| !r.is_empty()),
+        },
+        10,
+    )
+    .await?
+    else {
+        return finish(TIMED_OUT).await;
+    };
+    acknowledge(ctx, &interaction).await?;
+    if let ComponentInteractionDataKind::RoleSelect { values } = &interaction.data.kind {
+        guild.admin_role_ids = values.to_owned();
+    }
+
+    // 4. Welcome channel and message
+    let Some(interaction) = ask(
+        ctx,
+        &reply,
+        (4, 4),
+        "欢迎消息",
+        "选择发送欢迎消息的频道, 之后会询问消息内容。",
+        text_channels(),
+        1,
+    )
+    .await?
+    else {
+        return finish(TIMED_OUT).await;
+    };
+    if let Some(&channel_id) = selected_channels(&interaction).first() {
+        let defaults = guild
+            .welcome_message
+            .to_owned()
+            .map(|message| WelcomeModal {
+                message: message.to_string(),
+            });
+        let modal = poise::execute_modal_on_component_interaction(
+            ctx,
+            interaction,
+            defaults,
+            Some(STEP_TIMEOUT),
+        )
+        .await?;
+        if let Some(modal) = modal {
+            match modal.template() {
+                Ok(template) => {
+                    guild.welcome_channel_id = Some(channel_id);
+                    guild.welcome_message = Some(template);
+                }
+                Err(why) => {
+                    return finish(&format!(
+                        "❌ **错误**\n\n无效的欢迎消息模板: {why}, 设置向导已取消。"
+                    ))
+                    .await;
+                }
+            }
+        }
+    } else {
+        acknowledge(ctx, &interaction).await?;
+    }
+
+    // Confirm before writing anything
+    let summary = format!(
+        "**日志频道**: {}\n**树洞频道**: {}\n**管理角色**: {}\n**欢迎频道**: {}\n**欢迎消息**: {}",
+        guild
+            .log_channel_id
+            .map_or("未设置".into(), |c| c.mention().to_string()),
+        if tree_holes.is_empty() {
+            "不变".into()
+        } else {
+            tree_holes
+                .iter()
+                .map(|(c, hole)| format!("{} ({}s)", c.mention(), hole.duration.as_secs()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        },
+        if guild.admin_role_ids.is_empty() {
+            "未设置".into()
+        } else {
+            guild
+                .admin_role_ids
+                .iter()
+                .map(|r| r.mention().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        },
+        guild
+            .welcome_channel_id
+            .map_or("未设置".into(), |c| c.mention().to_string()),
+        guild
+            .welcome_message
+            .as_ref()
+            .map_or("未设置".into(), |t| t.to_string()),
+    );
+    let save_id = format!("{}_setup_save", ctx.id());
+    let cancel_id = format!("{}_setup_cancel", ctx.id());
+    reply
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(
+                    CreateEmbed::new()
+                        .title("⚙️ 设置向导 - 确认")
+                        .description(&summary)
+                        .color(BLURPLE),
+                )
+                .components(vec![CreateActionRow::Buttons(vec![
+                    CreateButton::new(&save_id)
+                        .label("保存")
+                        .style(ButtonStyle::Success),
+                    CreateButton::new(&cancel_id)
+                        .label("取消")
+                        .style(ButtonStyle::Danger),
+                ])]),
+        )
+        .await?;
+    let Some(interaction) = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .custom_ids(vec![save_id.to_owned(), cancel_id])
+        .timeout(STEP_TIMEOUT)
+        .await
+    else {
+        return finish(TIMED_OUT).await;
+    };
+    acknowledge(ctx, &interaction).await?;
+    if interaction.data.custom_id != save_id {
+        return finish("已取消, 未保存任何更改。").await;
+    }
+
+    let log_channel_id = guild.log_channel_id;
+    guild.tree_holes.extend(tree_holes);
+    ctx.data().cfg.rcu(|cfg| {
+        let mut cfg = BotCfg::clone(cfg);
+        cfg.guilds.insert(guild_id, GuildCfg::clone(&guild));
+        cfg
+    });
+    if let Err(why) = ctx.data().cfg.load().write() {
+        finish(&format!("❌ **错误**\n\n无法更新配置文件: {why:?}")).await?;
+        return Err(why);
+    }
+    finish("✅ **成功**\n\n服务器设置已保存。").await?;
+    if let Some(channel_id) = log_channel_id {
+        let embed = CreateEmbed::new()
+            .title("⚙️ 服务器设置已更新")
+            .description(summary)
+            .footer(CreateEmbedFooter::new(format!(
+                "由 {} 更新",
+                ctx.author().name
+            )))
+            .timestamp(Timestamp::now())
+            .color(BLURPLE);
+        channel_id
+            .send_message(ctx, CreateMessage::new().embed(embed))
+            .await?;
+    }
+    Ok(())
+}
@@ -6,13 +6,18 @@ use poise::{CreateReply, command};
 use serenity::all::{colours::roles::DARK_GREEN, *};
 
 use super::{
-    super::{Context, check_admin},
+    super::{Context, defer_output, send_output, user_tz},
     guild_choices, timestamp_choices,
 };
-use crate::error::BotError;
+use crate::{config::PermissionLevel, error::BotError, utils::format};
 
 /// 获取频道活跃度统计
-#[command(slash_command, guild_only, ephemeral, check = "check_admin")]
+#[command(
+    slash_command,
+    guild_only,
+    ephemeral,
+    custom_data = "PermissionLevel::Admin"
+)]
 pub async fn channel_stats(
     ctx: Context<'_>,
     #[description = "显示前 N 个活跃频道，默认为 20"]
@@ -32,16 +37,13 @@ pub async fn channel_stats(
 ) -> Result<(), BotError> {
     let ephemeral = ephemeral.unwrap_or(true);
     let top_n = top_n.unwrap_or(20); // 默认显示前20个频道
-    if ephemeral {
-        ctx.defer_ephemeral().await?;
-    } else {
-        ctx.defer().await?;
-    }
+    defer_output(ctx, ephemeral).await?;
     let guild_id = guild
         .map(|g| g.id)
         .or_else(|| ctx.guild_id())
         .expect("Guild ID should be present in a guild context");
     let guild_name = guild_id.name(ctx).unwrap_or_else(|| guild_id.to_string());
+    let tz = user_tz(ctx).await?;
     let now = Instant::now();
     let data = ctx
         .data()
@@ -89,10 +91,10 @@ pub async fn channel_stats(
         .enumerate()
         .map(|(i, (name, count))| {
             format!(
-                "{}. {} ({:.2}%) - {}",
+                "{}. {} ({}) - {}",
                 i + 1,
-                count,
-                (count * 100) as f64 / sum_f64,
+                format::number(count as i64),
+                format::percent(count as f64, sum_f64),
                 name,
             )
         })
@@ -118,11 +120,11 @@ pub async fn channel_stats(
                 "{} - {}",
                 from.map_or_else(
                     || "不限".into(),
-                    |f| f.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+                    |f| tz.localize(&f).to_rfc3339_opts(SecondsFormat::AutoSi, true)
                 ),
                 to.map_or_else(
                     || "不限".into(),
-                    |t| t.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+                    |t| tz.localize(&t).to_rfc3339_opts(SecondsFormat::AutoSi, true)
                 )
             ),
             false,
@@ -130,7 +132,7 @@ pub async fn channel_stats(
         .description(ranking_text)
         .color(DARK_GREEN);
     let reply = CreateReply::default().embed(embed).ephemeral(ephemeral);
-    ctx.send(reply).await?;
+    send_output(ctx, reply).await?;
 
     Ok(())
 }
@@ -6,8 +6,13 @@ use serenity::all::*;
 pub use user::*;
 
 use super::Context;
-use crate::error::BotError;
-#[command(slash_command, guild_only, owners_only, ephemeral)]
+use crate::{config::PermissionLevel, error::BotError};
+#[command(
+    slash_command,
+    guild_only,
+    ephemeral,
+    custom_data = "PermissionLevel::Owner"
+)]
 /// **危险** 清除所有频道统计数据，请在确认表单中输入 "yes" 以确认。
 pub async fn nuke_channel_stats(ctx: Context<'_>, confirm: String) -> Result<(), BotError> {
     if confirm != "yes" {
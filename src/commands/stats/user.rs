@@ -6,12 +6,21 @@ use poise::{CreateReply, command};
 use serenity::all::{colours::roles::DARK_GREEN, *};
 
 use super::{
-    super::{Context, check_admin},
+    super::{Context, defer_output, send_output, user_tz},
     guild_choices, timestamp_choices,
 };
-use crate::{error::BotError, utils::get_all_children_channels};
+use crate::{
+    config::PermissionLevel,
+    error::BotError,
+    utils::{format, get_all_children_channels},
+};
 
-#[command(slash_command, guild_only, ephemeral, check = "check_admin")]
+#[command(
+    slash_command,
+    guild_only,
+    ephemeral,
+    custom_data = "PermissionLevel::Admin"
+)]
 /// 获取用户活跃度统计
 pub async fn user_stats(
     ctx: Context<'_>,
@@ -33,14 +42,11 @@ pub async fn user_stats(
 ) -> Result<(), BotError> {
     let ephemeral = ephemeral.unwrap_or(true);
     let top_n = top_n.unwrap_or(20); // 默认显示前20个用户
-    if ephemeral {
-        ctx.defer_ephemeral().await?;
-    } else {
-        ctx.defer().await?;
-    }
+    defer_output(ctx, ephemeral).await?;
     let guild = guild.unwrap_or_else(|| ctx.guild().unwrap().to_owned());
     let guild_id = guild.id;
     let guild_name = guild.name.to_owned();
+    let tz = user_tz(ctx).await?;
     let now = Instant::now();
     let channels = channel.as_ref().map(|c| {
         get_all_children_channels(&guild, c)
@@ -84,10 +90,10 @@ pub async fn user_stats(
         .enumerate()
         .map(|(i, (name, count))| {
             format!(
-                "{}. {} ({:.2}%) - {}",
+                "{}. {} ({}) - {}",
                 i + 1,
-                count,
-                (count * 100) as f64 / sum as f64,
+                format::number(count as i64),
+                format::percent(count as f64, sum as f64),
                 name,
             )
         })
@@ -118,15 +124,15 @@ pub async fn user_stats(
             "统计时间范围",
             format!(
                 "{} - {}",
-                from.map_or_else(|| "不限".into(), |f| f.to_rfc3339()),
-                to.map_or_else(|| "不限".into(), |t| t.to_rfc3339())
+                from.map_or_else(|| "不限".into(), |f| tz.localize(&f).to_rfc3339()),
+                to.map_or_else(|| "不限".into(), |t| tz.localize(&t).to_rfc3339())
             ),
             false,
         )
         .description(ranking_text)
         .color(DARK_GREEN);
     let reply = CreateReply::default().embed(embed).ephemeral(ephemeral);
-    ctx.send(reply).await?;
+    send_output(ctx, reply).await?;
 
     Ok(())
 }
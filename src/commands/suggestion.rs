@@ -0,0 +1,208 @@
+use poise::command;
+use serenity::all::{
+    colours::{
+        branding::{BLURPLE, GREEN, RED},
+        css::POSITIVE,
+    },
+    *,
+};
+
+use super::Context;
+use crate::{
+    config::PermissionLevel,
+    database::BotDatabase,
+    error::BotError,
+    repo::{
+        prefs::DmKind,
+        suggestions::{APPROVED, DENIED, IMPLEMENTED, Suggestion},
+    },
+};
+
+/// Custom ID prefixes of the voting buttons, followed by the suggestion ID
+pub const UP_PREFIX: &str = "suggestion:up:";
+pub const DOWN_PREFIX: &str = "suggestion:down:";
+
+/// The embed showing a suggestion with its status and current votes
+pub async fn suggestion_embed(
+    db: &BotDatabase,
+    suggestion: &Suggestion,
+) -> Result<CreateEmbed, BotError> {
+    let (up, down) = db.suggestions().tally(suggestion.id).await?;
+    let (status, colour) = match suggestion.status.as_str() {
+        APPROVED => ("✅ 已采纳", GREEN),
+        DENIED => ("⛔ 已拒绝", RED),
+        IMPLEMENTED => ("🚀 已实现", POSITIVE),
+        _ => ("⏳ 待审核", BLURPLE),
+    };
+    let mut embed = CreateEmbed::new()
+        .title(format!("💡 建议 #{}", suggestion.id))
+        .description(&suggestion.content)
+        .field("提出者", suggestion.author_id().mention().to_string(), true)
+        .field("状态", status, true)
+        .field("投票", format!("👍 {up} · 👎 {down}"), true)
+        .colour(colour)
+        .timestamp(Timestamp::from(suggestion.created_at));
+    if let Some(note) = &suggestion.note {
+        let handler = suggestion
+            .handler_id()
+            .map(|id| format!(" ({})", id.mention()))
+            .unwrap_or_default();
+        embed = embed.field("备注", format!("{note}{handler}"), false);
+    }
+    Ok(embed)
+}
+
+pub fn vote_buttons(id: i32) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{UP_PREFIX}{id}"))
+            .emoji('👍')
+            .style(ButtonStyle::Secondary),
+        CreateButton::new(format!("{DOWN_PREFIX}{id}"))
+            .emoji('👎')
+            .style(ButtonStyle::Secondary),
+    ])]
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    subcommands("suggestion_approve", "suggestion_deny", "suggestion_implement"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "建议"),
+    description_localized("zh-CN", "处理成员提出的建议")
+)]
+/// Settles suggestions posted in the suggestion channel.
+pub async fn suggestion(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "approve",
+    name_localized("zh-CN", "采纳"),
+    description_localized("zh-CN", "采纳一条建议"),
+    ephemeral
+)]
+/// Approves a suggestion.
+async fn suggestion_approve(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "编号")]
+    #[description_localized("zh-CN", "建议编号")]
+    #[description = "Suggestion number"]
+    id: i32,
+    #[name_localized("zh-CN", "备注")]
+    #[description_localized("zh-CN", "告知提出者的理由")]
+    #[description = "Reason shown to the author"]
+    note: Option<String>,
+) -> Result<(), BotError> {
+    decide(ctx, id, note, APPROVED, "采纳").await
+}
+
+#[command(
+    slash_command,
+    rename = "deny",
+    name_localized("zh-CN", "拒绝"),
+    description_localized("zh-CN", "拒绝一条建议"),
+    ephemeral
+)]
+/// Denies a suggestion.
+async fn suggestion_deny(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "编号")]
+    #[description_localized("zh-CN", "建议编号")]
+    #[description = "Suggestion number"]
+    id: i32,
+    #[name_localized("zh-CN", "备注")]
+    #[description_localized("zh-CN", "告知提出者的理由")]
+    #[description = "Reason shown to the author"]
+    note: Option<String>,
+) -> Result<(), BotError> {
+    decide(ctx, id, note, DENIED, "拒绝").await
+}
+
+#[command(
+    slash_command,
+    rename = "implement",
+    name_localized("zh-CN", "已实现"),
+    description_localized("zh-CN", "将一条建议标记为已实现"),
+    ephemeral
+)]
+/// Marks a suggestion as implemented.
+async fn suggestion_implement(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "编号")]
+    #[description_localized("zh-CN", "建议编号")]
+    #[description = "Suggestion number"]
+    id: i32,
+    #[name_localized("zh-CN", "备注")]
+    #[description_localized("zh-CN", "告知提出者的说明")]
+    #[description = "Note shown to the author"]
+    note: Option<String>,
+) -> Result<(), BotError> {
+    decide(ctx, id, note, IMPLEMENTED, "标记为已实现").await
+}
+
+/// Set the status of a suggestion, refresh its embed and tell the author.
+async fn decide(
+    ctx: Context<'_>,
+    id: i32,
+    note: Option<String>,
+    status: &str,
+    verb: &str,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let db = &ctx.data().db;
+    if !db
+        .suggestions()
+        .set_status(guild_id, id, status, ctx.author().id, note.as_deref())
+        .await?
+    {
+        ctx.say(format!("❌ **错误**\n\n没有编号为 `#{id}` 的建议。"))
+            .await?;
+        return Ok(());
+    }
+    let suggestion = db
+        .suggestions()
+        .get(id)
+        .await?
+        .expect("The suggestion was just updated");
+    if let Some(message_id) = suggestion.message_id() {
+        let embed = suggestion_embed(db, &suggestion).await?;
+        // Settled suggestions take no more votes
+        let components = if status == APPROVED {
+            vote_buttons(id)
+        } else {
+            vec![]
+        };
+        suggestion
+            .channel_id()
+            .edit_message(
+                ctx,
+                message_id,
+                EditMessage::new().embed(embed).components(components),
+            )
+            .await?;
+    }
+    let notice = format!(
+        "💡 你在 **{}** 提出的建议 #{id} 已被{verb}。{}\n\n> {}",
+        ctx.guild().map(|g| g.name.to_owned()).unwrap_or_default(),
+        note.map(|n| format!("\n备注: {n}")).unwrap_or_default(),
+        suggestion.content.chars().take(500).collect::<String>()
+    );
+    let author_id = suggestion.author_id();
+    let outcome = if !db.prefs().allows_dm(author_id, DmKind::Suggestions).await? {
+        ", 提出者已关闭此类通知"
+    } else if author_id
+        .direct_message(ctx, CreateMessage::new().content(notice))
+        .await
+        .is_err()
+    {
+        ", 但无法私信通知提出者"
+    } else {
+        ""
+    };
+    ctx.say(format!("✅ **成功**\n\n已{verb}建议 `#{id}`{outcome}。"))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use chrono::Utc;
+use poise::{CreateReply, command};
+use serenity::all::*;
+
+use super::{
+    Context,
+    autocomplete::{duration_choices, todo_choices},
+};
+use crate::{
+    config::PermissionLevel, database::BotDatabase, error::BotError, repo::todos::Todo,
+    utils::DurationArg,
+};
+
+/// One line of the board or `/todo list` per open task
+fn todo_line(todo: &Todo) -> String {
+    let mut line = format!("`#{}` {}", todo.id, todo.content);
+    if let Some(assignee_id) = todo.assignee_id() {
+        line += &format!(" · {}", assignee_id.mention());
+    }
+    if let Some(due_at) = todo.due_at {
+        let overdue = if due_at <= Utc::now() { "⚠️ " } else { "" };
+        line += &format!(
+            " · {overdue}{}",
+            FormattedTimestamp::new(due_at.into(), Some(FormattedTimestampStyle::RelativeTime))
+        );
+    }
+    line
+}
+
+fn board_embed(todos: &[Todo]) -> CreateEmbed {
+    let description = if todos.is_empty() {
+        "暂无待办任务 🎉".to_owned()
+    } else {
+        let mut description = String::new();
+        for line in todos.iter().map(todo_line) {
+            // Embed descriptions are capped at 4096 characters
+            if description.chars().count() + line.chars().count() > 4000 {
+                description += "\n…";
+                break;
+            }
+            description += &line;
+            description.push('\n');
+        }
+        description
+    };
+    CreateEmbed::new()
+        .title("📋 待办任务")
+        .description(description)
+        .footer(CreateEmbedFooter::new(format!("共 {} 项", todos.len())))
+        .timestamp(Timestamp::now())
+        .color(0x5865F2)
+}
+
+/// Rewrite the pinned board in the todo channel of a guild, posting and pinning a new one if it
+/// is gone.
+pub async fn refresh_board(
+    http: impl CacheHttp,
+    db: &BotDatabase,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> Result<(), BotError> {
+    let embed = board_embed(&db.todos().open(guild_id).await?);
+    let key = format!("todo_board:{guild_id}");
+    let board = db
+        .watch()
+        .get(&key)
+        .await?
+        .and_then(|id| id.parse::<u64>().ok());
+    if let Some(message_id) = board {
+        let edited = channel_id
+            .edit_message(
+                http.http(),
+                MessageId::new(message_id),
+                EditMessage::new().embed(embed.to_owned()),
+            )
+            .await;
+        if edited.is_ok() {
+            return Ok(());
+        }
+    }
+    let msg = channel_id
+        .send_message(http.http(), CreateMessage::new().embed(embed))
+        .await?;
+    msg.pin(http.http()).await?;
+    db.watch().set(&key, &msg.id.to_string()).await
+}
+
+/// The todo channel of the invoking guild, telling the user when there is none.
+async fn todo_channel(ctx: Context<'_>) -> Result<Option<ChannelId>, BotError> {
+    let channel_id = ctx
+        .data()
+        .cfg
+        .load()
+        .guilds
+        .get(&ctx.guild_id().unwrap())
+        .and_then(|g| g.todo_channel_id);
+    if channel_id.is_none() {
+        ctx.say("❌ **错误**\n\n本服务器未配置待办频道。").await?;
+    }
+    Ok(channel_id)
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    subcommands("todo_add", "todo_done", "todo_list"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "待办"),
+    description_localized("zh-CN", "管理团队的待办任务")
+)]
+/// Manages the task list of the mod team.
+pub async fn todo(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "add",
+    name_localized("zh-CN", "添加"),
+    description_localized("zh-CN", "添加一项任务"),
+    ephemeral
+)]
+/// Adds a task.
+async fn todo_add(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "内容")]
+    #[description_localized("zh-CN", "任务内容")]
+    #[description = "What needs doing"]
+    #[max_length = 500]
+    content: String,
+    #[name_localized("zh-CN", "负责人")]
+    #[description_localized("zh-CN", "负责此任务的成员")]
+    #[description = "Member taking the task"]
+    assignee: Option<User>,
+    #[name_localized("zh-CN", "期限")]
+    #[description_localized("zh-CN", "多久后到期, 例如 2h, 3d")]
+    #[description = "Due in, e.g. 2h, 3d"]
+    #[autocomplete = "duration_choices"]
+    due: Option<DurationArg>,
+) -> Result<(), BotError> {
+    let due_at = due.map(|DurationArg(duration)| Utc::now() + duration);
+    let Some(channel_id) = todo_channel(ctx).await? else {
+        return Ok(());
+    };
+    let guild_id = ctx.guild_id().unwrap();
+    let db = &ctx.data().db;
+    let id = db
+        .todos()
+        .add(
+            guild_id,
+            &content,
+            assignee.as_ref().map(|u| u.id),
+            ctx.author().id,
+            due_at,
+        )
+        .await?;
+    refresh_board(ctx, db, guild_id, channel_id).await?;
+    ctx.say(format!("✅ **成功**\n\n已添加任务 `#{id}`。"))
+        .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "done",
+    name_localized("zh-CN", "完成"),
+    description_localized("zh-CN", "将一项任务标记为完成"),
+    ephemeral
+)]
+/// Marks a task as done.
+async fn todo_done(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "编号")]
+    #[description_localized("zh-CN", "任务编号")]
+    #[description = "Task number"]
+    #[autocomplete = "todo_choices"]
+    id: i32,
+) -> Result<(), BotError> {
+    let Some(channel_id) = todo_channel(ctx).await? else {
+        return Ok(());
+    };
+    let guild_id = ctx.guild_id().unwrap();
+    let db = &ctx.data().db;
+    if !db.todos().done(guild_id, id).await? {
+        ctx.say(format!("❌ **错误**\n\n没有编号为 `#{id}` 的待办任务。"))
+            .await?;
+        return Ok(());
+    }
+    refresh_board(ctx, db, guild_id, channel_id).await?;
+    ctx.say(format!("✅ **成功**\n\n任务 `#{id}` 已完成。"))
+        .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "list",
+    name_localized("zh-CN", "列表"),
+    description_localized("zh-CN", "查看待办任务"),
+    ephemeral
+)]
+/// Lists the open tasks.
+async fn todo_list(ctx: Context<'_>) -> Result<(), BotError> {
+    let todos = ctx.data().db.todos().open(ctx.guild_id().unwrap()).await?;
+    ctx.send(CreateReply::default().embed(board_embed(&todos)))
+        .await?;
+    Ok(())
+}
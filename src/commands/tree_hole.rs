@@ -1,10 +1,24 @@
-use std::{collections::HashSet, time::Duration};
-
-use poise::{CreateReply, command};
+use poise::{CreateReply, Modal, command};
 use serenity::all::*;
 
-use super::Context;
-use crate::{config::BotCfg, error::BotError};
+use super::{
+    Context,
+    autocomplete::{duration_choices, tree_hole_choices},
+};
+use crate::{
+    config::{BotCfg, PermissionLevel},
+    error::BotError,
+    handlers::purge,
+    utils::{DurationArg, parse_duration},
+};
+
+#[derive(Debug, Modal)]
+#[name = "设置保留时间"]
+struct TtlModal {
+    #[name = "保留时长"]
+    #[placeholder = "从消息发送时算起, 例如 12h, 7d"]
+    duration: String,
+}
 
 #[command(
     slash_command,
@@ -20,23 +34,32 @@ pub async fn register_tree_hole(
     #[name_localized("zh-CN", "树洞频道")]
     #[description_localized("zh-CN", "要注册的树洞频道")]
     #[description = "The tree hole channel to register"]
-    #[channel_types("Text")]
+    #[channel_types("Text", "News", "Forum")]
     channel: GuildChannel,
     #[name_localized("zh-CN", "清理时间")]
-    #[description_localized("zh-CN", "清理时间, 单位为秒")]
-    #[description = "The cleanup time in seconds"]
-    secs: u64,
+    #[description_localized("zh-CN", "消息保留多久后清理, 例如 30m, 12h, 3天")]
+    #[description = "How long messages are kept, e.g. 30m, 12h, 3d"]
+    #[autocomplete = "duration_choices"]
+    duration: DurationArg,
 ) -> Result<(), BotError> {
-    // channel must be a text channel
-    if channel.guild_id != ctx.guild_id().unwrap() {
+    let guild_id = ctx.guild_id().unwrap();
+    if channel.guild_id != guild_id {
         ctx.say("❌ **错误**\n\n树洞频道必须在当前服务器中。")
             .await?;
         return Ok(());
     }
 
+    let duration = duration.0.to_std().expect("Parsed durations are positive");
     ctx.data().cfg.rcu(|cfg| {
         let mut cfg = BotCfg::clone(cfg);
-        cfg.tree_holes.insert(channel.id, Duration::from_secs(secs));
+        // Re-registering only changes the duration, exemptions stay
+        cfg.guilds
+            .entry(guild_id)
+            .or_default()
+            .tree_holes
+            .entry(channel.id)
+            .or_default()
+            .duration = duration;
         cfg
     });
     if let Err(why) = ctx.data().cfg.load().write() {
@@ -47,7 +70,7 @@ pub async fn register_tree_hole(
     ctx.say(format!(
         "✅ **成功**\n\n树洞频道 {} 已注册, 清理时间为 {} 秒。",
         channel.mention(),
-        secs
+        duration.as_secs()
     ))
     .await?;
     Ok(())
@@ -66,21 +89,34 @@ pub async fn unregister_tree_hole(
     #[name_localized("zh-CN", "树洞频道")]
     #[description_localized("zh-CN", "要取消注册的树洞频道")]
     #[description = "The tree hole channel to unregister"]
-    #[channel_types("Text")]
-    channel: GuildChannel,
+    #[autocomplete = "tree_hole_choices"]
+    channel: String,
 ) -> Result<(), BotError> {
-    if channel.guild_id != ctx.guild_id().unwrap() {
+    let channel = match channel.parse::<ChannelId>() {
+        Ok(id) => ctx.guild().and_then(|g| g.channels.get(&id).cloned()),
+        Err(_) => None,
+    };
+    let Some(channel) = channel else {
         ctx.say("❌ **错误**\n\n树洞频道必须在当前服务器中。")
             .await?;
         return Ok(());
-    }
-    if !ctx.data().cfg.load().tree_holes.contains_key(&channel.id) {
+    };
+    if !ctx
+        .data()
+        .cfg
+        .load()
+        .guilds
+        .get(&channel.guild_id)
+        .is_some_and(|g| g.tree_holes.contains_key(&channel.id))
+    {
         ctx.say("❌ **错误**\n\n该频道不是注册的树洞频道。").await?;
         return Ok(());
     }
     ctx.data().cfg.rcu(|cfg| {
         let mut cfg = BotCfg::clone(cfg);
-        cfg.tree_holes.remove(&channel.id);
+        if let Some(guild) = cfg.guilds.get_mut(&channel.guild_id) {
+            guild.tree_holes.remove(&channel.id);
+        }
         cfg
     });
     if let Err(why) = ctx.data().cfg.load().write() {
@@ -105,22 +141,21 @@ pub async fn unregister_tree_hole(
     ephemeral
 )]
 pub async fn list_tree_holes(ctx: Context<'_>) -> Result<(), BotError> {
-    let current_channels = ctx
-        .guild()
-        .unwrap()
-        .channels
-        .keys()
-        .cloned()
-        .collect::<HashSet<_>>();
+    let guild_id = ctx.guild_id().unwrap();
     let holes = ctx
         .data()
         .cfg
         .load()
-        .tree_holes
-        .iter()
-        .filter(|(channel_id, _)| current_channels.contains(channel_id))
-        .map(|(channel_id, duration)| (*channel_id, *duration))
-        .collect::<Vec<_>>();
+        .guilds
+        .get(&guild_id)
+        .map(|guild| {
+            guild
+                .tree_holes
+                .iter()
+                .map(|(channel_id, hole)| (*channel_id, hole.duration))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
 
     if holes.is_empty() {
         ctx.say("当前没有注册的树洞频道。").await?;
@@ -145,3 +180,119 @@ pub async fn list_tree_holes(ctx: Context<'_>) -> Result<(), BotError> {
     ctx.send(reply).await?;
     Ok(())
 }
+
+#[command(
+    slash_command,
+    guild_only,
+    rename = "treehole",
+    subcommands("tree_hole_purge"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Owner",
+    name_localized("zh-CN", "树洞"),
+    description_localized("zh-CN", "树洞紧急管理")
+)]
+/// Emergency moderation of tree holes.
+pub async fn tree_hole(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "purge",
+    name_localized("zh-CN", "立即清空"),
+    description_localized("zh-CN", "立即删除树洞中所有未置顶的消息"),
+    ephemeral
+)]
+/// Deletes all unpinned messages of a tree hole right now, regardless of its TTL.
+async fn tree_hole_purge(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "树洞频道")]
+    #[description_localized("zh-CN", "要清空的树洞频道")]
+    #[description = "The tree hole channel to purge"]
+    #[autocomplete = "tree_hole_choices"]
+    channel: String,
+    #[name_localized("zh-CN", "截止消息")]
+    #[description_localized("zh-CN", "只删除该消息之前的消息, 消息链接或 ID")]
+    #[description = "Only delete messages sent before this one, as a link or ID"]
+    before: Option<String>,
+) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let Some(channel_id) = channel.parse::<ChannelId>().ok().filter(|id| {
+        ctx.data()
+            .cfg
+            .load()
+            .guilds
+            .get(&guild_id)
+            .is_some_and(|g| g.tree_holes.contains_key(id))
+    }) else {
+        ctx.say("❌ **错误**\n\n该频道不是注册的树洞频道。").await?;
+        return Ok(());
+    };
+    let before = match before {
+        Some(before) => {
+            let message_id = utils::parse_message_url(&before)
+                .map(|(_, _, message_id)| message_id)
+                .or_else(|| before.parse().ok());
+            let Some(message_id) = message_id else {
+                ctx.say("❌ **错误**\n\n无效的消息链接或 ID。").await?;
+                return Ok(());
+            };
+            Some(message_id)
+        }
+        None => None,
+    };
+    ctx.defer_ephemeral().await?;
+    let count = purge(ctx.serenity_context(), channel_id, before).await?;
+    ctx.say(format!(
+        "✅ **成功**\n\n已删除 {} 中的 {count} 条消息。",
+        channel_id.mention()
+    ))
+    .await?;
+    Ok(())
+}
+
+#[command(
+    context_menu_command = "Set TTL",
+    guild_only,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "设置保留时间"),
+    ephemeral
+)]
+/// Keeps a tree hole message for longer than its channel does, e.g. a useful answer.
+pub async fn ttl(ctx: Context<'_>, message: Message) -> Result<(), BotError> {
+    let db = &ctx.data().db;
+    let Some(deletion) = db.tree_holes().get(message.id).await? else {
+        ctx.say("❌ **错误**\n\n该消息没有待执行的删除计划。")
+            .await?;
+        return Ok(());
+    };
+    let Context::Application(app_ctx) = ctx else {
+        unreachable!("ttl is a context menu command");
+    };
+    let Some(modal) = TtlModal::execute(app_ctx).await? else {
+        return Ok(()); // The user dismissed the modal
+    };
+    let Some(duration) = parse_duration(&modal.duration) else {
+        ctx.say("❌ **错误**\n\n无效的时长, 例如 `12h`, `7d`。")
+            .await?;
+        return Ok(());
+    };
+    let delete_at = message.timestamp.to_utc() + duration;
+    let at = |t: Timestamp| FormattedTimestamp::new(t, Some(FormattedTimestampStyle::RelativeTime));
+    // Running timers only ever wait longer, so deadlines can be pushed back but not brought forward
+    if delete_at <= deletion.delete_at.to_utc() {
+        ctx.say(format!(
+            "❌ **错误**\n\n新的删除时间须晚于当前的 {}。",
+            at(deletion.delete_at.into())
+        ))
+        .await?;
+        return Ok(());
+    }
+    db.tree_holes().postpone(message.id, delete_at).await?;
+    ctx.say(format!(
+        "✅ **成功**\n\n该消息将于 {} 被删除。",
+        at(delete_at.into())
+    ))
+    .await?;
+    Ok(())
+}
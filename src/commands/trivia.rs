@@ -0,0 +1,387 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    sync::LazyLock,
+    time::Duration,
+};
+
+use dashmap::DashSet;
+use futures::StreamExt;
+use itertools::Itertools;
+use percent_encoding::percent_decode_str;
+use poise::{ChoiceParameter, CreateReply, command};
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+use serenity::all::{colours::roles::DARK_GREEN, *};
+use snafu::ensure_whatever;
+use tracing::error;
+
+use super::Context;
+use crate::{database::BotDatabase, error::BotError};
+
+const API: &str = "https://opentdb.com/api.php";
+const ROUND_TIMEOUT: Duration = Duration::from_secs(20);
+const BUTTON_PREFIX: &str = "trivia_";
+
+/// Channels with a trivia game in progress
+static ACTIVE: LazyLock<DashSet<ChannelId>> = LazyLock::new(DashSet::new);
+
+#[derive(Debug, Clone, Copy, ChoiceParameter)]
+pub enum TriviaCategory {
+    #[name = "General Knowledge"]
+    #[name_localized("zh-CN", "综合知识")]
+    General,
+    #[name = "Books"]
+    #[name_localized("zh-CN", "书籍")]
+    Books,
+    #[name = "Film"]
+    #[name_localized("zh-CN", "电影")]
+    Film,
+    #[name = "Music"]
+    #[name_localized("zh-CN", "音乐")]
+    Music,
+    #[name = "Video Games"]
+    #[name_localized("zh-CN", "电子游戏")]
+    VideoGames,
+    #[name = "Science & Nature"]
+    #[name_localized("zh-CN", "科学与自然")]
+    Science,
+    #[name = "Computers"]
+    #[name_localized("zh-CN", "计算机")]
+    Computers,
+    #[name = "Mathematics"]
+    #[name_localized("zh-CN", "数学")]
+    Mathematics,
+    #[name = "Geography"]
+    #[name_localized("zh-CN", "地理")]
+    Geography,
+    #[name = "History"]
+    #[name_localized("zh-CN", "历史")]
+    History,
+    #[name = "Anime & Manga"]
+    #[name_localized("zh-CN", "动漫")]
+    Anime,
+}
+
+impl TriviaCategory {
+    /// Open Trivia DB category id
+    pub fn id(self) -> u32 {
+        match self {
+            Self::General => 9,
+            Self::Books => 10,
+            Self::Film => 11,
+            Self::Music => 12,
+            Self::VideoGames => 15,
+            Self::Science => 17,
+            Self::Computers => 18,
+            Self::Mathematics => 19,
+            Self::Geography => 22,
+            Self::History => 23,
+            Self::Anime => 31,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TriviaResponse {
+    response_code: u8,
+    results: Vec<Question>,
+}
+
+#[derive(Deserialize)]
+struct Question {
+    category: String,
+    difficulty: String,
+    question: String,
+    correct_answer: String,
+    incorrect_answers: Vec<String>,
+}
+
+fn decode(s: &str) -> String {
+    percent_decode_str(s).decode_utf8_lossy().into_owned()
+}
+
+async fn fetch_questions(category: Option<u32>, amount: u8) -> Result<Vec<Question>, BotError> {
+    let mut query = vec![
+        ("amount", amount.to_string()),
+        ("type", "multiple".to_string()),
+        ("encode", "url3986".to_string()),
+    ];
+    if let Some(category) = category {
+        query.push(("category", category.to_string()));
+    }
+    let res = reqwest::Client::new()
+        .get(API)
+        .query(&query)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<TriviaResponse>()
+        .await?;
+    ensure_whatever!(
+        res.response_code == 0,
+        "Open Trivia DB returned response code {}",
+        res.response_code
+    );
+    Ok(res.results)
+}
+
+fn answer_buttons(answers: &[String], reveal: Option<usize>) -> Vec<CreateActionRow> {
+    let buttons = answers
+        .iter()
+        .enumerate()
+        .map(|(i, answer)| {
+            let label = format!("{}. {}", (b'A' + i as u8) as char, answer);
+            let style = match reveal {
+                Some(correct) if correct == i => ButtonStyle::Success,
+                Some(_) => ButtonStyle::Secondary,
+                None => ButtonStyle::Primary,
+            };
+            CreateButton::new(format!("{BUTTON_PREFIX}{i}"))
+                .label(label.chars().take(80).collect::<String>())
+                .style(style)
+                .disabled(reveal.is_some())
+        })
+        .collect();
+    vec![CreateActionRow::Buttons(buttons)]
+}
+
+/// Run `rounds` trivia questions in a channel and record the scores.
+pub async fn run_trivia(
+    ctx: &serenity::all::Context,
+    db: &BotDatabase,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    category: Option<u32>,
+    rounds: u8,
+) -> Result<(), BotError> {
+    ensure_whatever!(
+        ACTIVE.insert(channel_id),
+        "A trivia game is already running in channel {channel_id}"
+    );
+    let result = play(ctx, db, guild_id, channel_id, category, rounds).await;
+    ACTIVE.remove(&channel_id);
+    result
+}
+
+async fn play(
+    ctx: &serenity::all::Context,
+    db: &BotDatabase,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    category: Option<u32>,
+    rounds: u8,
+) -> Result<(), BotError> {
+    let questions = fetch_questions(category, rounds).await?;
+    let total = questions.len();
+    let mut scores: HashMap<UserId, u64> = HashMap::new();
+
+    for (round, question) in questions.into_iter().enumerate() {
+        let correct_answer = decode(&question.correct_answer);
+        let mut answers = question
+            .incorrect_answers
+            .iter()
+            .map(|a| decode(a))
+            .chain([correct_answer.to_owned()])
+            .collect::<Vec<_>>();
+        answers.shuffle(&mut rand::rng());
+        let correct = answers
+            .iter()
+            .position(|a| *a == correct_answer)
+            .expect("The correct answer is always among the answers");
+
+        let embed = CreateEmbed::new()
+            .title(format!("🧠 第 {}/{} 题", round + 1, total))
+            .description(decode(&question.question))
+            .field("分类", decode(&question.category), true)
+            .field("难度", decode(&question.difficulty), true)
+            .footer(CreateEmbedFooter::new(format!(
+                "{} 秒内作答, 每人仅限一次",
+                ROUND_TIMEOUT.as_secs()
+            )))
+            .color(0x7289DA);
+        let mut msg = channel_id
+            .send_message(
+                ctx,
+                CreateMessage::new()
+                    .embed(embed.to_owned())
+                    .components(answer_buttons(&answers, None)),
+            )
+            .await?;
+
+        let mut picks: HashMap<UserId, usize> = HashMap::new();
+        let mut interactions = ComponentInteractionCollector::new(ctx)
+            .message_id(msg.id)
+            .timeout(ROUND_TIMEOUT)
+            .stream();
+        while let Some(interaction) = interactions.next().await {
+            let Some(choice) = interaction
+                .data
+                .custom_id
+                .strip_prefix(BUTTON_PREFIX)
+                .and_then(|i| i.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            let content = match picks.entry(interaction.user.id) {
+                Entry::Occupied(_) => "❌ 你已经回答过了。",
+                Entry::Vacant(entry) => {
+                    entry.insert(choice);
+                    "✅ 已记录你的答案。"
+                }
+            };
+            // The pick is recorded either way, a failed acknowledgement must not end the game
+            if let Err(e) = interaction
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(content)
+                            .ephemeral(true),
+                    ),
+                )
+                .await
+            {
+                error!("Failed to acknowledge trivia answer: {e}");
+            }
+        }
+
+        let winners = picks
+            .into_iter()
+            .filter(|(_, choice)| *choice == correct)
+            .map(|(user_id, _)| user_id)
+            .collect::<Vec<_>>();
+        for user_id in &winners {
+            *scores.entry(*user_id).or_default() += 1;
+        }
+        let winners = if winners.is_empty() {
+            "无人答对".to_string()
+        } else {
+            winners.iter().map(|u| u.mention().to_string()).join(", ")
+        };
+        msg.edit(
+            ctx,
+            EditMessage::new()
+                .embed(embed.field("正确答案", correct_answer, false).field(
+                    "答对的人",
+                    winners,
+                    false,
+                ))
+                .components(answer_buttons(&answers, Some(correct))),
+        )
+        .await?;
+    }
+
+    for (user_id, points) in &scores {
+        db.trivia().add(guild_id, *user_id, *points).await?;
+    }
+    let ranking = scores
+        .into_iter()
+        .sorted_by(|a, b| b.1.cmp(&a.1))
+        .enumerate()
+        .map(|(i, (user_id, points))| format!("{}. {} - {} 分", i + 1, user_id.mention(), points))
+        .join("\n");
+    channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new().embed(
+                CreateEmbed::new()
+                    .title("🏁 问答结束")
+                    .description(if ranking.is_empty() {
+                        "本轮无人得分。".to_string()
+                    } else {
+                        ranking
+                    })
+                    .color(DARK_GREEN),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    subcommands("trivia_start", "trivia_leaderboard"),
+    subcommand_required,
+    name_localized("zh-CN", "问答"),
+    description_localized("zh-CN", "知识问答游戏")
+)]
+/// Trivia quiz game.
+pub async fn trivia(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    rename = "start",
+    name_localized("zh-CN", "开始"),
+    description_localized("zh-CN", "在当前频道开始一轮知识问答")
+)]
+/// Starts a round of trivia questions in this channel.
+pub async fn trivia_start(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "分类")]
+    #[description_localized("zh-CN", "题目分类, 默认为任意分类")]
+    #[description = "Question category, any category by default"]
+    category: Option<TriviaCategory>,
+    #[name_localized("zh-CN", "题数")]
+    #[description_localized("zh-CN", "题目数量, 默认为 5")]
+    #[description = "Number of questions, 5 by default"]
+    #[min = 1]
+    #[max = 20]
+    rounds: Option<u8>,
+) -> Result<(), BotError> {
+    if ACTIVE.contains(&ctx.channel_id()) {
+        ctx.send(
+            CreateReply::default()
+                .content("❌ 当前频道已有正在进行的问答。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    ctx.say("🎲 知识问答即将开始!").await?;
+    run_trivia(
+        ctx.serenity_context(),
+        &ctx.data().db,
+        ctx.guild_id().unwrap(),
+        ctx.channel_id(),
+        category.map(TriviaCategory::id),
+        rounds.unwrap_or(5),
+    )
+    .await
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    rename = "leaderboard",
+    name_localized("zh-CN", "排行榜"),
+    description_localized("zh-CN", "查看本服务器的问答排行榜"),
+    ephemeral
+)]
+/// Shows the trivia leaderboard of this guild.
+pub async fn trivia_leaderboard(ctx: Context<'_>) -> Result<(), BotError> {
+    let guild_id = ctx.guild_id().unwrap();
+    let scores = ctx.data().db.trivia().leaderboard(guild_id, 10).await?;
+    if scores.is_empty() {
+        ctx.say("本服务器还没有问答记录。").await?;
+        return Ok(());
+    }
+    let ranking = scores
+        .iter()
+        .enumerate()
+        .map(|(i, s)| format!("{}. {} - {} 分", i + 1, s.user_id().mention(), s.score()))
+        .join("\n");
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("🏆 问答排行榜")
+                .description(ranking)
+                .color(DARK_GREEN),
+        ),
+    )
+    .await?;
+    Ok(())
+}
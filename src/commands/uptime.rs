@@ -0,0 +1,159 @@
+use std::{collections::VecDeque, sync::LazyLock, time::Duration};
+
+use chrono::FixedOffset;
+use dashmap::DashMap;
+use poise::{CreateReply, command};
+use serenity::all::{
+    colours::css::{DANGER, POSITIVE},
+    *,
+};
+
+use super::{Context, autocomplete::monitor_choices, send_output};
+use crate::{config::PermissionLevel, error::BotError, utils::chart};
+
+/// Outcome of the latest check of an uptime monitor
+#[derive(Debug, Clone)]
+pub struct EndpointStatus {
+    pub up: bool,
+    pub response_time: Duration,
+    /// Why the check failed, if it did
+    pub reason: Option<String>,
+    pub checked_at: Timestamp,
+}
+
+/// Latest status per monitor name, updated by the uptime handler
+pub static STATUS: LazyLock<DashMap<String, EndpointStatus>> = LazyLock::new(DashMap::new);
+
+/// Checks kept per monitor for `/uptime-monitor history`
+const HISTORY_LEN: usize = 120;
+
+pub(crate) static HISTORY: LazyLock<DashMap<String, VecDeque<EndpointStatus>>> =
+    LazyLock::new(DashMap::new);
+
+/// Store the outcome of a check, returning the one before it
+pub fn record(name: &str, status: EndpointStatus) -> Option<EndpointStatus> {
+    let mut history = HISTORY.entry(name.to_owned()).or_default();
+    if history.len() == HISTORY_LEN {
+        history.pop_front();
+    }
+    history.push_back(status.to_owned());
+    STATUS.insert(name.to_owned(), status)
+}
+
+#[command(
+    slash_command,
+    rename = "uptime-monitor",
+    subcommands("uptime_status", "uptime_history"),
+    subcommand_required,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "可用性监控"),
+    description_localized("zh-CN", "HTTP 端点可用性监控")
+)]
+/// HTTP endpoint uptime monitoring.
+pub async fn uptime_monitor(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "status",
+    name_localized("zh-CN", "状态"),
+    description_localized("zh-CN", "查看各端点的当前状态与响应时间"),
+    ephemeral
+)]
+/// Shows whether each monitored endpoint is up and how fast it responds.
+pub async fn uptime_status(ctx: Context<'_>) -> Result<(), BotError> {
+    let monitors = ctx.data().cfg.load().uptime_monitors.to_owned();
+    if monitors.is_empty() {
+        ctx.say("当前没有配置任何监控端点。").await?;
+        return Ok(());
+    }
+    let mut all_up = true;
+    let lines = monitors
+        .iter()
+        .map(|monitor| match STATUS.get(&monitor.name) {
+            Some(status) => {
+                all_up &= status.up;
+                format!(
+                    "{} **{}** - {}ms{} · <t:{}:R>",
+                    if status.up { "🟢" } else { "🔴" },
+                    monitor.name,
+                    status.response_time.as_millis(),
+                    status
+                        .reason
+                        .as_ref()
+                        .map(|r| format!(" ({r})"))
+                        .unwrap_or_default(),
+                    status.checked_at.unix_timestamp()
+                )
+            }
+            None => format!("⚪ **{}** - 尚未检查", monitor.name),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let embed = CreateEmbed::new()
+        .title("📡 可用性监控")
+        .description(lines)
+        .color(if all_up { POSITIVE } else { DANGER })
+        .timestamp(Timestamp::now());
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "history",
+    name_localized("zh-CN", "历史"),
+    description_localized("zh-CN", "查看端点最近的响应时间图表"),
+    ephemeral
+)]
+/// Charts the recent response times of a monitored endpoint.
+pub async fn uptime_history(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "名称")]
+    #[description_localized("zh-CN", "监控端点名称")]
+    #[description = "Name of the monitor"]
+    #[autocomplete = "monitor_choices"]
+    name: String,
+) -> Result<(), BotError> {
+    let offset = FixedOffset::east_opt(ctx.data().cfg.load().time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    let Some((series, labels)) = HISTORY.get(&name).map(|history| {
+        history
+            .iter()
+            .map(|s| {
+                let at = s.checked_at.with_timezone(&offset).format("%H:%M");
+                ((s.response_time.as_millis() as u64, s.up), at.to_string())
+            })
+            .unzip::<_, _, Vec<_>, Vec<_>>()
+    }) else {
+        ctx.say(format!("❌ **错误**\n\n监控端点 **{name}** 尚未检查。"))
+            .await?;
+        return Ok(());
+    };
+    let up = series.iter().filter(|s| s.1).count();
+    let average = series.iter().filter(|s| s.1).map(|s| s.0).sum::<u64>() / up.max(1) as u64;
+    let embed = CreateEmbed::new()
+        .title(format!("📡 {name} · 最近 {} 次检查", series.len()))
+        .field(
+            "可用率",
+            format!("{:.1}%", up as f64 * 100.0 / series.len() as f64),
+            true,
+        )
+        .field("平均响应", format!("{average}ms"), true)
+        .image("attachment://history.png")
+        .footer(CreateEmbedFooter::new("红色为失败的检查"))
+        .color(if up == series.len() { POSITIVE } else { DANGER })
+        .timestamp(Timestamp::now());
+    send_output(
+        ctx,
+        CreateReply::default()
+            .embed(embed)
+            .attachment(CreateAttachment::bytes(
+                chart::columns(&series, &labels, "MS", ["OK", "FAIL"]),
+                "history.png",
+            )),
+    )
+    .await?;
+    Ok(())
+}
@@ -1,6 +1,14 @@
 mod help;
+mod notifications;
 mod ping;
+mod services;
 mod system;
+mod timestamp;
+mod timezone;
 pub use help::*;
+pub use notifications::*;
 pub use ping::*;
+pub use services::*;
 pub use system::*;
+pub use timestamp::*;
+pub use timezone::*;
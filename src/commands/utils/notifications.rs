@@ -0,0 +1,72 @@
+use poise::{ChoiceParameter, command};
+
+use super::super::Context;
+use crate::{error::BotError, repo::prefs::DmKind};
+
+#[command(
+    slash_command,
+    subcommands("notifications_set", "notifications_show"),
+    subcommand_required,
+    name_localized("zh-CN", "通知"),
+    description_localized("zh-CN", "管理机器人发给你的私信")
+)]
+/// Manages which DMs the bot sends you.
+pub async fn notifications(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "set",
+    name_localized("zh-CN", "设置"),
+    description_localized("zh-CN", "开启或关闭一类私信"),
+    ephemeral
+)]
+/// Turns a kind of DM on or off.
+pub async fn notifications_set(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "类型")]
+    #[description_localized("zh-CN", "私信类型")]
+    #[description = "Kind of DM"]
+    kind: DmKind,
+    #[name_localized("zh-CN", "接收")]
+    #[description_localized("zh-CN", "是否接收")]
+    #[description = "Whether to receive them"]
+    enabled: bool,
+) -> Result<(), BotError> {
+    ctx.data()
+        .db
+        .prefs()
+        .set_dm(ctx.author().id, kind, enabled)
+        .await?;
+    ctx.say(format!(
+        "✅ **成功**\n\n已{}**{}**。",
+        if enabled { "开启" } else { "关闭" },
+        kind.localized_name("zh-CN").unwrap_or(kind.name())
+    ))
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "show",
+    name_localized("zh-CN", "查看"),
+    description_localized("zh-CN", "查看你的私信设置"),
+    ephemeral
+)]
+/// Shows which DMs you receive.
+pub async fn notifications_show(ctx: Context<'_>) -> Result<(), BotError> {
+    let prefs = ctx.data().db.prefs();
+    let mut lines = vec!["🔔 **私信设置**\n".to_owned()];
+    for kind in DmKind::ALL {
+        let allowed = prefs.allows_dm(ctx.author().id, kind).await?;
+        lines.push(format!(
+            "{} {}",
+            if allowed { "✅" } else { "🔕" },
+            kind.localized_name("zh-CN").unwrap_or(kind.name())
+        ));
+    }
+    ctx.say(lines.join("\n")).await?;
+    Ok(())
+}
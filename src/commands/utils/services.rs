@@ -0,0 +1,167 @@
+use poise::{CreateReply, command};
+use serenity::all::{
+    colours::css::{DANGER, POSITIVE},
+    *,
+};
+use zbus::{Connection, proxy, zvariant::OwnedObjectPath};
+
+use super::super::Context;
+use crate::{config::PermissionLevel, error::BotError, utils::format};
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait Manager {
+    /// Object of a unit, loading it if needed, which also works for units that are not running
+    fn load_unit(&self, name: &str) -> zbus::Result<OwnedObjectPath>;
+}
+
+#[proxy(
+    interface = "org.freedesktop.systemd1.Unit",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait Unit {
+    #[zbus(property)]
+    fn id(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn active_state(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn sub_state(&self) -> zbus::Result<String>;
+}
+
+/// Only service units have memory accounting and restart counts
+#[proxy(
+    interface = "org.freedesktop.systemd1.Service",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait Service {
+    #[zbus(property)]
+    fn memory_current(&self) -> zbus::Result<u64>;
+    #[zbus(property, name = "NRestarts")]
+    fn n_restarts(&self) -> zbus::Result<u32>;
+}
+
+/// State of a systemd unit as reported over DBus
+#[derive(Debug, Default, PartialEq, Eq)]
+struct UnitStatus {
+    id: String,
+    active: String,
+    sub: String,
+    /// Absent for units without memory accounting
+    memory: Option<u64>,
+    restarts: Option<u32>,
+}
+
+impl UnitStatus {
+    fn healthy(&self) -> bool {
+        self.active == "active"
+    }
+
+    /// Embed field of the unit
+    fn field(&self) -> (String, String, bool) {
+        let emoji = if self.healthy() { "🟢" } else { "🔴" };
+        let value = format!(
+            "状态: `{}/{}`\n内存: {}\n重启: {}",
+            self.active,
+            self.sub,
+            self.memory.map_or_else(|| "-".to_owned(), format::bytes),
+            self.restarts
+                .map_or_else(|| "-".to_owned(), |r| r.to_string()),
+        );
+        (format!("{emoji} {}", self.id), value, true)
+    }
+}
+
+async fn unit_status(conn: &Connection, name: &str) -> Result<UnitStatus, BotError> {
+    let path = ManagerProxy::new(conn).await?.load_unit(name).await?;
+    let unit = UnitProxy::builder(conn).path(&path)?.build().await?;
+    let service = ServiceProxy::builder(conn).path(&path)?.build().await?;
+    Ok(UnitStatus {
+        id: unit.id().await?,
+        active: unit.active_state().await?,
+        sub: unit.sub_state().await?,
+        // The maximum value stands for no accounting
+        memory: service
+            .memory_current()
+            .await
+            .ok()
+            .filter(|m| *m != u64::MAX),
+        restarts: service.n_restarts().await.ok(),
+    })
+}
+
+async fn units_status(units: &[String]) -> Result<Vec<UnitStatus>, BotError> {
+    let conn = Connection::system().await?;
+    let mut statuses = Vec::with_capacity(units.len());
+    for unit in units {
+        statuses.push(unit_status(&conn, unit).await?);
+    }
+    Ok(statuses)
+}
+
+#[command(
+    slash_command,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "服务状态"),
+    description_localized("zh-CN", "查看主机上配置的 systemd 服务状态"),
+    ephemeral
+)]
+/// Shows the state, memory and restarts of the systemd units configured in `services`.
+pub async fn services(ctx: Context<'_>) -> Result<(), BotError> {
+    let units = ctx.data().cfg.load().services.to_owned();
+    if units.is_empty() {
+        ctx.say("当前没有配置任何服务。").await?;
+        return Ok(());
+    }
+    let statuses = match units_status(&units).await {
+        Ok(statuses) => statuses,
+        Err(why) => {
+            ctx.say(format!("❌ **错误**\n\n无法获取服务状态: {why}"))
+                .await?;
+            return Ok(());
+        }
+    };
+    let all_healthy = statuses.iter().all(UnitStatus::healthy);
+    // Embeds hold at most 25 fields
+    let embed = CreateEmbed::new()
+        .title("🧩 服务状态")
+        .fields(statuses.iter().take(25).map(UnitStatus::field))
+        .color(if all_healthy { POSITIVE } else { DANGER })
+        .timestamp(Timestamp::now());
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_field() {
+        let unit = UnitStatus {
+            id: "dc-bot.service".to_owned(),
+            active: "active".to_owned(),
+            sub: "running".to_owned(),
+            memory: Some(47_185_920),
+            restarts: Some(2),
+        };
+        assert_eq!(
+            unit.field(),
+            (
+                "🟢 dc-bot.service".to_owned(),
+                "状态: `active/running`\n内存: 45.0 MiB\n重启: 2".to_owned(),
+                true
+            )
+        );
+        let unit = UnitStatus {
+            id: "backup.timer".to_owned(),
+            active: "failed".to_owned(),
+            sub: "failed".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(unit.field().0, "🔴 backup.timer");
+        assert!(unit.field().1.ends_with("内存: -\n重启: -"));
+    }
+}
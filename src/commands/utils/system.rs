@@ -6,8 +6,10 @@ use serenity::all::{
 };
 use sysinfo::System;
 
-use super::super::Context;
-use crate::error::BotError;
+use super::super::{Context, say_long};
+use crate::{config::PermissionLevel, error::BotError, utils::format};
+
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
 
 #[command(
     slash_command,
@@ -25,19 +27,16 @@ pub async fn system_info(ctx: Context<'_>, ephemeral: Option<bool>) -> Result<()
     let e = epoch::mib()?;
     let allocated = stats::allocated::mib()?;
     e.advance()?;
-    let allocated_value = allocated.read()?;
-    let allocated_mb = allocated_value / 1024 / 1024; // Convert to MB
+    let allocated = allocated.read()?;
     let sys = System::new_all();
     let cpu = sys.cpus().len().to_string();
     let cpu_usage = sys.global_cpu_usage();
-    let total_memory = sys.total_memory() / 1024 / 1024; // Convert to MB
-    let used_memory = sys.used_memory() / 1024 / 1024; // Convert to MB
-    let memory_usage = (used_memory as f64 / total_memory as f64) * 100.0;
+    let (total_memory, used_memory) = (sys.total_memory(), sys.used_memory());
     let cached_users = ctx.cache().user_count();
     let cached_guilds = ctx.cache().guild_count();
     let cached_channels = ctx.cache().guild_channel_count();
     let rust_version = compile_time::rustc_version_str!();
-    let db_size = ctx.data().db.size().await? / 1024 / 1024; // Convert to MB
+    let db_size = ctx.data().db.size().await?;
     let latency = ctx.ping().await;
     let metrics = tokio::runtime::Handle::current().metrics();
     let queue_count = metrics.global_queue_depth();
@@ -65,12 +64,17 @@ pub async fn system_info(ctx: Context<'_>, ephemeral: Option<bool>) -> Result<()
         .field("🔥 CPU 使用率", format!("{cpu_usage:.1}%"), true)
         .field(
             "🧠 系统内存",
-            format!("{memory_usage:.1}% ({used_memory} MB / {total_memory} MB)"),
+            format!(
+                "{} ({} / {})",
+                format::percent(used_memory as f64, total_memory as f64),
+                format::bytes(used_memory),
+                format::bytes(total_memory)
+            ),
             true,
         )
         // row 2
-        .field("💭 Bot 内存", format!("{allocated_mb} MB"), true)
-        .field("⛁ 数据库大小", format!("{db_size} MB"), true)
+        .field("💭 Bot 内存", format::bytes(allocated as u64), true)
+        .field("⛁ 数据库大小", format::bytes(db_size as u64), true)
         .field(
             "⏱️ WebSocket 延迟",
             format!("{} ms", latency.as_millis()),
@@ -81,9 +85,17 @@ pub async fn system_info(ctx: Context<'_>, ephemeral: Option<bool>) -> Result<()
         .field("🚀 Tokio 活跃任务", active_count.to_string(), true)
         .field("🛠️ Tokio 工作线程", workers.to_string(), true)
         // row 4
-        .field("👥 缓存用户数", cached_users.to_string(), true)
-        .field("🌐 缓存服务器数", cached_guilds.to_string(), true)
-        .field("📺 缓存频道数", cached_channels.to_string(), true)
+        .field("👥 缓存用户数", format::number(cached_users as i64), true)
+        .field(
+            "🌐 缓存服务器数",
+            format::number(cached_guilds as i64),
+            true,
+        )
+        .field(
+            "📺 缓存频道数",
+            format::number(cached_channels as i64),
+            true,
+        )
         .thumbnail(ctx.cache().current_user().avatar_url().unwrap_or_default())
         .timestamp(chrono::Utc::now())
         .footer(CreateEmbedFooter::new("系统监控"))
@@ -100,7 +112,7 @@ pub async fn system_info(ctx: Context<'_>, ephemeral: Option<bool>) -> Result<()
 #[command(
     slash_command,
     default_member_permissions = "ADMINISTRATOR",
-    owners_only,
+    custom_data = "PermissionLevel::Owner",
     ephemeral
 )]
 pub async fn guilds_info(ctx: Context<'_>) -> Result<(), BotError> {
@@ -133,6 +145,9 @@ pub async fn guilds_info(ctx: Context<'_>) -> Result<(), BotError> {
         ctx.say("没有找到任何服务器信息。").await?;
         return Ok(());
     }
+    if message.chars().count() > EMBED_DESCRIPTION_LIMIT {
+        return say_long(ctx, message).await;
+    }
     ctx.send(
         CreateReply::default().embed(
             CreateEmbed::new()
@@ -0,0 +1,75 @@
+use chrono_tz::Tz;
+use poise::{CreateReply, command};
+use serenity::all::CreateEmbed;
+
+use super::{
+    super::{Context, autocomplete::timezone_choices},
+    user_tz,
+};
+use crate::{error::BotError, utils::UserTz};
+
+/// Discord timestamp styles and their descriptions
+const STYLES: &[(char, &str)] = &[
+    ('t', "短时间"),
+    ('T', "长时间"),
+    ('d', "短日期"),
+    ('D', "长日期"),
+    ('f', "短日期时间"),
+    ('F', "长日期时间"),
+    ('R', "相对时间"),
+];
+
+#[command(
+    slash_command,
+    name_localized("zh-CN", "时间戳"),
+    description_localized("zh-CN", "把日期时间转换为 Discord 时间戳"),
+    ephemeral
+)]
+/// Converts a datetime into Discord timestamp markup.
+pub async fn timestamp(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "时间")]
+    #[description_localized("zh-CN", "例如 2025-07-10 20:00、明天 08:00、20:15 或 RFC3339 格式")]
+    #[description = "e.g. 2025-07-10 20:00, tomorrow 08:00, 20:15 or RFC3339"]
+    datetime: String,
+    #[name_localized("zh-CN", "时区")]
+    #[description_localized("zh-CN", "IANA 时区, 默认为你设置的时区")]
+    #[description = "IANA timezone, defaults to your saved timezone"]
+    #[autocomplete = "timezone_choices"]
+    timezone: Option<String>,
+) -> Result<(), BotError> {
+    let tz = match timezone.as_deref().map(str::parse::<Tz>) {
+        Some(Ok(tz)) => UserTz::Iana(tz),
+        Some(Err(_)) => {
+            ctx.say("❌ **错误**\n\n未知的时区, 请使用 IANA 时区名称, 例如 `Asia/Shanghai`。")
+                .await?;
+            return Ok(());
+        }
+        None => user_tz(ctx).await?,
+    };
+    let parsed = tz.parse(&datetime);
+    let Some(parsed) = parsed else {
+        ctx.say("❌ **错误**\n\n无法解析该时间, 请使用 `2025-07-10 20:00`、`明天 08:00` 或 RFC3339 格式。")
+            .await?;
+        return Ok(());
+    };
+    let secs = parsed.timestamp();
+    let description = STYLES
+        .iter()
+        .map(|(style, name)| format!("{name}: <t:{secs}:{style}>\n`<t:{secs}:{style}>`"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    ctx.send(
+        CreateReply::default().embed(
+            CreateEmbed::new()
+                .title("🕒 Discord 时间戳")
+                .description(description)
+                .field("Unix", secs.to_string(), true)
+                .field("时区", tz.to_string(), true)
+                .field("本地时间", parsed.to_rfc3339(), true)
+                .color(0x7289DA),
+        ),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use chrono::{FixedOffset, Utc};
+use chrono_tz::Tz;
+use poise::command;
+use serenity::all::MessageBuilder;
+
+use super::super::{Context, autocomplete::timezone_choices};
+use crate::{error::BotError, utils::UserTz};
+
+/// The timezone of the invoking user, falling back to the configured offset.
+pub async fn user_tz(ctx: Context<'_>) -> Result<UserTz, BotError> {
+    let offset = FixedOffset::east_opt(ctx.data().cfg.load().time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    let pref = ctx.data().db.prefs().get(ctx.author().id).await?;
+    Ok(UserTz::new(
+        pref.as_ref().and_then(|p| p.timezone.as_deref()),
+        offset,
+    ))
+}
+
+#[command(
+    slash_command,
+    subcommands("timezone_set", "timezone_show", "timezone_clear"),
+    subcommand_required,
+    name_localized("zh-CN", "时区"),
+    description_localized("zh-CN", "管理你的时区偏好")
+)]
+/// Manages your timezone preference.
+pub async fn timezone(_ctx: Context<'_>) -> Result<(), BotError> {
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "set",
+    name_localized("zh-CN", "设置"),
+    description_localized("zh-CN", "设置你的时区"),
+    ephemeral
+)]
+/// Sets your timezone.
+pub async fn timezone_set(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "时区")]
+    #[description_localized("zh-CN", "IANA 时区, 例如 Asia/Shanghai")]
+    #[description = "IANA timezone, e.g. Asia/Shanghai"]
+    #[autocomplete = "timezone_choices"]
+    timezone: String,
+) -> Result<(), BotError> {
+    let Ok(tz) = timezone.parse::<Tz>() else {
+        ctx.say("❌ **错误**\n\n未知的时区, 请使用 IANA 时区名称, 例如 `Asia/Shanghai`。")
+            .await?;
+        return Ok(());
+    };
+    ctx.data()
+        .db
+        .prefs()
+        .set_timezone(ctx.author().id, Some(tz.name().to_owned()))
+        .await?;
+    ctx.say(
+        MessageBuilder::new()
+            .push("✅ **成功**\n\n你的时区已设置为 ")
+            .push_bold_safe(tz.name())
+            .push(format!(
+                ", 当前时间为 {}。",
+                Utc::now().with_timezone(&tz).format("%Y-%m-%d %H:%M")
+            ))
+            .build(),
+    )
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "show",
+    name_localized("zh-CN", "查看"),
+    description_localized("zh-CN", "查看你的时区"),
+    ephemeral
+)]
+/// Shows your timezone.
+pub async fn timezone_show(ctx: Context<'_>) -> Result<(), BotError> {
+    let tz = user_tz(ctx).await?;
+    let note = match tz {
+        UserTz::Iana(_) => "",
+        UserTz::Fixed(_) => " (未设置, 使用默认时区)",
+    };
+    ctx.say(format!(
+        "🕒 你的时区为 **{tz}**{note}, 当前时间为 {}。",
+        tz.localize(&Utc::now()).format("%Y-%m-%d %H:%M")
+    ))
+    .await?;
+    Ok(())
+}
+
+#[command(
+    slash_command,
+    rename = "clear",
+    name_localized("zh-CN", "清除"),
+    description_localized("zh-CN", "清除你的时区设置"),
+    ephemeral
+)]
+/// Clears your timezone, falling back to the bot default.
+pub async fn timezone_clear(ctx: Context<'_>) -> Result<(), BotError> {
+    ctx.data()
+        .db
+        .prefs()
+        .set_timezone(ctx.author().id, None)
+        .await?;
+    ctx.say("✅ **成功**\n\n你的时区设置已清除。").await?;
+    Ok(())
+}
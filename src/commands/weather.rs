@@ -0,0 +1,259 @@
+use poise::{CreateReply, command};
+use serde::Deserialize;
+use serenity::all::*;
+use snafu::OptionExt;
+
+use super::Context;
+use crate::{config::WeatherProvider, error::BotError};
+
+/// Current conditions at a location, normalized across providers
+struct Weather {
+    location: String,
+    description: String,
+    emoji: &'static str,
+    temperature: f64,
+    feels_like: f64,
+    humidity: f64,
+    /// Wind speed in km/h
+    wind_speed: f64,
+}
+
+/// Describe a WMO weather interpretation code as used by Open-Meteo.
+fn wmo_code(code: u8) -> (&'static str, &'static str) {
+    match code {
+        0 => ("晴", "☀️"),
+        1 => ("大部晴朗", "🌤️"),
+        2 => ("多云", "⛅"),
+        3 => ("阴", "☁️"),
+        45 | 48 => ("雾", "🌫️"),
+        51 | 53 | 55 | 56 | 57 => ("毛毛雨", "🌦️"),
+        61 | 63 | 65 | 66 | 67 | 80 | 81 | 82 => ("雨", "🌧️"),
+        71 | 73 | 75 | 77 | 85 | 86 => ("雪", "🌨️"),
+        95 | 96 | 99 => ("雷暴", "⛈️"),
+        _ => ("未知", "🌡️"),
+    }
+}
+
+/// Pick an emoji for an OpenWeatherMap condition id.
+fn owm_emoji(id: u16) -> &'static str {
+    match id {
+        200..300 => "⛈️",
+        300..400 => "🌦️",
+        500..600 => "🌧️",
+        600..700 => "🌨️",
+        700..800 => "🌫️",
+        800 => "☀️",
+        801 | 802 => "⛅",
+        _ => "☁️",
+    }
+}
+
+impl WeatherProvider {
+    async fn current(&self, location: &str) -> Result<Option<Weather>, BotError> {
+        let client = reqwest::Client::new();
+        match self {
+            Self::OpenMeteo => {
+                #[derive(Deserialize)]
+                struct Geocoding {
+                    #[serde(default)]
+                    results: Vec<Place>,
+                }
+                #[derive(Deserialize)]
+                struct Place {
+                    name: String,
+                    country: Option<String>,
+                    latitude: f64,
+                    longitude: f64,
+                }
+                #[derive(Deserialize)]
+                struct Forecast {
+                    current: Current,
+                }
+                #[derive(Deserialize)]
+                struct Current {
+                    temperature_2m: f64,
+                    apparent_temperature: f64,
+                    relative_humidity_2m: f64,
+                    wind_speed_10m: f64,
+                    weather_code: u8,
+                }
+
+                let Some(place) = client
+                    .get("https://geocoding-api.open-meteo.com/v1/search")
+                    .query(&[("name", location), ("count", "1"), ("language", "zh")])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<Geocoding>()
+                    .await?
+                    .results
+                    .into_iter()
+                    .next()
+                else {
+                    return Ok(None);
+                };
+                let current = client
+                    .get("https://api.open-meteo.com/v1/forecast")
+                    .query(&[
+                        ("latitude", place.latitude.to_string()),
+                        ("longitude", place.longitude.to_string()),
+                        (
+                            "current",
+                            "temperature_2m,apparent_temperature,relative_humidity_2m,\
+                             wind_speed_10m,weather_code"
+                                .to_string(),
+                        ),
+                    ])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<Forecast>()
+                    .await?
+                    .current;
+                let (description, emoji) = wmo_code(current.weather_code);
+                Ok(Some(Weather {
+                    location: match place.country {
+                        Some(country) => format!("{}, {}", place.name, country),
+                        None => place.name,
+                    },
+                    description: description.to_string(),
+                    emoji,
+                    temperature: current.temperature_2m,
+                    feels_like: current.apparent_temperature,
+                    humidity: current.relative_humidity_2m,
+                    wind_speed: current.wind_speed_10m,
+                }))
+            }
+            Self::OpenWeatherMap { api_key } => {
+                #[derive(Deserialize)]
+                struct Response {
+                    name: String,
+                    sys: Sys,
+                    main: Main,
+                    wind: Wind,
+                    weather: Vec<Condition>,
+                }
+                #[derive(Deserialize)]
+                struct Sys {
+                    country: Option<String>,
+                }
+                #[derive(Deserialize)]
+                struct Main {
+                    temp: f64,
+                    feels_like: f64,
+                    humidity: f64,
+                }
+                #[derive(Deserialize)]
+                struct Wind {
+                    speed: f64,
+                }
+                #[derive(Deserialize)]
+                struct Condition {
+                    id: u16,
+                    description: String,
+                }
+
+                let res = client
+                    .get("https://api.openweathermap.org/data/2.5/weather")
+                    .query(&[
+                        ("q", location),
+                        ("appid", api_key),
+                        ("units", "metric"),
+                        ("lang", "zh_cn"),
+                    ])
+                    .send()
+                    .await?;
+                if res.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok(None);
+                }
+                let res = res.error_for_status()?.json::<Response>().await?;
+                let condition = res
+                    .weather
+                    .into_iter()
+                    .next()
+                    .whatever_context::<&str, BotError>("OpenWeatherMap returned no conditions")?;
+                Ok(Some(Weather {
+                    location: match res.sys.country {
+                        Some(country) => format!("{}, {}", res.name, country),
+                        None => res.name,
+                    },
+                    description: condition.description,
+                    emoji: owm_emoji(condition.id),
+                    temperature: res.main.temp,
+                    feels_like: res.main.feels_like,
+                    humidity: res.main.humidity,
+                    // m/s to km/h
+                    wind_speed: res.wind.speed * 3.6,
+                }))
+            }
+        }
+    }
+}
+
+#[command(
+    slash_command,
+    name_localized("zh-CN", "天气"),
+    description_localized("zh-CN", "查询当前天气")
+)]
+/// Shows the current weather at a location.
+pub async fn weather(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "地点")]
+    #[description_localized("zh-CN", "城市或地点, 默认为你保存的地点")]
+    #[description = "City or place, defaults to your saved location"]
+    location: Option<String>,
+    #[name_localized("zh-CN", "保存")]
+    #[description_localized("zh-CN", "是否保存为默认地点")]
+    #[description = "Save as your default location"]
+    save: Option<bool>,
+) -> Result<(), BotError> {
+    let db = &ctx.data().db;
+    let location = match location {
+        Some(location) => location,
+        None => {
+            let saved = db
+                .prefs()
+                .get(ctx.author().id)
+                .await?
+                .and_then(|p| p.weather_location);
+            let Some(saved) = saved else {
+                ctx.send(
+                    CreateReply::default()
+                        .content("❌ **错误**\n\n请指定地点, 或使用 `保存` 选项设置默认地点。")
+                        .ephemeral(true),
+                )
+                .await?;
+                return Ok(());
+            };
+            saved
+        }
+    };
+    ctx.defer().await?;
+    let provider = ctx.data().cfg.load().weather.to_owned();
+    let Some(weather) = provider.current(&location).await? else {
+        ctx.say(
+            MessageBuilder::new()
+                .push("❌ **错误**\n\n找不到地点 ")
+                .push_bold_safe(&location)
+                .build(),
+        )
+        .await?;
+        return Ok(());
+    };
+    if save.unwrap_or(false) {
+        db.prefs()
+            .set_weather_location(ctx.author().id, Some(location))
+            .await?;
+    }
+    let embed = CreateEmbed::new()
+        .title(format!("{} {}", weather.emoji, weather.location))
+        .description(weather.description)
+        .field("🌡️ 温度", format!("{:.1} °C", weather.temperature), true)
+        .field("🤒 体感", format!("{:.1} °C", weather.feels_like), true)
+        .field("💧 湿度", format!("{:.0}%", weather.humidity), true)
+        .field("🌬️ 风速", format!("{:.1} km/h", weather.wind_speed), true)
+        .color(0x7289DA)
+        .timestamp(Timestamp::now());
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use poise::{CreateReply, Modal, command};
+use serenity::all::*;
+
+use super::Context;
+use crate::{
+    config::{BotCfg, GuildCfg, PermissionLevel},
+    error::BotError,
+    utils::template::Template,
+};
+
+#[derive(Debug, Modal)]
+#[name = "欢迎消息"]
+pub(super) struct WelcomeModal {
+    #[name = "欢迎消息模板, 支持 {user} 和 {guild}"]
+    #[placeholder = "欢迎 {user} 加入 {guild}!"]
+    #[paragraph]
+    #[max_length = 2000]
+    pub message: String,
+}
+
+impl WelcomeModal {
+    /// Parse the submitted text, checking it only uses the welcome variables.
+    pub fn template(&self) -> Result<Template, String> {
+        let template = self.message.parse::<Template>()?;
+        template.validate(GuildCfg::WELCOME_VARS)?;
+        Ok(template)
+    }
+}
+
+#[command(
+    slash_command,
+    guild_only,
+    custom_data = "PermissionLevel::Admin",
+    name_localized("zh-CN", "欢迎消息"),
+    description_localized("zh-CN", "编辑新成员加入时的欢迎消息")
+)]
+/// Edits the message greeting new members.
+pub async fn welcome(
+    ctx: Context<'_>,
+    #[name_localized("zh-CN", "频道")]
+    #[description_localized("zh-CN", "发送欢迎消息的频道, 默认保持不变")]
+    #[description = "Channel receiving the greeting, unchanged by default"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> Result<(), BotError> {
+    let Context::Application(app_ctx) = ctx else {
+        unreachable!("welcome is a slash command");
+    };
+    let guild_id = ctx.guild_id().expect("welcome is guild only");
+    let current = ctx.data().cfg.load().guilds.get(&guild_id).cloned();
+    let Some(channel_id) = channel
+        .map(|c| c.id)
+        .or_else(|| current.as_ref().and_then(|g| g.welcome_channel_id))
+    else {
+        ctx.send(
+            CreateReply::default()
+                .content("❌ **错误**\n\n请指定发送欢迎消息的频道。")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+    let defaults = current
+        .and_then(|g| g.welcome_message)
+        .map(|message| WelcomeModal {
+            message: message.to_string(),
+        });
+    let Some(modal) = poise::execute_modal(app_ctx, defaults, None).await? else {
+        return Ok(()); // The user dismissed the modal
+    };
+    let template = match modal.template() {
+        Ok(template) => template,
+        Err(why) => {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!("❌ **错误**\n\n无效的欢迎消息模板: {why}"))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    ctx.data().cfg.rcu(|cfg| {
+        let mut cfg = BotCfg::clone(cfg);
+        let guild = cfg.guilds.entry(guild_id).or_default();
+        guild.welcome_channel_id = Some(channel_id);
+        guild.welcome_message = Some(template.to_owned());
+        cfg
+    });
+    if let Err(why) = ctx.data().cfg.load().write() {
+        ctx.say(format!("❌ **错误**\n\n无法更新配置文件: {why:?}"))
+            .await?;
+        return Err(why);
+    }
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "✅ **成功**\n\n欢迎消息已更新, 将发送到 {}。",
+                channel_id.mention()
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
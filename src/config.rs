@@ -6,20 +6,25 @@ use std::{
 };
 
 use arc_swap::ArcSwap;
+use chrono::NaiveTime;
 use figment::{
     Figment,
     providers::{Env, Format, Json},
 };
+use poise::ChoiceParameter;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
-use serde_with::{DurationSeconds, serde_as};
+use serde_with::{DurationSeconds, FromInto, PickFirst, serde_as};
 use serenity::{
-    all::{ChannelId, Context, GuildId, RoleId, UserId},
+    all::{ChannelId, Context, GuildId, Member, MessageId, ReactionType, RoleId, UserId},
     prelude::TypeMapKey,
 };
 use snafu::{OptionExt, ResultExt};
 
-use crate::error::BotError;
+use crate::{
+    error::BotError,
+    utils::{MAX_DURATION, template::Template},
+};
 
 #[serde_as]
 #[derive(Deserialize, Serialize, Debug, Default, Clone)]
@@ -33,14 +38,1084 @@ pub struct BotCfg {
     pub extra_admin_user_ids: Vec<UserId>,
     pub cookie_endpoint: Option<Url>,
     pub cookie_secret: String,
-    #[serde_as(as = "Vec<(_, DurationSeconds)>")]
-    pub tree_holes: HashMap<ChannelId, Duration>,
+    /// Tree holes from before they were configured per guild, moved into the section of their
+    /// guild once the cache knows it, see [`BotCfg::adopt_tree_holes`]
+    #[serde_as(as = "Vec<(_, PickFirst<(_, FromInto<u64>)>)>")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tree_holes: HashMap<ChannelId, TreeHoleCfg>,
+    /// Put every tree hole in dry-run mode, see [`TreeHoleCfg::dry_run`]
+    #[serde(default)]
+    pub tree_hole_dry_run: bool,
+    /// Channel receiving what tree holes in dry-run mode would delete, on top of the logs
+    #[serde(default)]
+    pub tree_hole_debug_channel_id: Option<ChannelId>,
     pub toilets: HashSet<ChannelId>,
     pub extra_owners: HashSet<UserId>,
+    #[serde(default)]
+    pub trivia: Vec<TriviaCfg>,
+    /// Piston compatible code execution endpoint
+    #[serde(default)]
+    pub sandbox_endpoint: Option<Url>,
+    /// Roles allowed to execute code through the sandbox
+    #[serde(default)]
+    pub trusted_role_ids: Vec<RoleId>,
+    /// Paste service receiving outputs too long for a message
+    #[serde(default)]
+    pub paste_endpoint: Option<Url>,
+    #[serde(default)]
+    pub weather: WeatherProvider,
+    #[serde(default)]
+    pub price_watches: Vec<PriceWatch>,
+    /// Polling interval of the price watches
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_price_interval")]
+    pub price_interval: Duration,
+    /// Steam Web API key used by `/gamestats`, optional for public endpoints
+    #[serde(default)]
+    pub steam_api_key: Option<String>,
+    #[serde(default)]
+    pub game_topic: Option<GameTopicCfg>,
+    #[serde(default)]
+    pub game_servers: Vec<GameServerCfg>,
+    #[serde(default)]
+    pub server_status: Option<ServerStatusCfg>,
+    /// Embed showing the bot's own status, edited in place
+    #[serde(default)]
+    pub bot_status: Option<BotStatusCfg>,
+    #[serde(default)]
+    pub github_releases: Vec<ReleaseWatch>,
+    /// Token raising the GitHub API rate limit of the release watcher
+    #[serde(default)]
+    pub github_token: Option<String>,
+    /// Polling interval of the release watcher
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_release_interval")]
+    pub release_interval: Duration,
+    /// Channel receiving operational alerts
+    #[serde(default)]
+    pub ops_channel_id: Option<ChannelId>,
+    /// Role pinged with critical alerts
+    #[serde(default)]
+    pub ops_role_id: Option<RoleId>,
+    #[serde(default)]
+    pub crash_loop: Option<CrashLoopCfg>,
+    #[serde(default)]
+    pub package_watches: Vec<PackageWatch>,
+    /// Polling interval of the package update watcher
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_package_interval")]
+    pub package_interval: Duration,
+    #[serde(default)]
+    pub domain_monitor: Option<DomainMonitorCfg>,
+    #[serde(default)]
+    pub uptime_monitors: Vec<UptimeMonitor>,
+    #[serde(default)]
+    pub backup: Option<BackupCfg>,
+    /// systemd units on the host shown by `/services`, e.g. `dc-bot.service`
+    #[serde(default)]
+    pub services: Vec<String>,
+    /// Usage quotas keyed by qualified command name, e.g. `run` or `weather`
+    #[serde(default)]
+    pub quotas: HashMap<String, QuotaCfg>,
+    /// Per-guild settings, written by `/setup`
+    #[serde_as(as = "Vec<(_, _)>")]
+    #[serde(default)]
+    pub guilds: HashMap<GuildId, GuildCfg>,
+    /// Minimum permission level per qualified command name, overriding the built-in default. The
+    /// level of a subcommand applies even if its parent requires more.
+    #[serde(default)]
+    pub command_levels: HashMap<String, PermissionLevel>,
+    /// Channels each qualified command name may or may not run in
+    #[serde(default)]
+    pub command_channels: HashMap<String, CommandChannels>,
+    /// Whether replies of a qualified command name are only shown to whoever ran it, overriding
+    /// the guild's `ephemeral`. Replies the command keeps private always stay private.
+    #[serde(default)]
+    pub command_ephemeral: HashMap<String, bool>,
+    /// Bot-maintained info messages by name, synced with `/board sync`
+    #[serde(default)]
+    pub boards: HashMap<String, BoardCfg>,
+    /// Channels whose messages are copied into an archive, keyed by source channel
+    #[serde(default)]
+    pub mirrors: HashMap<ChannelId, MirrorCfg>,
+    /// Twitch application credentials for go-live announcements
+    #[serde(default)]
+    pub twitch: Option<TwitchCfg>,
+    #[serde(default)]
+    pub youtube_api_key: Option<String>,
+    /// Creators whose new uploads and VODs are announced
+    #[serde(default)]
+    pub uploads: Vec<UploadWatch>,
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_release_interval")]
+    pub upload_interval: Duration,
+    /// Channels opened and closed on a recurring schedule
+    #[serde(default)]
+    pub office_hours: Vec<OfficeHoursCfg>,
+    /// Channel names and topics kept up to date from templates
+    #[serde(default)]
+    pub channel_labels: Vec<ChannelLabel>,
+    /// Affiliated guilds sharing their bans
+    #[serde(default)]
+    pub ban_sync: Vec<BanSyncGroup>,
+    /// Channels restricted to some languages, keyed by channel
+    #[serde(default)]
+    pub language_rules: HashMap<ChannelId, LanguageRule>,
+    /// Channels whose threads are renamed after their first message, unless named by hand
+    #[serde(default)]
+    pub thread_naming: HashSet<ChannelId>,
+    /// Actions taken on messages once enough members react with an emoji
+    #[serde(default)]
+    pub reaction_rules: Vec<ReactionRule>,
+    /// Conversation starters rotated into channels on a schedule
+    #[serde(default)]
+    pub topic_rotations: Vec<TopicRotation>,
     #[serde(skip)]
     pub path: PathBuf,
 }
 
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GuildCfg {
+    /// Channel receiving bot logs of this guild
+    #[serde(default)]
+    pub log_channel_id: Option<ChannelId>,
+    /// Channels whose messages are deleted after a while, a duration in seconds or a full
+    /// [`TreeHoleCfg`] each
+    #[serde_as(as = "Vec<(_, PickFirst<(_, FromInto<u64>)>)>")]
+    #[serde(default)]
+    pub tree_holes: HashMap<ChannelId, TreeHoleCfg>,
+    /// Bot admin roles of this guild, on top of `adminRoleIds`
+    #[serde(default)]
+    pub admin_role_ids: Vec<RoleId>,
+    #[serde(default)]
+    pub welcome_channel_id: Option<ChannelId>,
+    /// Greeting for new members, see [`GuildCfg::WELCOME_VARS`]
+    #[serde(default)]
+    pub welcome_message: Option<Template>,
+    #[serde(default)]
+    pub kudos: Option<KudosCfg>,
+    #[serde(default)]
+    pub voice_idle: Option<VoiceIdleCfg>,
+    #[serde(default)]
+    pub go_live: Option<GoLiveCfg>,
+    #[serde(default)]
+    pub join_gate: Option<JoinGateCfg>,
+    #[serde(default)]
+    pub link_scan: Option<LinkScanCfg>,
+    /// Channel receiving member reports of messages, reporting is disabled without it
+    #[serde(default)]
+    pub report_channel_id: Option<ChannelId>,
+    #[serde(default)]
+    pub invite_filter: Option<InviteFilterCfg>,
+    /// Roles granted nightly to members meeting activity criteria
+    #[serde(default)]
+    pub activity_roles: Vec<ActivityRole>,
+    /// DMs sent to new members over their first days, see [`GuildCfg::WELCOME_VARS`]
+    #[serde(default)]
+    pub drip: Vec<DripStep>,
+    /// Channel whose messages become suggestions members vote on
+    #[serde(default)]
+    pub suggestion_channel_id: Option<ChannelId>,
+    /// Mod channel holding the pinned `/todo` board and due date pings
+    #[serde(default)]
+    pub todo_channel_id: Option<ChannelId>,
+    #[serde(default)]
+    pub faq: Option<FaqCfg>,
+    #[serde(default)]
+    pub repeat_offenses: Option<RepeatOffenseCfg>,
+    /// Whether command replies are only shown to whoever ran the command, unless set per command.
+    /// Replies a command keeps private always stay private.
+    #[serde(default)]
+    pub ephemeral: Option<bool>,
+    /// Private channels of this guild always receiving the output of a qualified command name,
+    /// e.g. `growth`, wherever in the guild it is run
+    #[serde(default)]
+    pub command_output: HashMap<String, ChannelId>,
+}
+
+impl GuildCfg {
+    pub const WELCOME_VARS: &[&str] = &["user", "guild", "count"];
+}
+
+/// Peer recognition through a reaction emoji or `/kudos`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KudosCfg {
+    /// A unicode emoji, or the ID of a custom emoji
+    pub emoji: String,
+    /// Channel receiving the leaderboard of the past month on the first of each month
+    #[serde(default)]
+    pub channel_id: Option<ChannelId>,
+    /// Kudos a member may give with `/kudos` per day
+    #[serde(default = "default_kudos_daily_limit")]
+    pub daily_limit: u64,
+}
+
+/// Whether a reaction is `spec`, a unicode emoji or the ID of a custom emoji
+fn emoji_matches(spec: &str, emoji: &ReactionType) -> bool {
+    match emoji {
+        ReactionType::Unicode(s) => s == spec,
+        ReactionType::Custom { id, .. } => id.to_string() == spec,
+        _ => false,
+    }
+}
+
+impl KudosCfg {
+    pub fn matches(&self, emoji: &ReactionType) -> bool {
+        emoji_matches(&self.emoji, emoji)
+    }
+}
+
+fn default_kudos_daily_limit() -> u64 {
+    3
+}
+
+/// Disconnect members who stay muted and deafened in voice channels
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceIdleCfg {
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_voice_idle_timeout")]
+    pub timeout: Duration,
+    #[serde(default)]
+    pub exempt_role_ids: Vec<RoleId>,
+}
+
+fn default_voice_idle_timeout() -> Duration {
+    Duration::from_secs(1800)
+}
+
+/// Announce members going live on Discord and configured Twitch streamers
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GoLiveCfg {
+    pub channel_id: ChannelId,
+    #[serde(default)]
+    pub role_id: Option<RoleId>,
+    /// Minimum time between two announcements of the same streamer
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_go_live_cooldown")]
+    pub cooldown: Duration,
+    /// Twitch logins polled through the Helix API, requires `twitch`
+    #[serde(default)]
+    pub twitch_logins: Vec<String>,
+}
+
+/// Membership applications submitted with `/apply` and reviewed by moderators
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinGateCfg {
+    /// Channel receiving applications with approve and deny buttons
+    pub review_channel_id: ChannelId,
+    /// Role granted on approval
+    pub member_role_id: RoleId,
+    /// Up to five questions of at most 45 characters, as shown in the form
+    pub questions: Vec<String>,
+}
+
+/// Daily check of links and invites posted in resource channels
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkScanCfg {
+    pub channel_ids: Vec<ChannelId>,
+    /// Local time of the daily scan, in the configured time offset
+    pub time: NaiveTime,
+    /// Delete messages whose links are all dead instead of flagging them with a reaction
+    #[serde(default)]
+    pub remove: bool,
+    /// Recent messages scanned per channel
+    #[serde(default = "default_link_scan_limit")]
+    pub limit: usize,
+}
+
+/// Replies to questions in help channels that an FAQ entry already answers
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FaqCfg {
+    /// Channel whose messages are the FAQ entries, one per message
+    pub channel_id: ChannelId,
+    pub help_channel_ids: Vec<ChannelId>,
+    /// Keyword similarity from 0 to 1 a question needs to an entry to be answered with it
+    #[serde(default = "default_faq_min_score")]
+    pub min_score: f64,
+}
+
+fn default_faq_min_score() -> f64 {
+    0.5
+}
+
+/// Flags members who keep posting content the bot removed, e.g. the same spam across channels.
+/// Only a hash of the content is kept.
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RepeatOffenseCfg {
+    /// Removals of the same content by one member before it is reported to the log channel
+    #[serde(default = "default_offense_threshold")]
+    pub threshold: u32,
+    /// How long removals of the same content keep counting after the latest one
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_offense_window")]
+    pub window: Duration,
+}
+
+fn default_offense_threshold() -> u32 {
+    3
+}
+
+fn default_offense_window() -> Duration {
+    Duration::from_secs(7 * 24 * 3600)
+}
+
+/// Removal of invites to other servers, except the guild itself and its partners
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteFilterCfg {
+    /// Guilds whose invites are allowed, checked against the guild an invite resolves to
+    #[serde(default)]
+    pub partner_guild_ids: Vec<GuildId>,
+    /// Roles allowed to post any invite
+    #[serde(default)]
+    pub exempt_role_ids: Vec<RoleId>,
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DripStep {
+    /// Time after joining, 0 for right away
+    #[serde_as(as = "DurationSeconds")]
+    pub delay: Duration,
+    pub message: Template,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityRole {
+    pub role_id: RoleId,
+    #[serde(flatten)]
+    pub criterion: ActivityCriterion,
+    /// Take the role back from members who no longer meet the criterion
+    #[serde(default)]
+    pub revoke: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ActivityCriterion {
+    /// At least `count` messages, only in `channelId` if set, over the last `days` days if set
+    Messages {
+        #[serde(default)]
+        channel_id: Option<ChannelId>,
+        count: u64,
+        #[serde(default)]
+        days: Option<u32>,
+    },
+    /// A message on each of the last `days` days
+    Streak { days: u32 },
+    /// Joined the channel of at least `count` scheduled events while they were running
+    Events { count: u64 },
+}
+
+fn default_link_scan_limit() -> usize {
+    200
+}
+
+fn default_go_live_cooldown() -> Duration {
+    Duration::from_secs(3600)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TriviaCfg {
+    /// Channel receiving the daily trivia post
+    pub channel_id: ChannelId,
+    /// Local time of the post, in the configured time offset
+    pub time: NaiveTime,
+    pub rounds: u8,
+    /// Open Trivia DB category id, any category if unset
+    #[serde(default)]
+    pub category: Option<u32>,
+}
+
+/// Backend used by `/weather`
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(
+    tag = "provider",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum WeatherProvider {
+    /// Open-Meteo, free and keyless
+    #[default]
+    OpenMeteo,
+    OpenWeatherMap {
+        api_key: String,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, ChoiceParameter)]
+#[serde(rename_all = "camelCase")]
+pub enum Market {
+    #[name = "Crypto"]
+    #[name_localized("zh-CN", "加密货币")]
+    Crypto,
+    #[name = "Stock"]
+    #[name_localized("zh-CN", "股票")]
+    Stock,
+}
+
+/// Alert a channel when `symbol` crosses one of the thresholds
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceWatch {
+    pub symbol: String,
+    pub market: Market,
+    pub channel_id: ChannelId,
+    #[serde(default)]
+    pub above: Option<f64>,
+    #[serde(default)]
+    pub below: Option<f64>,
+    /// Role pinged with the alert
+    #[serde(default)]
+    pub role_id: Option<RoleId>,
+}
+
+/// Keep a channel topic updated with the live player count of a Steam game
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameTopicCfg {
+    pub app_id: u32,
+    pub channel_id: ChannelId,
+    /// Topic text, see [`GameTopicCfg::VARS`]
+    #[serde(default = "default_game_topic")]
+    pub template: Template,
+}
+
+impl GameTopicCfg {
+    pub const VARS: &[&str] = &["name", "players"];
+}
+
+fn default_game_topic() -> Template {
+    "🎮 {name}: {players} 人在线"
+        .parse()
+        .expect("Default game topic is a valid template")
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ServerKind {
+    /// Minecraft Java edition, queried with Server List Ping
+    Minecraft,
+    /// Source engine games, queried with `A2S_INFO`
+    Source,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameServerCfg {
+    pub name: String,
+    pub kind: ServerKind,
+    /// `host:port` of the server
+    pub address: String,
+}
+
+/// Where the game server status embed lives
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatusCfg {
+    pub channel_id: ChannelId,
+    /// Status message edited in place, created and saved on first run
+    #[serde(default)]
+    pub message_id: Option<MessageId>,
+    /// Role pinged when a server goes down or comes back
+    #[serde(default)]
+    pub role_id: Option<RoleId>,
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_server_interval")]
+    pub interval: Duration,
+}
+
+fn default_server_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BotStatusCfg {
+    pub channel_id: ChannelId,
+    /// Status message edited in place, created and saved on first run
+    #[serde(default)]
+    pub message_id: Option<MessageId>,
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_bot_status_interval")]
+    pub interval: Duration,
+}
+
+fn default_bot_status_interval() -> Duration {
+    Duration::from_secs(300)
+}
+
+/// Announce new releases of a GitHub repository in a channel
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseWatch {
+    /// `owner/name` of the repository
+    pub repo: String,
+    pub channel_id: ChannelId,
+    /// Watch tags instead, for repositories that do not publish releases
+    #[serde(default)]
+    pub tags: bool,
+    #[serde(default)]
+    pub prereleases: bool,
+    /// Role pinged with the announcement
+    #[serde(default)]
+    pub role_id: Option<RoleId>,
+}
+
+fn default_release_interval() -> Duration {
+    Duration::from_secs(900)
+}
+
+/// A package or container image whose new versions are announced to the ops channel
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(tag = "registry", rename_all = "camelCase")]
+pub enum PackageWatch {
+    Crates {
+        name: String,
+    },
+    Pypi {
+        name: String,
+    },
+    /// Image reference such as `nginx:latest` or `ghcr.io/owner/app:edge`, watched by digest
+    Docker {
+        image: String,
+    },
+}
+
+fn default_package_interval() -> Duration {
+    Duration::from_secs(3600)
+}
+
+/// Domains whose DNS, TLS certificate and registration are checked daily
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainMonitorCfg {
+    pub domains: Vec<String>,
+    /// Warn the ops channel this many days before an expiry
+    #[serde(default = "default_warn_days")]
+    pub warn_days: i64,
+}
+
+fn default_warn_days() -> i64 {
+    14
+}
+
+/// An HTTP endpoint polled for availability, alerting the ops channel on state changes
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UptimeMonitor {
+    pub name: String,
+    pub url: Url,
+    #[serde(default = "default_expected_status")]
+    pub expected_status: u16,
+    /// Text the response body must contain
+    #[serde(default)]
+    pub keyword: Option<String>,
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_server_interval")]
+    pub interval: Duration,
+}
+
+fn default_expected_status() -> u16 {
+    200
+}
+
+/// Alert on the ops channel when the bot keeps restarting
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashLoopCfg {
+    /// Starts within `window` tolerated before alerting
+    #[serde(default = "default_crash_loop_restarts")]
+    pub restarts: usize,
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default = "default_crash_loop_window")]
+    pub window: Duration,
+}
+
+fn default_crash_loop_restarts() -> usize {
+    3
+}
+
+fn default_crash_loop_window() -> Duration {
+    Duration::from_secs(10 * 60)
+}
+
+/// Daily database backups
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupCfg {
+    /// Local time of the daily backup, in the configured time offset
+    pub time: NaiveTime,
+    /// Number of backups kept, at least 1, older ones are deleted
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+    pub target: BackupTarget,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum BackupTarget {
+    /// A directory on the host
+    Local { path: PathBuf },
+    /// An S3 compatible bucket, addressed path-style
+    S3 {
+        endpoint: Url,
+        bucket: String,
+        #[serde(default = "default_region")]
+        region: String,
+        access_key: String,
+        secret_key: String,
+        /// Key prefix of the backups, e.g. `backups/`
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+fn default_region() -> String {
+    "us-east-1".into()
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaCfg {
+    /// Uses allowed per period
+    pub limit: u64,
+    #[serde_as(as = "DurationSeconds")]
+    pub period: Duration,
+    #[serde(default)]
+    pub scope: QuotaScope,
+}
+
+/// Who a quota is counted against
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum QuotaScope {
+    #[default]
+    User,
+    Guild,
+}
+
+/// Who may run a command, ordered from least to most privileged
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionLevel {
+    #[default]
+    Everyone,
+    /// `extraAdminUserIds`, or members with a global or per-guild admin role
+    Admin,
+    /// The application owner and `extraOwners`
+    Owner,
+}
+
+impl std::fmt::Display for PermissionLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Everyone => "所有人",
+            Self::Admin => "管理员",
+            Self::Owner => "所有者",
+        })
+    }
+}
+
+/// An info message such as the rules, kept in sync with an embed source file
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BoardCfg {
+    pub channel_id: ChannelId,
+    /// JSON or YAML embed source, as accepted by `/embed post`
+    pub path: PathBuf,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TwitchCfg {
+    pub client_id: String,
+    pub client_secret: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadWatch {
+    pub creator: Creator,
+    pub channel_id: ChannelId,
+    #[serde(default)]
+    pub role_id: Option<RoleId>,
+    /// Announcement text, see [`UploadWatch::VARS`]
+    #[serde(default = "default_upload_template")]
+    pub template: Template,
+}
+
+impl UploadWatch {
+    pub const VARS: &[&str] = &["creator", "title", "url"];
+}
+
+fn default_upload_template() -> Template {
+    "📺 **{creator}** 发布了新视频: {title}\n{url}"
+        .parse()
+        .expect("Default upload template is a valid template")
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(
+    tag = "platform",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Creator {
+    /// Past broadcasts of a Twitch channel, requires `twitch`
+    Twitch { login: String },
+    /// Uploads of a YouTube channel, requires `youtubeApiKey`
+    Youtube { channel_id: String },
+}
+
+/// Channels unlocked for `@everyone` by `open` and locked again by `close`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OfficeHoursCfg {
+    pub name: String,
+    pub channel_ids: Vec<ChannelId>,
+    /// Cron expression with seconds, e.g. `0 0 20 * * Fri`, in the configured time offset
+    pub open: cron::Schedule,
+    pub close: cron::Schedule,
+    /// Channel receiving the open and close announcements
+    #[serde(default)]
+    pub announce_channel_id: Option<ChannelId>,
+    #[serde(default)]
+    pub open_message: Option<String>,
+    #[serde(default)]
+    pub close_message: Option<String>,
+}
+
+/// A channel taking the next of `topics` on every tick of `schedule`, without repeats until all were used
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicRotation {
+    pub channel_id: ChannelId,
+    /// Cron expression with seconds, e.g. `0 0 9 * * *`, in the configured time offset
+    pub schedule: cron::Schedule,
+    pub topics: Vec<String>,
+    /// Post and pin the topic as a message, replacing the previous pin, instead of setting the
+    /// channel topic
+    #[serde(default)]
+    pub pin: bool,
+}
+
+/// A channel whose name and/or topic is rendered from live stats, see [`ChannelLabel::VARS`]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelLabel {
+    pub channel_id: ChannelId,
+    #[serde(default)]
+    pub name: Option<Template>,
+    #[serde(default)]
+    pub topic: Option<Template>,
+}
+
+impl ChannelLabel {
+    pub const VARS: &[&str] = &[
+        "members",
+        "online",
+        "next_event",
+        "servers_up",
+        "servers_total",
+        "players",
+    ];
+}
+
+/// Guilds whose bans and unbans are propagated to each other
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BanSyncGroup {
+    pub guild_ids: Vec<GuildId>,
+    /// Users never banned or unbanned through the sync, e.g. alts of staff
+    #[serde(default)]
+    pub exclude_user_ids: Vec<UserId>,
+    #[serde(default)]
+    pub sync_unbans: bool,
+    /// Only log what would be propagated to each guild's log channel
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Copy of a channel's messages, typically into a read-only channel or thread, so tree holes
+/// keep a permanent record
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorCfg {
+    /// Archive channel or thread
+    pub target: ChannelId,
+    /// Leave out who sent each message
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReactionRule {
+    pub channel_ids: Vec<ChannelId>,
+    /// A unicode emoji, or the ID of a custom emoji
+    pub emoji: String,
+    /// Distinct members whose reactions trigger the action, the author excluded
+    pub threshold: usize,
+    pub action: ReactionAction,
+    /// Reactions of members who joined more recently are ignored, so fresh alts cannot vote
+    #[serde_as(as = "DurationSeconds")]
+    #[serde(default)]
+    pub min_member_age: Duration,
+    /// Messages of these roles are never acted on
+    #[serde(default)]
+    pub exempt_role_ids: Vec<RoleId>,
+}
+
+impl ReactionRule {
+    pub fn matches(&self, emoji: &ReactionType) -> bool {
+        emoji_matches(&self.emoji, emoji)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReactionAction {
+    /// Delete the message and report it to the guild's log channel
+    Delete,
+    Pin,
+}
+
+/// Deletion policy of a tree hole channel. A bare number of seconds sets the duration alone.
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeHoleCfg {
+    /// How long messages stay before they are deleted
+    #[serde_as(as = "DurationSeconds")]
+    pub duration: Duration,
+    /// Messages of members with these roles are kept, e.g. moderators
+    #[serde(default)]
+    pub exempt_role_ids: Vec<RoleId>,
+    /// Messages of these users are kept, e.g. bots posting notices
+    #[serde(default)]
+    pub exempt_user_ids: Vec<UserId>,
+    #[serde(default)]
+    pub warning: Option<TreeHoleWarning>,
+    /// Channel receiving a copy of every message before it is deleted, as an audit trail
+    #[serde(default)]
+    pub archive_channel_id: Option<ChannelId>,
+    #[serde(default)]
+    pub keep: Option<TreeHoleKeep>,
+    /// Keep only the newest this many messages, trimming older ones as new ones arrive. The
+    /// duration still applies, whichever removes a message first.
+    #[serde(default)]
+    pub max_messages: Option<usize>,
+    /// Delete messages without images or videos right away, e.g. in meme or art channels.
+    /// Media posts expire after the duration as usual.
+    #[serde(default)]
+    pub media_only: bool,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// Append a live "disappears in" timestamp to the bot's own messages, e.g. mirrored posts
+    #[serde(default)]
+    pub countdown: bool,
+    /// Delete messages right away and repost them through a webhook under a pseudonym, which
+    /// changes daily, so not even their authors show. The mirrors then expire as usual.
+    #[serde(default)]
+    pub anonymous: bool,
+    /// Messages with any of these kinds of content are kept, so shared resources outlive chatter
+    #[serde(default)]
+    pub keep_content: Vec<TreeHoleContent>,
+    /// Keep messages posted through webhooks, e.g. feeds and integrations. Mirrors of an
+    /// anonymous tree hole follow the policy regardless.
+    #[serde(default)]
+    pub keep_webhooks: bool,
+    /// Keep messages of other bots. The bot's own messages follow the policy regardless.
+    #[serde(default)]
+    pub keep_bots: bool,
+    /// What editing a message does to its deletion, so edits cannot revive expiring posts.
+    /// Without it edits leave the deadline alone.
+    #[serde(default)]
+    pub on_edit: Option<TreeHoleEdit>,
+    /// A reply to a message, or a message in a thread started from it, keeps it at least this
+    /// long after, so ongoing conversations are not cut off
+    #[serde_as(as = "Option<DurationSeconds>")]
+    #[serde(default)]
+    pub reply_grace: Option<Duration>,
+    /// Only report which messages would be deleted and when, to try out a policy on an existing
+    /// channel. Members see no warnings or countdowns meanwhile.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// How a tree hole treats an edited message, e.g. `"delete"` or `{ "shorten": 300 }`
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TreeHoleEdit {
+    /// The full duration starts over from the edit
+    Reset,
+    /// The message goes at most this long after the edit
+    Shorten(#[serde_as(as = "DurationSeconds")] Duration),
+    /// The message goes right away
+    Delete,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TreeHoleContent {
+    Attachments,
+    /// Attachments that are images
+    Images,
+    Links,
+    Embeds,
+}
+
+/// Daily window without tree hole deletions, in the configured time offset, so members are not
+/// woken by mass deletes. Deletions due meanwhile happen once it ends. `start` after `end`
+/// spans midnight, e.g. 23:00 to 08:00.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// Lets trusted members keep a message by reacting to it, without taking up a pin
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeHoleKeep {
+    /// Members with one of these roles can keep messages
+    pub role_ids: Vec<RoleId>,
+    /// Unicode emoji or custom emoji ID
+    #[serde(default = "default_keep_emoji")]
+    pub emoji: String,
+}
+
+fn default_keep_emoji() -> String {
+    "📌".to_owned()
+}
+
+impl TreeHoleKeep {
+    pub fn matches(&self, emoji: &ReactionType) -> bool {
+        emoji_matches(&self.emoji, emoji)
+    }
+}
+
+/// Heads-up before a tree hole message is deleted, so its author can copy it or ask for a pin
+#[serde_as]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeHoleWarning {
+    #[serde_as(as = "DurationSeconds")]
+    pub before: Duration,
+    /// Reply with a notice, deleted along with the message, instead of reacting with ⏳
+    #[serde(default)]
+    pub reply: bool,
+}
+
+impl From<u64> for TreeHoleCfg {
+    fn from(secs: u64) -> Self {
+        Self {
+            duration: Duration::from_secs(secs),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<TreeHoleCfg> for u64 {
+    fn from(cfg: TreeHoleCfg) -> Self {
+        cfg.duration.as_secs()
+    }
+}
+
+impl TreeHoleCfg {
+    /// The policy as applied, with `dry_run` forced on by `treeHoleDryRun`
+    pub fn applied(&self, dry_run: bool) -> Self {
+        let mut hole = self.to_owned();
+        if hole.dry_run || dry_run {
+            hole.dry_run = true;
+            hole.warning = None;
+            hole.countdown = false;
+        }
+        hole
+    }
+
+    /// Whether messages of a user with `roles` are kept
+    pub fn exempts(&self, user_id: UserId, roles: &[RoleId]) -> bool {
+        self.exempt_user_ids.contains(&user_id)
+            || roles.iter().any(|r| self.exempt_role_ids.contains(r))
+    }
+}
+
+/// Languages expected in a channel, detected per message
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageRule {
+    /// ISO 639-3 codes, e.g. `eng` or `cmn`
+    pub languages: Vec<whatlang::Lang>,
+    /// Detection confidence from 0 to 1 required to act on a message
+    #[serde(default = "default_language_confidence")]
+    pub min_confidence: f64,
+    /// Shorter messages are too ambiguous to judge
+    #[serde(default = "default_language_min_chars")]
+    pub min_chars: usize,
+    /// Delete off-language messages instead of replying with a reminder
+    #[serde(default)]
+    pub remove: bool,
+}
+
+fn default_language_confidence() -> f64 {
+    0.8
+}
+
+fn default_language_min_chars() -> usize {
+    20
+}
+
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandChannels {
+    /// Only these channels, and threads in them, if not empty
+    #[serde(default)]
+    pub allow: Vec<ChannelId>,
+    #[serde(default)]
+    pub deny: Vec<ChannelId>,
+}
+
+fn default_price_interval() -> Duration {
+    Duration::from_secs(300)
+}
+
 impl TypeMapKey for BotCfg {
     type Value = Arc<ArcSwap<BotCfg>>;
 }
@@ -62,13 +1137,143 @@ impl GetCfg for Context {
 
 impl BotCfg {
     pub fn read(path: impl AsRef<Path>) -> Result<Self, BotError> {
-        Ok(Self {
+        let cfg = Self {
             path: path.as_ref().to_owned(),
             ..Figment::new()
                 .merge(Json::file(path))
                 .merge(Env::prefixed("DOG_BOT_"))
                 .extract_lossy()
                 .whatever_context::<&str, BotError>("Failed to read bot configuration")?
+        };
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    /// Check the configuration for mistakes that would only show once it is used, e.g. templates
+    /// using variables unavailable where they are rendered or impossible limits.
+    fn validate(&self) -> Result<(), BotError> {
+        for (guild_id, guild) in &self.guilds {
+            if let Some(template) = &guild.welcome_message
+                && let Err(why) = template.validate(GuildCfg::WELCOME_VARS)
+            {
+                snafu::whatever!("Invalid welcome message of guild {guild_id}: {why}");
+            }
+            for step in &guild.drip {
+                if let Err(why) = step.message.validate(GuildCfg::WELCOME_VARS) {
+                    snafu::whatever!("Invalid drip message of guild {guild_id}: {why}");
+                }
+            }
+            if let Some(gate) = &guild.join_gate
+                && (gate.questions.is_empty()
+                    || gate.questions.len() > 5
+                    || gate.questions.iter().any(|q| q.chars().count() > 45))
+            {
+                snafu::whatever!(
+                    "Join gate of guild {guild_id} needs 1 to 5 questions of at most 45 characters"
+                );
+            }
+        }
+        if let Some(topic) = &self.game_topic
+            && let Err(why) = topic.template.validate(GameTopicCfg::VARS)
+        {
+            snafu::whatever!("Invalid game topic template: {why}");
+        }
+        for label in &self.channel_labels {
+            for template in label.name.iter().chain(&label.topic) {
+                if let Err(why) = template.validate(ChannelLabel::VARS) {
+                    snafu::whatever!("Invalid label of channel {}: {why}", label.channel_id);
+                }
+            }
+        }
+        let mut synced = HashSet::new();
+        for group in &self.ban_sync {
+            if let Some(guild_id) = group.guild_ids.iter().find(|id| !synced.insert(**id)) {
+                snafu::whatever!("Guild {guild_id} is in more than one ban sync group");
+            }
+        }
+        if let Some(rotation) = self.topic_rotations.iter().find(|r| r.topics.is_empty()) {
+            snafu::whatever!("Topic rotation of {} has no topics", rotation.channel_id);
+        }
+        if let Some((channel_id, _)) = self
+            .all_tree_holes()
+            .map(|(_, channel_id, hole)| (channel_id, hole))
+            .chain(&self.tree_holes)
+            .find(|(_, hole)| hole.max_messages == Some(0))
+        {
+            snafu::whatever!("Tree hole {channel_id} keeps at most 0 messages");
+        }
+        if let Some((channel_id, _)) = self
+            .all_tree_holes()
+            .map(|(_, channel_id, hole)| (channel_id, hole))
+            .chain(&self.tree_holes)
+            .find(|(_, hole)| hole.duration > MAX_DURATION.to_std().unwrap())
+        {
+            snafu::whatever!("Tree hole {channel_id} keeps messages longer than ten years");
+        }
+        if self.backup.as_ref().is_some_and(|backup| backup.keep == 0) {
+            snafu::whatever!("Backups must keep at least the latest one");
+        }
+        if let Some(rule) = self.reaction_rules.iter().find(|r| r.threshold == 0) {
+            snafu::whatever!("Reaction rule for {} has a threshold of 0", rule.emoji);
+        }
+        for watch in &self.uploads {
+            if let Err(why) = watch.template.validate(UploadWatch::VARS) {
+                snafu::whatever!("Invalid upload template of {:?}: {why}", watch.creator);
+            }
+        }
+        Ok(())
+    }
+
+    /// Tree holes of all guilds
+    pub fn all_tree_holes(&self) -> impl Iterator<Item = (GuildId, &ChannelId, &TreeHoleCfg)> {
+        self.guilds.iter().flat_map(|(guild_id, guild)| {
+            guild
+                .tree_holes
+                .iter()
+                .map(|(channel_id, hole)| (*guild_id, channel_id, hole))
+        })
+    }
+
+    /// The tree hole configured for a channel in any guild
+    pub fn tree_hole(&self, channel_id: ChannelId) -> Option<&TreeHoleCfg> {
+        self.guilds
+            .values()
+            .find_map(|guild| guild.tree_holes.get(&channel_id))
+    }
+
+    /// Move tree holes from the old top-level map into the sections of their guilds, as far as
+    /// `guild_of` knows them. Returns whether any moved.
+    pub fn adopt_tree_holes(&mut self, guild_of: impl Fn(ChannelId) -> Option<GuildId>) -> bool {
+        let adopted = self
+            .tree_holes
+            .keys()
+            .filter_map(|channel_id| Some((*channel_id, guild_of(*channel_id)?)))
+            .collect::<Vec<_>>();
+        for (channel_id, guild_id) in &adopted {
+            let hole = self.tree_holes.remove(channel_id).unwrap();
+            self.guilds
+                .entry(*guild_id)
+                .or_default()
+                .tree_holes
+                .insert(*channel_id, hole);
+        }
+        !adopted.is_empty()
+    }
+
+    /// Whether a user is a bot admin through `extraAdminUserIds` or a global or per-guild admin
+    /// role, or an owner through `extraOwners`. The application owner is not known here, see
+    /// [`crate::utils::owners`].
+    pub fn is_admin(&self, user_id: UserId, member: Option<&Member>) -> bool {
+        if self.extra_admin_user_ids.contains(&user_id) || self.extra_owners.contains(&user_id) {
+            return true;
+        }
+        let Some(member) = member else {
+            return false;
+        };
+        let guild_roles = self.guilds.get(&member.guild_id).map(|g| &g.admin_role_ids);
+        member.roles.iter().any(|role| {
+            self.admin_role_ids.contains(role)
+                || guild_roles.is_some_and(|roles| roles.contains(role))
         })
     }
 
@@ -61,4 +61,15 @@ impl BotDatabase {
             Ok(0)
         }
     }
+
+    /// Write a consistent copy of the database to `dest`, which must not exist yet.
+    pub async fn snapshot(&self, dest: impl AsRef<Path>) -> Result<(), BotError> {
+        let stmt = Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "VACUUM INTO ?",
+            [dest.as_ref().display().to_string().into()],
+        );
+        self.db.execute(stmt).await?;
+        Ok(())
+    }
 }
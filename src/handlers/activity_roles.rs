@@ -0,0 +1,312 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, NaiveTime, TimeDelta, Utc};
+use dashmap::DashMap;
+use futures::StreamExt;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    config::{ActivityCriterion, ActivityRole, GetCfg},
+    database::{BotDatabase, GetDb},
+    error::BotError,
+    utils::{
+        alert::{Severity, guild_log},
+        schedule,
+    },
+};
+
+/// Grants roles for activity nightly and records who attends scheduled events
+#[derive(Default)]
+pub struct ActivityRoleHandler {
+    started: AtomicBool,
+    /// Channels of running scheduled events
+    running: DashMap<ChannelId, (GuildId, ScheduledEventId)>,
+}
+
+/// Length of the run of consecutive active days ending on `last`
+fn streak(days: &HashSet<NaiveDate>, last: NaiveDate) -> u32 {
+    (0..)
+        .take_while(|i| {
+            last.checked_sub_days(Days::new(*i))
+                .is_some_and(|day| days.contains(&day))
+        })
+        .count() as u32
+}
+
+/// Members of a guild meeting `criterion` as of the end of yesterday
+async fn qualifying(
+    db: &BotDatabase,
+    guild_id: GuildId,
+    criterion: &ActivityCriterion,
+    now: DateTime<Utc>,
+    offset: FixedOffset,
+) -> Result<HashSet<UserId>, BotError> {
+    Ok(match criterion {
+        ActivityCriterion::Messages {
+            channel_id,
+            count,
+            days,
+        } => db
+            .message()
+            .get_user_stats(
+                guild_id,
+                channel_id.as_ref().map(std::slice::from_ref),
+                days.map(|days| now - TimeDelta::days(days.into())),
+                None::<DateTime<Utc>>,
+            )
+            .await?
+            .into_iter()
+            .filter(|(_, messages)| messages >= count)
+            .map(|(user_id, _)| user_id)
+            .collect(),
+        ActivityCriterion::Streak { days } => {
+            let yesterday = now.with_timezone(&offset).date_naive() - Days::new(1);
+            let mut active = HashMap::<UserId, HashSet<NaiveDate>>::new();
+            for (user_id, at) in db
+                .message()
+                .get_activity(guild_id, now - TimeDelta::days(i64::from(*days) + 1))
+                .await?
+            {
+                active
+                    .entry(user_id)
+                    .or_default()
+                    .insert(at.with_timezone(&offset).date_naive());
+            }
+            active
+                .into_iter()
+                .filter(|(_, active)| streak(active, yesterday) >= *days)
+                .map(|(user_id, _)| user_id)
+                .collect()
+        }
+        ActivityCriterion::Events { count } => db
+            .events()
+            .attendance(guild_id)
+            .await?
+            .into_iter()
+            .filter(|(_, events)| events >= count)
+            .map(|(user_id, _)| user_id)
+            .collect(),
+    })
+}
+
+/// Grant and revoke the activity roles of a guild, returning how many roles changed hands.
+async fn evaluate(
+    ctx: &Context,
+    guild_id: GuildId,
+    rules: &[ActivityRole],
+    offset: FixedOffset,
+) -> Result<(usize, usize), BotError> {
+    let db = ctx.db().await?;
+    let now = Utc::now();
+    let mut qualified = Vec::with_capacity(rules.len());
+    for rule in rules {
+        qualified.push(qualifying(&db, guild_id, &rule.criterion, now, offset).await?);
+    }
+    let (mut granted, mut revoked) = (0, 0);
+    let mut members = guild_id.members_iter(ctx).boxed();
+    while let Some(member) = members.next().await {
+        let member = member?;
+        if member.user.bot {
+            continue;
+        }
+        for (rule, qualified) in rules.iter().zip(&qualified) {
+            let has_role = member.roles.contains(&rule.role_id);
+            let qualifies = qualified.contains(&member.user.id);
+            // One member above the bot's role must not hold up everyone else
+            if qualifies && !has_role {
+                match member.add_role(ctx, rule.role_id).await {
+                    Ok(()) => granted += 1,
+                    Err(e) => error!(
+                        "Failed to grant role {} to {}: {e}",
+                        rule.role_id, member.user.name
+                    ),
+                }
+            } else if !qualifies && has_role && rule.revoke {
+                match member.remove_role(ctx, rule.role_id).await {
+                    Ok(()) => revoked += 1,
+                    Err(e) => error!(
+                        "Failed to revoke role {} from {}: {e}",
+                        rule.role_id, member.user.name
+                    ),
+                }
+            }
+        }
+    }
+    Ok((granted, revoked))
+}
+
+async fn evaluate_all(ctx: &Context) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?.load_full();
+    let offset = FixedOffset::east_opt(cfg.time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    for (&guild_id, guild) in cfg
+        .guilds
+        .iter()
+        .filter(|(_, g)| !g.activity_roles.is_empty())
+    {
+        match evaluate(ctx, guild_id, &guild.activity_roles, offset).await {
+            Ok((0, 0)) => {}
+            Ok((granted, revoked)) => {
+                info!("Activity roles of guild {guild_id}: {granted} granted, {revoked} revoked");
+                // A broken log channel must not keep the other guilds from being evaluated
+                if let Err(e) = guild_log(
+                    ctx,
+                    guild_id,
+                    Severity::Info,
+                    "活跃身份组已更新",
+                    format!("授予 {granted} 个, 收回 {revoked} 个。"),
+                )
+                .await
+                {
+                    error!("Failed to log activity roles of guild {guild_id}: {e}");
+                }
+            }
+            Err(e) => error!("Failed to evaluate activity roles of guild {guild_id}: {e}"),
+        }
+    }
+    Ok(())
+}
+
+impl ActivityRoleHandler {
+    /// Start tracking a running event, counting the members already in its channel.
+    async fn track(&self, ctx: &Context, event: &ScheduledEvent) -> Result<(), BotError> {
+        let Some(channel_id) = event.channel_id else {
+            return Ok(()); // External events have no channel to attend
+        };
+        self.running.insert(channel_id, (event.guild_id, event.id));
+        let present = ctx
+            .cache
+            .guild(event.guild_id)
+            .map(|g| {
+                g.voice_states
+                    .values()
+                    .filter(|v| v.channel_id == Some(channel_id))
+                    .map(|v| v.user_id)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let db = ctx.db().await?;
+        for user_id in present {
+            db.events()
+                .attend(event.guild_id, event.id, user_id)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a guild rewards event attendance, so its events are worth tracking
+async fn tracks_events(ctx: &Context, guild_id: GuildId) -> Result<bool, BotError> {
+    Ok(ctx
+        .cfg()
+        .await?
+        .load()
+        .guilds
+        .get(&guild_id)
+        .is_some_and(|g| {
+            g.activity_roles
+                .iter()
+                .any(|r| matches!(r.criterion, ActivityCriterion::Events { .. }))
+        }))
+}
+
+#[async_trait]
+impl EventHandler for ActivityRoleHandler {
+    async fn guild_scheduled_event_update(&self, ctx: Context, event: ScheduledEvent) {
+        match event.status {
+            ScheduledEventStatus::Active => match tracks_events(&ctx, event.guild_id).await {
+                Ok(true) => {
+                    if let Err(e) = self.track(&ctx, &event).await {
+                        error!("Failed to track event {}: {e}", event.id);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => error!("Failed to get bot configuration: {e}"),
+            },
+            _ => {
+                if let Some(channel_id) = event.channel_id {
+                    self.running
+                        .remove_if(&channel_id, |_, (_, id)| *id == event.id);
+                }
+            }
+        }
+    }
+
+    async fn voice_state_update(&self, ctx: Context, _old: Option<VoiceState>, new: VoiceState) {
+        let Some((guild_id, event_id)) = new
+            .channel_id
+            .and_then(|channel_id| self.running.get(&channel_id).map(|e| *e))
+        else {
+            return;
+        };
+        let res = async {
+            ctx.db()
+                .await?
+                .events()
+                .attend(guild_id, event_id, new.user_id)
+                .await
+        };
+        if let Err(e) = res.await {
+            error!("Failed to record attendance of event {event_id}: {e}");
+        }
+    }
+
+    async fn cache_ready(&self, ctx: Context, guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return; // Already scheduled
+        }
+        // Events may have started while the bot was offline
+        for guild_id in guilds {
+            if !tracks_events(&ctx, guild_id).await.unwrap_or_default() {
+                continue;
+            }
+            match guild_id.scheduled_events(&ctx, false).await {
+                Ok(events) => {
+                    for event in events
+                        .iter()
+                        .filter(|e| e.status == ScheduledEventStatus::Active)
+                    {
+                        if let Err(e) = self.track(&ctx, event).await {
+                            error!("Failed to track event {}: {e}", event.id);
+                        }
+                    }
+                }
+                Err(e) => error!("Failed to get scheduled events of guild {guild_id}: {e}"),
+            }
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load();
+        let offset = FixedOffset::east_opt(cfg.time_offset)
+            .expect("Failed to create FixedOffset with the configured time offset");
+        info!("Scheduling nightly activity roles");
+        schedule::daily(NaiveTime::MIN, offset, move || {
+            let ctx = ctx.to_owned();
+            async move {
+                if let Err(e) = evaluate_all(&ctx).await {
+                    error!("Failed to evaluate activity roles: {e}");
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_streak() {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let days = HashSet::from([day(10), day(11), day(12), day(8)]);
+        assert_eq!(streak(&days, day(12)), 3);
+        assert_eq!(streak(&days, day(13)), 0);
+        assert_eq!(streak(&days, day(8)), 1);
+    }
+}
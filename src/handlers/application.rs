@@ -0,0 +1,134 @@
+use serenity::all::{
+    colours::branding::{GREEN, RED},
+    *,
+};
+use tracing::error;
+
+use crate::{
+    commands::apply::{APPROVE_PREFIX, DENY_PREFIX},
+    config::GetCfg,
+    database::GetDb,
+    error::BotError,
+    utils::owners,
+};
+
+/// Handles the approve and deny buttons of join applications, which outlive restarts
+pub struct ApplicationHandler;
+
+async fn respond(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    content: &str,
+) -> Result<(), BotError> {
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn review(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    id: i32,
+    approved: bool,
+) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?.load_full();
+    if !cfg.is_admin(interaction.user.id, interaction.member.as_ref())
+        && !owners(ctx).await?.contains(&interaction.user.id)
+    {
+        return respond(ctx, interaction, "❌ **错误**\n\n只有管理员可以审核申请。").await;
+    }
+    let db = ctx.db().await?;
+    let Some(application) = db.applications().get(id).await? else {
+        return respond(ctx, interaction, "❌ **错误**\n\n申请不存在。").await;
+    };
+    let guild_id = application.guild_id();
+    let Some(gate) = cfg.guilds.get(&guild_id).and_then(|g| g.join_gate.as_ref()) else {
+        return respond(ctx, interaction, "❌ **错误**\n\n本服务器未开放入服申请。").await;
+    };
+    if !db
+        .applications()
+        .review(id, interaction.user.id, approved)
+        .await?
+    {
+        return respond(
+            ctx,
+            interaction,
+            "❌ **错误**\n\n该申请已被其他管理员处理。",
+        )
+        .await;
+    }
+    let user_id = application.user_id();
+    let reason = format!("入服申请 #{id} 由 {} 审核", interaction.user.name);
+    let outcome = if approved {
+        ctx.http
+            .add_member_role(guild_id, user_id, gate.member_role_id, Some(&reason))
+            .await
+            .map(|_| "✅ 已通过")
+    } else {
+        guild_id
+            .kick_with_reason(ctx, user_id, &reason)
+            .await
+            .map(|_| "⛔ 已拒绝并踢出")
+    };
+    let outcome = match outcome {
+        Ok(outcome) => outcome.to_string(),
+        // The member may have left in the meantime, the review is recorded regardless
+        Err(why) => format!("⚠️ 已记录, 但操作失败: {why}"),
+    };
+    let mut embed = interaction
+        .message
+        .embeds
+        .first()
+        .cloned()
+        .map(CreateEmbed::from)
+        .unwrap_or_default();
+    embed = embed
+        .field(
+            "审核结果",
+            format!("{outcome} ({})", interaction.user.mention()),
+            false,
+        )
+        .color(if approved { GREEN } else { RED });
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for ApplicationHandler {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Component(interaction) = interaction else {
+            return;
+        };
+        let custom_id = &interaction.data.custom_id;
+        let (id, approved) = if let Some(id) = custom_id.strip_prefix(APPROVE_PREFIX) {
+            (id, true)
+        } else if let Some(id) = custom_id.strip_prefix(DENY_PREFIX) {
+            (id, false)
+        } else {
+            return;
+        };
+        let Ok(id) = id.parse() else {
+            return;
+        };
+        if let Err(e) = review(&ctx, &interaction, id, approved).await {
+            error!("Failed to review application #{id}: {e}");
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::FixedOffset;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::backup::run_backup,
+    config::GetCfg,
+    database::GetDb,
+    utils::{
+        alert::{Severity, alert},
+        schedule,
+    },
+};
+
+#[derive(Default)]
+pub struct BackupHandler {
+    started: AtomicBool,
+}
+
+#[async_trait]
+impl EventHandler for BackupHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load_full();
+        let Some(backup) = cfg.backup.to_owned() else {
+            return;
+        };
+        let offset = FixedOffset::east_opt(cfg.time_offset)
+            .expect("Failed to create FixedOffset with the configured time offset");
+        info!("Scheduling daily database backup at {}", backup.time);
+        schedule::daily(backup.time, offset, move || {
+            let ctx = ctx.to_owned();
+            let backup = backup.to_owned();
+            async move {
+                let result = match ctx.db().await {
+                    Ok(db) => run_backup(&db, &backup).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(name) => info!("Database backed up as {name}"),
+                    Err(e) => {
+                        error!("Failed to back up database: {e}");
+                        if let Err(e) =
+                            alert(&ctx, Severity::Critical, "数据库备份失败", e.to_string()).await
+                        {
+                            error!("Failed to send backup alert: {e}");
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
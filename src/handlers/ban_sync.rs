@@ -0,0 +1,143 @@
+use std::{
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    config::GetCfg,
+    error::BotError,
+    utils::alert::{Severity, guild_log},
+};
+
+/// How long a ban or unban made by the sync is recognized when its event comes back
+const ECHO_WINDOW: Duration = Duration::from_secs(60);
+
+/// Softbans in progress, whose ban and unban both stay in their guild
+static SOFTBANS: LazyLock<DashMap<(GuildId, UserId), Instant>> = LazyLock::new(DashMap::new);
+
+/// Keep the ban and unban of a softban from being propagated, called before banning.
+pub fn expect_softban(guild_id: GuildId, user_id: UserId) {
+    SOFTBANS.retain(|_, at| at.elapsed() < ECHO_WINDOW);
+    SOFTBANS.insert((guild_id, user_id), Instant::now());
+}
+
+fn is_softban(guild_id: GuildId, user_id: UserId) -> bool {
+    SOFTBANS
+        .get(&(guild_id, user_id))
+        .is_some_and(|at| at.elapsed() < ECHO_WINDOW)
+}
+
+/// Propagates bans and unbans across the guilds of a ban sync group
+#[derive(Default)]
+pub struct BanSyncHandler {
+    /// Bans and unbans made by the sync itself, which must not be propagated again
+    pending: DashMap<(GuildId, UserId), Instant>,
+}
+
+impl BanSyncHandler {
+    fn is_echo(&self, guild_id: GuildId, user_id: UserId) -> bool {
+        self.pending
+            .remove(&(guild_id, user_id))
+            .is_some_and(|(_, at)| at.elapsed() < ECHO_WINDOW)
+    }
+
+    async fn sync(
+        &self,
+        ctx: &Context,
+        source: GuildId,
+        user: &User,
+        ban: bool,
+    ) -> Result<(), BotError> {
+        if self.is_echo(source, user.id)
+            || is_softban(source, user.id)
+            || user.id == ctx.cache.current_user().id
+        {
+            return Ok(());
+        }
+        let cfg = ctx.cfg().await?.load_full();
+        let Some(group) = cfg.ban_sync.iter().find(|g| g.guild_ids.contains(&source)) else {
+            return Ok(());
+        };
+        if group.exclude_user_ids.contains(&user.id) || (!ban && !group.sync_unbans) {
+            return Ok(());
+        }
+        let source_name = ctx
+            .cache
+            .guild(source)
+            .map_or_else(|| source.to_string(), |g| g.name.to_owned());
+        let action = if ban { "封禁" } else { "解封" };
+        let reason = format!("封禁同步: 已在 {source_name} {action}");
+        let target = format!("{} (`{}`)", user.mention(), user.name);
+        for &guild_id in group.guild_ids.iter().filter(|id| **id != source) {
+            if group.dry_run {
+                if let Err(e) = guild_log(
+                    ctx,
+                    guild_id,
+                    Severity::Info,
+                    format!("封禁同步 (演练): {action}"),
+                    format!("{target} 已在 **{source_name}** 被{action}, 将会同步至本服务器。"),
+                )
+                .await
+                {
+                    error!("Failed to log ban sync to guild {guild_id}: {e}");
+                }
+                continue;
+            }
+            self.pending.insert((guild_id, user.id), Instant::now());
+            let res = if ban {
+                guild_id.ban_with_reason(ctx, user.id, 0, &reason).await
+            } else {
+                ctx.http.remove_ban(guild_id, user.id, Some(&reason)).await
+            };
+            let (severity, description) = match res {
+                Ok(()) => {
+                    info!("Synced {action} of {} to guild {guild_id}", user.name);
+                    (
+                        Severity::Info,
+                        format!("{target} 已在 **{source_name}** 被{action}, 已同步至本服务器。"),
+                    )
+                }
+                Err(why) => {
+                    // No event will come back for a failed action
+                    self.pending.remove(&(guild_id, user.id));
+                    (
+                        Severity::Warning,
+                        format!("{target} 已在 **{source_name}** 被{action}, 但同步失败: {why}"),
+                    )
+                }
+            };
+            // A broken log channel must not stop the propagation to the remaining guilds
+            if let Err(e) = guild_log(
+                ctx,
+                guild_id,
+                severity,
+                format!("封禁同步: {action}"),
+                description,
+            )
+            .await
+            {
+                error!("Failed to log ban sync to guild {guild_id}: {e}");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventHandler for BanSyncHandler {
+    async fn guild_ban_addition(&self, ctx: Context, guild_id: GuildId, banned_user: User) {
+        if let Err(e) = self.sync(&ctx, guild_id, &banned_user, true).await {
+            error!("Failed to sync ban of {}: {e}", banned_user.name);
+        }
+    }
+
+    async fn guild_ban_removal(&self, ctx: Context, guild_id: GuildId, unbanned_user: User) {
+        if let Err(e) = self.sync(&ctx, guild_id, &unbanned_user, false).await {
+            error!("Failed to sync unban of {}: {e}", unbanned_user.name);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use serenity::{
+    all::{colours::branding::GREEN, *},
+    gateway::ShardManager,
+    prelude::TypeMapKey,
+};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{BotCfg, BotStatusCfg, GetCfg},
+    error::BotError,
+    utils::{format, schedule},
+};
+
+/// The shard manager, inserted after the client is built so handlers can read shard latencies
+pub struct ShardManagerKey;
+
+impl TypeMapKey for ShardManagerKey {
+    type Value = Arc<ShardManager>;
+}
+
+/// Keeps a single embed with the bot's uptime, version, enabled modules and latency up to date
+#[derive(Default)]
+pub struct BotStatusHandler {
+    started: AtomicBool,
+    online_since: OnceLock<DateTime<Utc>>,
+}
+
+#[async_trait]
+impl EventHandler for BotStatusHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let since = *self.online_since.get_or_init(Utc::now);
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        let Some(status) = cfg.load().bot_status.to_owned() else {
+            return;
+        };
+        info!(
+            "Updating the bot status message every {}s",
+            status.interval.as_secs()
+        );
+        schedule::every(status.interval, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.to_owned();
+            async move {
+                if let Err(e) = update_status(&ctx, &cfg, since).await {
+                    error!("Failed to update bot status: {e}");
+                }
+            }
+        });
+    }
+}
+
+/// Names of the optional modules configured to run
+fn modules(cfg: &BotCfg) -> Vec<&'static str> {
+    [
+        ("树洞", cfg.all_tree_holes().next().is_some()),
+        ("答题", !cfg.trivia.is_empty()),
+        ("价格提醒", !cfg.price_watches.is_empty()),
+        ("游戏服务器", !cfg.game_servers.is_empty()),
+        ("版本发布", !cfg.github_releases.is_empty()),
+        ("软件包更新", !cfg.package_watches.is_empty()),
+        ("域名监控", cfg.domain_monitor.is_some()),
+        ("可用性监控", !cfg.uptime_monitors.is_empty()),
+        ("备份", cfg.backup.is_some()),
+        ("镜像", !cfg.mirrors.is_empty()),
+        ("直播通知", cfg.twitch.is_some()),
+        ("视频更新", !cfg.uploads.is_empty()),
+        ("开放时间", !cfg.office_hours.is_empty()),
+        ("话题轮换", !cfg.topic_rotations.is_empty()),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect()
+}
+
+async fn latency(ctx: &Context) -> Option<Duration> {
+    let manager = ctx.data.read().await.get::<ShardManagerKey>().cloned()?;
+    let runners = manager.runners.lock().await;
+    runners.get(&ctx.shard_id)?.latency
+}
+
+async fn update_status(
+    ctx: &Context,
+    cfg: &ArcSwap<BotCfg>,
+    since: DateTime<Utc>,
+) -> Result<(), BotError> {
+    let current = cfg.load_full();
+    let Some(status) = &current.bot_status else {
+        return Ok(());
+    };
+    let uptime = (Utc::now() - since).to_std().unwrap_or_default();
+    let latency = match latency(ctx).await {
+        Some(latency) => format!("{} ms", latency.as_millis()),
+        None => "未知".to_string(),
+    };
+    let modules = modules(&current);
+    let embed = CreateEmbed::new()
+        .title("🤖 机器人状态")
+        .field("运行时间", format::duration(uptime), true)
+        .field("版本", env!("CARGO_PKG_VERSION"), true)
+        .field("延迟", latency, true)
+        .field(
+            "上次重启",
+            FormattedTimestamp::new(since.into(), Some(FormattedTimestampStyle::RelativeTime))
+                .to_string(),
+            true,
+        )
+        .field("服务器", ctx.cache.guild_count().to_string(), true)
+        .field(
+            "已启用模块",
+            if modules.is_empty() {
+                "无".to_string()
+            } else {
+                modules.join(", ")
+            },
+            false,
+        )
+        .color(GREEN)
+        .timestamp(Timestamp::now());
+    update_status_message(ctx, cfg, status, embed).await
+}
+
+/// Edit the saved status message, posting and saving a new one if it is gone.
+async fn update_status_message(
+    ctx: &Context,
+    cfg: &ArcSwap<BotCfg>,
+    status: &BotStatusCfg,
+    embed: CreateEmbed,
+) -> Result<(), BotError> {
+    if let Some(message_id) = status.message_id {
+        let edit = EditMessage::new().embed(embed.to_owned());
+        match status.channel_id.edit_message(ctx, message_id, edit).await {
+            Ok(_) => return Ok(()),
+            Err(e) => warn!("Failed to edit bot status message, reposting: {e}"),
+        }
+    }
+    let message = status
+        .channel_id
+        .send_message(ctx, CreateMessage::new().embed(embed))
+        .await?;
+    cfg.rcu(|cfg| {
+        let mut cfg = BotCfg::clone(cfg);
+        if let Some(status) = &mut cfg.bot_status {
+            status.message_id = Some(message.id);
+        }
+        cfg
+    });
+    cfg.load().write()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_modules() {
+        let mut cfg = BotCfg::default();
+        assert!(modules(&cfg).is_empty());
+        cfg.guilds
+            .entry(GuildId::new(1))
+            .or_default()
+            .tree_holes
+            .insert(ChannelId::new(1), 60.into());
+        assert_eq!(modules(&cfg), ["树洞"]);
+    }
+}
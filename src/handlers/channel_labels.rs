@@ -0,0 +1,205 @@
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::FixedOffset;
+use dashmap::DashMap;
+use futures::future::join_all;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    config::{BotCfg, ChannelLabel, GameServerCfg, GetCfg},
+    error::BotError,
+    utils::{schedule, template::Template},
+};
+
+const UPDATE_INTERVAL: Duration = Duration::from_secs(300);
+/// Discord allows two name or topic edits per channel in this window
+const EDIT_WINDOW: Duration = Duration::from_secs(600);
+const EDITS_PER_WINDOW: usize = 2;
+
+/// Renders `channelLabels` into channel names and topics
+#[derive(Default)]
+pub struct ChannelLabelHandler {
+    started: AtomicBool,
+    /// Recent edits of each channel, oldest first
+    edits: Arc<DashMap<ChannelId, VecDeque<Instant>>>,
+}
+
+/// Values of the game server variables, shared by all labels of one update
+struct Servers {
+    up: usize,
+    total: usize,
+    players: u32,
+}
+
+async fn query_servers(servers: &[GameServerCfg]) -> Servers {
+    let results = join_all(servers.iter().map(GameServerCfg::query)).await;
+    let infos = results.into_iter().flatten().collect::<Vec<_>>();
+    Servers {
+        up: infos.len(),
+        total: servers.len(),
+        players: infos.iter().map(|i| i.players).sum(),
+    }
+}
+
+/// The next scheduled or running event of a guild, with its start in `offset`
+async fn next_event(
+    ctx: &Context,
+    guild_id: GuildId,
+    offset: FixedOffset,
+) -> Result<String, BotError> {
+    let next = guild_id
+        .scheduled_events(ctx, false)
+        .await?
+        .into_iter()
+        .filter(|e| {
+            matches!(
+                e.status,
+                ScheduledEventStatus::Scheduled | ScheduledEventStatus::Active
+            )
+        })
+        .min_by_key(|e| e.start_time);
+    Ok(next.map_or_else(
+        || "无".to_string(),
+        |e| {
+            format!(
+                "{} {}",
+                e.name,
+                e.start_time.with_timezone(&offset).format("%m-%d %H:%M")
+            )
+        },
+    ))
+}
+
+/// Record an edit of `channel_id` unless it already used up the current window.
+fn try_edit(edits: &DashMap<ChannelId, VecDeque<Instant>>, channel_id: ChannelId) -> bool {
+    let mut recent = edits.entry(channel_id).or_default();
+    while recent.front().is_some_and(|at| at.elapsed() >= EDIT_WINDOW) {
+        recent.pop_front();
+    }
+    if recent.len() >= EDITS_PER_WINDOW {
+        return false;
+    }
+    recent.push_back(Instant::now());
+    true
+}
+
+async fn update_label(
+    ctx: &Context,
+    cfg: &BotCfg,
+    servers: &Servers,
+    edits: &DashMap<ChannelId, VecDeque<Instant>>,
+    label: &ChannelLabel,
+) -> Result<(), BotError> {
+    let Some(channel) = label.channel_id.to_channel(ctx).await?.guild() else {
+        snafu::whatever!("Channel {} is not a guild channel", label.channel_id);
+    };
+    let (members, online) = ctx
+        .cache
+        .guild(channel.guild_id)
+        .map(|g| {
+            let online = g
+                .presences
+                .values()
+                .filter(|p| p.status != OnlineStatus::Offline)
+                .count();
+            (g.member_count, online)
+        })
+        .unwrap_or_default();
+    let offset = FixedOffset::east_opt(cfg.time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    let uses_event = label
+        .name
+        .iter()
+        .chain(&label.topic)
+        .any(|t| t.uses("next_event"));
+    let event = if uses_event {
+        next_event(ctx, channel.guild_id, offset).await?
+    } else {
+        String::new()
+    };
+    let render = |template: &Option<Template>| {
+        template.as_ref().map(|t| {
+            t.render(&[
+                ("members", &members),
+                ("online", &online),
+                ("next_event", &event),
+                ("servers_up", &servers.up),
+                ("servers_total", &servers.total),
+                ("players", &servers.players),
+            ])
+        })
+    };
+    let mut edit = EditChannel::new();
+    let mut changed = false;
+    if let Some(name) = render(&label.name)
+        && name != channel.name
+    {
+        edit = edit.name(name);
+        changed = true;
+    }
+    if let Some(topic) = render(&label.topic)
+        && channel.topic.as_deref() != Some(topic.as_str())
+    {
+        edit = edit.topic(topic);
+        changed = true;
+    }
+    // Skipped updates are picked up again on a later tick
+    if changed && try_edit(edits, label.channel_id) {
+        label.channel_id.edit(ctx, edit).await?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for ChannelLabelHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        if cfg.load().channel_labels.is_empty() {
+            return;
+        }
+        info!(
+            "Updating channel labels every {}s",
+            UPDATE_INTERVAL.as_secs()
+        );
+        let edits = self.edits.to_owned();
+        schedule::every(UPDATE_INTERVAL, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.load_full();
+            let edits = edits.to_owned();
+            async move {
+                let servers = query_servers(&cfg.game_servers).await;
+                for label in &cfg.channel_labels {
+                    if let Err(e) = update_label(&ctx, &cfg, &servers, &edits, label).await {
+                        error!("Failed to update label of {}: {e}", label.channel_id);
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_try_edit() {
+        let edits = DashMap::new();
+        let channel_id = ChannelId::new(1);
+        assert!(try_edit(&edits, channel_id));
+        assert!(try_edit(&edits, channel_id));
+        assert!(!try_edit(&edits, channel_id));
+        assert!(try_edit(&edits, ChannelId::new(2)));
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use chrono::Utc;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::moderation::set_channel_muted,
+    database::GetDb,
+    error::BotError,
+    utils::{
+        alert::{Severity, guild_log},
+        schedule,
+    },
+};
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Lifts channel mutes once they expire, including those that expired while offline
+#[derive(Default)]
+pub struct ChannelMuteHandler {
+    started: AtomicBool,
+}
+
+async fn sweep(ctx: &Context) -> Result<(), BotError> {
+    let db = ctx.db().await?;
+    for mute in db.channel_mutes().expired(Utc::now()).await? {
+        let (channel_id, user_id) = (mute.channel_id(), mute.user_id());
+        if let Err(e) = set_channel_muted(ctx, channel_id, user_id, false).await {
+            error!("Failed to lift channel mute of {user_id} in {channel_id}: {e}");
+        }
+        // Dropped even if lifting failed, e.g. because the channel is gone
+        db.channel_mutes().remove(channel_id, user_id).await?;
+        guild_log(
+            ctx,
+            mute.guild_id(),
+            Severity::Resolved,
+            "频道禁言已到期",
+            format!(
+                "{} 在 {} 的禁言已解除。",
+                user_id.mention(),
+                channel_id.mention()
+            ),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for ChannelMuteHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        info!(
+            "Checking for expired channel mutes every {}s",
+            SWEEP_INTERVAL.as_secs()
+        );
+        schedule::every(SWEEP_INTERVAL, move || {
+            let ctx = ctx.to_owned();
+            async move {
+                if let Err(e) = sweep(&ctx).await {
+                    error!("Failed to lift expired channel mutes: {e}");
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use serenity::all::*;
+use tracing::{error, warn};
+
+use crate::{
+    config::{CrashLoopCfg, GetCfg},
+    database::GetDb,
+    error::BotError,
+    utils::{
+        alert::{Severity, alert},
+        crash::{clear_last_panic, clear_starts, last_panic},
+        format,
+    },
+};
+
+/// Panic messages are cut to this many characters to fit an embed
+const MAX_PANIC_LEN: usize = 3000;
+
+/// Reports a crash loop to the ops channel once the bot manages to connect again
+pub struct CrashLoopHandler {
+    /// Starts within the configured window, this one included
+    starts: usize,
+    panic_file: PathBuf,
+    checked: AtomicBool,
+}
+
+impl CrashLoopHandler {
+    pub fn new(starts: usize, panic_file: PathBuf) -> Self {
+        Self {
+            starts,
+            panic_file,
+            checked: AtomicBool::new(false),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for CrashLoopHandler {
+    async fn ready(&self, ctx: Context, _ready: Ready) {
+        if self.checked.swap(true, Ordering::Relaxed) {
+            return; // Reconnected, not restarted
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load_full();
+        let Some(crash_loop) = cfg.crash_loop.as_ref() else {
+            return;
+        };
+        if self.starts <= crash_loop.restarts {
+            return;
+        }
+        if let Err(e) = self.report(&ctx, crash_loop).await {
+            error!("Failed to report crash loop: {e}");
+        }
+    }
+}
+
+impl CrashLoopHandler {
+    async fn report(&self, ctx: &Context, crash_loop: &CrashLoopCfg) -> Result<(), BotError> {
+        let panic = last_panic(&self.panic_file, crash_loop.window).map_or_else(
+            || "没有记录到 panic。".to_owned(),
+            |panic| {
+                let panic = panic.chars().take(MAX_PANIC_LEN).collect::<String>();
+                format!("最近一次 panic:\n```\n{panic}\n```")
+            },
+        );
+        let description = format!(
+            "机器人在 {} 内启动了 {} 次, 可能处于崩溃循环。\n\n{panic}",
+            format::duration(crash_loop.window),
+            self.starts
+        );
+        alert(ctx, Severity::Critical, "崩溃循环", description).await?;
+        // Quoted once, a later crash loop has its own panic
+        if let Err(e) = clear_last_panic(&self.panic_file) {
+            warn!("Failed to remove {}: {e}", self.panic_file.display());
+        }
+        // Another report takes as many restarts again
+        clear_starts(&ctx.db().await?).await
+    }
+}
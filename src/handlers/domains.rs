@@ -0,0 +1,166 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serenity::all::*;
+use snafu::{OptionExt, ResultExt};
+use tokio::net::lookup_host;
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{DomainMonitorCfg, GetCfg},
+    error::BotError,
+    utils::{
+        alert::{Severity, alert},
+        schedule,
+    },
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+#[derive(Default)]
+pub struct DomainHandler {
+    started: AtomicBool,
+}
+
+#[async_trait]
+impl EventHandler for DomainHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        if cfg.load().domain_monitor.is_none() {
+            return;
+        }
+        info!("Checking domain and certificate expiry daily");
+        schedule::every(CHECK_INTERVAL, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.load_full();
+            async move {
+                let Some(monitor) = &cfg.domain_monitor else {
+                    return;
+                };
+                for domain in &monitor.domains {
+                    if let Err(e) = check_domain(&ctx, monitor, domain).await {
+                        error!("Failed to check domain {domain}: {e}");
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Expiry of the certificate served on port 443.
+async fn cert_expiry(domain: &str) -> Result<DateTime<Utc>, BotError> {
+    let client = reqwest::Client::builder()
+        .tls_info(true)
+        // Still read expired or otherwise invalid certificates so they can be reported
+        .danger_accept_invalid_certs(true)
+        .timeout(Duration::from_secs(15))
+        .build()?;
+    let res = client.head(format!("https://{domain}/")).send().await?;
+    let der = res
+        .extensions()
+        .get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .whatever_context::<&str, BotError>("Server presented no certificate")?;
+    let (_, cert) = x509_parser::parse_x509_certificate(der)
+        .whatever_context::<&str, BotError>("Failed to parse certificate")?;
+    DateTime::from_timestamp(cert.validity().not_after.timestamp(), 0)
+        .whatever_context("Certificate expiry is out of range")
+}
+
+/// Registration expiry from RDAP, `None` if the registry does not publish one.
+async fn domain_expiry(domain: &str) -> Result<Option<DateTime<Utc>>, BotError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Rdap {
+        #[serde(default)]
+        events: Vec<Event>,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Event {
+        event_action: String,
+        event_date: DateTime<Utc>,
+    }
+
+    let res = reqwest::get(format!("https://rdap.org/domain/{domain}")).await?;
+    if res.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None); // Subdomains and some ccTLDs have no RDAP record
+    }
+    Ok(res
+        .error_for_status()?
+        .json::<Rdap>()
+        .await?
+        .events
+        .into_iter()
+        .find(|e| e.event_action == "expiration")
+        .map(|e| e.event_date))
+}
+
+async fn check_domain(
+    ctx: &Context,
+    monitor: &DomainMonitorCfg,
+    domain: &str,
+) -> Result<(), BotError> {
+    let resolved = lookup_host((domain, 443))
+        .await
+        .map(|mut addrs| addrs.next().is_some());
+    if !matches!(resolved, Ok(true)) {
+        // Nothing else can be checked without an address
+        return alert(
+            ctx,
+            Severity::Critical,
+            "DNS 解析失败",
+            format!("**{domain}** 无法解析到任何地址"),
+        )
+        .await;
+    }
+
+    let now = Utc::now();
+    let report = async |what: &str, expiry: DateTime<Utc>| {
+        let days = (expiry - now).num_days();
+        let (severity, text) = if expiry <= now {
+            (Severity::Critical, format!("**{domain}** 的{what}已过期"))
+        } else if days < monitor.warn_days {
+            (
+                Severity::Warning,
+                format!("**{domain}** 的{what}将在 {days} 天后过期"),
+            )
+        } else {
+            return Ok(());
+        };
+        alert(
+            ctx,
+            severity,
+            format!("{what}即将过期"),
+            format!("{text} ({})", expiry.format("%Y-%m-%d")),
+        )
+        .await
+    };
+
+    match cert_expiry(domain).await {
+        Ok(expiry) => report("TLS 证书", expiry).await?,
+        Err(e) => {
+            alert(
+                ctx,
+                Severity::Critical,
+                "TLS 检查失败",
+                format!("无法获取 **{domain}** 的证书: {e}"),
+            )
+            .await?
+        }
+    }
+    match domain_expiry(domain).await {
+        Ok(Some(expiry)) => report("域名注册", expiry).await?,
+        Ok(None) => {}
+        // RDAP outages are not worth paging anyone over
+        Err(e) => warn!("Failed to look up registration of {domain}: {e}"),
+    }
+    Ok(())
+}
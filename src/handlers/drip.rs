@@ -0,0 +1,169 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use chrono::Utc;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    config::GetCfg, database::GetDb, error::BotError, repo::prefs::DmKind, utils::schedule,
+};
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+/// Custom ID prefix of the opt-out button, followed by the guild ID
+const OPT_OUT_PREFIX: &str = "drip:optout:";
+
+/// Sends new members the configured sequence of DMs, until they opt out or leave
+#[derive(Default)]
+pub struct DripHandler {
+    started: AtomicBool,
+}
+
+async fn sweep(ctx: &Context) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?.load_full();
+    let db = ctx.db().await?;
+    for message in db.drip().due(Utc::now()).await? {
+        let (guild_id, user_id) = (message.guild_id(), message.user_id());
+        if !db.prefs().allows_dm(user_id, DmKind::Drip).await? {
+            db.drip().cancel(guild_id, user_id).await?;
+            continue;
+        }
+        // Steps removed from the configuration since the member joined are skipped
+        let Some(step) = cfg
+            .guilds
+            .get(&guild_id)
+            .and_then(|g| g.drip.get(message.step as usize))
+        else {
+            db.drip().done(&message).await?;
+            continue;
+        };
+        let (guild_name, count) = ctx
+            .cache
+            .guild(guild_id)
+            .map(|g| (g.name.to_owned(), g.member_count))
+            .unwrap_or_else(|| (guild_id.to_string(), 0));
+        let content = step.message.render(&[
+            ("user", &user_id.mention()),
+            ("guild", &guild_name),
+            ("count", &count),
+        ]);
+        let dm = CreateMessage::new()
+            .content(content)
+            .components(vec![CreateActionRow::Buttons(vec![
+                CreateButton::new(format!("{OPT_OUT_PREFIX}{guild_id}"))
+                    .label("不再接收此类消息")
+                    .style(ButtonStyle::Secondary),
+            ])]);
+        match user_id.direct_message(ctx, dm).await {
+            Ok(_) => db.drip().done(&message).await?,
+            Err(e) => {
+                // Closed DMs stay closed, so the rest of the campaign would fail as well
+                info!("Stopping drip DMs to {user_id} of guild {guild_id}: {e}");
+                db.drip().cancel(guild_id, user_id).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn opt_out(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    guild_id: GuildId,
+) -> Result<(), BotError> {
+    let db = ctx.db().await?;
+    // The same opt-out as in the notification settings, so it holds should they join again
+    db.prefs()
+        .set_dm(interaction.user.id, DmKind::Drip, false)
+        .await?;
+    let cancelled = db.drip().cancel(guild_id, interaction.user.id).await?;
+    info!(
+        "{} opted out of {cancelled} drip DMs of guild {guild_id}",
+        interaction.user.name
+    );
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content("✅ **成功**\n\n你将不会再收到此类消息。"),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for DripHandler {
+    async fn guild_member_addition(&self, ctx: Context, member: Member) {
+        if member.user.bot {
+            return;
+        }
+        let res = async {
+            let cfg = ctx.cfg().await?.load_full();
+            let Some(guild) = cfg.guilds.get(&member.guild_id) else {
+                return Ok(());
+            };
+            let now = Utc::now();
+            ctx.db()
+                .await?
+                .drip()
+                .schedule(
+                    member.guild_id,
+                    member.user.id,
+                    guild.drip.iter().map(|step| now + step.delay),
+                )
+                .await
+        };
+        if let Err(e) = res.await {
+            error!("Failed to schedule drip DMs for {}: {e}", member.user.id);
+        }
+    }
+
+    async fn guild_member_removal(
+        &self,
+        ctx: Context,
+        guild_id: GuildId,
+        user: User,
+        _member: Option<Member>,
+    ) {
+        let res = async { ctx.db().await?.drip().cancel(guild_id, user.id).await };
+        if let Err(e) = res.await {
+            error!("Failed to cancel drip DMs for {}: {e}", user.id);
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Component(interaction) = interaction else {
+            return;
+        };
+        let Some(Ok(guild_id)) = interaction
+            .data
+            .custom_id
+            .strip_prefix(OPT_OUT_PREFIX)
+            .map(str::parse::<u64>)
+        else {
+            return;
+        };
+        if let Err(e) = opt_out(&ctx, &interaction, GuildId::new(guild_id)).await {
+            error!("Failed to opt {} out of drip DMs: {e}", interaction.user.id);
+        }
+    }
+
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        info!("Sending due drip DMs every {}s", SWEEP_INTERVAL.as_secs());
+        schedule::every(SWEEP_INTERVAL, move || {
+            let ctx = ctx.to_owned();
+            async move {
+                if let Err(e) = sweep(&ctx).await {
+                    error!("Failed to send drip DMs: {e}");
+                }
+            }
+        });
+    }
+}
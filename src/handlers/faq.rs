@@ -0,0 +1,97 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::faq::{best_match, keywords, rebuild},
+    config::GetCfg,
+};
+
+/// Questions with fewer keywords are too vague to answer
+const MIN_KEYWORDS: usize = 3;
+/// An entry is not suggested again in the same channel within this time
+const COOLDOWN: Duration = Duration::from_secs(600);
+
+/// Answers questions in help channels with a link to the matching FAQ entry
+#[derive(Default)]
+pub struct FaqHandler {
+    started: AtomicBool,
+    /// When each entry, by link, was last suggested in a channel
+    answered: DashMap<(ChannelId, String), Instant>,
+}
+
+#[async_trait]
+impl EventHandler for FaqHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return; // Already indexed
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load_full();
+        for (guild_id, faq) in cfg
+            .guilds
+            .iter()
+            .filter_map(|(id, g)| Some((id, g.faq.as_ref()?)))
+        {
+            match rebuild(&ctx, *guild_id, faq.channel_id).await {
+                Ok(count) => info!("Indexed {count} FAQ entries of guild {guild_id}"),
+                Err(e) => error!("Failed to index the FAQ of guild {guild_id}: {e}"),
+            }
+        }
+    }
+
+    async fn message(&self, ctx: Context, msg: Message) {
+        if msg.author.bot {
+            return;
+        }
+        let Some(guild_id) = msg.guild_id else {
+            return;
+        };
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load_full();
+        let Some(faq) = cfg.guilds.get(&guild_id).and_then(|g| g.faq.as_ref()) else {
+            return;
+        };
+        if !faq.help_channel_ids.contains(&msg.channel_id) {
+            return;
+        }
+        let question = keywords(&msg.content);
+        if question.len() < MIN_KEYWORDS {
+            return;
+        }
+        let Some(entry) = best_match(guild_id, &question, faq.min_score) else {
+            return;
+        };
+        let key = (msg.channel_id, entry.link.to_owned());
+        if self
+            .answered
+            .get(&key)
+            .is_some_and(|at| at.elapsed() < COOLDOWN)
+        {
+            return;
+        }
+        self.answered.retain(|_, at| at.elapsed() < COOLDOWN);
+        self.answered.insert(key, Instant::now());
+        let reply = CreateMessage::new()
+            .content(format!(
+                "📖 这个问题或许在常见问题中已有解答: [{}]({})",
+                entry.title, entry.link
+            ))
+            .reference_message(&msg)
+            .allowed_mentions(CreateAllowedMentions::new());
+        if let Err(e) = msg.channel_id.send_message(&ctx, reply).await {
+            error!("Failed to answer {} from the FAQ: {e}", msg.id);
+        }
+    }
+}
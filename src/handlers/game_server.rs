@@ -0,0 +1,182 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Instant,
+};
+
+use arc_swap::ArcSwap;
+use futures::future::join_all;
+use serenity::all::{
+    colours::branding::{GREEN, RED},
+    *,
+};
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+use crate::{
+    commands::{integrations::GAME_SERVERS, uptime::EndpointStatus},
+    config::{BotCfg, GetCfg, ServerStatusCfg},
+    error::BotError,
+    utils::schedule,
+};
+
+/// Fields Discord allows in an embed, the last is kept for servers that don't fit
+const MAX_FIELDS: usize = 25;
+
+#[derive(Default)]
+pub struct GameServerHandler {
+    started: AtomicBool,
+    /// Whether each server was up on the previous check, keyed by name
+    up: Arc<Mutex<HashMap<String, bool>>>,
+}
+
+#[async_trait]
+impl EventHandler for GameServerHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        let Some(status) = cfg.load().server_status.to_owned() else {
+            return;
+        };
+        info!(
+            "Monitoring {} game servers every {}s",
+            cfg.load().game_servers.len(),
+            status.interval.as_secs()
+        );
+        let up = self.up.to_owned();
+        schedule::every(status.interval, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.to_owned();
+            let up = up.to_owned();
+            async move {
+                if let Err(e) = check_servers(&ctx, &cfg, &up).await {
+                    error!("Failed to update game server status: {e}");
+                }
+            }
+        });
+    }
+}
+
+async fn check_servers(
+    ctx: &Context,
+    cfg: &ArcSwap<BotCfg>,
+    up: &Mutex<HashMap<String, bool>>,
+) -> Result<(), BotError> {
+    let current = cfg.load_full();
+    let Some(status) = &current.server_status else {
+        return Ok(());
+    };
+    let results = join_all(current.game_servers.iter().map(async |server| {
+        let started = Instant::now();
+        let result = server.query().await;
+        GAME_SERVERS.insert(
+            server.name.to_owned(),
+            EndpointStatus {
+                up: result.is_ok(),
+                response_time: started.elapsed(),
+                reason: result.as_ref().err().map(|e| e.to_string()),
+                checked_at: Timestamp::now(),
+            },
+        );
+        result
+    }))
+    .await;
+
+    let mut embed = CreateEmbed::new()
+        .title("🖥️ 游戏服务器状态")
+        .timestamp(Timestamp::now());
+    let mut changes = vec![];
+    let mut up = up.lock().await;
+    let shown = if results.len() > MAX_FIELDS {
+        MAX_FIELDS - 1
+    } else {
+        results.len()
+    };
+    for (i, (server, result)) in current.game_servers.iter().zip(&results).enumerate() {
+        let value = match result {
+            Ok(info) => format!(
+                "🟢 在线 {}/{}\n{}\n`{}`",
+                info.players, info.max_players, info.detail, server.address
+            ),
+            Err(e) => {
+                warn!("Game server {} is unreachable: {e}", server.name);
+                format!("🔴 离线\n`{}`", server.address)
+            }
+        };
+        if i < shown {
+            embed = embed.field(&server.name, value, true);
+        }
+        let is_up = result.is_ok();
+        // Only report transitions, not the state observed on the first check
+        if up
+            .insert(server.name.to_owned(), is_up)
+            .is_some_and(|was| was != is_up)
+        {
+            changes.push((server.name.as_str(), is_up));
+        }
+    }
+    drop(up);
+    let rest = &results[shown..];
+    if !rest.is_empty() {
+        let online = rest.iter().filter(|r| r.is_ok()).count();
+        embed = embed.field(
+            format!("其余 {} 个服务器", rest.len()),
+            format!("🟢 {online} 在线\n🔴 {} 离线", rest.len() - online),
+            true,
+        );
+    }
+    let all_up = results.iter().all(Result::is_ok);
+    embed = embed.color(if all_up { GREEN } else { RED });
+    update_status_message(ctx, cfg, status, embed).await?;
+
+    for (name, is_up) in changes {
+        let text = if is_up {
+            format!("🟢 **{name}** 已恢复在线")
+        } else {
+            format!("🔴 **{name}** 已离线")
+        };
+        let mut msg = CreateMessage::new();
+        if let Some(role_id) = status.role_id {
+            msg = msg
+                .content(format!("{} {text}", role_id.mention()))
+                .allowed_mentions(CreateAllowedMentions::new().roles([role_id]));
+        } else {
+            msg = msg.content(text);
+        }
+        status.channel_id.send_message(ctx, msg).await?;
+    }
+    Ok(())
+}
+
+/// Edit the saved status message, posting and saving a new one if it is gone.
+async fn update_status_message(
+    ctx: &Context,
+    cfg: &ArcSwap<BotCfg>,
+    status: &ServerStatusCfg,
+    embed: CreateEmbed,
+) -> Result<(), BotError> {
+    if let Some(message_id) = status.message_id {
+        let edit = EditMessage::new().embed(embed.to_owned());
+        match status.channel_id.edit_message(ctx, message_id, edit).await {
+            Ok(_) => return Ok(()),
+            Err(e) => warn!("Failed to edit game server status message, reposting: {e}"),
+        }
+    }
+    let message = status
+        .channel_id
+        .send_message(ctx, CreateMessage::new().embed(embed))
+        .await?;
+    cfg.rcu(|cfg| {
+        let mut cfg = BotCfg::clone(cfg);
+        if let Some(status) = &mut cfg.server_status {
+            status.message_id = Some(message.id);
+        }
+        cfg
+    });
+    cfg.load().write()
+}
@@ -0,0 +1,66 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{commands::gamestats::game_stats, config::GetCfg, error::BotError, utils::schedule};
+
+/// Discord only allows two topic edits per channel every ten minutes
+const TOPIC_INTERVAL: Duration = Duration::from_secs(600);
+
+#[derive(Default)]
+pub struct GameTopicHandler {
+    started: AtomicBool,
+}
+
+#[async_trait]
+impl EventHandler for GameTopicHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        if cfg.load().game_topic.is_none() {
+            return;
+        }
+        info!("Updating the game player count topic every 10 minutes");
+        schedule::every(TOPIC_INTERVAL, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.load_full();
+            async move {
+                let Some(topic) = &cfg.game_topic else {
+                    return;
+                };
+                let f = async || -> Result<(), BotError> {
+                    let Some(stats) =
+                        game_stats(cfg.steam_api_key.as_deref(), &topic.app_id.to_string()).await?
+                    else {
+                        snafu::whatever!("Steam app {} not found", topic.app_id);
+                    };
+                    let text = topic
+                        .template
+                        .render(&[("name", &stats.name), ("players", &stats.players)]);
+                    let current = topic
+                        .channel_id
+                        .to_channel(&ctx)
+                        .await?
+                        .guild()
+                        .and_then(|c| c.topic);
+                    if current.as_deref() != Some(text.as_str()) {
+                        topic
+                            .channel_id
+                            .edit(&ctx, EditChannel::new().topic(text))
+                            .await?;
+                    }
+                    Ok(())
+                };
+                if let Err(e) = f().await {
+                    error!("Failed to update game topic of {}: {e}", topic.channel_id);
+                }
+            }
+        });
+    }
+}
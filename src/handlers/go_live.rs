@@ -0,0 +1,230 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use serde::Deserialize;
+use serenity::all::{colours::css::DANGER, *};
+use tracing::{error, info};
+
+use crate::{
+    config::{GetCfg, GoLiveCfg},
+    error::BotError,
+    utils::{schedule, twitch::Twitch},
+};
+
+const TWITCH_INTERVAL: Duration = Duration::from_secs(120);
+
+/// A stream identified by platform and user, e.g. `discord:1234` or `twitch:login`
+type StreamKey = (GuildId, String);
+
+/// Posts go-live announcements for Discord streams and polled Twitch channels
+#[derive(Default)]
+pub struct GoLiveHandler {
+    started: AtomicBool,
+    live: Arc<DashMap<StreamKey, ()>>,
+    announced: Arc<DashMap<StreamKey, Instant>>,
+}
+
+struct Stream {
+    name: String,
+    url: String,
+    title: Option<String>,
+    game: Option<String>,
+    thumbnail: Option<String>,
+}
+
+/// Announce a stream unless the same streamer was announced within the cooldown.
+async fn announce(
+    ctx: &Context,
+    announced: &DashMap<StreamKey, Instant>,
+    key: StreamKey,
+    go_live: &GoLiveCfg,
+    stream: Stream,
+) -> Result<(), BotError> {
+    if announced
+        .get(&key)
+        .is_some_and(|at| at.elapsed() < go_live.cooldown)
+    {
+        return Ok(());
+    }
+    announced.insert(key, Instant::now());
+    let mut embed = CreateEmbed::new()
+        .title(format!("🔴 {} 正在直播", stream.name))
+        .url(&stream.url)
+        .color(DANGER)
+        .timestamp(Timestamp::now());
+    if let Some(title) = stream.title {
+        embed = embed.description(title);
+    }
+    if let Some(game) = stream.game {
+        embed = embed.field("🎮 游戏", game, true);
+    }
+    if let Some(thumbnail) = stream.thumbnail {
+        embed = embed.image(thumbnail);
+    }
+    let mut msg = CreateMessage::new()
+        .embed(embed)
+        .allowed_mentions(CreateAllowedMentions::new());
+    if let Some(role_id) = go_live.role_id {
+        msg = msg
+            .content(role_id.mention().to_string())
+            .allowed_mentions(CreateAllowedMentions::new().roles([role_id]));
+    }
+    go_live.channel_id.send_message(ctx, msg).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct TwitchStream {
+    user_login: String,
+    user_name: String,
+    title: String,
+    game_name: String,
+    /// Contains `{width}` and `{height}` placeholders
+    thumbnail_url: String,
+}
+
+#[async_trait]
+impl EventHandler for GoLiveHandler {
+    async fn presence_update(&self, ctx: Context, presence: Presence) {
+        let Some(guild_id) = presence.guild_id else {
+            return;
+        };
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load_full();
+        let Some(go_live) = cfg.guilds.get(&guild_id).and_then(|g| g.go_live.as_ref()) else {
+            return;
+        };
+        let key = (guild_id, format!("discord:{}", presence.user.id));
+        let Some(activity) = presence
+            .activities
+            .iter()
+            .find(|a| a.kind == ActivityType::Streaming)
+        else {
+            self.live.remove(&key);
+            return;
+        };
+        if self.live.insert(key.to_owned(), ()).is_some() {
+            return; // Still the same stream
+        }
+        let name = match presence.user.to_user() {
+            Some(user) => user.display_name().to_owned(),
+            None => ctx
+                .cache
+                .user(presence.user.id)
+                .map(|u| u.display_name().to_owned())
+                .unwrap_or_else(|| presence.user.id.to_string()),
+        };
+        let stream = Stream {
+            name,
+            url: activity
+                .url
+                .as_ref()
+                .map(|u| u.to_string())
+                .unwrap_or_default(),
+            title: activity.details.to_owned(),
+            game: activity.state.to_owned(),
+            thumbnail: None,
+        };
+        if let Err(e) = announce(&ctx, &self.announced, key, go_live, stream).await {
+            error!("Failed to announce stream of {}: {e}", presence.user.id);
+        }
+    }
+
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load_full();
+        let Some(twitch) = cfg.twitch.to_owned() else {
+            return;
+        };
+        info!(
+            "Polling Twitch streams every {}s",
+            TWITCH_INTERVAL.as_secs()
+        );
+        let twitch = Arc::new(Twitch::new(twitch));
+        let live = self.live.to_owned();
+        let announced = self.announced.to_owned();
+        schedule::every(TWITCH_INTERVAL, move || {
+            let ctx = ctx.to_owned();
+            let twitch = twitch.to_owned();
+            let live = live.to_owned();
+            let announced = announced.to_owned();
+            async move {
+                let cfg = ctx
+                    .cfg()
+                    .await
+                    .expect("Failed to get bot configuration")
+                    .load_full();
+                let logins = cfg
+                    .guilds
+                    .values()
+                    .filter_map(|g| g.go_live.as_ref())
+                    .flat_map(|g| g.twitch_logins.iter().map(|l| l.to_lowercase()))
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                // Helix accepts at most 100 logins per request
+                let mut streams = Vec::new();
+                for chunk in logins.chunks(100) {
+                    let query = chunk
+                        .iter()
+                        .map(|l| ("user_login", l.as_str()))
+                        .collect::<Vec<_>>();
+                    match twitch.get::<TwitchStream>("streams", &query).await {
+                        Ok(chunk) => streams.extend(chunk),
+                        Err(e) => {
+                            error!("Failed to poll Twitch streams: {e}");
+                            return;
+                        }
+                    }
+                }
+                for (guild_id, guild) in &cfg.guilds {
+                    let Some(go_live) = &guild.go_live else {
+                        continue;
+                    };
+                    for login in &go_live.twitch_logins {
+                        let login = login.to_lowercase();
+                        let key = (*guild_id, format!("twitch:{login}"));
+                        let Some(stream) = streams.iter().find(|s| s.user_login == login) else {
+                            live.remove(&key);
+                            continue;
+                        };
+                        if live.insert(key.to_owned(), ()).is_some() {
+                            continue;
+                        }
+                        let stream = Stream {
+                            name: stream.user_name.to_owned(),
+                            url: format!("https://twitch.tv/{login}"),
+                            title: Some(stream.title.to_owned()),
+                            game: Some(stream.game_name.to_owned()).filter(|g| !g.is_empty()),
+                            thumbnail: Some(
+                                stream
+                                    .thumbnail_url
+                                    .replace("{width}", "1280")
+                                    .replace("{height}", "720"),
+                            ),
+                        };
+                        if let Err(e) = announce(&ctx, &announced, key, go_live, stream).await {
+                            error!("Failed to announce Twitch stream of {login}: {e}");
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use chrono::{FixedOffset, NaiveTime, Utc};
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::growth::day, config::GetCfg, database::GetDb, error::BotError, utils::schedule,
+};
+
+/// Counts joins and leaves and takes the nightly member snapshots behind `/growth`
+#[derive(Default)]
+pub struct GrowthHandler {
+    started: AtomicBool,
+}
+
+async fn today(ctx: &Context) -> Result<String, BotError> {
+    let offset = FixedOffset::east_opt(ctx.cfg().await?.load().time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    Ok(day(Utc::now(), offset))
+}
+
+/// Record the member count and role distribution of every guild for `day`.
+async fn snapshot(ctx: &Context, day: &str) -> Result<(), BotError> {
+    let db = ctx.db().await?;
+    for guild_id in ctx.cache.guilds() {
+        let Some((count, roles)) = ctx.cache.guild(guild_id).map(|guild| {
+            let mut roles = HashMap::<RoleId, u64>::new();
+            for role_id in guild.members.values().flat_map(|m| &m.roles) {
+                *roles.entry(*role_id).or_default() += 1;
+            }
+            (guild.member_count, roles)
+        }) else {
+            continue;
+        };
+        db.members().snapshot(guild_id, day, count, &roles).await?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for GrowthHandler {
+    async fn guild_member_addition(&self, ctx: Context, member: Member) {
+        let f = async || {
+            ctx.db()
+                .await?
+                .members()
+                .joined(member.guild_id, &today(&ctx).await?)
+                .await
+        };
+        if let Err(e) = f().await {
+            error!("Failed to count {} joining: {e}", member.user.id);
+        }
+    }
+
+    async fn guild_member_removal(
+        &self,
+        ctx: Context,
+        guild_id: GuildId,
+        user: User,
+        _member: Option<Member>,
+    ) {
+        let f = async || {
+            ctx.db()
+                .await?
+                .members()
+                .left(guild_id, &today(&ctx).await?)
+                .await
+        };
+        if let Err(e) = f().await {
+            error!("Failed to count {} leaving: {e}", user.id);
+        }
+    }
+
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return; // Snapshots are already scheduled
+        }
+        let offset = FixedOffset::east_opt(
+            ctx.cfg()
+                .await
+                .expect("Failed to get bot configuration")
+                .load()
+                .time_offset,
+        )
+        .expect("Failed to create FixedOffset with the configured time offset");
+        info!("Scheduling nightly member snapshots");
+        // Just before midnight, so the snapshot closes the day whose joins it sits with
+        let at = NaiveTime::from_hms_opt(23, 59, 0).unwrap();
+        schedule::daily(at, offset, move || {
+            let ctx = ctx.to_owned();
+            async move {
+                if let Err(e) = snapshot(&ctx, &day(Utc::now(), offset)).await {
+                    error!("Failed to snapshot members: {e}");
+                }
+            }
+        });
+    }
+}
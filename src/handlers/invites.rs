@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use reqwest::Url;
+use serenity::all::*;
+use tracing::{error, info, warn};
+
+use super::{
+    links::invite_code,
+    modules::{MODULES, Module, ModuleHandler},
+};
+use crate::{
+    config::GetCfg,
+    error::BotError,
+    utils::{
+        alert::{Severity, guild_log},
+        offenses::record_removal,
+    },
+};
+
+const INVITE_HOSTS: [&str; 3] = [
+    "discord.gg/",
+    "discord.com/invite/",
+    "discordapp.com/invite/",
+];
+
+/// Lookups of an invite, which must resolve before the message holding it is let through
+const LOOKUP_ATTEMPTS: u32 = 3;
+const LOOKUP_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Removes invites to servers other than the guild itself and its partners
+pub struct InviteFilterHandler;
+
+/// Find the invite codes in a message, including links written without a scheme or in any case.
+fn invite_codes(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            // Lowercasing only ASCII keeps the offsets into `word`, codes are case-sensitive
+            let lower = word.to_ascii_lowercase();
+            let start = INVITE_HOSTS.iter().filter_map(|h| lower.find(h)).min()?;
+            let url =
+                word[start..].trim_end_matches([')', '>', ']', '.', ',', '!', '?', '"', '\'']);
+            let url = Url::parse(&format!("https://{url}")).ok()?;
+            invite_code(&url).map(str::to_owned)
+        })
+        .collect()
+}
+
+/// Whether a message may hold an invite, before anything is looked up
+fn mentions_discord(content: &str) -> bool {
+    content.to_ascii_lowercase().contains("discord")
+}
+
+/// The guild an invite leads to, `None` for dead invites and group DM invites. Other failures are
+/// retried, as letting an invite through unchecked would defeat the filter.
+async fn invite_guild(ctx: &Context, code: &str) -> Result<Option<InviteGuild>, serenity::Error> {
+    let mut attempt = 1;
+    loop {
+        match ctx.http.get_invite(code, false, false, None).await {
+            Ok(invite) => return Ok(invite.guild),
+            // Dead invites are harmless
+            Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(res)))
+                if res.status_code == StatusCode::NOT_FOUND =>
+            {
+                return Ok(None);
+            }
+            Err(why) if attempt < LOOKUP_ATTEMPTS => {
+                warn!("Failed to look up invite {code}, retrying: {why}");
+                tokio::time::sleep(LOOKUP_RETRY_DELAY * attempt).await;
+                attempt += 1;
+            }
+            Err(why) => return Err(why),
+        }
+    }
+}
+
+async fn filter(ctx: &Context, msg: &Message) -> Result<(), BotError> {
+    let Some(guild_id) = msg.guild_id else {
+        return Ok(());
+    };
+    let cfg = ctx.cfg().await?.load_full();
+    let Some(filter) = cfg
+        .guilds
+        .get(&guild_id)
+        .and_then(|g| g.invite_filter.as_ref())
+    else {
+        return Ok(());
+    };
+    if msg.member.as_ref().is_some_and(|m| {
+        m.roles
+            .iter()
+            .any(|role| filter.exempt_role_ids.contains(role))
+    }) {
+        return Ok(());
+    }
+    for code in invite_codes(&msg.content) {
+        let target = match invite_guild(ctx, &code).await {
+            Ok(Some(target))
+                if target.id == guild_id || filter.partner_guild_ids.contains(&target.id) =>
+            {
+                continue;
+            }
+            Ok(Some(target)) => format!("非合作服务器 **{}** (`{}`)", target.name, target.id),
+            Ok(None) => continue,
+            // Fail closed, the invite may lead anywhere
+            Err(why) => {
+                warn!("Failed to look up invite {code}, removing it unchecked: {why}");
+                "无法验证的服务器".to_owned()
+            }
+        };
+        msg.delete(ctx).await?;
+        info!("Removed invite {code} from {}", msg.author.name);
+        guild_log(
+            ctx,
+            guild_id,
+            Severity::Info,
+            "已删除邀请链接",
+            format!(
+                "{} 在 {} 发送了{target}的邀请链接 `{code}`, 已删除。",
+                msg.author.mention(),
+                msg.channel_id.mention(),
+            ),
+        )
+        .await?;
+        record_removal(ctx, guild_id, msg).await?;
+        return Ok(());
+    }
+    Ok(())
+}
+
+impl ModuleHandler for InviteFilterHandler {
+    fn module(&self) -> Module {
+        Module::Automod
+    }
+}
+
+#[async_trait]
+impl EventHandler for InviteFilterHandler {
+    async fn message(&self, ctx: Context, msg: Message) {
+        if msg.author.bot || !mentions_discord(&msg.content) || !MODULES.is_enabled(Module::Automod)
+        {
+            return;
+        }
+        if let Err(e) = filter(&ctx, &msg).await {
+            error!("Failed to filter invites of message {}: {e}", msg.id);
+        }
+    }
+
+    /// Invites edited into a message are filtered like new ones
+    async fn message_update(
+        &self,
+        ctx: Context,
+        _old_if_available: Option<Message>,
+        new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        if event.author.as_ref().is_some_and(|author| author.bot)
+            || !event.content.as_deref().is_some_and(mentions_discord)
+            || !MODULES.is_enabled(Module::Automod)
+        {
+            return;
+        }
+        let mut msg = match new {
+            Some(msg) => msg,
+            None => match event.channel_id.message(&ctx, event.id).await {
+                Ok(msg) => msg,
+                Err(e) => {
+                    error!("Failed to fetch edited message {}: {e}", event.id);
+                    return;
+                }
+            },
+        };
+        // Fetched messages lack the guild and member that exemptions are checked against
+        event.apply_to_message(&mut msg);
+        msg.guild_id = msg.guild_id.or(event.guild_id);
+        if let Err(e) = filter(&ctx, &msg).await {
+            error!("Failed to filter invites of edited message {}: {e}", msg.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_invite_codes() {
+        assert_eq!(
+            invite_codes("来玩 discord.gg/abc, 或者 <https://discord.com/invite/xyz>!"),
+            ["abc", "xyz"]
+        );
+        assert!(invite_codes("https://discord.com/channels/1/2 discord.gg/").is_empty());
+        assert_eq!(
+            invite_codes("Discord.GG/AbC HTTPS://DISCORD.COM/invite/Xy"),
+            ["AbC", "Xy"]
+        );
+        assert!(mentions_discord("DISCORD.gg/abc"));
+    }
+}
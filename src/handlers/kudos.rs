@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{Datelike, FixedOffset, NaiveTime, Utc};
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::kudos::{leaderboard_embed, month, previous_month},
+    config::GetCfg,
+    database::GetDb,
+    error::BotError,
+    utils::schedule,
+};
+
+/// Counts kudos reactions and posts the monthly leaderboards
+#[derive(Default)]
+pub struct KudosHandler {
+    started: AtomicBool,
+}
+
+/// Apply a kudos reaction being added or removed, ignoring other emojis.
+async fn on_reaction(ctx: &Context, reaction: &Reaction, added: bool) -> Result<(), BotError> {
+    let Some(guild_id) = reaction.guild_id else {
+        return Ok(());
+    };
+    let cfg = ctx.cfg().await?.load_full();
+    let Some(kudos) = cfg.guilds.get(&guild_id).and_then(|g| g.kudos.as_ref()) else {
+        return Ok(());
+    };
+    if !kudos.matches(&reaction.emoji) {
+        return Ok(());
+    }
+    let message = reaction.message(ctx).await?;
+    if message.author.bot || reaction.user_id == Some(message.author.id) {
+        return Ok(());
+    }
+    let offset = FixedOffset::east_opt(cfg.time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    // Kudos belong to the month the message was sent in
+    let month = month(*message.timestamp, offset);
+    let db = ctx.db().await?;
+    if added {
+        db.kudos().give(guild_id, message.author.id, &month).await?;
+    } else {
+        db.kudos().take(guild_id, message.author.id, &month).await?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for KudosHandler {
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        if let Err(e) = on_reaction(&ctx, &reaction, true).await {
+            error!("Failed to count kudos on {}: {e}", reaction.message_id);
+        }
+    }
+
+    async fn reaction_remove(&self, ctx: Context, reaction: Reaction) {
+        if let Err(e) = on_reaction(&ctx, &reaction, false).await {
+            error!("Failed to remove kudos on {}: {e}", reaction.message_id);
+        }
+    }
+
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return; // Leaderboards are already scheduled
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load();
+        let offset = FixedOffset::east_opt(cfg.time_offset)
+            .expect("Failed to create FixedOffset with the configured time offset");
+        info!("Scheduling monthly kudos leaderboards");
+        schedule::daily(NaiveTime::MIN, offset, move || {
+            let ctx = ctx.to_owned();
+            async move {
+                let now = Utc::now();
+                if now.with_timezone(&offset).day() != 1 {
+                    return;
+                }
+                let month = previous_month(now, offset);
+                let f = async || -> Result<(), BotError> {
+                    let cfg = ctx.cfg().await?.load_full();
+                    let db = ctx.db().await?;
+                    for (guild_id, guild) in &cfg.guilds {
+                        let Some(channel_id) = guild.kudos.as_ref().and_then(|k| k.channel_id)
+                        else {
+                            continue;
+                        };
+                        let Some(embed) = leaderboard_embed(&db, *guild_id, &month).await? else {
+                            continue;
+                        };
+                        if let Err(e) = channel_id
+                            .send_message(&ctx, CreateMessage::new().embed(embed))
+                            .await
+                        {
+                            error!("Failed to post kudos leaderboard to {channel_id}: {e}");
+                        }
+                    }
+                    Ok(())
+                };
+                if let Err(e) = f().await {
+                    error!("Failed to post kudos leaderboards: {e}");
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,172 @@
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serenity::all::*;
+use tracing::{error, info};
+use whatlang::Lang;
+
+use super::modules::{MODULES, Module, ModuleHandler};
+use crate::{
+    config::{GetCfg, LanguageRule},
+    error::BotError,
+    utils::offenses::record_removal,
+};
+
+/// Minimum time between two reminders to the same member in the same channel
+const REMINDER_COOLDOWN: Duration = Duration::from_secs(600);
+const REMINDER_LIFETIME: Duration = Duration::from_secs(30);
+
+/// Reminds of or removes messages not written in their channel's languages
+#[derive(Default)]
+pub struct LanguageHandler {
+    reminded: DashMap<(ChannelId, UserId), Instant>,
+}
+
+/// The detected language of `text` if it confidently breaks `rule`. Links, mentions and
+/// custom emojis are ignored, so they neither count as text nor skew the detection.
+fn off_language(rule: &LanguageRule, text: &str) -> Option<Lang> {
+    let text = text
+        .split_whitespace()
+        .filter(|word| !word.starts_with("http") && !word.starts_with('<'))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.chars().filter(|c| c.is_alphabetic()).count() < rule.min_chars {
+        return None;
+    }
+    let info = whatlang::detect(&text)?;
+    (!rule.languages.contains(&info.lang()) && info.confidence() >= rule.min_confidence)
+        .then_some(info.lang())
+}
+
+impl LanguageHandler {
+    async fn enforce(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        rule: &LanguageRule,
+    ) -> Result<(), BotError> {
+        let Some(lang) = off_language(rule, &msg.content) else {
+            return Ok(());
+        };
+        let languages = rule
+            .languages
+            .iter()
+            .map(|l| l.name())
+            .collect::<Vec<_>>()
+            .join(" / ");
+        if rule.remove {
+            msg.delete(ctx).await?;
+            info!(
+                "Removed {} message of {} in {}",
+                lang.eng_name(),
+                msg.author.name,
+                msg.channel_id
+            );
+            if let Some(guild_id) = msg.guild_id {
+                record_removal(ctx, guild_id, msg).await?;
+            }
+        }
+        let key = (msg.channel_id, msg.author.id);
+        if self
+            .reminded
+            .get(&key)
+            .is_some_and(|at| at.elapsed() < REMINDER_COOLDOWN)
+        {
+            return Ok(());
+        }
+        self.reminded.insert(key, Instant::now());
+        let content = if rule.remove {
+            format!(
+                "💬 {} 本频道仅限使用 {languages}, 你的消息已被删除。",
+                msg.author.mention()
+            )
+        } else {
+            format!(
+                "💬 {} 友情提示: 本频道请使用 {languages} 交流~",
+                msg.author.mention()
+            )
+        };
+        let reminder = msg
+            .channel_id
+            .send_message(
+                ctx,
+                CreateMessage::new()
+                    .content(content)
+                    .allowed_mentions(CreateAllowedMentions::new().users([msg.author.id])),
+            )
+            .await?;
+        // Reminders are only meant for the author, so they do not stay around
+        let http = ctx.http.to_owned();
+        tokio::spawn(async move {
+            tokio::time::sleep(REMINDER_LIFETIME).await;
+            if let Err(e) = reminder.delete(http).await {
+                error!("Failed to delete language reminder: {e}");
+            }
+        });
+        Ok(())
+    }
+}
+
+impl ModuleHandler for LanguageHandler {
+    fn module(&self) -> Module {
+        Module::Automod
+    }
+
+    fn reset(&self) {
+        self.reminded.clear();
+    }
+}
+
+#[async_trait]
+impl EventHandler for LanguageHandler {
+    async fn message(&self, ctx: Context, msg: Message) {
+        if msg.author.bot || msg.content.is_empty() || !MODULES.is_enabled(Module::Automod) {
+            return;
+        }
+        let Some(rule) = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load()
+            .language_rules
+            .get(&msg.channel_id)
+            .cloned()
+        else {
+            return;
+        };
+        if let Err(e) = self.enforce(&ctx, &msg, &rule).await {
+            error!("Failed to enforce language rule in {}: {e}", msg.channel_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_off_language() {
+        let rule = LanguageRule {
+            languages: vec![Lang::Eng],
+            min_confidence: 0.5,
+            min_chars: 10,
+            remove: false,
+        };
+        assert_eq!(
+            off_language(&rule, "今天天气真不错, 我们一起去公园散步吧, 顺便买点水果"),
+            Some(Lang::Cmn)
+        );
+        assert_eq!(
+            off_language(
+                &rule,
+                "The weather is really nice today, let's go for a walk"
+            ),
+            None
+        );
+        // Too short once the link is ignored
+        assert_eq!(
+            off_language(&rule, "看 https://example.com/a-long-english-path"),
+            None
+        );
+    }
+}
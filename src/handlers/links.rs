@@ -0,0 +1,297 @@
+use std::{
+    net::IpAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use chrono::FixedOffset;
+use reqwest::{
+    Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    config::{GetCfg, LinkScanCfg},
+    error::BotError,
+    utils::{
+        alert::{Severity, guild_log},
+        schedule,
+    },
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const FLAG_EMOJI: char = '⚠';
+const MAX_REDIRECTS: usize = 10;
+
+/// Checks links in resource channels daily and flags or removes dead ones
+#[derive(Default)]
+pub struct LinkScanHandler {
+    started: AtomicBool,
+}
+
+/// Find the http(s) URLs in a message, without surrounding brackets and punctuation.
+pub fn extract_urls(text: &str) -> Vec<Url> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            let start = word.find("https://").or_else(|| word.find("http://"))?;
+            let url =
+                word[start..].trim_end_matches([')', '>', ']', '.', ',', '!', '?', '"', '\'']);
+            Url::parse(url).ok()
+        })
+        .collect()
+}
+
+/// The invite code of a Discord invite link
+pub(super) fn invite_code(url: &Url) -> Option<&str> {
+    let path = url.path().trim_matches('/');
+    match url.host_str()? {
+        "discord.gg" => Some(path),
+        "discord.com" | "discordapp.com" => path.strip_prefix("invite/"),
+        _ => None,
+    }
+    .filter(|code| !code.is_empty() && !code.contains('/'))
+}
+
+/// Whether an address is on the internet. Links are posted by users, who must not get the bot
+/// to request the host it runs on or its private network.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            // 100.64.0.0/10 is carrier-grade NAT
+            let shared = a == 100 && (64..128).contains(&b);
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || shared)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(ip.into()),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+/// Whether a URL may be requested. Hosts given by name are checked once resolved by
+/// `PublicResolver`, addresses are checked here as they are never resolved.
+fn is_allowed(url: &Url) -> bool {
+    url.host_str().is_some_and(|host| {
+        host.trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .map_or(true, is_public)
+    })
+}
+
+/// Resolves hosts to their public addresses only
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect::<Vec<_>>();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether a link is definitely dead, that is its host or Discord confirmed it is gone. Timeouts,
+/// server errors and failures to connect count as alive, so neither a flaky host nor an outage
+/// on the bot's side gets links flagged.
+async fn is_dead(ctx: &Context, client: &reqwest::Client, url: &Url) -> bool {
+    if let Some(code) = invite_code(url) {
+        return matches!(
+            ctx.http.get_invite(code, false, false, None).await,
+            Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(res)))
+                if res.status_code == StatusCode::NOT_FOUND
+        );
+    }
+    if !is_allowed(url) {
+        return false;
+    }
+    // Some hosts reject HEAD, retry those with GET
+    let res = match client.head(url.to_owned()).send().await {
+        Ok(res) if res.status() != reqwest::StatusCode::METHOD_NOT_ALLOWED => Ok(res),
+        _ => client.get(url.to_owned()).send().await,
+    };
+    res.is_ok_and(|res| {
+        matches!(
+            res.status(),
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE
+        )
+    })
+}
+
+async fn scan_channel(
+    ctx: &Context,
+    client: &reqwest::Client,
+    scan: &LinkScanCfg,
+    channel_id: ChannelId,
+) -> Result<Vec<String>, BotError> {
+    let mut report = Vec::new();
+    let mut before = None;
+    let mut scanned = 0;
+    while scanned < scan.limit {
+        let mut request = GetMessages::new().limit((scan.limit - scanned).min(100) as u8);
+        if let Some(before) = before {
+            request = request.before(before);
+        }
+        let messages = channel_id.messages(ctx, request).await?;
+        let Some(last) = messages.last() else {
+            break;
+        };
+        before = Some(last.id);
+        scanned += messages.len();
+        for msg in messages {
+            let urls = extract_urls(&msg.content);
+            if urls.is_empty() {
+                continue;
+            }
+            let mut dead = Vec::new();
+            for url in &urls {
+                if is_dead(ctx, client, url).await {
+                    dead.push(url.as_str());
+                }
+            }
+            if dead.is_empty() {
+                continue;
+            }
+            let action = if scan.remove && dead.len() == urls.len() {
+                msg.delete(ctx).await?;
+                "已删除"
+            } else {
+                msg.react(ctx, FLAG_EMOJI).await?;
+                "已标记"
+            };
+            report.push(format!("- {action} {}: {}", msg.link(), dead.join(", ")));
+        }
+    }
+    Ok(report)
+}
+
+#[async_trait]
+impl EventHandler for LinkScanHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load();
+        let offset = FixedOffset::east_opt(cfg.time_offset)
+            .expect("Failed to create FixedOffset with the configured time offset");
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .dns_resolver(Arc::new(PublicResolver))
+            .redirect(redirect::Policy::custom(|attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    attempt.error("too many redirects")
+                } else if is_allowed(attempt.url()) {
+                    attempt.follow()
+                } else {
+                    attempt.stop()
+                }
+            }))
+            .build()
+            .expect("Failed to build HTTP client");
+        for (guild_id, guild) in &cfg.guilds {
+            let Some(scan) = guild.link_scan.to_owned() else {
+                continue;
+            };
+            info!("Scanning links of guild {guild_id} daily at {}", scan.time);
+            let guild_id = *guild_id;
+            let ctx = ctx.to_owned();
+            let client = client.to_owned();
+            schedule::daily(scan.time, offset, move || {
+                let ctx = ctx.to_owned();
+                let client = client.to_owned();
+                let scan = scan.to_owned();
+                async move {
+                    let mut report = Vec::new();
+                    for channel_id in &scan.channel_ids {
+                        match scan_channel(&ctx, &client, &scan, *channel_id).await {
+                            Ok(lines) => report.extend(lines),
+                            Err(e) => error!("Failed to scan links in {channel_id}: {e}"),
+                        }
+                    }
+                    if report.is_empty() {
+                        return;
+                    }
+                    let mut description = report.join("\n");
+                    if description.chars().count() > 4000 {
+                        description = description.chars().take(4000).collect::<String>() + "\n…";
+                    }
+                    if let Err(e) = guild_log(
+                        &ctx,
+                        guild_id,
+                        Severity::Warning,
+                        format!("发现 {} 条失效链接", report.len()),
+                        description,
+                    )
+                    .await
+                    {
+                        error!("Failed to report dead links of guild {guild_id}: {e}");
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_extract_urls() {
+        let urls = extract_urls(
+            "看这里 <https://example.com/a>, 还有 (https://discord.gg/abc). 以及 ftp://x",
+        );
+        assert_eq!(
+            urls.iter().map(Url::as_str).collect::<Vec<_>>(),
+            ["https://example.com/a", "https://discord.gg/abc"]
+        );
+        assert_eq!(invite_code(&urls[1]), Some("abc"));
+        assert_eq!(
+            invite_code(&Url::parse("https://discord.com/invite/xyz").unwrap()),
+            Some("xyz")
+        );
+        assert_eq!(invite_code(&urls[0]), None);
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let allowed = |url| is_allowed(&Url::parse(url).unwrap());
+        assert!(allowed("https://example.com/a"));
+        assert!(allowed("http://93.184.215.14/"));
+        assert!(!allowed("http://127.0.0.1:8080/admin"));
+        assert!(!allowed("http://10.0.0.1/"));
+        assert!(!allowed("http://169.254.169.254/latest/meta-data"));
+        assert!(!allowed("http://100.100.1.1/"));
+        assert!(!allowed("http://[::1]/"));
+        assert!(!allowed("http://[::ffff:192.168.1.1]/"));
+        assert!(!allowed("http://[fd00::1]/"));
+    }
+}
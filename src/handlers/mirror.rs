@@ -0,0 +1,62 @@
+use serenity::all::*;
+use tracing::error;
+
+use crate::{config::GetCfg, error::BotError};
+
+/// Copies messages of mirrored channels into their archive
+pub struct MirrorHandler;
+
+#[async_trait]
+impl EventHandler for MirrorHandler {
+    async fn message(&self, ctx: Context, msg: Message) {
+        if msg.author.bot || (msg.content.is_empty() && msg.attachments.is_empty()) {
+            return;
+        }
+        let Some(mirror) = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load()
+            .mirrors
+            .get(&msg.channel_id)
+            .cloned()
+        else {
+            return; // Not a mirrored channel
+        };
+        let f = async || -> Result<(), BotError> {
+            let mut description = msg.content.to_owned();
+            // Attachment links expire, so only their names are kept
+            for attachment in &msg.attachments {
+                description.push_str(&format!("\n📎 {}", attachment.filename));
+            }
+            let mut embed = CreateEmbed::new()
+                .description(description)
+                .footer(CreateEmbedFooter::new(format!(
+                    "#{}",
+                    msg.channel_id.name(&ctx).await?
+                )))
+                .timestamp(msg.timestamp);
+            if !mirror.anonymous {
+                embed = embed.author(
+                    CreateEmbedAuthor::new(msg.author.display_name()).icon_url(msg.author.face()),
+                );
+            }
+            mirror
+                .target
+                .send_message(
+                    &ctx,
+                    CreateMessage::new()
+                        .embed(embed)
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                )
+                .await?;
+            Ok(())
+        };
+        if let Err(e) = f().await {
+            error!(
+                "Failed to mirror message {} from {} to {}: {e}",
+                msg.id, msg.channel_id, mirror.target
+            );
+        }
+    }
+}
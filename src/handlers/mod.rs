@@ -1,11 +1,89 @@
 mod active;
+mod activity_roles;
+mod application;
+mod backup;
+mod ban_sync;
 mod boot;
+mod bot_status;
+mod channel_labels;
+mod channel_mute;
 mod cookie;
+mod crash_loop;
+mod domains;
+mod drip;
+mod faq;
 mod flush;
+mod game_server;
+mod game_topic;
+mod go_live;
+mod growth;
+mod invites;
+mod kudos;
+mod language;
+mod links;
+mod mirror;
+mod modules;
+mod office_hours;
+mod onboarding;
+mod packages;
+mod panics;
+mod price;
+mod ratelimits;
+mod reaction_rules;
+mod releases;
+mod report;
+mod suggestion;
+mod thread_naming;
+mod todo;
+mod topics;
 mod tree_hole;
+mod trivia;
+mod uploads;
+mod uptime;
+mod voice_idle;
+mod welcome;
 
 pub use active::ActiveHandler;
+pub use activity_roles::ActivityRoleHandler;
+pub use application::ApplicationHandler;
+pub use backup::BackupHandler;
+pub use ban_sync::{BanSyncHandler, expect_softban};
 pub use boot::BootHandler;
+pub use bot_status::{BotStatusHandler, ShardManagerKey};
+pub use channel_labels::ChannelLabelHandler;
+pub use channel_mute::ChannelMuteHandler;
 pub use cookie::CookieHandler;
+pub use crash_loop::CrashLoopHandler;
+pub use domains::DomainHandler;
+pub use drip::DripHandler;
+pub use faq::FaqHandler;
 pub use flush::FlushHandler;
-pub use tree_hole::TreeHoleHandler;
+pub use game_server::GameServerHandler;
+pub use game_topic::GameTopicHandler;
+pub use go_live::GoLiveHandler;
+pub use growth::GrowthHandler;
+pub use invites::InviteFilterHandler;
+pub use kudos::KudosHandler;
+pub use language::LanguageHandler;
+pub use links::{LinkScanHandler, extract_urls};
+pub use mirror::MirrorHandler;
+pub use modules::{MODULES, Module, ModuleHandler, ModulesHandler};
+pub use office_hours::OfficeHoursHandler;
+pub use onboarding::OnboardingHandler;
+pub use packages::PackageHandler;
+pub use panics::PanicHandler;
+pub use price::PriceHandler;
+pub use ratelimits::RatelimitHandler;
+pub use reaction_rules::ReactionRuleHandler;
+pub use releases::ReleaseHandler;
+pub use report::ReportHandler;
+pub use suggestion::SuggestionHandler;
+pub use thread_naming::ThreadNamingHandler;
+pub use todo::TodoHandler;
+pub use topics::TopicRotationHandler;
+pub use tree_hole::{TreeHoleHandler, purge};
+pub use trivia::TriviaHandler;
+pub use uploads::UploadHandler;
+pub use uptime::UptimeHandler;
+pub use voice_idle::VoiceIdleHandler;
+pub use welcome::WelcomeHandler;
@@ -0,0 +1,162 @@
+use std::sync::{Arc, LazyLock, OnceLock, RwLock};
+
+use dashmap::{DashMap, DashSet};
+use poise::ChoiceParameter;
+use serenity::all::*;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+/// A group of handlers that can be switched off and on again at runtime, so one that misbehaves
+/// is isolated without restarting the bot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ChoiceParameter)]
+pub enum Module {
+    /// Invite and language filters
+    #[name = "Automod"]
+    #[name_localized("zh-CN", "自动审核")]
+    Automod,
+    /// Release, package and upload announcements
+    #[name = "Feeds"]
+    #[name_localized("zh-CN", "订阅推送")]
+    Feeds,
+}
+
+/// A handler that belongs to a [`Module`]. Event handlers of a module check
+/// [`ModuleRegistry::is_enabled`] themselves, jobs are started and aborted by the registry.
+#[async_trait]
+pub trait ModuleHandler: Send + Sync {
+    fn module(&self) -> Module;
+
+    /// Spawn the handler's background jobs, aborted when its module is disabled
+    async fn start(&self, _ctx: &Context) -> Vec<JoinHandle<()>> {
+        vec![]
+    }
+
+    /// Drop state kept between events, so the handler starts afresh when re-enabled
+    fn reset(&self) {}
+}
+
+/// Every module handler of the bot, with the jobs currently running for each module. Disabled
+/// modules are only remembered until the bot restarts.
+#[derive(Default)]
+pub struct ModuleRegistry {
+    handlers: RwLock<Vec<Arc<dyn ModuleHandler>>>,
+    disabled: DashSet<Module>,
+    jobs: DashMap<Module, Vec<JoinHandle<()>>>,
+    /// Set once the cache is ready, jobs of modules enabled before then start with the rest
+    ctx: OnceLock<Context>,
+}
+
+pub static MODULES: LazyLock<ModuleRegistry> = LazyLock::new(ModuleRegistry::default);
+
+impl ModuleRegistry {
+    /// Add a handler at startup, returning it to be registered as an event handler as well
+    pub fn register<H: ModuleHandler + 'static>(&self, handler: H) -> Arc<H> {
+        let handler = Arc::new(handler);
+        self.handlers.write().unwrap().push(handler.to_owned());
+        handler
+    }
+
+    pub fn is_enabled(&self, module: Module) -> bool {
+        !self.disabled.contains(&module)
+    }
+
+    fn handlers(&self, module: Module) -> Vec<Arc<dyn ModuleHandler>> {
+        self.handlers
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|h| h.module() == module)
+            .cloned()
+            .collect()
+    }
+
+    async fn spawn(&self, ctx: &Context, module: Module) {
+        let mut jobs = vec![];
+        for handler in self.handlers(module) {
+            jobs.extend(handler.start(ctx).await);
+        }
+        self.jobs.insert(module, jobs);
+    }
+
+    /// Start the jobs of every enabled module, once
+    async fn start(&self, ctx: &Context) {
+        if self.ctx.set(ctx.to_owned()).is_err() {
+            return;
+        }
+        for module in [Module::Automod, Module::Feeds] {
+            if self.is_enabled(module) {
+                self.spawn(ctx, module).await;
+            }
+        }
+    }
+
+    /// Switch a module off, aborting its jobs and dropping its state. Returns whether it was on.
+    pub fn disable(&self, module: Module) -> bool {
+        if !self.disabled.insert(module) {
+            return false;
+        }
+        if let Some((_, jobs)) = self.jobs.remove(&module) {
+            jobs.iter().for_each(JoinHandle::abort);
+        }
+        self.handlers(module).iter().for_each(|h| h.reset());
+        info!("Disabled module {module:?}");
+        true
+    }
+
+    /// Switch a module back on, restarting its jobs. Returns whether it was off.
+    pub async fn enable(&self, module: Module) -> bool {
+        if self.disabled.remove(&module).is_none() {
+            return false;
+        }
+        if let Some(ctx) = self.ctx.get() {
+            self.spawn(ctx, module).await;
+        }
+        info!("Enabled module {module:?}");
+        true
+    }
+}
+
+/// Starts the jobs of registered modules once the cache is ready
+pub struct ModulesHandler;
+
+#[async_trait]
+impl EventHandler for ModulesHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        MODULES.start(&ctx).await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+
+    impl ModuleHandler for Counter {
+        fn module(&self) -> Module {
+            Module::Feeds
+        }
+
+        fn reset(&self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_toggle() {
+        let modules = ModuleRegistry::default();
+        let counter = modules.register(Counter::default());
+        assert!(modules.is_enabled(Module::Feeds));
+        assert!(modules.disable(Module::Feeds));
+        assert!(!modules.disable(Module::Feeds));
+        assert!(!modules.is_enabled(Module::Feeds));
+        assert!(modules.is_enabled(Module::Automod));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert!(modules.enable(Module::Feeds).await);
+        assert!(!modules.enable(Module::Feeds).await);
+        assert!(modules.is_enabled(Module::Feeds));
+    }
+}
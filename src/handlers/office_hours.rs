@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::FixedOffset;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    config::{GetCfg, OfficeHoursCfg},
+    error::BotError,
+    utils::schedule,
+};
+
+/// Opens and closes channels on the `officeHours` schedules
+#[derive(Default)]
+pub struct OfficeHoursHandler {
+    started: AtomicBool,
+}
+
+/// Deny or restore `@everyone` speaking in a channel, keeping its other overwrites.
+async fn set_locked(ctx: &Context, channel_id: ChannelId, locked: bool) -> Result<(), BotError> {
+    let Some(channel) = channel_id.to_channel(ctx).await?.guild() else {
+        snafu::whatever!("Channel {channel_id} is not a guild channel");
+    };
+    let everyone = RoleId::new(channel.guild_id.get());
+    let permission = match channel.kind {
+        ChannelType::Voice | ChannelType::Stage => Permissions::CONNECT,
+        _ => Permissions::SEND_MESSAGES | Permissions::SEND_MESSAGES_IN_THREADS,
+    };
+    let (mut allow, mut deny) = channel
+        .permission_overwrites
+        .iter()
+        .find(|o| o.kind == PermissionOverwriteType::Role(everyone))
+        .map_or((Permissions::empty(), Permissions::empty()), |o| {
+            (o.allow, o.deny)
+        });
+    if locked {
+        allow.remove(permission);
+        deny.insert(permission);
+    } else {
+        deny.remove(permission);
+    }
+    channel
+        .create_permission(
+            ctx,
+            PermissionOverwrite {
+                allow,
+                deny,
+                kind: PermissionOverwriteType::Role(everyone),
+            },
+        )
+        .await?;
+    Ok(())
+}
+
+async fn toggle(ctx: &Context, hours: &OfficeHoursCfg, locked: bool) {
+    for channel_id in &hours.channel_ids {
+        if let Err(e) = set_locked(ctx, *channel_id, locked).await {
+            error!(
+                "Failed to {} {channel_id} for {}: {e}",
+                if locked { "lock" } else { "unlock" },
+                hours.name
+            );
+        }
+    }
+    let Some(announce_channel_id) = hours.announce_channel_id else {
+        return;
+    };
+    let content = if locked {
+        hours
+            .close_message
+            .to_owned()
+            .unwrap_or_else(|| format!("🔒 **{}** 已关闭, 下次再见!", hours.name))
+    } else {
+        hours
+            .open_message
+            .to_owned()
+            .unwrap_or_else(|| format!("🔓 **{}** 已开放, 欢迎加入!", hours.name))
+    };
+    if let Err(e) = announce_channel_id
+        .send_message(ctx, CreateMessage::new().content(content))
+        .await
+    {
+        error!(
+            "Failed to announce {} in {announce_channel_id}: {e}",
+            hours.name
+        );
+    }
+}
+
+#[async_trait]
+impl EventHandler for OfficeHoursHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load();
+        let offset = FixedOffset::east_opt(cfg.time_offset)
+            .expect("Failed to create FixedOffset with the configured time offset");
+        for hours in cfg.office_hours.iter().cloned() {
+            info!(
+                "Scheduling {}: open at `{}`, close at `{}`",
+                hours.name, hours.open, hours.close
+            );
+            for (schedule, locked) in [
+                (hours.open.to_owned(), false),
+                (hours.close.to_owned(), true),
+            ] {
+                let ctx = ctx.to_owned();
+                let hours = hours.to_owned();
+                schedule::cron(schedule, offset, move || {
+                    let ctx = ctx.to_owned();
+                    let hours = hours.to_owned();
+                    async move { toggle(&ctx, &hours, locked).await }
+                });
+            }
+        }
+    }
+}
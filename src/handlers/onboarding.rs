@@ -0,0 +1,89 @@
+use serenity::all::{colours::branding::BLURPLE, *};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::{BotCfg, GetCfg, GuildCfg},
+    error::BotError,
+};
+
+/// Greets a guild the bot was just added to and gives it a config entry to fill in
+pub struct OnboardingHandler;
+
+#[async_trait]
+impl EventHandler for OnboardingHandler {
+    async fn guild_create(&self, ctx: Context, guild: Guild, is_new: Option<bool>) {
+        if is_new != Some(true) {
+            return; // Known guilds also arrive on every start
+        }
+        info!("Joined guild {} ({})", guild.name, guild.id);
+        if let Err(e) = add_guild_cfg(&ctx, guild.id).await {
+            error!("Failed to add configuration of guild {}: {e}", guild.id);
+        }
+        let setup = CreateEmbed::new()
+            .title(format!("👋 已加入 {}", guild.name))
+            .description(
+                "已为该服务器创建默认配置, 所有功能默认关闭。\n\
+                 在服务器中使用 `/setup` 设置日志频道、树洞、管理员角色和欢迎消息, \
+                 使用 `!help` 查看机器人信息。",
+            )
+            .color(BLURPLE);
+        let user_id = inviter(&ctx, guild.id).await.unwrap_or(guild.owner_id);
+        if let Err(e) = user_id
+            .direct_message(&ctx, CreateMessage::new().embed(setup))
+            .await
+        {
+            warn!(
+                "Failed to send setup summary of {} to {user_id}: {e}",
+                guild.id
+            );
+        }
+        let Some(channel_id) = guild.system_channel_id else {
+            return;
+        };
+        let intro = CreateEmbed::new()
+            .title("👋 大家好")
+            .description(
+                "管理员可以使用 `/setup` 完成设置, 所有人可以使用 `!help` 查看机器人信息。",
+            )
+            .color(BLURPLE);
+        if let Err(e) = channel_id
+            .send_message(&ctx, CreateMessage::new().embed(intro))
+            .await
+        {
+            warn!("Failed to introduce the bot in {channel_id}: {e}");
+        }
+    }
+}
+
+/// Who added the bot, from the audit log if the bot may read it
+async fn inviter(ctx: &Context, guild_id: GuildId) -> Option<UserId> {
+    let logs = guild_id
+        .audit_logs(
+            ctx,
+            Some(audit_log::Action::Member(audit_log::MemberAction::BotAdd)),
+            None,
+            None,
+            Some(5),
+        )
+        .await
+        .ok()?;
+    let bot_id = ctx.cache.current_user().id;
+    logs.entries
+        .into_iter()
+        .find(|entry| entry.target_id.map(|id| id.get()) == Some(bot_id.get()))
+        .map(|entry| entry.user_id)
+}
+
+/// Give a guild an empty configuration entry, unless it already has one.
+async fn add_guild_cfg(ctx: &Context, guild_id: GuildId) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?;
+    if cfg.load().guilds.contains_key(&guild_id) {
+        return Ok(());
+    }
+    cfg.rcu(|cfg| {
+        let mut cfg = BotCfg::clone(cfg);
+        cfg.guilds.entry(guild_id).or_insert_with(GuildCfg::default);
+        cfg
+    });
+    cfg.load().write()
+}
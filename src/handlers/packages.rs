@@ -0,0 +1,257 @@
+use std::time::Duration;
+
+use reqwest::{StatusCode, header};
+use serde::Deserialize;
+use serenity::all::*;
+use snafu::OptionExt;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use super::modules::{Module, ModuleHandler};
+use crate::{
+    config::{GetCfg, PackageWatch},
+    database::GetDb,
+    error::BotError,
+    utils::{
+        alert::{Severity, alert},
+        schedule,
+    },
+};
+
+const MANIFEST_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+pub struct PackageHandler;
+
+#[async_trait]
+impl ModuleHandler for PackageHandler {
+    fn module(&self) -> Module {
+        Module::Feeds
+    }
+
+    async fn start(&self, ctx: &Context) -> Vec<JoinHandle<()>> {
+        let ctx = ctx.to_owned();
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        let interval = cfg.load().package_interval;
+        info!("Polling package updates every {}s", interval.as_secs());
+        vec![schedule::every(interval, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.load_full();
+            async move {
+                let client = reqwest::Client::builder()
+                    .user_agent("dc-bot")
+                    .timeout(Duration::from_secs(30))
+                    .build()
+                    .expect("Failed to build HTTP client");
+                for watch in &cfg.package_watches {
+                    if let Err(e) = check_package(&ctx, &client, watch).await {
+                        error!("Failed to check {} for updates: {e}", watch.key());
+                    }
+                }
+            }
+        })]
+    }
+}
+
+impl PackageWatch {
+    /// Key of the last seen version in the watch state table
+    fn key(&self) -> String {
+        match self {
+            Self::Crates { name } => format!("crates:{name}"),
+            Self::Pypi { name } => format!("pypi:{name}"),
+            Self::Docker { image } => format!("docker:{image}"),
+        }
+    }
+
+    fn url(&self) -> String {
+        match self {
+            Self::Crates { name } => format!("https://crates.io/crates/{name}"),
+            Self::Pypi { name } => format!("https://pypi.org/project/{name}/"),
+            Self::Docker { image } => {
+                let (registry, repo, _) = parse_image(image);
+                match registry {
+                    "registry-1.docker.io" => format!("https://hub.docker.com/r/{repo}"),
+                    registry => format!("https://{registry}/{repo}"),
+                }
+            }
+        }
+    }
+
+    /// The latest version, or manifest digest for images
+    async fn latest(&self, client: &reqwest::Client) -> Result<String, BotError> {
+        match self {
+            Self::Crates { name } => {
+                #[derive(Deserialize)]
+                struct Response {
+                    #[serde(rename = "crate")]
+                    krate: Crate,
+                }
+                #[derive(Deserialize)]
+                struct Crate {
+                    max_stable_version: Option<String>,
+                    newest_version: String,
+                }
+                let res = client
+                    .get(format!("https://crates.io/api/v1/crates/{name}"))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<Response>()
+                    .await?;
+                Ok(res
+                    .krate
+                    .max_stable_version
+                    .unwrap_or(res.krate.newest_version))
+            }
+            Self::Pypi { name } => {
+                #[derive(Deserialize)]
+                struct Response {
+                    info: Info,
+                }
+                #[derive(Deserialize)]
+                struct Info {
+                    version: String,
+                }
+                Ok(client
+                    .get(format!("https://pypi.org/pypi/{name}/json"))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<Response>()
+                    .await?
+                    .info
+                    .version)
+            }
+            Self::Docker { image } => image_digest(client, image).await,
+        }
+    }
+}
+
+/// Split an image reference into registry host, repository and tag.
+fn parse_image(image: &str) -> (&str, String, &str) {
+    let (rest, tag) = match image.rsplit_once(':') {
+        Some((rest, tag)) if !tag.contains('/') => (rest, tag),
+        _ => (image, "latest"),
+    };
+    match rest.split_once('/') {
+        Some((host, repo)) if host.contains(['.', ':']) || host == "localhost" => {
+            (host, repo.to_string(), tag)
+        }
+        Some(_) => ("registry-1.docker.io", rest.to_string(), tag),
+        None => ("registry-1.docker.io", format!("library/{rest}"), tag),
+    }
+}
+
+/// Parse a `Bearer realm="..",service="..",scope=".."` challenge into the token URL.
+fn token_url(challenge: &str) -> Option<String> {
+    let params = challenge.strip_prefix("Bearer ")?;
+    let mut realm = None;
+    let mut query = vec![];
+    for param in params.split(',') {
+        let (key, value) = param.trim().split_once('=')?;
+        let value = value.trim_matches('"');
+        match key {
+            "realm" => realm = Some(value),
+            key => query.push(format!("{key}={value}")),
+        }
+    }
+    Some(format!("{}?{}", realm?, query.join("&")))
+}
+
+async fn image_digest(client: &reqwest::Client, image: &str) -> Result<String, BotError> {
+    #[derive(Deserialize)]
+    struct Token {
+        #[serde(alias = "access_token")]
+        token: String,
+    }
+
+    let (registry, repo, tag) = parse_image(image);
+    let url = format!("https://{registry}/v2/{repo}/manifests/{tag}");
+    let head = || client.head(&url).header(header::ACCEPT, MANIFEST_TYPES);
+    let mut res = head().send().await?;
+    // Registries hand out anonymous pull tokens through the auth challenge
+    if res.status() == StatusCode::UNAUTHORIZED {
+        let token_url = res
+            .headers()
+            .get(header::WWW_AUTHENTICATE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(token_url)
+            .whatever_context::<&str, BotError>("Registry sent no usable auth challenge")?;
+        let token = client
+            .get(token_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Token>()
+            .await?;
+        res = head().bearer_auth(token.token).send().await?;
+    }
+    Ok(res
+        .error_for_status()?
+        .headers()
+        .get("docker-content-digest")
+        .and_then(|h| h.to_str().ok())
+        .whatever_context::<&str, BotError>("Registry sent no manifest digest")?
+        .to_string())
+}
+
+async fn check_package(
+    ctx: &Context,
+    client: &reqwest::Client,
+    watch: &PackageWatch,
+) -> Result<(), BotError> {
+    let latest = watch.latest(client).await?;
+    let db = ctx.db().await?;
+    let key = watch.key();
+    let last_seen = db.watch().get(&key).await?;
+    if last_seen.as_deref() == Some(latest.as_str()) {
+        return Ok(());
+    }
+    db.watch().set(&key, &latest).await?;
+    let Some(last_seen) = last_seen else {
+        info!("Tracking {key} from {latest}");
+        return Ok(());
+    };
+    alert(
+        ctx,
+        Severity::Info,
+        format!("{key} 有新版本"),
+        format!("`{last_seen}` → `{latest}`\n{}", watch.url()),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_image() {
+        assert_eq!(
+            parse_image("nginx"),
+            ("registry-1.docker.io", "library/nginx".into(), "latest")
+        );
+        assert_eq!(
+            parse_image("grafana/grafana:11.0.0"),
+            ("registry-1.docker.io", "grafana/grafana".into(), "11.0.0")
+        );
+        assert_eq!(
+            parse_image("ghcr.io/owner/app:edge"),
+            ("ghcr.io", "owner/app".into(), "edge")
+        );
+        assert_eq!(
+            parse_image("localhost:5000/app"),
+            ("localhost:5000", "app".into(), "latest")
+        );
+        assert_eq!(
+            token_url(
+                r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/nginx:pull""#
+            )
+            .as_deref(),
+            Some(
+                "https://auth.docker.io/token?service=registry.docker.io&scope=repository:library/nginx:pull"
+            )
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serenity::all::{colours::css::DANGER, *};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::{error, warn};
+
+use crate::{
+    database::GetDb,
+    error::BotError,
+    utils::{
+        crash::{PanicReport, record_panic},
+        owners,
+    },
+};
+
+/// Backtraces are cut to this many characters to fit an embed
+const MAX_BACKTRACE_LEN: usize = 3000;
+/// Discord's limit for embed field values
+const MAX_FIELD_LEN: usize = 1024;
+/// The same panic is sent to the owners at most once per this long, the rest is only recorded
+const REPORT_COOLDOWN: Duration = Duration::from_secs(600);
+
+/// Panics recently sent to the owners, so one in a hot path does not flood their DMs
+#[derive(Default)]
+struct Throttle(HashMap<(String, String), (Instant, usize)>);
+
+impl Throttle {
+    /// Whether a panic is to be sent now, with how many of the same were held back since it last
+    /// was. Panics are the same if they come from the same task with the same message.
+    fn admit(&mut self, report: &PanicReport, now: Instant) -> Option<usize> {
+        self.0
+            .retain(|_, (sent, held)| now - *sent < REPORT_COOLDOWN || *held > 0);
+        let key = (report.task.to_owned(), report.message.to_owned());
+        let held = match self.0.get_mut(&key) {
+            Some((sent, held)) if now - *sent < REPORT_COOLDOWN => {
+                *held += 1;
+                return None;
+            }
+            Some((_, held)) => *held,
+            None => 0,
+        };
+        self.0.insert(key, (now, 0));
+        Some(held)
+    }
+}
+
+/// Records panics captured by the panic hook and sends them to the owners
+pub struct PanicHandler {
+    /// Taken by the first `cache_ready`
+    reports: Mutex<Option<UnboundedReceiver<PanicReport>>>,
+}
+
+impl PanicHandler {
+    pub fn new(reports: UnboundedReceiver<PanicReport>) -> Self {
+        Self {
+            reports: Mutex::new(Some(reports)),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for PanicHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        let Some(mut reports) = self.reports.lock().unwrap().take() else {
+            return; // Already reporting
+        };
+        tokio::spawn(async move {
+            let mut throttle = Throttle::default();
+            while let Some(report) = reports.recv().await {
+                let held = throttle.admit(&report, Instant::now());
+                if let Err(e) = report_panic(&ctx, &report, held).await {
+                    error!("Failed to report panic in {}: {e}", report.task);
+                }
+            }
+        });
+    }
+}
+
+/// Record a panic, and send it to the owners unless `held` is `None`, noting how many of the same
+/// were held back before.
+async fn report_panic(
+    ctx: &Context,
+    report: &PanicReport,
+    held: Option<usize>,
+) -> Result<(), BotError> {
+    record_panic(&ctx.db().await?, report).await?;
+    let Some(held) = held else {
+        return Ok(());
+    };
+    let backtrace = report
+        .backtrace
+        .chars()
+        .take(MAX_BACKTRACE_LEN)
+        .collect::<String>();
+    let message = report
+        .message
+        .chars()
+        .take(MAX_FIELD_LEN)
+        .collect::<String>();
+    let mut embed = CreateEmbed::new()
+        .title("💥 Panic")
+        .field("任务", format!("`{}`", report.task), false)
+        .field("信息", message, false)
+        .description(format!("```\n{backtrace}\n```"))
+        .colour(DANGER)
+        .timestamp(Timestamp::from(report.at));
+    if held > 0 {
+        embed = embed.field("此前未通知的相同 panic", held.to_string(), false);
+    }
+    for owner in owners(ctx).await? {
+        if let Err(e) = owner
+            .direct_message(ctx, CreateMessage::new().embed(embed.to_owned()))
+            .await
+        {
+            warn!("Failed to send panic report to owner {owner}: {e}");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn test_throttle() {
+        let report = |message: &str| PanicReport {
+            at: Utc::now(),
+            task: "task".to_owned(),
+            message: message.to_owned(),
+            backtrace: String::new(),
+        };
+        let mut throttle = Throttle::default();
+        let start = Instant::now();
+        let minutes = |m: u64| start + Duration::from_secs(m * 60);
+        assert_eq!(throttle.admit(&report("a"), start), Some(0));
+        assert_eq!(throttle.admit(&report("a"), minutes(1)), None);
+        assert_eq!(throttle.admit(&report("b"), minutes(2)), Some(0));
+        assert_eq!(throttle.admit(&report("a"), minutes(3)), None);
+        assert_eq!(throttle.admit(&report("a"), minutes(11)), Some(2));
+        assert_eq!(throttle.admit(&report("a"), minutes(30)), Some(0));
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use dashmap::DashMap;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::price::format_price,
+    config::{GetCfg, Market, PriceWatch},
+    error::BotError,
+    utils::schedule,
+};
+
+#[derive(Default)]
+pub struct PriceHandler {
+    started: AtomicBool,
+    /// Last observed price per symbol, used to detect threshold crossings
+    last: Arc<DashMap<(Market, String), f64>>,
+}
+
+#[async_trait]
+impl EventHandler for PriceHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        let interval = cfg.load().price_interval;
+        info!("Polling price watches every {}s", interval.as_secs());
+        let last = self.last.to_owned();
+        schedule::every(interval, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.to_owned();
+            let last = last.to_owned();
+            async move {
+                // Watches are re-read every tick so edits apply without a restart
+                let watches = cfg.load().price_watches.to_owned();
+                if let Err(e) = check_watches(&ctx, &watches, &last).await {
+                    error!("Failed to check price watches: {e}");
+                }
+            }
+        });
+    }
+}
+
+async fn check_watches(
+    ctx: &Context,
+    watches: &[PriceWatch],
+    last: &DashMap<(Market, String), f64>,
+) -> Result<(), BotError> {
+    let mut quotes = HashMap::new();
+    for watch in watches {
+        let key = (watch.market, watch.symbol.to_uppercase());
+        if !quotes.contains_key(&key) {
+            let quote = match watch.market.quote(&watch.symbol).await {
+                Ok(Some(quote)) => quote,
+                Ok(None) => {
+                    error!("Unknown symbol {} in price watch", watch.symbol);
+                    continue;
+                }
+                // The other watches still get checked
+                Err(e) => {
+                    error!("Failed to get the price of {}: {e}", watch.symbol);
+                    continue;
+                }
+            };
+            quotes.insert(key.to_owned(), quote);
+        }
+        let quote = &quotes[&key];
+        let Some(prev) = last.get(&key).map(|p| *p) else {
+            continue; // First observation, nothing to compare against yet
+        };
+        let crossed = match (watch.above, watch.below) {
+            (Some(above), _) if prev < above && quote.price >= above => Some(("📈 上穿", above)),
+            (_, Some(below)) if prev > below && quote.price <= below => Some(("📉 下穿", below)),
+            _ => None,
+        };
+        let Some((direction, threshold)) = crossed else {
+            continue;
+        };
+        let mut msg = CreateMessage::new().embed(
+            CreateEmbed::new()
+                .title(format!("{direction} {threshold}"))
+                .description(format!(
+                    "**{}** 当前价格 {}",
+                    quote.symbol,
+                    format_price(quote)
+                ))
+                .timestamp(Timestamp::now()),
+        );
+        if let Some(role_id) = watch.role_id {
+            msg = msg
+                .content(role_id.mention().to_string())
+                .allowed_mentions(CreateAllowedMentions::new().roles([role_id]));
+        }
+        // Later watches still get checked and the quotes recorded, so this alert isn't resent
+        if let Err(e) = watch.channel_id.send_message(ctx, msg).await {
+            error!(
+                "Failed to send price alert for {} to {}: {e}",
+                quote.symbol, watch.channel_id
+            );
+        }
+    }
+    for (key, quote) in quotes {
+        last.insert(key, quote.price);
+    }
+    Ok(())
+}
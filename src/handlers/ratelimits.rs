@@ -0,0 +1,20 @@
+use serenity::all::*;
+use tracing::warn;
+
+use crate::commands::ratelimits::record_ratelimit;
+
+/// Collects the rate limits serenity's ratelimiter runs into for `/ratelimits`
+pub struct RatelimitHandler;
+
+#[async_trait]
+impl EventHandler for RatelimitHandler {
+    async fn ratelimit(&self, data: RatelimitInfo) {
+        if data.global {
+            warn!(
+                "Globally rate limited on {} for {:?}",
+                data.path, data.timeout
+            );
+        }
+        record_ratelimit(&data);
+    }
+}
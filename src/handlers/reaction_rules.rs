@@ -0,0 +1,133 @@
+use dashmap::DashSet;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    config::{GetCfg, ReactionAction, ReactionRule},
+    error::BotError,
+    utils::{
+        alert::{Severity, guild_log},
+        offenses::record_removal,
+    },
+};
+
+/// Deletes or pins messages once enough members react with a rule's emoji
+#[derive(Default)]
+pub struct ReactionRuleHandler {
+    /// Messages already acted on, so late reactions do not trigger again
+    handled: DashSet<MessageId>,
+}
+
+/// Members whose reaction counts towards `rule`: not bots, not the author and not too new
+async fn voters(
+    ctx: &Context,
+    guild_id: GuildId,
+    msg: &Message,
+    rule: &ReactionRule,
+    emoji: &ReactionType,
+) -> Result<Vec<UserId>, BotError> {
+    let users = msg
+        .channel_id
+        .reaction_users(ctx, msg.id, emoji.to_owned(), Some(100), None)
+        .await?;
+    let joined_before = Timestamp::from(chrono::Utc::now() - rule.min_member_age);
+    let mut voters = vec![];
+    for user in users {
+        if user.bot || user.id == msg.author.id {
+            continue;
+        }
+        if !rule.min_member_age.is_zero() {
+            let Ok(member) = guild_id.member(ctx, user.id).await else {
+                continue; // Left the guild since reacting
+            };
+            if member.joined_at.is_none_or(|at| at > joined_before) {
+                continue;
+            }
+        }
+        voters.push(user.id);
+    }
+    Ok(voters)
+}
+
+impl ReactionRuleHandler {
+    async fn on_reaction(&self, ctx: &Context, reaction: &Reaction) -> Result<(), BotError> {
+        let Some(guild_id) = reaction.guild_id else {
+            return Ok(());
+        };
+        if self.handled.contains(&reaction.message_id) {
+            return Ok(());
+        }
+        let cfg = ctx.cfg().await?.load_full();
+        let Some(rule) = cfg
+            .reaction_rules
+            .iter()
+            .find(|r| r.channel_ids.contains(&reaction.channel_id) && r.matches(&reaction.emoji))
+        else {
+            return Ok(());
+        };
+        let msg = reaction.message(ctx).await?;
+        if msg.pinned && rule.action == ReactionAction::Pin {
+            return Ok(());
+        }
+        if !rule.exempt_role_ids.is_empty() {
+            let member = guild_id.member(ctx, msg.author.id).await.ok();
+            if member.is_some_and(|m| m.roles.iter().any(|r| rule.exempt_role_ids.contains(r))) {
+                return Ok(());
+            }
+        }
+        let voters = voters(ctx, guild_id, &msg, rule, &reaction.emoji).await?;
+        if voters.len() < rule.threshold || !self.handled.insert(msg.id) {
+            return Ok(());
+        }
+        let voters = voters
+            .iter()
+            .map(|id| id.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        match rule.action {
+            ReactionAction::Delete => {
+                msg.delete(ctx).await?;
+                info!("Deleted message {} by reaction vote", msg.id);
+                guild_log(
+                    ctx,
+                    guild_id,
+                    Severity::Warning,
+                    format!("投票删除 {}", reaction.emoji),
+                    format!(
+                        "{} 在 {} 的消息已被投票删除。\n\n> {}\n\n投票者: {voters}",
+                        msg.author.mention(),
+                        msg.channel_id.mention(),
+                        msg.content.chars().take(1000).collect::<String>()
+                    ),
+                )
+                .await?;
+                record_removal(ctx, guild_id, &msg).await?;
+            }
+            ReactionAction::Pin => {
+                msg.pin(ctx).await?;
+                info!("Pinned message {} by reaction vote", msg.id);
+                guild_log(
+                    ctx,
+                    guild_id,
+                    Severity::Info,
+                    format!("投票置顶 {}", reaction.emoji),
+                    format!("{} 已被投票置顶。\n\n投票者: {voters}", msg.link()),
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventHandler for ReactionRuleHandler {
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        if let Err(e) = self.on_reaction(&ctx, &reaction).await {
+            error!(
+                "Failed to apply reaction rule to {}: {e}",
+                reaction.message_id
+            );
+        }
+    }
+}
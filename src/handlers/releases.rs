@@ -0,0 +1,172 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serenity::all::{colours::branding::BLURPLE, *};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use super::modules::{Module, ModuleHandler};
+use crate::{
+    config::{GetCfg, ReleaseWatch},
+    database::{BotDatabase, GetDb},
+    error::BotError,
+    utils::schedule,
+};
+
+/// Maximum length of release notes quoted in an announcement
+const NOTES_LIMIT: usize = 1000;
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    name: Option<String>,
+    html_url: String,
+    body: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+    prerelease: bool,
+    draft: bool,
+}
+
+#[derive(Deserialize)]
+struct Tag {
+    name: String,
+}
+
+pub struct ReleaseHandler;
+
+#[async_trait]
+impl ModuleHandler for ReleaseHandler {
+    fn module(&self) -> Module {
+        Module::Feeds
+    }
+
+    async fn start(&self, ctx: &Context) -> Vec<JoinHandle<()>> {
+        let ctx = ctx.to_owned();
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        let interval = cfg.load().release_interval;
+        info!("Polling GitHub releases every {}s", interval.as_secs());
+        vec![schedule::every(interval, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.load_full();
+            async move {
+                let db = match ctx.db().await {
+                    Ok(db) => db,
+                    Err(e) => return error!("Failed to get database: {e}"),
+                };
+                let client = reqwest::Client::new();
+                for watch in &cfg.github_releases {
+                    if let Err(e) =
+                        check_repo(&ctx, &db, &client, cfg.github_token.as_deref(), watch).await
+                    {
+                        error!("Failed to check releases of {}: {e}", watch.repo);
+                    }
+                }
+            }
+        })]
+    }
+}
+
+/// Fetch the newest releases (or tags) of a repository, newest first.
+async fn fetch(
+    client: &reqwest::Client,
+    token: Option<&str>,
+    watch: &ReleaseWatch,
+) -> Result<Vec<Release>, BotError> {
+    let path = if watch.tags { "tags" } else { "releases" };
+    let mut req = client
+        .get(format!(
+            "https://api.github.com/repos/{}/{path}",
+            watch.repo
+        ))
+        .query(&[("per_page", "10")])
+        .header(reqwest::header::USER_AGENT, "dc-bot")
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+    if let Some(token) = token {
+        req = req.bearer_auth(token);
+    }
+    let res = req.send().await?.error_for_status()?;
+    if !watch.tags {
+        return Ok(res
+            .json::<Vec<Release>>()
+            .await?
+            .into_iter()
+            .filter(|r| !r.draft && (watch.prereleases || !r.prerelease))
+            .collect());
+    }
+    // Tags carry no metadata, present them as bare releases
+    Ok(res
+        .json::<Vec<Tag>>()
+        .await?
+        .into_iter()
+        .map(|t| Release {
+            html_url: format!("https://github.com/{}/releases/tag/{}", watch.repo, t.name),
+            tag_name: t.name,
+            name: None,
+            body: None,
+            published_at: None,
+            prerelease: false,
+            draft: false,
+        })
+        .collect())
+}
+
+async fn check_repo(
+    ctx: &Context,
+    db: &BotDatabase,
+    client: &reqwest::Client,
+    token: Option<&str>,
+    watch: &ReleaseWatch,
+) -> Result<(), BotError> {
+    let releases = fetch(client, token, watch).await?;
+    let Some(latest) = releases.first() else {
+        return Ok(());
+    };
+    let key = format!("github:{}", watch.repo);
+    let last_seen = db.watch().get(&key).await?;
+    if last_seen.as_deref() == Some(latest.tag_name.as_str()) {
+        return Ok(());
+    }
+    let Some(last_seen) = last_seen else {
+        // First run, remember the current version without announcing it
+        db.watch().set(&key, &latest.tag_name).await?;
+        info!("Tracking {} from {}", watch.repo, latest.tag_name);
+        return Ok(());
+    };
+    let new = releases
+        .iter()
+        .take_while(|r| r.tag_name != last_seen)
+        .collect::<Vec<_>>();
+    // Announce oldest first so the channel reads chronologically
+    for release in new.into_iter().rev() {
+        let mut embed = CreateEmbed::new()
+            .title(format!(
+                "📦 {} {}",
+                watch.repo,
+                release.name.as_deref().unwrap_or(&release.tag_name)
+            ))
+            .url(&release.html_url)
+            .color(BLURPLE);
+        if let Some(body) = release.body.as_deref().filter(|b| !b.trim().is_empty()) {
+            let mut notes = body.chars().take(NOTES_LIMIT).collect::<String>();
+            if notes.len() < body.len() {
+                notes.push_str("\n...");
+            }
+            embed = embed.description(notes);
+        }
+        if let Some(published_at) = release.published_at {
+            embed = embed.timestamp(Timestamp::from(published_at));
+        }
+        if release.prerelease {
+            embed = embed.footer(CreateEmbedFooter::new("预发布版本"));
+        }
+        let mut msg = CreateMessage::new().embed(embed);
+        if let Some(role_id) = watch.role_id {
+            msg = msg
+                .content(role_id.mention().to_string())
+                .allowed_mentions(CreateAllowedMentions::new().roles([role_id]));
+        }
+        watch.channel_id.send_message(ctx, msg).await?;
+        // Remembered only once announced, so a failed send is retried on the next poll
+        db.watch().set(&key, &release.tag_name).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use serenity::all::{
+    colours::branding::{GREEN, RED, YELLOW},
+    *,
+};
+use tracing::error;
+
+use crate::{
+    commands::report::{DELETE_PREFIX, DISMISS_PREFIX, WARN_PREFIX},
+    config::GetCfg,
+    database::GetDb,
+    error::BotError,
+    repo::reports::{DELETED, DISMISSED, WARNED},
+    utils::owners,
+};
+
+/// Handles the moderator buttons of message reports, which outlive restarts
+pub struct ReportHandler;
+
+#[derive(Debug, Clone, Copy)]
+enum Action {
+    Delete,
+    Warn,
+    Dismiss,
+}
+
+async fn respond(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    content: &str,
+) -> Result<(), BotError> {
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .ephemeral(true),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn handle(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    id: i32,
+    action: Action,
+) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?.load_full();
+    if !cfg.is_admin(interaction.user.id, interaction.member.as_ref())
+        && !owners(ctx).await?.contains(&interaction.user.id)
+    {
+        return respond(ctx, interaction, "❌ **错误**\n\n只有管理员可以处理举报。").await;
+    }
+    let db = ctx.db().await?;
+    let Some(report) = db.reports().get(id).await? else {
+        return respond(ctx, interaction, "❌ **错误**\n\n举报不存在。").await;
+    };
+    let status = match action {
+        Action::Delete => DELETED,
+        Action::Warn => WARNED,
+        Action::Dismiss => DISMISSED,
+    };
+    if !db
+        .reports()
+        .resolve(id, interaction.user.id, status)
+        .await?
+    {
+        return respond(
+            ctx,
+            interaction,
+            "❌ **错误**\n\n该举报已被其他管理员处理。",
+        )
+        .await;
+    }
+    let link = report
+        .message_id()
+        .link(report.channel_id(), Some(report.guild_id()));
+    let outcome = match action {
+        Action::Delete => report
+            .channel_id()
+            .delete_message(ctx, report.message_id())
+            .await
+            .map(|_| "🗑️ 已删除消息"),
+        Action::Warn => {
+            let guild = ctx
+                .cache
+                .guild(report.guild_id())
+                .map(|g| g.name.to_owned())
+                .unwrap_or_default();
+            report
+                .author_id()
+                .direct_message(
+                    ctx,
+                    // The reporter's own words could give them away
+                    CreateMessage::new().content(format!(
+                        "⚠️ 你在 **{guild}** 的消息被举报, 经管理员确认违反了服务器规则, 请注意言行。\n\n消息: {link}"
+                    )),
+                )
+                .await
+                .map(|_| "⚠️ 已私信警告作者")
+        }
+        Action::Dismiss => Ok("✖️ 已驳回"),
+    };
+    let outcome = match outcome {
+        Ok(outcome) => outcome.to_string(),
+        // The message may be gone or the author may not accept DMs, the decision is recorded regardless
+        Err(why) => format!("⚠️ 已记录, 但操作失败: {why}"),
+    };
+    let embed = interaction
+        .message
+        .embeds
+        .first()
+        .cloned()
+        .map(CreateEmbed::from)
+        .unwrap_or_default()
+        .field(
+            "处理结果",
+            format!("{outcome} ({})", interaction.user.mention()),
+            false,
+        )
+        .color(match action {
+            Action::Delete => RED,
+            Action::Warn => YELLOW,
+            Action::Dismiss => GREEN,
+        });
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .embed(embed)
+                    .components(vec![]),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for ReportHandler {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Component(interaction) = interaction else {
+            return;
+        };
+        let custom_id = &interaction.data.custom_id;
+        let (id, action) = if let Some(id) = custom_id.strip_prefix(DELETE_PREFIX) {
+            (id, Action::Delete)
+        } else if let Some(id) = custom_id.strip_prefix(WARN_PREFIX) {
+            (id, Action::Warn)
+        } else if let Some(id) = custom_id.strip_prefix(DISMISS_PREFIX) {
+            (id, Action::Dismiss)
+        } else {
+            return;
+        };
+        let Ok(id) = id.parse() else {
+            return;
+        };
+        if let Err(e) = handle(&ctx, &interaction, id, action).await {
+            error!("Failed to handle report #{id}: {e}");
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::suggestion::{DOWN_PREFIX, UP_PREFIX, suggestion_embed, vote_buttons},
+    config::GetCfg,
+    database::GetDb,
+    error::BotError,
+    repo::suggestions::{APPROVED, OPEN},
+};
+
+/// Turns messages in suggestion channels into votable embeds and counts the votes
+pub struct SuggestionHandler;
+
+async fn submit(ctx: &Context, msg: &Message, guild_id: GuildId) -> Result<(), BotError> {
+    let db = ctx.db().await?;
+    let id = db
+        .suggestions()
+        .create(guild_id, msg.channel_id, msg.author.id, &msg.content)
+        .await?;
+    let suggestion = db
+        .suggestions()
+        .get(id)
+        .await?
+        .expect("The suggestion was just created");
+    let posted = msg
+        .channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new()
+                .embed(suggestion_embed(&db, &suggestion).await?)
+                .components(vote_buttons(id)),
+        )
+        .await?;
+    db.suggestions().set_message(id, posted.id).await?;
+    msg.delete(ctx).await?;
+    info!("Posted suggestion #{id} of {}", msg.author.name);
+    Ok(())
+}
+
+async fn vote(
+    ctx: &Context,
+    interaction: &ComponentInteraction,
+    id: i32,
+    up: bool,
+) -> Result<(), BotError> {
+    let db = ctx.db().await?;
+    let Some(suggestion) = db.suggestions().get(id).await? else {
+        return Ok(());
+    };
+    if ![OPEN, APPROVED].contains(&suggestion.status.as_str()) {
+        interaction
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content("❌ **错误**\n\n该建议已结束投票。")
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+    db.suggestions().vote(id, interaction.user.id, up).await?;
+    // Rebuilt from the database so concurrent votes never show a stale tally
+    let embed = suggestion_embed(&db, &suggestion).await?;
+    interaction
+        .create_response(
+            ctx,
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new().embed(embed),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for SuggestionHandler {
+    async fn message(&self, ctx: Context, msg: Message) {
+        let Some(guild_id) = msg.guild_id else {
+            return;
+        };
+        if msg.author.bot || msg.content.is_empty() {
+            return;
+        }
+        let is_suggestion_channel = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load()
+            .guilds
+            .get(&guild_id)
+            .is_some_and(|g| g.suggestion_channel_id == Some(msg.channel_id));
+        if !is_suggestion_channel {
+            return;
+        }
+        if let Err(e) = submit(&ctx, &msg, guild_id).await {
+            error!("Failed to post suggestion of {}: {e}", msg.author.name);
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let Interaction::Component(interaction) = interaction else {
+            return;
+        };
+        let custom_id = &interaction.data.custom_id;
+        let (id, up) = if let Some(id) = custom_id.strip_prefix(UP_PREFIX) {
+            (id, true)
+        } else if let Some(id) = custom_id.strip_prefix(DOWN_PREFIX) {
+            (id, false)
+        } else {
+            return;
+        };
+        let Ok(id) = id.parse() else {
+            return;
+        };
+        if let Err(e) = vote(&ctx, &interaction, id, up).await {
+            error!("Failed to count vote on suggestion #{id}: {e}");
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use dashmap::DashSet;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{config::GetCfg, error::BotError};
+
+/// Thread names are limited to 100 characters, shorter titles read better in the list
+const TITLE_LIMIT: usize = 60;
+/// Names the clients suggest for threads created without a starter message
+const GENERIC_NAMES: [&str; 2] = ["New Thread", "新子区"];
+
+/// Renames threads in configured channels after a summary of their first message
+#[derive(Default)]
+pub struct ThreadNamingHandler {
+    /// Empty threads waiting for their first message
+    pending: DashSet<ChannelId>,
+}
+
+/// A short title for a message: its first sentence without markup, links or mentions.
+fn title(content: &str) -> Option<String> {
+    let mut in_code = false;
+    let line = content
+        .lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                return false;
+            }
+            !in_code
+        })
+        .map(|line| {
+            line.split_whitespace()
+                .filter(|word| !word.starts_with("http") && !word.starts_with('<'))
+                .collect::<Vec<_>>()
+                .join(" ")
+                .replace(['*', '_', '~', '`', '|', '#', '>'], "")
+                .trim()
+                .to_string()
+        })
+        .find(|line| !line.is_empty())?;
+    // Keep a question mark, it tells a question from a statement at a glance
+    let sentence = match line.find(['。', '！', '!', '？', '?']) {
+        Some(end) => {
+            let terminator = line[end..].chars().next().unwrap();
+            let end = if matches!(terminator, '?' | '？') {
+                end + terminator.len_utf8()
+            } else {
+                end
+            };
+            &line[..end]
+        }
+        None => line.split(". ").next().unwrap_or(&line),
+    };
+    let sentence = sentence.trim();
+    if sentence.chars().count() <= TITLE_LIMIT {
+        return (!sentence.is_empty()).then(|| sentence.to_string());
+    }
+    let cut = sentence.chars().take(TITLE_LIMIT - 1).collect::<String>();
+    // Avoid cutting a word in half, scripts without spaces are cut anywhere
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > cut.len() / 2 => &cut[..space],
+        _ => &cut,
+    };
+    Some(format!("{}…", cut.trim_end()))
+}
+
+/// Whether a thread still has the name Discord gave it, rather than one picked by hand
+fn is_default_name(name: &str, starter: Option<&str>) -> bool {
+    GENERIC_NAMES.contains(&name)
+        || starter.is_some_and(|content| {
+            let prefix = name.trim_end_matches("...").trim_end();
+            !prefix.is_empty() && content.starts_with(prefix)
+        })
+}
+
+async fn rename(ctx: &Context, thread: ChannelId, content: &str) -> Result<(), BotError> {
+    let Some(title) = title(content) else {
+        return Ok(());
+    };
+    thread
+        .edit_thread(ctx, EditThread::new().name(&title))
+        .await?;
+    info!("Renamed thread {thread} to {title}");
+    Ok(())
+}
+
+impl ThreadNamingHandler {
+    async fn on_thread(&self, ctx: &Context, thread: &GuildChannel) -> Result<(), BotError> {
+        // Threads started from a message share its ID
+        let starter = thread
+            .parent_id
+            .unwrap_or_default()
+            .message(ctx, MessageId::new(thread.id.get()))
+            .await
+            .ok();
+        let content = starter.as_ref().map(|m| m.content.as_str());
+        if !is_default_name(&thread.name, content) {
+            return Ok(());
+        }
+        match content {
+            Some(content) => rename(ctx, thread.id, content).await,
+            None => {
+                self.pending.insert(thread.id);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for ThreadNamingHandler {
+    async fn thread_create(&self, ctx: Context, thread: GuildChannel) {
+        let Some(parent_id) = thread.parent_id else {
+            return;
+        };
+        // Only freshly created threads have no messages yet
+        if thread.message_count.unwrap_or_default() > 1
+            || !ctx
+                .cfg()
+                .await
+                .expect("Failed to get bot configuration")
+                .load()
+                .thread_naming
+                .contains(&parent_id)
+        {
+            return;
+        }
+        if let Err(e) = self.on_thread(&ctx, &thread).await {
+            error!("Failed to name thread {}: {e}", thread.id);
+        }
+    }
+
+    async fn message(&self, ctx: Context, msg: Message) {
+        if msg.author.bot || self.pending.remove(&msg.channel_id).is_none() {
+            return;
+        }
+        if let Err(e) = rename(&ctx, msg.channel_id, &msg.content).await {
+            error!("Failed to name thread {}: {e}", msg.channel_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_title() {
+        assert_eq!(
+            title("**求助**: 编译报错怎么办? 代码如下\n```rust\nfn main() {}\n```").as_deref(),
+            Some("求助: 编译报错怎么办?")
+        );
+        assert_eq!(
+            title("```\nlog\n```\n<@123> see https://example.com. It broke").as_deref(),
+            Some("see It broke")
+        );
+        assert_eq!(title("```\nonly code\n```"), None);
+        let long = "word ".repeat(30);
+        let title = title(&long).unwrap();
+        assert!(title.chars().count() <= TITLE_LIMIT && title.ends_with("word…"));
+        assert!(is_default_name("New Thread", None));
+        assert!(is_default_name(
+            "编译报错怎么办",
+            Some("编译报错怎么办? 代码如下")
+        ));
+        assert!(!is_default_name("Rust 编译问题", Some("编译报错怎么办?")));
+    }
+}
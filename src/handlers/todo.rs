@@ -0,0 +1,74 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use chrono::Utc;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::todo::refresh_board, config::GetCfg, database::GetDb, error::BotError,
+    utils::schedule,
+};
+
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Pings the todo channel when `/todo` tasks fall due
+#[derive(Default)]
+pub struct TodoHandler {
+    started: AtomicBool,
+}
+
+async fn sweep(ctx: &Context) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?.load_full();
+    let db = ctx.db().await?;
+    let due = db.todos().due(Utc::now()).await?;
+    for todo in &due {
+        let Some(channel_id) = cfg
+            .guilds
+            .get(&todo.guild_id())
+            .and_then(|g| g.todo_channel_id)
+        else {
+            db.todos().set_reminded(todo.id).await?;
+            continue;
+        };
+        let who = todo.assignee_id().unwrap_or(todo.created_by()).mention();
+        channel_id
+            .say(
+                ctx,
+                format!("⏰ {who} 任务 `#{}` 已到期: {}", todo.id, todo.content),
+            )
+            .await?;
+        db.todos().set_reminded(todo.id).await?;
+        info!("Reminded {who} of task #{}", todo.id);
+    }
+    // Mark the newly overdue tasks on the boards
+    let mut guilds = due.iter().map(|t| t.guild_id()).collect::<Vec<_>>();
+    guilds.sort();
+    guilds.dedup();
+    for guild_id in guilds {
+        if let Some(channel_id) = cfg.guilds.get(&guild_id).and_then(|g| g.todo_channel_id) {
+            refresh_board(ctx, &db, guild_id, channel_id).await?;
+        }
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for TodoHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        info!("Checking due tasks every {}s", SWEEP_INTERVAL.as_secs());
+        schedule::every(SWEEP_INTERVAL, move || {
+            let ctx = ctx.to_owned();
+            async move {
+                if let Err(e) = sweep(&ctx).await {
+                    error!("Failed to remind of due tasks: {e}");
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::FixedOffset;
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+use serenity::{all::*, json};
+use tracing::{error, info};
+
+use crate::{
+    config::{GetCfg, TopicRotation},
+    database::GetDb,
+    error::BotError,
+    utils::schedule,
+};
+
+/// Rotates conversation starters into channels on the `topicRotations` schedules
+#[derive(Default)]
+pub struct TopicRotationHandler {
+    started: AtomicBool,
+}
+
+/// What a rotation already posted, kept as watch state so restarts do not repeat topics
+#[derive(Deserialize, Serialize, Default)]
+struct History {
+    used: Vec<String>,
+    /// The pinned message of the current topic
+    message_id: Option<MessageId>,
+}
+
+/// Pick a topic not used yet. Once all were used the history starts over, still avoiding the
+/// topic just posted.
+fn pick<'a>(topics: &'a [String], used: &mut Vec<String>) -> Option<&'a String> {
+    let fresh = topics
+        .iter()
+        .filter(|t| !used.contains(t))
+        .collect::<Vec<_>>();
+    let topic = if fresh.is_empty() {
+        let last = used.pop();
+        used.clear();
+        let rest = topics
+            .iter()
+            .filter(|t| Some(*t) != last.as_ref())
+            .collect::<Vec<_>>();
+        rest.choose(&mut rand::rng())
+            .copied()
+            .or_else(|| topics.first())
+    } else {
+        fresh.choose(&mut rand::rng()).copied()
+    }?;
+    used.push(topic.to_owned());
+    Some(topic)
+}
+
+async fn rotate(ctx: &Context, rotation: &TopicRotation) -> Result<(), BotError> {
+    let db = ctx.db().await?;
+    let key = format!("topic:{}", rotation.channel_id);
+    let mut history = match db.watch().get(&key).await? {
+        Some(value) => json::from_str::<History>(&value)?,
+        None => History::default(),
+    };
+    let Some(topic) = pick(&rotation.topics, &mut history.used) else {
+        return Ok(());
+    };
+    if rotation.pin {
+        let msg = rotation
+            .channel_id
+            .say(ctx, format!("💬 **今日话题**\n\n{topic}"))
+            .await?;
+        msg.pin(ctx).await?;
+        if let Some(previous) = history.message_id.replace(msg.id) {
+            // Already gone if someone unpinned or deleted it by hand
+            let _ = rotation.channel_id.unpin(ctx, previous).await;
+        }
+    } else {
+        rotation
+            .channel_id
+            .edit(ctx, EditChannel::new().topic(topic))
+            .await?;
+    }
+    db.watch().set(&key, &json::to_string(&history)?).await?;
+    info!("Rotated the topic of {} to {topic}", rotation.channel_id);
+    Ok(())
+}
+
+#[async_trait]
+impl EventHandler for TopicRotationHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load();
+        let offset = FixedOffset::east_opt(cfg.time_offset)
+            .expect("Failed to create FixedOffset with the configured time offset");
+        for rotation in cfg.topic_rotations.iter().cloned() {
+            info!(
+                "Rotating {} topics into {} at `{}`",
+                rotation.topics.len(),
+                rotation.channel_id,
+                rotation.schedule
+            );
+            let ctx = ctx.to_owned();
+            schedule::cron(rotation.schedule.to_owned(), offset, move || {
+                let ctx = ctx.to_owned();
+                let rotation = rotation.to_owned();
+                async move {
+                    if let Err(e) = rotate(&ctx, &rotation).await {
+                        error!("Failed to rotate the topic of {}: {e}", rotation.channel_id);
+                    }
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pick() {
+        let topics = ["a", "b", "c"].map(String::from);
+        let mut used = vec![];
+        let mut seen = (0..3)
+            .map(|_| pick(&topics, &mut used).unwrap().to_owned())
+            .collect::<Vec<_>>();
+        seen.sort();
+        assert_eq!(seen, topics);
+        let last = used.last().cloned();
+        let next = pick(&topics, &mut used).unwrap();
+        assert_ne!(Some(next), last.as_ref());
+        assert_eq!(used.len(), 1);
+        assert!(pick(&[], &mut used).is_none());
+    }
+}
@@ -1,34 +1,104 @@
-use std::time::Duration;
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    sync::{
+        Arc, LazyLock, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
-use chrono::TimeDelta;
-use dashmap::DashMap;
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeDelta, Utc};
+use dashmap::{DashMap, DashSet, mapref::entry::Entry};
 use futures::{StreamExt, TryStreamExt, stream::FuturesUnordered};
 use serenity::{all::*, json::json};
-use tokio::{spawn, task::JoinHandle};
-use tracing::{error, warn};
+use sha2::{Digest, Sha256};
+use snafu::OptionExt;
+use tokio::{
+    spawn,
+    sync::{Notify, Semaphore},
+};
+use tracing::{error, info, warn};
 
-use crate::{config::GetCfg, error::BotError};
+use crate::{
+    config::{
+        BotCfg, GetCfg, QuietHours, TreeHoleCfg, TreeHoleContent, TreeHoleEdit, TreeHoleWarning,
+    },
+    database::GetDb,
+    error::BotError,
+    handlers::extract_urls,
+    utils::{crash, schedule},
+};
 
-#[derive(Default)]
 pub struct TreeHoleHandler {
-    msgs: DashMap<MessageId, JoinHandle<()>>,
+    timers: Arc<Timers>,
+    /// Parent of each channel seen, `None` for channels that are not threads
+    parents: DashMap<ChannelId, Option<ChannelId>>,
+    /// Channels being trimmed to their newest messages
+    trimming: DashSet<ChannelId>,
+    /// Webhooks mirroring the posts of anonymous tree holes, by the channel they belong to
+    webhooks: DashMap<ChannelId, Webhook>,
+}
+
+impl Default for TreeHoleHandler {
+    fn default() -> Self {
+        Self {
+            timers: TIMERS.to_owned(),
+            parents: DashMap::default(),
+            trimming: DashSet::default(),
+            webhooks: DashMap::default(),
+        }
+    }
+}
+
+/// Timers of the running handler, shared with [`purge`] which is called from outside of it
+static TIMERS: LazyLock<Arc<Timers>> = LazyLock::new(Arc::default);
+
+/// Messages fetched beyond the limit of a count-based tree hole when trimming on a new message
+const TRIM_BATCH: usize = 100;
+/// Deletion requests in flight at once across all tree holes, so a backlog expiring together
+/// (e.g. right after startup) queues up instead of hitting Discord's rate limits
+static DELETIONS: Semaphore = Semaphore::const_new(4);
+/// Timers due at the same moment are spread over up to this long
+const JITTER: Duration = Duration::from_secs(10);
+/// How long links in media-only tree holes get to unfurl into embeds before they are judged
+const EMBED_WAIT: Duration = Duration::from_secs(5);
+
+/// Wait for a free deletion slot and run `f` in it.
+async fn throttled<T>(f: impl Future<Output = T>) -> T {
+    let _permit = DELETIONS
+        .acquire()
+        .await
+        .expect("Semaphore is never closed");
+    f.await
 }
 
 #[async_trait]
 impl EventHandler for TreeHoleHandler {
     async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if let Err(e) = adopt_tree_holes(&ctx).await {
+            error!("Failed to move tree holes into their guilds: {e}");
+        }
+        self.timers.drive(&ctx);
+        #[cfg(feature = "profiling")]
+        {
+            use crate::commands::profile::track;
+            let timers = self.timers.to_owned();
+            track("树洞计时器", move || timers.timers.len());
+            let timers = self.timers.to_owned();
+            track("树洞计时队列", move || {
+                timers.queue.lock().unwrap().len()
+            });
+        }
+        // Exact deadlines first, the rescan then only picks up messages sent while offline
+        if let Err(e) = self.reschedule(&ctx).await {
+            error!("Failed to reschedule tree hole deletions: {e}");
+        }
         self.delete_messages(ctx).await;
     }
 
     async fn channel_pins_update(&self, ctx: Context, event: ChannelPinsUpdateEvent) {
-        if ctx
-            .cfg()
-            .await
-            .expect("Failed to get bot configuration")
-            .load()
-            .tree_holes
-            .contains_key(&event.channel_id)
-        {
+        if self.hole(&ctx, event.channel_id).await.is_none() {
             return; // Not a tree hole channel, ignore the message
         };
         if event.last_pin_timestamp.is_none() {
@@ -42,11 +112,15 @@ impl EventHandler for TreeHoleHandler {
             );
             return; // If we can't fetch pinned messages, we can't do anything
         };
-        {
-            pinned_messages
-                .into_iter()
-                .filter_map(|msg| self.msgs.remove(&msg.id))
-                .for_each(|(_, handle)| handle.abort());
+        for msg in pinned_messages {
+            if self.timers.cancel(msg.id)
+                && let Err(e) = forget(&ctx, msg.id).await
+            {
+                error!(
+                    "Failed to forget deletion of pinned message {}: {e}",
+                    msg.id
+                );
+            }
         }
         self.delete_messages(ctx).await;
     }
@@ -57,26 +131,162 @@ impl EventHandler for TreeHoleHandler {
     // dispatched simultaneously.
     async fn message(&self, ctx: Context, msg: Message) {
         let channel_id = msg.channel_id;
-        let Some(dur) = ctx
-            .cfg()
-            .await
-            .expect("Failed to get bot configuration")
-            .load()
-            .tree_holes
-            .get(&channel_id)
-            .cloned()
-        else {
+        let Some(hole) = self.hole(&ctx, channel_id).await else {
             return; // Not a tree hole channel, ignore the message
         };
-        // Store the handle in the map
-        _ = self.msgs.entry(msg.id).or_insert(spawn(async move {
-            tokio::time::sleep(dur).await;
-            if let Err(err) = msg.delete(ctx).await {
-                error!("Failed to delete message {}: {}", msg.id, err);
+        if let Some(grace) = hole.reply_grace
+            && msg.author.id != ctx.cache.current_user().id
+        {
+            self.extend_discussed(&ctx, &msg, grace, &hole).await;
+        }
+        let roles = msg.member.as_ref().map_or(&[][..], |m| &m.roles);
+        if hole.exempts(msg.author.id, roles)
+            || keeps_content(&msg, &hole)
+            || self.keeps_source(&msg, &hole, ctx.cache.current_user().id)
+        {
+            return;
+        }
+        if msg.author.id == ctx.cache.current_user().id && msg.message_reference.is_some() {
+            return; // Deletion notices go along with the message they reply to
+        }
+        if hole.media_only && !has_media_unfurled(&ctx, &msg).await {
+            if hole.dry_run {
+                self.timers
+                    .rehearse(&ctx, channel_id, &[msg.id], "不含媒体")
+                    .await;
+            } else if let Err(e) = throttled(msg.delete(&ctx)).await {
+                error!("Failed to delete message {} without media: {e}", msg.id);
+            }
+            return;
+        }
+        if hole.anonymous && !hole.dry_run && !msg.author.bot && msg.webhook_id.is_none() {
+            match self.anonymize(&ctx, &msg, &hole).await {
+                // The mirror arrives as a message of its own
+                Ok(true) => return,
+                Ok(false) => {}
+                Err(e) => error!("Failed to anonymize message {}: {e}", msg.id),
+            }
+        }
+        // Mirrors are scheduled by `anonymize`, along with their countdown
+        if !self.is_mirror(&msg) {
+            let delete_at = msg.timestamp.to_utc() + TimeDelta::from_std(hole.duration).unwrap();
+            if hole.countdown {
+                countdown(&ctx, &msg, delete_at).await;
+            }
+            if let Err(e) = self
+                .schedule(&ctx, channel_id, msg.id, delete_at, &hole)
+                .await
+            {
+                error!("Failed to schedule deletion of message {}: {e}", msg.id);
+            }
+        }
+        if let (Some(max), Some(guild_id)) = (hole.max_messages, msg.guild_id)
+            && quiet_now(&ctx, hole.quiet_hours).await.is_none()
+            // A trim already running catches up with this message on the next one
+            && self.trimming.insert(channel_id)
+        {
+            if let Err(e) = self.trim(&ctx, guild_id, channel_id, &hole, max).await {
+                error!("Failed to trim tree hole channel {channel_id}: {e}");
             }
-        }));
-        // clean up aborted tasks
-        self.msgs.retain(|_, handle| !handle.is_finished());
+            self.trimming.remove(&channel_id);
+        }
+    }
+
+    async fn message_update(
+        &self,
+        ctx: Context,
+        _old_if_available: Option<Message>,
+        _new: Option<Message>,
+        event: MessageUpdateEvent,
+    ) {
+        let Some(edited_at) = event.edited_timestamp else {
+            return; // Not an edit, e.g. an embed unfurling
+        };
+        if event
+            .author
+            .as_ref()
+            .is_some_and(|author| author.id == ctx.cache.current_user().id)
+        {
+            return; // The bot edits its own messages to show their countdown
+        }
+        let Some((_, delete_at)) = self.timers.deadline(event.id) else {
+            return; // Not scheduled for deletion
+        };
+        let Some(hole) = self.hole(&ctx, event.channel_id).await else {
+            return;
+        };
+        let Some(edit) = hole.on_edit else {
+            return;
+        };
+        let deletion = edited_deadline(edit, delete_at, hole.duration, edited_at.to_utc());
+        if deletion == delete_at {
+            return;
+        }
+        if let Err(e) = self
+            .move_deadline(&ctx, event.channel_id, event.id, deletion, &hole)
+            .await
+        {
+            error!(
+                "Failed to move deletion of edited message {}: {e}",
+                event.id
+            );
+        }
+    }
+
+    async fn message_delete(
+        &self,
+        ctx: Context,
+        channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        _guild_id: Option<GuildId>,
+    ) {
+        self.forget_deleted(&ctx, channel_id, &[deleted_message_id])
+            .await;
+    }
+
+    async fn message_delete_bulk(
+        &self,
+        ctx: Context,
+        channel_id: ChannelId,
+        multiple_deleted_messages_ids: Vec<MessageId>,
+        _guild_id: Option<GuildId>,
+    ) {
+        self.forget_deleted(&ctx, channel_id, &multiple_deleted_messages_ids)
+            .await;
+    }
+
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        let Some(keep) = self
+            .hole(&ctx, reaction.channel_id)
+            .await
+            .and_then(|hole| hole.keep)
+        else {
+            return;
+        };
+        let roles = reaction.member.as_ref().map_or(&[][..], |m| &m.roles);
+        if !keep.matches(&reaction.emoji) || !roles.iter().any(|r| keep.role_ids.contains(r)) {
+            return;
+        }
+        if !self.timers.cancel(reaction.message_id) {
+            return; // Not scheduled for deletion
+        }
+        if let Err(e) = forget(&ctx, reaction.message_id).await {
+            error!(
+                "Failed to forget deletion of kept message {}: {e}",
+                reaction.message_id
+            );
+        }
+        // The bot's own reaction marks the message as kept for later scans
+        if let Err(e) = ctx
+            .http
+            .create_reaction(reaction.channel_id, reaction.message_id, &reaction.emoji)
+            .await
+        {
+            error!(
+                "Failed to mark message {} as kept: {e}",
+                reaction.message_id
+            );
+        }
     }
 
     async fn resume(&self, ctx: Context, _resumed: ResumedEvent) {
@@ -84,80 +294,1368 @@ impl EventHandler for TreeHoleHandler {
     }
 }
 
+/// Tell the author a message is about to go, returning the notice to delete along with it.
+async fn warn(
+    ctx: &Context,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    delete_at: DateTime<Utc>,
+    warning: TreeHoleWarning,
+) -> Result<Option<MessageId>, BotError> {
+    if !warning.reply {
+        ctx.http
+            .create_reaction(channel_id, message_id, &ReactionType::Unicode("⏳".into()))
+            .await?;
+        return Ok(None);
+    }
+    let at = FormattedTimestamp::new(
+        delete_at.into(),
+        Some(FormattedTimestampStyle::RelativeTime),
+    );
+    let notice = channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new()
+                .content(format!(
+                    "⏳ 这条消息将于 {at} 被删除, 如需保留请联系管理员置顶。"
+                ))
+                .reference_message((channel_id, message_id))
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+    Ok(Some(notice.id))
+}
+
+/// Attachments up to this size are uploaded again, since their links die with the message
+const ARCHIVE_UPLOAD_LIMIT: u32 = 8 * 1024 * 1024;
+
+/// Repost a message about to be deleted to the archive channel of its tree hole. Failures are
+/// only logged, so the deletion still goes ahead.
+async fn archive(ctx: &Context, archive_channel_id: ChannelId, msg: &Message) {
+    let mut embed = CreateEmbed::new()
+        .author(CreateEmbedAuthor::new(msg.author.display_name()).icon_url(msg.author.face()))
+        .field("频道", msg.channel_id.mention().to_string(), true)
+        .field("作者", msg.author.mention().to_string(), true)
+        .footer(CreateEmbedFooter::new(format!("消息 ID: {}", msg.id)))
+        .timestamp(msg.timestamp);
+    if !msg.content.is_empty() {
+        embed = embed.description(msg.content.to_owned());
+    }
+    let mut files = vec![];
+    for attachment in msg.attachments.iter().take(10) {
+        if attachment.size <= ARCHIVE_UPLOAD_LIMIT
+            && let Ok(file) = CreateAttachment::url(ctx, &attachment.url).await
+        {
+            files.push(file);
+        } else {
+            embed = embed.field("附件", attachment.filename.to_owned(), false);
+        }
+    }
+    if let Err(e) = archive_channel_id
+        .send_files(ctx, files, CreateMessage::new().embed(embed))
+        .await
+    {
+        error!("Failed to archive message {}: {e}", msg.id);
+    }
+}
+
+/// Marks the countdown line, so it is replaced rather than added again
+const COUNTDOWN: &str = "-# ⏳ ";
+
+/// The content of a message with its countdown line set to `delete_at`, if it still fits
+fn with_countdown(content: &str, delete_at: DateTime<Utc>) -> Option<String> {
+    let body = content
+        .lines()
+        .filter(|line| !line.starts_with(COUNTDOWN))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let at = FormattedTimestamp::new(
+        delete_at.into(),
+        Some(FormattedTimestampStyle::RelativeTime),
+    );
+    let content = if body.is_empty() {
+        format!("{COUNTDOWN}将于 {at} 消失")
+    } else {
+        format!("{body}\n{COUNTDOWN}将于 {at} 消失")
+    };
+    (content.chars().count() <= 2000).then_some(content)
+}
+
+/// Show when one of the bot's own messages disappears. Relative timestamps tick on the clients,
+/// so the message only needs editing again when its deadline moves.
+async fn countdown(ctx: &Context, msg: &Message, delete_at: DateTime<Utc>) {
+    if msg.author.id != ctx.cache.current_user().id {
+        return;
+    }
+    let Some(content) = with_countdown(&msg.content, delete_at).filter(|c| *c != msg.content)
+    else {
+        return;
+    };
+    if let Err(e) = msg
+        .channel_id
+        .edit_message(ctx, msg.id, EditMessage::new().content(content))
+        .await
+    {
+        error!("Failed to show the countdown of message {}: {e}", msg.id);
+    }
+}
+
+/// When an edited message is deleted under its tree hole's edit policy
+fn edited_deadline(
+    edit: TreeHoleEdit,
+    delete_at: DateTime<Utc>,
+    duration: Duration,
+    edited_at: DateTime<Utc>,
+) -> DateTime<Utc> {
+    match edit {
+        TreeHoleEdit::Reset => edited_at + TimeDelta::from_std(duration).unwrap(),
+        TreeHoleEdit::Shorten(within) => {
+            delete_at.min(edited_at + TimeDelta::from_std(within).unwrap())
+        }
+        TreeHoleEdit::Delete => edited_at,
+    }
+}
+
+/// The deadline of a message pushed back to `grace` after activity at `at`, if that is later
+fn with_grace(
+    delete_at: DateTime<Utc>,
+    grace: Duration,
+    at: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let extended = at + TimeDelta::from_std(grace).unwrap();
+    (extended > delete_at).then_some(extended)
+}
+
+/// Whether a message was kept with a reaction, which the bot then adds as well
+fn kept(msg: &Message, hole: &TreeHoleCfg) -> bool {
+    hole.keep.as_ref().is_some_and(|keep| {
+        msg.reactions
+            .iter()
+            .any(|r| r.me && keep.matches(&r.reaction_type))
+    })
+}
+
+/// Whether a message has a kind of content its tree hole keeps
+fn keeps_content(msg: &Message, hole: &TreeHoleCfg) -> bool {
+    hole.keep_content.iter().any(|kind| match kind {
+        TreeHoleContent::Attachments => !msg.attachments.is_empty(),
+        TreeHoleContent::Images => msg.attachments.iter().any(|a| {
+            a.content_type
+                .as_deref()
+                .is_some_and(|t| t.starts_with("image/"))
+        }),
+        TreeHoleContent::Links => !extract_urls(&msg.content).is_empty(),
+        TreeHoleContent::Embeds => !msg.embeds.is_empty(),
+    })
+}
+
+/// Whether a message carries an image or video, attached or embedded
+fn has_media(msg: &Message) -> bool {
+    let attached = msg.attachments.iter().any(|a| {
+        a.content_type
+            .as_deref()
+            .is_some_and(|t| t.starts_with("image/") || t.starts_with("video/"))
+    });
+    let embedded = msg.embeds.iter().any(|e| {
+        matches!(e.kind.as_deref(), Some("image" | "video" | "gifv"))
+            || e.image.is_some()
+            || e.video.is_some()
+    });
+    attached || embedded
+}
+
+/// Whether a message carries media, once the embeds of its links unfurled. Discord only adds
+/// those in a later update, so pasted GIF and video links would look like plain text at first.
+async fn has_media_unfurled(ctx: &Context, msg: &Message) -> bool {
+    if has_media(msg) || extract_urls(&msg.content).is_empty() {
+        return has_media(msg);
+    }
+    tokio::time::sleep(EMBED_WAIT).await;
+    match msg.channel_id.message(ctx, msg.id).await {
+        Ok(msg) => has_media(&msg),
+        Err(e) => {
+            // Leave it to the usual timer rather than delete what may be media
+            warn!("Failed to check embeds of message {}: {e}", msg.id);
+            true
+        }
+    }
+}
+
+/// Whether a message is kept for coming from a webhook or another bot
+fn keeps_source(msg: &Message, hole: &TreeHoleCfg, own_id: UserId) -> bool {
+    if msg.webhook_id.is_some() {
+        hole.keep_webhooks
+    } else {
+        hole.keep_bots && msg.author.bot && msg.author.id != own_id
+    }
+}
+
+/// Mirrors of anonymous tree holes are posted under this webhook
+const WEBHOOK_NAME: &str = "树洞";
+
+/// Salt of pseudonyms, so they cannot be recomputed from user IDs outside this process
+static PSEUDONYM_SALT: LazyLock<[u8; 16]> = LazyLock::new(rand::random);
+
+/// The name an author posts under in an anonymous tree hole on a day, the same for all their
+/// posts there that day
+fn pseudonym(salt: &[u8], author_id: UserId, channel_id: ChannelId, day: NaiveDate) -> String {
+    let hash = Sha256::new()
+        .chain_update(salt)
+        .chain_update(author_id.get().to_le_bytes())
+        .chain_update(channel_id.get().to_le_bytes())
+        .chain_update(day.to_string())
+        .finalize();
+    format!("匿名 {}", hex::encode_upper(&hash[..2]))
+}
+
+/// When the quiet hours `at` falls in end, if it falls in them
+fn quiet_until(quiet: QuietHours, at: DateTime<Utc>, offset: FixedOffset) -> Option<DateTime<Utc>> {
+    let time = at.with_timezone(&offset).time();
+    let inside = if quiet.start <= quiet.end {
+        quiet.start <= time && time < quiet.end
+    } else {
+        // Spans midnight
+        time >= quiet.start || time < quiet.end
+    };
+    inside.then(|| schedule::next_daily(at, quiet.end, offset))
+}
+
+/// When the quiet hours of a tree hole going on right now end
+async fn quiet_now(ctx: &Context, quiet: Option<QuietHours>) -> Option<DateTime<Utc>> {
+    let offset = FixedOffset::east_opt(ctx.cfg().await.ok()?.load().time_offset)
+        .expect("Failed to create FixedOffset with the configured time offset");
+    quiet_until(quiet?, Utc::now(), offset)
+}
+
+/// Whether the author of a fetched message is exempt. History carries no member data, so roles
+/// are looked up once per author and kept in `roles`.
+async fn exempt(
+    ctx: &Context,
+    guild_id: GuildId,
+    hole: &TreeHoleCfg,
+    msg: &Message,
+    roles: &mut HashMap<UserId, Vec<RoleId>>,
+) -> bool {
+    if !hole.exempt_role_ids.is_empty() && !roles.contains_key(&msg.author.id) {
+        let member_roles = guild_id
+            .member(ctx, msg.author.id)
+            .await
+            .map(|m| m.roles)
+            .unwrap_or_default();
+        roles.insert(msg.author.id, member_roles);
+    }
+    let author_roles = roles.get(&msg.author.id).map_or(&[][..], Vec::as_slice);
+    hole.exempts(msg.author.id, author_roles)
+}
+
+/// Discord only bulk deletes messages younger than two weeks, less a margin for the request
+const BULK_DELETE_MAX_AGE: TimeDelta = TimeDelta::days(14)
+    .checked_sub(&TimeDelta::minutes(5))
+    .unwrap();
+/// Retries of a deletion that failed for a transient reason, e.g. a rate limit or server error
+const DELETE_RETRIES: u32 = 5;
+/// Delay before the first retry, doubling with each further one
+const RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Split messages into those that can still be bulk deleted and those too old for it.
+fn by_age(message_ids: &[MessageId], now: DateTime<Utc>) -> (Vec<MessageId>, Vec<MessageId>) {
+    message_ids
+        .iter()
+        .partition(|m| now - m.created_at().to_utc() < BULK_DELETE_MAX_AGE)
+}
+
+/// How long to wait after the `failures`th failed deletion of a message, `None` once retries
+/// are used up.
+fn retry_delay(failures: u32) -> Option<TimeDelta> {
+    (1..=DELETE_RETRIES)
+        .contains(&failures)
+        .then(|| TimeDelta::from_std(RETRY_DELAY * 2u32.pow(failures - 1)).unwrap())
+}
+
+/// Whether a failed request may succeed when tried again later
+fn transient(err: &Error) -> bool {
+    match err {
+        Error::Http(HttpError::UnsuccessfulRequest(res)) => {
+            res.status_code.is_server_error() || res.status_code == StatusCode::TOO_MANY_REQUESTS
+        }
+        Error::Http(HttpError::Request(_)) => true,
+        _ => false,
+    }
+}
+
+/// Delete messages of a channel in bulk, one by one for those too old for it, logging failures.
+/// Returns the messages whose deletion failed for a transient reason, to be retried.
+async fn delete_all(
+    ctx: &Context,
+    channel_id: ChannelId,
+    message_ids: &[MessageId],
+) -> Vec<MessageId> {
+    let (recent, old) = by_age(message_ids, Utc::now());
+    let bulk = recent.chunks(100).map(|chunk| async move {
+        let deleted = throttled(async {
+            if let [m] = chunk {
+                // If there's only one message, we must use the simpler delete_message method
+                ctx.http.delete_message(channel_id, *m, None).await
+            } else {
+                ctx.http
+                    .delete_messages(channel_id, &json!({"messages": chunk}), None)
+                    .await
+            }
+        })
+        .await;
+        (chunk.to_vec(), deleted)
+    });
+    let bulk = bulk.collect::<FuturesUnordered<_>>().collect::<Vec<_>>();
+    let single = old
+        .iter()
+        .map(|m| async move {
+            let deleted = throttled(ctx.http.delete_message(channel_id, *m, None)).await;
+            (vec![*m], deleted)
+        })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<_>>();
+    let (bulk, single) = futures::join!(bulk, single);
+    let mut failed = vec![];
+    for (message_ids, deleted) in bulk.into_iter().chain(single) {
+        let Err(e) = deleted else {
+            continue;
+        };
+        error!("Failed to delete messages in channel {channel_id}: {e}");
+        if transient(&e) {
+            failed.extend(message_ids);
+        }
+    }
+    failed
+}
+
+/// The persisted deadline of a message, which `/ttl` can move
+async fn postponed(
+    ctx: &Context,
+    message_id: MessageId,
+) -> Result<Option<DateTime<Utc>>, BotError> {
+    Ok(ctx
+        .db()
+        .await?
+        .tree_holes()
+        .get(message_id)
+        .await?
+        .map(|d| d.delete_at.to_utc()))
+}
+
+/// Delete every unpinned message of a tree hole channel right away, or only those sent before
+/// `before`, as if its TTL were zero. Exemptions and archiving are skipped, this is for
+/// emergencies. Returns how many messages were deleted.
+pub async fn purge(
+    ctx: &Context,
+    channel_id: ChannelId,
+    before: Option<MessageId>,
+) -> Result<usize, BotError> {
+    let message_ids = channel_id
+        .messages_iter(ctx)
+        .try_filter(|msg| {
+            let purged = !msg.pinned && before.is_none_or(|before| msg.id < before);
+            async move { purged }
+        })
+        .map_ok(|msg| msg.id)
+        .try_collect::<Vec<_>>()
+        .await?;
+    ctx.db()
+        .await?
+        .tree_holes()
+        .remove_many(&message_ids)
+        .await?;
+    // Their timers would otherwise still warn about and delete messages that are gone
+    for message_id in &message_ids {
+        TIMERS.rehearsed.remove(message_id);
+        TIMERS.cancel(*message_id);
+    }
+    let failed = delete_all(ctx, channel_id, &message_ids).await;
+    let purged = message_ids.len() - failed.len();
+    info!("Purged {purged} messages in tree hole {channel_id}");
+    Ok(purged)
+}
+
+/// Move tree holes still configured at the top level into their guilds' sections, as the cache
+/// now knows which guild each channel belongs to.
+async fn adopt_tree_holes(ctx: &Context) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?;
+    if cfg.load().tree_holes.is_empty() {
+        return Ok(());
+    }
+    let guild_of = |channel_id: ChannelId| {
+        ctx.cache.guilds().into_iter().find(|guild_id| {
+            ctx.cache
+                .guild(guild_id)
+                .is_some_and(|g| g.channels.contains_key(&channel_id))
+        })
+    };
+    let mut adopted = false;
+    cfg.rcu(|cfg| {
+        let mut cfg = BotCfg::clone(cfg);
+        adopted = cfg.adopt_tree_holes(guild_of);
+        cfg
+    });
+    for channel_id in cfg.load().tree_holes.keys() {
+        warn!("Tree hole {channel_id} is in no known guild and stays inactive");
+    }
+    if adopted {
+        info!("Moved tree holes into the configuration of their guilds");
+        cfg.load().write()?;
+    }
+    Ok(())
+}
+
+/// Drop the persisted deadline of a message that is deleted or kept.
+async fn forget(ctx: &Context, message_id: MessageId) -> Result<(), BotError> {
+    ctx.db().await?.tree_holes().remove(message_id).await
+}
+
+/// What a timer does when it comes due
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Warn,
+    Delete,
+}
+
+/// When a timer for a message deleted at `delete_at` should fire next and what it does then.
+/// Warnings whose time already passed, e.g. during a restart, are skipped.
+fn next_step(
+    delete_at: DateTime<Utc>,
+    warning: Option<TreeHoleWarning>,
+    now: DateTime<Utc>,
+    jitter: TimeDelta,
+) -> (DateTime<Utc>, Step) {
+    if let Some(warning) = warning {
+        let warn_at = delete_at - TimeDelta::from_std(warning.before).unwrap();
+        if warn_at > now {
+            return (warn_at, Step::Warn);
+        }
+    }
+    (delete_at.max(now) + jitter, Step::Delete)
+}
+
+fn jitter() -> TimeDelta {
+    TimeDelta::from_std(JITTER.mul_f64(rand::random())).unwrap()
+}
+
+/// When a message's timer fires next
+type Due = (DateTime<Utc>, MessageId);
+
+struct Timer {
+    channel_id: ChannelId,
+    delete_at: DateTime<Utc>,
+    next: (DateTime<Utc>, Step),
+    /// Deletion notice replying to the message
+    notice: Option<MessageId>,
+    hole: TreeHoleCfg,
+    /// Failed attempts at deleting the message
+    failures: u32,
+}
+
+/// Deletion timers of all scheduled messages. A single driver task sleeps until the earliest
+/// one is due, instead of every message holding a sleeping task of its own.
+#[derive(Default)]
+struct Timers {
+    timers: DashMap<MessageId, Timer>,
+    /// Due times by message, earliest on top. Entries of cancelled or moved timers stay until
+    /// popped and are then skipped.
+    queue: Mutex<BinaryHeap<Reverse<Due>>>,
+    wake: Notify,
+    driving: AtomicBool,
+    /// Messages a tree hole in dry-run mode would have deleted, so they are reported only once
+    rehearsed: DashSet<MessageId>,
+}
+
+impl Timers {
+    fn contains(&self, message_id: &MessageId) -> bool {
+        self.timers.contains_key(message_id)
+    }
+
+    /// Start the timer of a message unless it already has one.
+    fn start(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        delete_at: DateTime<Utc>,
+        hole: &TreeHoleCfg,
+    ) {
+        let Entry::Vacant(entry) = self.timers.entry(message_id) else {
+            return;
+        };
+        let next = next_step(delete_at, hole.warning, Utc::now(), jitter());
+        entry.insert(Timer {
+            channel_id,
+            delete_at,
+            next,
+            notice: None,
+            hole: hole.to_owned(),
+            failures: 0,
+        });
+        self.push(next.0, message_id);
+    }
+
+    /// Try deleting a message again after a transient failure, waiting longer after each one.
+    /// Returns `false` once the retries are used up.
+    fn retry(&self, message_id: MessageId, timer: &Timer) -> bool {
+        let failures = timer.failures + 1;
+        let Some(delay) = retry_delay(failures) else {
+            return false;
+        };
+        let next = (Utc::now() + delay, Step::Delete);
+        self.timers.insert(
+            message_id,
+            Timer {
+                channel_id: timer.channel_id,
+                delete_at: timer.delete_at,
+                next,
+                notice: timer.notice,
+                hole: timer.hole.to_owned(),
+                failures,
+            },
+        );
+        self.push(next.0, message_id);
+        true
+    }
+
+    /// Queue messages whose deletion in bulk failed for a transient reason for another attempt.
+    fn retry_all(&self, channel_id: ChannelId, message_ids: &[MessageId], hole: &TreeHoleCfg) {
+        let now = Utc::now();
+        for message_id in message_ids {
+            let timer = Timer {
+                channel_id,
+                delete_at: now,
+                next: (now, Step::Delete),
+                notice: None,
+                hole: hole.to_owned(),
+                failures: 0,
+            };
+            self.retry(*message_id, &timer);
+        }
+    }
+
+    /// Where and when a message with a running timer is deleted
+    fn deadline(&self, message_id: MessageId) -> Option<(ChannelId, DateTime<Utc>)> {
+        self.timers
+            .get(&message_id)
+            .map(|timer| (timer.channel_id, timer.delete_at))
+    }
+
+    /// Move a running timer to a new deadline, returning its deletion notice, which no longer
+    /// applies.
+    fn move_to(&self, message_id: MessageId, delete_at: DateTime<Utc>) -> Option<MessageId> {
+        let mut timer = self.timers.get_mut(&message_id)?;
+        let next = next_step(delete_at, timer.hole.warning, Utc::now(), jitter());
+        timer.delete_at = delete_at;
+        timer.next = next;
+        let notice = timer.notice.take();
+        drop(timer);
+        self.push(next.0, message_id);
+        notice
+    }
+
+    /// Report what a tree hole in dry-run mode would delete now, instead of deleting it.
+    async fn rehearse(
+        &self,
+        ctx: &Context,
+        channel_id: ChannelId,
+        message_ids: &[MessageId],
+        why: &str,
+    ) {
+        for message_id in message_ids {
+            self.rehearsed.insert(*message_id);
+        }
+        info!(
+            "Dry run: would delete {} messages in {channel_id} ({why}): {message_ids:?}",
+            message_ids.len()
+        );
+        let Some(debug_channel_id) = ctx
+            .cfg()
+            .await
+            .ok()
+            .and_then(|cfg| cfg.load().tree_hole_debug_channel_id)
+        else {
+            return;
+        };
+        let mut ids = message_ids
+            .iter()
+            .take(20)
+            .map(|m| format!("`{m}`"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if message_ids.len() > 20 {
+            ids.push_str(" …");
+        }
+        let report = CreateEmbed::new()
+            .title("🧪 树洞演练")
+            .description(format!(
+                "{} 中的 {} 条消息{why}, 将被删除。\n\n{ids}",
+                channel_id.mention(),
+                message_ids.len()
+            ))
+            .timestamp(Timestamp::now());
+        if let Err(e) = debug_channel_id
+            .send_message(ctx, CreateMessage::new().embed(report))
+            .await
+        {
+            error!("Failed to report tree hole dry run in {channel_id}: {e}");
+        }
+    }
+
+    /// Stop the timer of a message, returning whether it had one.
+    fn cancel(&self, message_id: MessageId) -> bool {
+        self.timers.remove(&message_id).is_some()
+    }
+
+    /// Stop the timer of a message and hand it back, if it had one.
+    fn take(&self, message_id: MessageId) -> Option<Timer> {
+        self.timers.remove(&message_id).map(|(_, timer)| timer)
+    }
+
+    fn push(&self, at: DateTime<Utc>, message_id: MessageId) {
+        let mut queue = self.queue.lock().unwrap();
+        let earliest = queue.peek().is_none_or(|Reverse((first, _))| at < *first);
+        queue.push(Reverse((at, message_id)));
+        if earliest {
+            self.wake.notify_one();
+        }
+    }
+
+    /// Move a timer that is still running to its next step.
+    fn advance(&self, message_id: MessageId, next: (DateTime<Utc>, Step)) {
+        if let Some(mut timer) = self.timers.get_mut(&message_id) {
+            timer.next = next;
+            drop(timer);
+            self.push(next.0, message_id);
+        }
+    }
+
+    /// Run the driver task, once.
+    fn drive(self: &Arc<Self>, ctx: &Context) {
+        if self.driving.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let (timers, ctx) = (self.to_owned(), ctx.to_owned());
+        spawn(async move {
+            loop {
+                let first = timers.queue.lock().unwrap().peek().copied();
+                let Some(Reverse((at, message_id))) = first else {
+                    timers.wake.notified().await;
+                    continue;
+                };
+                if let Ok(wait) = (at - Utc::now()).to_std() {
+                    // An earlier timer may come in while waiting
+                    tokio::select! {
+                        () = tokio::time::sleep(wait) => {}
+                        () = timers.wake.notified() => continue,
+                    }
+                }
+                timers.queue.lock().unwrap().pop();
+                let step = timers
+                    .timers
+                    .get(&message_id)
+                    .filter(|timer| timer.next.0 == at)
+                    .map(|timer| timer.next.1);
+                if let Some(step) = step {
+                    let fire = timers.to_owned().fire(ctx.to_owned(), message_id, step);
+                    spawn(crash::named("tree hole timer", fire));
+                }
+            }
+        });
+    }
+
+    async fn fire(self: Arc<Self>, ctx: Context, message_id: MessageId, step: Step) {
+        let Some((channel_id, delete_at, hole)) = self
+            .timers
+            .get(&message_id)
+            .map(|t| (t.channel_id, t.delete_at, t.hole.to_owned()))
+        else {
+            return; // Cancelled in the meantime
+        };
+        // `/ttl` may have pushed the deadline back in the meantime, which moves the
+        // warning along with it
+        if let Ok(Some(deletion)) = postponed(&ctx, message_id).await
+            && deletion > delete_at
+        {
+            let notice = self.move_to(message_id, deletion);
+            if hole.countdown
+                && let Ok(msg) = ctx.http.get_message(channel_id, message_id).await
+            {
+                countdown(&ctx, &msg, deletion).await;
+            }
+            if let Some(notice) = notice
+                && let Err(err) = ctx.http.delete_message(channel_id, notice, None).await
+            {
+                error!("Failed to delete outdated deletion notice {notice}: {err}");
+            }
+            return;
+        }
+        if step == Step::Warn {
+            let warning = hole.warning.expect("Only timers with a warning warn");
+            match warn(&ctx, channel_id, message_id, delete_at, warning).await {
+                Ok(Some(notice)) => {
+                    if let Some(mut timer) = self.timers.get_mut(&message_id) {
+                        timer.notice = Some(notice);
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => error!("Failed to warn about deletion of {message_id}: {err}"),
+            }
+            self.advance(message_id, (delete_at + jitter(), Step::Delete));
+            return;
+        }
+        if let Some(end) = quiet_now(&ctx, hole.quiet_hours).await {
+            self.advance(message_id, (end, Step::Delete));
+            return;
+        }
+        let Some((_, timer)) = self.timers.remove(&message_id) else {
+            return; // Cancelled in the meantime
+        };
+        if hole.dry_run {
+            self.rehearse(&ctx, channel_id, &[message_id], "到期").await;
+            if let Err(err) = forget(&ctx, message_id).await {
+                error!("Failed to forget deletion of message {message_id}: {err}");
+            }
+            return;
+        }
+        // Archived already on the first attempt
+        if let Some(archive_channel_id) = hole.archive_channel_id.filter(|_| timer.failures == 0) {
+            match ctx.http.get_message(channel_id, message_id).await {
+                Ok(msg) => archive(&ctx, archive_channel_id, &msg).await,
+                Err(err) => {
+                    error!("Failed to fetch message {message_id} to archive: {err}")
+                }
+            }
+        }
+        let deleted = throttled(async {
+            if message_id.get() == channel_id.get() {
+                // The starter message of a forum post takes the whole post with it
+                ctx.http.delete_channel(channel_id, None).await.map(drop)
+            } else {
+                ctx.http.delete_message(channel_id, message_id, None).await
+            }
+        })
+        .await;
+        if let Err(err) = deleted {
+            if transient(&err) && self.retry(message_id, &timer) {
+                warn!("Failed to delete message {message_id}, retrying: {err}");
+                return;
+            }
+            error!("Failed to delete message {message_id}: {err}");
+        }
+        if let Some(notice) = timer.notice
+            && let Err(err) = throttled(ctx.http.delete_message(channel_id, notice, None)).await
+        {
+            error!("Failed to delete deletion notice {notice}: {err}");
+        }
+        if let Err(err) = forget(&ctx, message_id).await {
+            error!("Failed to forget deletion of message {message_id}: {err}");
+        }
+    }
+}
+
 impl TreeHoleHandler {
+    /// The tree hole a channel belongs to, threads and forum posts following their parent.
+    async fn hole(&self, ctx: &Context, channel_id: ChannelId) -> Option<TreeHoleCfg> {
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load_full();
+        if let Some(hole) = cfg.tree_hole(channel_id) {
+            return Some(hole.applied(cfg.tree_hole_dry_run));
+        }
+        let parent_id = match self.parents.get(&channel_id).map(|p| *p) {
+            Some(parent_id) => parent_id,
+            None => {
+                let channel = channel_id.to_channel(ctx).await.ok()?.guild()?;
+                let parent_id = channel.thread_metadata.and(channel.parent_id);
+                self.parents.insert(channel_id, parent_id);
+                parent_id
+            }
+        };
+        cfg.tree_hole(parent_id?)
+            .map(|hole| hole.applied(cfg.tree_hole_dry_run))
+    }
+
+    /// Whether a message is a mirror posted by `anonymize`, in this run or an earlier one once
+    /// `delete_messages` loaded the webhooks
+    fn is_mirror(&self, msg: &Message) -> bool {
+        msg.webhook_id
+            .is_some_and(|id| self.webhooks.iter().any(|webhook| webhook.id == id))
+    }
+
+    /// Whether a message is kept for its source, see [`keeps_source`]. Mirrors of anonymous tree
+    /// holes are the bot's own and never kept as webhook messages.
+    fn keeps_source(&self, msg: &Message, hole: &TreeHoleCfg, own_id: UserId) -> bool {
+        keeps_source(msg, hole, own_id) && !self.is_mirror(msg)
+    }
+
+    /// The bot's webhook of a channel, created on first use
+    async fn webhook(&self, ctx: &Context, channel_id: ChannelId) -> Result<Webhook, BotError> {
+        if let Some(webhook) = self.webhooks.get(&channel_id) {
+            return Ok(webhook.to_owned());
+        }
+        let own_id = ctx.cache.current_user().id;
+        let existing = channel_id.webhooks(ctx).await?.into_iter().find(|webhook| {
+            webhook.name.as_deref() == Some(WEBHOOK_NAME)
+                && webhook.user.as_ref().is_some_and(|user| user.id == own_id)
+        });
+        let webhook = match existing {
+            Some(webhook) => webhook,
+            None => {
+                channel_id
+                    .create_webhook(ctx, CreateWebhook::new(WEBHOOK_NAME))
+                    .await?
+            }
+        };
+        self.webhooks.insert(channel_id, webhook.to_owned());
+        Ok(webhook)
+    }
+
+    /// Repost a message of an anonymous tree hole under a pseudonym and delete the original.
+    /// Returns whether it did, messages without content or attachments stay as they are.
+    async fn anonymize(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        hole: &TreeHoleCfg,
+    ) -> Result<bool, BotError> {
+        if msg.content.is_empty() && msg.attachments.is_empty() {
+            return Ok(false); // e.g. only a sticker
+        }
+        // Webhooks belong to the tree hole itself and post into its threads
+        let parent_id = self.parents.get(&msg.channel_id).and_then(|p| *p);
+        let webhook = self
+            .webhook(ctx, parent_id.unwrap_or(msg.channel_id))
+            .await?;
+        let delete_at = Utc::now() + TimeDelta::from_std(hole.duration).unwrap();
+        let content = hole
+            .countdown
+            .then(|| with_countdown(&msg.content, delete_at))
+            .flatten()
+            .unwrap_or_else(|| msg.content.to_owned());
+        let mut files = vec![];
+        for attachment in &msg.attachments {
+            files.push(CreateAttachment::url(ctx, &attachment.url).await?);
+        }
+        let name = pseudonym(
+            &*PSEUDONYM_SALT,
+            msg.author.id,
+            msg.channel_id,
+            msg.timestamp.date_naive(),
+        );
+        let mut mirror = ExecuteWebhook::new()
+            .content(content)
+            .username(name)
+            .add_files(files)
+            // Mentions already pinged with the original
+            .allowed_mentions(CreateAllowedMentions::new());
+        if parent_id.is_some() {
+            mirror = mirror.in_thread(msg.channel_id);
+        }
+        let mirror = webhook
+            .execute(ctx, true, mirror)
+            .await?
+            .whatever_context::<&str, BotError>("Webhook returned no message")?;
+        msg.delete(ctx).await?;
+        self.schedule(ctx, msg.channel_id, mirror.id, delete_at, hole)
+            .await?;
+        Ok(true)
+    }
+
+    /// Persist the deadline of a message, so a restart can pick it up again, and start its timer.
+    async fn schedule(
+        &self,
+        ctx: &Context,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        delete_at: DateTime<Utc>,
+        hole: &TreeHoleCfg,
+    ) -> Result<(), BotError> {
+        ctx.db()
+            .await?
+            .tree_holes()
+            .schedule(channel_id, message_id, delete_at)
+            .await?;
+        self.timers.start(channel_id, message_id, delete_at, hole);
+        Ok(())
+    }
+
+    /// Move the deadline of a scheduled message. Timers follow a later persisted deadline, as set
+    /// by `/ttl`, so both have to move.
+    async fn move_deadline(
+        &self,
+        ctx: &Context,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        delete_at: DateTime<Utc>,
+        hole: &TreeHoleCfg,
+    ) -> Result<(), BotError> {
+        ctx.db()
+            .await?
+            .tree_holes()
+            .postpone(message_id, delete_at)
+            .await?;
+        let notice = self.timers.move_to(message_id, delete_at);
+        if hole.countdown {
+            let msg = ctx.http.get_message(channel_id, message_id).await?;
+            countdown(ctx, &msg, delete_at).await;
+        }
+        if let Some(notice) = notice {
+            ctx.http.delete_message(channel_id, notice, None).await?;
+        }
+        Ok(())
+    }
+
+    /// Keep the message a new one replies to, or the one its thread was started from, for at
+    /// least `grace` more.
+    async fn extend_discussed(
+        &self,
+        ctx: &Context,
+        msg: &Message,
+        grace: Duration,
+        hole: &TreeHoleCfg,
+    ) {
+        let replied = msg.message_reference.as_ref().and_then(|r| r.message_id);
+        // Threads started from a message share its ID, as forum posts do with their first message
+        let starter = MessageId::new(msg.channel_id.get());
+        for message_id in replied.into_iter().chain([starter]) {
+            let Some((channel_id, delete_at)) = self.timers.deadline(message_id) else {
+                continue; // Not scheduled for deletion
+            };
+            let Some(deletion) = with_grace(delete_at, grace, msg.timestamp.to_utc()) else {
+                continue;
+            };
+            if let Err(e) = self
+                .move_deadline(ctx, channel_id, message_id, deletion, hole)
+                .await
+            {
+                error!("Failed to extend deletion of discussed message {message_id}: {e}");
+            }
+        }
+    }
+
+    /// Drop the timers of messages deleted by someone else, e.g. a moderator, so they do not fire
+    /// at messages that are gone. Their deletion notices go with them.
+    async fn forget_deleted(
+        &self,
+        ctx: &Context,
+        channel_id: ChannelId,
+        message_ids: &[MessageId],
+    ) {
+        for message_id in message_ids {
+            self.timers.rehearsed.remove(message_id);
+            let Some(timer) = self.timers.take(*message_id) else {
+                continue; // Not scheduled, or deleted by its timer
+            };
+            if let Err(e) = forget(ctx, *message_id).await {
+                error!("Failed to forget deletion of deleted message {message_id}: {e}");
+            }
+            if let Some(notice) = timer.notice
+                && let Err(e) = throttled(ctx.http.delete_message(channel_id, notice, None)).await
+            {
+                error!("Failed to delete deletion notice {notice}: {e}");
+            }
+        }
+    }
+
+    /// Restart the timers persisted before a restart at their exact deadlines.
+    async fn reschedule(&self, ctx: &Context) -> Result<(), BotError> {
+        let db = ctx.db().await?;
+        for deletion in db.tree_holes().pending().await? {
+            let Some(hole) = self.hole(ctx, deletion.channel_id()).await else {
+                // No longer a tree hole, the message stays
+                db.tree_holes().remove(deletion.message_id()).await?;
+                continue;
+            };
+            self.timers.start(
+                deletion.channel_id(),
+                deletion.message_id(),
+                deletion.delete_at.to_utc(),
+                &hole,
+            );
+        }
+        Ok(())
+    }
+
     async fn delete_in_channel(
         &self,
         ctx: Context,
+        guild_id: GuildId,
         channel_id: ChannelId,
-        dur: Duration,
+        hole: &TreeHoleCfg,
     ) -> Result<(), BotError> {
         let messages = channel_id
             .messages_iter(ctx.to_owned())
             .try_collect::<Vec<_>>()
             .await?;
 
-        let delta = TimeDelta::from_std(dur).unwrap();
+        let delta = TimeDelta::from_std(hole.duration).unwrap();
         let now = chrono::Utc::now();
+        // Expired messages are left to their timers, which wait for the quiet hours to end
+        let quiet = quiet_now(&ctx, hole.quiet_hours).await.is_some();
+        let mut roles = HashMap::new();
+        let own_id = ctx.cache.current_user().id;
 
-        messages
-            .into_iter()
-            .filter(|msg| !msg.pinned && !self.msgs.contains_key(&msg.id))
-            .filter_map(|msg| {
-                let new_dur = delta - (now - msg.timestamp.to_utc());
-                if new_dur > chrono::Duration::zero() {
-                    let ctx = ctx.to_owned();
-                    let msg_id = msg.id;
-                    let h = spawn(async move {
-                        tokio::time::sleep(new_dur.to_std().unwrap()).await;
-                        if let Err(err) = msg.delete(ctx).await {
-                            error!("Failed to delete message {}: {}", msg.id, err);
-                        }
-                    });
-                    self.msgs.insert(msg_id, h);
-                    None // Don't collect this message, it's being handled
-                } else {
-                    Some(msg.id)
+        let (mut pending, mut expired) = (vec![], vec![]);
+        for msg in messages.into_iter().filter(|msg| {
+            !msg.pinned
+                && !self.timers.contains(&msg.id)
+                && !self.timers.rehearsed.contains(&msg.id)
+                && !kept(msg, hole)
+                && !keeps_content(msg, hole)
+                && !self.keeps_source(msg, hole, own_id)
+        }) {
+            if exempt(&ctx, guild_id, hole, &msg, &mut roles).await {
+                continue;
+            }
+            let delete_at = if hole.media_only && !has_media(&msg) {
+                msg.timestamp.to_utc() // Due right away
+            } else {
+                msg.timestamp.to_utc() + delta
+            };
+            if delete_at > now || quiet {
+                if hole.countdown {
+                    countdown(&ctx, &msg, delete_at).await;
                 }
-            })
-            .collect::<Vec<_>>()
-            .chunks(100)
-            .map(async |chunk| {
-                if let [m] = chunk {
-                    // If there's only one message, we must use the simpler delete_message method
-                    ctx.http.delete_message(channel_id, *m, None).await?
-                } else {
-                    ctx.http
-                        .delete_messages(channel_id, &json!({"messages": chunk}), None)
-                        .await?
-                };
-                Ok::<_, BotError>(())
-            })
-            .collect::<FuturesUnordered<_>>()
-            .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .filter_map(Result::err)
-            .for_each(|e| {
-                error!("Failed to delete old messages in channel {channel_id}: {e}");
-            });
+                pending.push((msg.id, delete_at));
+            } else {
+                expired.push(msg);
+            }
+        }
+        if hole.dry_run && !expired.is_empty() {
+            let ids = expired.iter().map(|msg| msg.id).collect::<Vec<_>>();
+            self.timers.rehearse(&ctx, channel_id, &ids, "已过期").await;
+            expired.clear();
+        }
+        if let Some(archive_channel_id) = hole.archive_channel_id {
+            // Oldest first, so the archive reads in order
+            for msg in expired.iter().rev() {
+                archive(&ctx, archive_channel_id, msg).await;
+            }
+        }
+        if expired.iter().any(|msg| msg.id.get() == channel_id.get()) {
+            // The starter message of a forum post expired, the whole post goes
+            throttled(channel_id.delete(&ctx)).await?;
+            return Ok(());
+        }
+        for (message_id, delete_at) in pending {
+            self.schedule(&ctx, channel_id, message_id, delete_at, hole)
+                .await?;
+        }
+        let expired = expired.into_iter().map(|msg| msg.id).collect::<Vec<_>>();
+        let failed = delete_all(&ctx, channel_id, &expired).await;
+        self.timers.retry_all(channel_id, &failed, hole);
+        if let Some(max) = hole.max_messages.filter(|_| !quiet) {
+            self.trim(&ctx, guild_id, channel_id, hole, max).await?;
+        }
+        Ok(())
+    }
+
+    /// Delete all but the newest `max` messages of a count-based tree hole, looking only a batch
+    /// past the limit, as new messages push out a few at a time.
+    async fn trim(
+        &self,
+        ctx: &Context,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        hole: &TreeHoleCfg,
+        max: usize,
+    ) -> Result<(), BotError> {
+        let messages = channel_id
+            .messages_iter(ctx)
+            .take(max + TRIM_BATCH)
+            .try_collect::<Vec<_>>()
+            .await?;
+        let mut roles = HashMap::new();
+        let own_id = ctx.cache.current_user().id;
+        let mut kept_count = 0;
+        let mut excess = vec![];
+        // Newest first; pinned, kept and exempt messages and those with kept content or from
+        // kept webhooks and bots do not count towards the limit, and forum posts keep their
+        // starter message
+        for msg in messages {
+            if msg.pinned
+                || self.timers.rehearsed.contains(&msg.id)
+                || kept(&msg, hole)
+                || keeps_content(&msg, hole)
+                || self.keeps_source(&msg, hole, own_id)
+                || msg.id.get() == channel_id.get()
+                || exempt(ctx, guild_id, hole, &msg, &mut roles).await
+            {
+                continue;
+            }
+            if kept_count < max {
+                kept_count += 1;
+            } else {
+                excess.push(msg);
+            }
+        }
+        if excess.is_empty() {
+            return Ok(());
+        }
+        for msg in &excess {
+            self.timers.cancel(msg.id);
+            forget(ctx, msg.id).await?;
+        }
+        if hole.dry_run {
+            let excess = excess.iter().map(|msg| msg.id).collect::<Vec<_>>();
+            let why = format!("超出 {max} 条上限");
+            self.timers.rehearse(ctx, channel_id, &excess, &why).await;
+            return Ok(());
+        }
+        if let Some(archive_channel_id) = hole.archive_channel_id {
+            for msg in excess.iter().rev() {
+                archive(ctx, archive_channel_id, msg).await;
+            }
+        }
+        let excess = excess.into_iter().map(|msg| msg.id).collect::<Vec<_>>();
+        let failed = delete_all(ctx, channel_id, &excess).await;
+        self.timers.retry_all(channel_id, &failed, hole);
+        Ok(())
+    }
+
+    /// Sweep a tree hole and the threads under it. Forum channels only hold posts, which are
+    /// threads of their own.
+    async fn delete_in_tree_hole(
+        &self,
+        ctx: &Context,
+        channel_id: ChannelId,
+        hole: &TreeHoleCfg,
+    ) -> Result<(), BotError> {
+        let channel = channel_id
+            .to_channel(ctx)
+            .await?
+            .guild()
+            .whatever_context::<&str, BotError>("Tree hole is not a guild channel")?;
+        if channel.kind != ChannelType::Forum {
+            self.delete_in_channel(ctx.to_owned(), channel.guild_id, channel_id, hole)
+                .await?;
+        }
+        let mut threads = channel.guild_id.get_active_threads(ctx).await?.threads;
+        threads.retain(|t| t.parent_id == Some(channel_id));
+        threads.extend(
+            channel_id
+                .get_archived_public_threads(ctx, None, None)
+                .await?
+                .threads,
+        );
+        for thread in threads {
+            self.parents.insert(thread.id, Some(channel_id));
+            if let Err(e) = self
+                .delete_in_channel(ctx.to_owned(), channel.guild_id, thread.id, hole)
+                .await
+            {
+                error!(
+                    "Failed to delete messages in tree hole thread {}: {e}",
+                    thread.id
+                );
+            }
+        }
         Ok(())
     }
 
     async fn delete_messages(&self, ctx: Context) {
-        for (channel_id, dur) in ctx
+        let cfg = ctx
             .cfg()
             .await
             .expect("Failed to get bot configuration")
-            .load()
-            .tree_holes
-            .iter()
-        {
-            if let Err(e) = self
-                .delete_in_channel(ctx.to_owned(), *channel_id, *dur)
-                .await
+            .load_full();
+        for (guild_id, channel_id, hole) in cfg.all_tree_holes() {
+            let hole = hole.applied(cfg.tree_hole_dry_run);
+            // Mirrors left from before a restart are told apart by their webhook
+            if hole.anonymous
+                && !hole.dry_run
+                && let Err(e) = self.webhook(&ctx, *channel_id).await
             {
-                error!("Failed to delete messages in tree hole channel {channel_id}: {e}");
+                error!("Failed to load webhook of anonymous tree hole {channel_id}: {e}");
+            }
+            if let Err(e) = self.delete_in_tree_hole(&ctx, *channel_id, &hole).await {
+                error!(
+                    "Failed to delete messages in tree hole channel {channel_id} of guild \
+                     {guild_id}: {e}"
+                );
             }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_by_age() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let sent = |days| {
+            MessageId::from(
+                ((now - TimeDelta::days(days)).timestamp_millis() as u64 - 1_420_070_400_000) << 22,
+            )
+        };
+        let (recent, old) = by_age(&[sent(1), sent(20), sent(13)], now);
+        assert_eq!(recent, [sent(1), sent(13)]);
+        assert_eq!(old, [sent(20)]);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Some(TimeDelta::seconds(30)));
+        assert_eq!(retry_delay(3), Some(TimeDelta::minutes(2)));
+        assert_eq!(retry_delay(DELETE_RETRIES + 1), None);
+    }
+
+    #[test]
+    fn test_next_step() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let hour = TimeDelta::hours(1);
+        let warning = Some(TreeHoleWarning {
+            before: Duration::from_secs(600),
+            reply: false,
+        });
+        let jitter = TimeDelta::seconds(3);
+        assert_eq!(
+            next_step(now + hour, warning, now, jitter),
+            (now + hour - TimeDelta::minutes(10), Step::Warn)
+        );
+        // Too late to warn
+        assert_eq!(
+            next_step(now + TimeDelta::minutes(5), warning, now, jitter),
+            (now + TimeDelta::minutes(5) + jitter, Step::Delete)
+        );
+        // Overdue after a restart
+        assert_eq!(
+            next_step(now - hour, None, now, jitter),
+            (now + jitter, Step::Delete)
+        );
+    }
+
+    #[test]
+    fn test_edited_deadline() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let delete_at = now + TimeDelta::hours(1);
+        let day = Duration::from_secs(86400);
+        assert_eq!(
+            edited_deadline(TreeHoleEdit::Reset, delete_at, day, now),
+            now + TimeDelta::days(1)
+        );
+        let shorten = TreeHoleEdit::Shorten(Duration::from_secs(600));
+        assert_eq!(
+            edited_deadline(shorten, delete_at, day, now),
+            now + TimeDelta::minutes(10)
+        );
+        // Never pushes the deadline back
+        assert_eq!(
+            edited_deadline(shorten, now + TimeDelta::minutes(5), day, now),
+            now + TimeDelta::minutes(5)
+        );
+        assert_eq!(
+            edited_deadline(TreeHoleEdit::Delete, delete_at, day, now),
+            now
+        );
+    }
+
+    #[test]
+    fn test_pseudonym() {
+        let (author_id, channel_id) = (UserId::new(1), ChannelId::new(2));
+        let day = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let name = pseudonym(b"salt", author_id, channel_id, day);
+        assert!(name.starts_with("匿名 ") && name.len() == "匿名 ".len() + 4);
+        assert_eq!(name, pseudonym(b"salt", author_id, channel_id, day));
+        assert_ne!(name, pseudonym(b"salt", UserId::new(3), channel_id, day));
+        assert_ne!(
+            name,
+            pseudonym(b"salt", author_id, channel_id, day.succ_opt().unwrap())
+        );
+        assert_ne!(name, pseudonym(b"pepper", author_id, channel_id, day));
+    }
+
+    #[test]
+    fn test_has_media() {
+        let mut msg = Message::default();
+        msg.content = "https://example.com".to_owned();
+        assert!(!has_media(&msg));
+        let mut embed = Embed::default();
+        embed.kind = Some("link".to_owned());
+        msg.embeds.push(embed.to_owned());
+        assert!(!has_media(&msg));
+        embed.kind = Some("gifv".to_owned());
+        msg.embeds.push(embed);
+        assert!(has_media(&msg));
+    }
+
+    #[test]
+    fn test_keeps_source() {
+        let own_id = UserId::new(1);
+        let hole = TreeHoleCfg {
+            keep_bots: true,
+            ..Default::default()
+        };
+        let mut msg = Message::default();
+        msg.author.bot = true;
+        msg.author.id = UserId::new(2);
+        assert!(keeps_source(&msg, &hole, own_id));
+        msg.author.id = own_id;
+        assert!(!keeps_source(&msg, &hole, own_id));
+        // Webhooks post as bots, but have an option of their own
+        msg.webhook_id = Some(WebhookId::new(3));
+        assert!(!keeps_source(&msg, &hole, own_id));
+    }
+
+    #[test]
+    fn test_with_grace() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let grace = Duration::from_secs(1800);
+        assert_eq!(
+            with_grace(now + TimeDelta::minutes(10), grace, now),
+            Some(now + TimeDelta::minutes(30))
+        );
+        assert_eq!(with_grace(now + TimeDelta::hours(1), grace, now), None);
+    }
+
+    #[test]
+    fn test_with_countdown() {
+        let at = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let later = DateTime::from_timestamp(1_800_003_600, 0).unwrap();
+        let content = with_countdown("hello", at).unwrap();
+        assert_eq!(content, "hello\n-# ⏳ 将于 <t:1800000000:R> 消失");
+        // Moving the deadline replaces the line
+        assert_eq!(
+            with_countdown(&content, later).unwrap(),
+            "hello\n-# ⏳ 将于 <t:1800003600:R> 消失"
+        );
+        assert_eq!(
+            with_countdown("", at).unwrap(),
+            "-# ⏳ 将于 <t:1800000000:R> 消失"
+        );
+        assert!(with_countdown(&"a".repeat(2000), at).is_none());
+    }
+
+    #[test]
+    fn test_quiet_until() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let time = |s: &str| s.parse().unwrap();
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+        let night = QuietHours {
+            start: time("23:00:00"),
+            end: time("08:00:00"),
+        };
+        assert_eq!(
+            quiet_until(night, at("2026-10-14T23:30:00+08:00"), offset),
+            Some(at("2026-10-15T08:00:00+08:00"))
+        );
+        assert_eq!(
+            quiet_until(night, at("2026-10-14T03:00:00+08:00"), offset),
+            Some(at("2026-10-14T08:00:00+08:00"))
+        );
+        assert_eq!(
+            quiet_until(night, at("2026-10-14T12:00:00+08:00"), offset),
+            None
+        );
+        let lunch = QuietHours {
+            start: time("12:00:00"),
+            end: time("13:00:00"),
+        };
+        assert!(quiet_until(lunch, at("2026-10-14T12:30:00+08:00"), offset).is_some());
+        assert!(quiet_until(lunch, at("2026-10-14T13:00:00+08:00"), offset).is_none());
+    }
+}
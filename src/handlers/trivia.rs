@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::FixedOffset;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::trivia::run_trivia, config::GetCfg, database::GetDb, error::BotError, utils::schedule,
+};
+
+#[derive(Default)]
+pub struct TriviaHandler {
+    started: AtomicBool,
+}
+
+#[async_trait]
+impl EventHandler for TriviaHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return; // Daily posts are already scheduled
+        }
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load();
+        let offset = FixedOffset::east_opt(cfg.time_offset)
+            .expect("Failed to create FixedOffset with the configured time offset");
+        for trivia in cfg.trivia.iter().cloned() {
+            info!(
+                "Scheduling daily trivia in channel {} at {}",
+                trivia.channel_id, trivia.time
+            );
+            let ctx = ctx.to_owned();
+            schedule::daily(trivia.time, offset, move || {
+                let ctx = ctx.to_owned();
+                let trivia = trivia.to_owned();
+                async move {
+                    let f = async || -> Result<(), BotError> {
+                        let channel = trivia.channel_id.to_channel(&ctx).await?;
+                        let Some(channel) = channel.guild() else {
+                            snafu::whatever!(
+                                "Channel {} is not a guild channel",
+                                trivia.channel_id
+                            );
+                        };
+                        let db = ctx.db().await?;
+                        run_trivia(
+                            &ctx,
+                            &db,
+                            channel.guild_id,
+                            channel.id,
+                            trivia.category,
+                            trivia.rounds,
+                        )
+                        .await
+                    };
+                    if let Err(e) = f().await {
+                        error!("Failed to run daily trivia in {}: {e}", trivia.channel_id);
+                    }
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serenity::all::*;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+use super::modules::{Module, ModuleHandler};
+use crate::{
+    config::{Creator, GetCfg, UploadWatch},
+    database::{BotDatabase, GetDb},
+    error::BotError,
+    utils::{schedule, twitch::Twitch},
+};
+
+/// A video as announced, newest first in fetch results
+struct Upload {
+    id: String,
+    title: String,
+    url: String,
+    creator: String,
+}
+
+#[derive(Deserialize)]
+struct TwitchUser {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct TwitchVideo {
+    id: String,
+    user_name: String,
+    title: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct PlaylistItems {
+    items: Vec<PlaylistItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistItem {
+    snippet: Snippet,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Snippet {
+    title: String,
+    channel_title: String,
+    resource_id: ResourceId,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceId {
+    video_id: String,
+}
+
+/// Polls creators for new uploads, remembering the last seen video of each
+pub struct UploadHandler;
+
+async fn fetch(
+    client: &reqwest::Client,
+    twitch: Option<&Twitch>,
+    youtube_api_key: Option<&str>,
+    creator: &Creator,
+) -> Result<Vec<Upload>, BotError> {
+    match creator {
+        Creator::Twitch { login } => {
+            let Some(twitch) = twitch else {
+                snafu::whatever!("Twitch credentials are not configured");
+            };
+            let Some(user) = twitch
+                .get::<TwitchUser>("users", &[("login", login)])
+                .await?
+                .into_iter()
+                .next()
+            else {
+                snafu::whatever!("Twitch user {login} does not exist");
+            };
+            Ok(twitch
+                .get::<TwitchVideo>(
+                    "videos",
+                    &[("user_id", &user.id), ("type", "archive"), ("first", "5")],
+                )
+                .await?
+                .into_iter()
+                .map(|v| Upload {
+                    id: v.id,
+                    title: v.title,
+                    url: v.url,
+                    creator: v.user_name,
+                })
+                .collect())
+        }
+        Creator::Youtube { channel_id } => {
+            let Some(api_key) = youtube_api_key else {
+                snafu::whatever!("YouTube API key is not configured");
+            };
+            // The uploads playlist of channel `UCxxx` is `UUxxx`
+            let Some(playlist_id) = channel_id.strip_prefix("UC").map(|id| format!("UU{id}"))
+            else {
+                snafu::whatever!("YouTube channel ID {channel_id} does not start with UC");
+            };
+            Ok(client
+                .get("https://www.googleapis.com/youtube/v3/playlistItems")
+                .query(&[
+                    ("part", "snippet"),
+                    ("playlistId", &playlist_id),
+                    ("maxResults", "5"),
+                    ("key", api_key),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<PlaylistItems>()
+                .await?
+                .items
+                .into_iter()
+                .map(|i| Upload {
+                    url: format!("https://youtu.be/{}", i.snippet.resource_id.video_id),
+                    id: i.snippet.resource_id.video_id,
+                    title: i.snippet.title,
+                    creator: i.snippet.channel_title,
+                })
+                .collect())
+        }
+    }
+}
+
+async fn check_creator(
+    ctx: &Context,
+    db: &BotDatabase,
+    client: &reqwest::Client,
+    twitch: Option<&Twitch>,
+    youtube_api_key: Option<&str>,
+    watch: &UploadWatch,
+) -> Result<(), BotError> {
+    let uploads = fetch(client, twitch, youtube_api_key, &watch.creator).await?;
+    let Some(latest) = uploads.first() else {
+        return Ok(());
+    };
+    let key = match &watch.creator {
+        Creator::Twitch { login } => format!("uploads:twitch:{login}"),
+        Creator::Youtube { channel_id } => format!("uploads:youtube:{channel_id}"),
+    };
+    let last_seen = db.watch().get(&key).await?;
+    if last_seen.as_deref() == Some(latest.id.as_str()) {
+        return Ok(());
+    }
+    db.watch().set(&key, &latest.id).await?;
+    let Some(last_seen) = last_seen else {
+        // First run, remember the current upload without announcing it
+        info!("Tracking uploads of {} from {}", latest.creator, latest.id);
+        return Ok(());
+    };
+    let new = uploads
+        .iter()
+        .take_while(|u| u.id != last_seen)
+        .collect::<Vec<_>>();
+    for upload in new.into_iter().rev() {
+        let mut content = watch.template.render(&[
+            ("creator", &upload.creator),
+            ("title", &upload.title),
+            ("url", &upload.url),
+        ]);
+        let mut mentions = CreateAllowedMentions::new();
+        if let Some(role_id) = watch.role_id {
+            content = format!("{} {content}", role_id.mention());
+            mentions = mentions.roles([role_id]);
+        }
+        watch
+            .channel_id
+            .send_message(
+                ctx,
+                CreateMessage::new()
+                    .content(content)
+                    .allowed_mentions(mentions),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ModuleHandler for UploadHandler {
+    fn module(&self) -> Module {
+        Module::Feeds
+    }
+
+    async fn start(&self, ctx: &Context) -> Vec<JoinHandle<()>> {
+        let ctx = ctx.to_owned();
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        let interval = cfg.load().upload_interval;
+        let twitch = cfg.load().twitch.to_owned().map(Twitch::new).map(Arc::new);
+        info!("Polling creator uploads every {}s", interval.as_secs());
+        let client = reqwest::Client::new();
+        vec![schedule::every(interval, move || {
+            let ctx = ctx.to_owned();
+            let cfg = cfg.load_full();
+            let client = client.to_owned();
+            let twitch = twitch.to_owned();
+            async move {
+                let db = match ctx.db().await {
+                    Ok(db) => db,
+                    Err(e) => return error!("Failed to get database: {e}"),
+                };
+                for watch in &cfg.uploads {
+                    if let Err(e) = check_creator(
+                        &ctx,
+                        &db,
+                        &client,
+                        twitch.as_deref(),
+                        cfg.youtube_api_key.as_deref(),
+                        watch,
+                    )
+                    .await
+                    {
+                        error!("Failed to check uploads of {:?}: {e}", watch.creator);
+                    }
+                }
+            }
+        })]
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    commands::uptime::{EndpointStatus, record},
+    config::{GetCfg, UptimeMonitor},
+    utils::{
+        alert::{Severity, alert},
+        schedule,
+    },
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+pub struct UptimeHandler {
+    started: AtomicBool,
+}
+
+#[async_trait]
+impl EventHandler for UptimeHandler {
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let cfg = ctx.cfg().await.expect("Failed to get bot configuration");
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client");
+        for monitor in cfg.load().uptime_monitors.iter().cloned() {
+            info!(
+                "Monitoring {} every {}s",
+                monitor.url,
+                monitor.interval.as_secs()
+            );
+            let ctx = ctx.to_owned();
+            let client = client.to_owned();
+            schedule::every(monitor.interval, move || {
+                let ctx = ctx.to_owned();
+                let client = client.to_owned();
+                let monitor = monitor.to_owned();
+                async move {
+                    let status = check(&client, &monitor).await;
+                    let previous = record(&monitor.name, status.to_owned());
+                    // Alert on transitions only, and stay quiet about the first check being up
+                    let changed = previous.map_or(!status.up, |p| p.up != status.up);
+                    if !changed {
+                        return;
+                    }
+                    let (severity, title) = if status.up {
+                        (Severity::Resolved, format!("{} 已恢复", monitor.name))
+                    } else {
+                        (Severity::Critical, format!("{} 无法访问", monitor.name))
+                    };
+                    let description = format!(
+                        "{}\n{}",
+                        monitor.url,
+                        status.reason.as_deref().unwrap_or("响应正常")
+                    );
+                    if let Err(e) = alert(&ctx, severity, title, description).await {
+                        error!("Failed to send uptime alert for {}: {e}", monitor.name);
+                    }
+                }
+            });
+        }
+    }
+}
+
+async fn check(client: &reqwest::Client, monitor: &UptimeMonitor) -> EndpointStatus {
+    let start = Instant::now();
+    let reason = async {
+        let res = client
+            .get(monitor.url.to_owned())
+            .send()
+            .await
+            .map_err(|e| format!("请求失败: {e}"))?;
+        if res.status().as_u16() != monitor.expected_status {
+            return Err(format!("状态码 {}", res.status().as_u16()));
+        }
+        if let Some(keyword) = &monitor.keyword {
+            let body = res.text().await.map_err(|e| format!("读取失败: {e}"))?;
+            if !body.contains(keyword.as_str()) {
+                return Err(format!("响应中缺少关键字 `{keyword}`"));
+            }
+        }
+        Ok(())
+    }
+    .await
+    .err();
+    EndpointStatus {
+        up: reason.is_none(),
+        response_time: start.elapsed(),
+        reason,
+        checked_at: Timestamp::now(),
+    }
+}
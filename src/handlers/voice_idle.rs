@@ -0,0 +1,129 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use serenity::all::*;
+use tracing::{error, info};
+
+use crate::{
+    config::GetCfg,
+    utils::{
+        alert::{Severity, guild_log},
+        schedule,
+    },
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Disconnects members who stay muted and deafened in voice longer than `voiceIdle.timeout`
+#[derive(Default)]
+pub struct VoiceIdleHandler {
+    started: AtomicBool,
+    /// Since when each member has been muted and deafened
+    idle: Arc<DashMap<(GuildId, UserId), Instant>>,
+}
+
+fn is_idle(state: &VoiceState) -> bool {
+    state.channel_id.is_some() && (state.self_mute || state.mute) && (state.self_deaf || state.deaf)
+}
+
+impl VoiceIdleHandler {
+    fn update(&self, guild_id: GuildId, state: &VoiceState) {
+        let key = (guild_id, state.user_id);
+        if is_idle(state) {
+            self.idle.entry(key).or_insert_with(Instant::now);
+        } else {
+            self.idle.remove(&key);
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for VoiceIdleHandler {
+    async fn voice_state_update(&self, _ctx: Context, _old: Option<VoiceState>, new: VoiceState) {
+        if let Some(guild_id) = new.guild_id {
+            self.update(guild_id, &new);
+        }
+    }
+
+    async fn cache_ready(&self, ctx: Context, guilds: Vec<GuildId>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // Members already idle when the bot starts count from now
+        for guild_id in guilds {
+            let Some(states) = ctx
+                .cache
+                .guild(guild_id)
+                .map(|g| g.voice_states.values().cloned().collect::<Vec<_>>())
+            else {
+                continue;
+            };
+            for state in states {
+                self.update(guild_id, &state);
+            }
+        }
+        info!(
+            "Checking for idle voice members every {}s",
+            CHECK_INTERVAL.as_secs()
+        );
+        let idle = self.idle.to_owned();
+        schedule::every(CHECK_INTERVAL, move || {
+            let ctx = ctx.to_owned();
+            let idle = idle.to_owned();
+            async move {
+                let cfg = ctx
+                    .cfg()
+                    .await
+                    .expect("Failed to get bot configuration")
+                    .load_full();
+                let expired = idle
+                    .iter()
+                    .filter_map(|entry| {
+                        let (guild_id, user_id) = *entry.key();
+                        let voice_idle = cfg.guilds.get(&guild_id)?.voice_idle.as_ref()?;
+                        (entry.value().elapsed() >= voice_idle.timeout)
+                            .then(|| (guild_id, user_id, voice_idle.to_owned()))
+                    })
+                    .collect::<Vec<_>>();
+                for (guild_id, user_id, voice_idle) in expired {
+                    idle.remove(&(guild_id, user_id));
+                    let exempt = ctx.cache.guild(guild_id).is_some_and(|g| {
+                        g.members.get(&user_id).is_some_and(|m| {
+                            m.roles
+                                .iter()
+                                .any(|r| voice_idle.exempt_role_ids.contains(r))
+                        })
+                    });
+                    if exempt {
+                        continue;
+                    }
+                    if let Err(e) = guild_id.disconnect_member(&ctx, user_id).await {
+                        error!("Failed to disconnect idle member {user_id} in {guild_id}: {e}");
+                        continue;
+                    }
+                    let minutes = voice_idle.timeout.as_secs() / 60;
+                    if let Err(e) = guild_log(
+                        &ctx,
+                        guild_id,
+                        Severity::Info,
+                        "已断开闲置语音成员",
+                        format!(
+                            "{} 闭麦且关闭扬声器超过 {minutes} 分钟。",
+                            user_id.mention()
+                        ),
+                    )
+                    .await
+                    {
+                        error!("Failed to log idle voice disconnect in {guild_id}: {e}");
+                    }
+                }
+            }
+        });
+    }
+}
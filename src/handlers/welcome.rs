@@ -0,0 +1,43 @@
+use serenity::all::*;
+use tracing::error;
+
+use crate::config::GetCfg;
+
+pub struct WelcomeHandler;
+
+#[async_trait]
+impl EventHandler for WelcomeHandler {
+    async fn guild_member_addition(&self, ctx: Context, member: Member) {
+        let cfg = ctx
+            .cfg()
+            .await
+            .expect("Failed to get bot configuration")
+            .load_full();
+        let Some(guild) = cfg.guilds.get(&member.guild_id) else {
+            return;
+        };
+        let (Some(channel_id), Some(template)) = (guild.welcome_channel_id, &guild.welcome_message)
+        else {
+            return; // Welcome messages are not set up in this guild
+        };
+        let (guild_name, count) = ctx
+            .cache
+            .guild(member.guild_id)
+            .map(|g| (g.name.to_owned(), g.member_count))
+            .unwrap_or_else(|| (member.guild_id.to_string(), 0));
+        let content = template.render(&[
+            ("user", &member.mention()),
+            ("guild", &guild_name),
+            ("count", &count),
+        ]);
+        let msg = CreateMessage::new()
+            .content(content)
+            .allowed_mentions(CreateAllowedMentions::new().users([member.user.id]));
+        if let Err(e) = channel_id.send_message(&ctx, msg).await {
+            error!(
+                "Failed to welcome {} in {}: {e}",
+                member.user.id, channel_id
+            );
+        }
+    }
+}
@@ -5,8 +5,10 @@ use chrono::{FixedOffset, Utc};
 use clap::Parser;
 use dc_bot::{
     Args, commands::framework, config::BotCfg, database::BotDatabase, error::BotError, handlers::*,
+    utils::crash,
 };
 use serenity::{Client, all::GatewayIntents};
+use tracing::info;
 use tracing_subscriber::{
     EnvFilter,
     fmt::{format::Writer, time::FormatTime},
@@ -31,9 +33,27 @@ impl FormatTime for TimeFormatter {
     }
 }
 
+/// Resolves on ctrl-c, or on SIGTERM as sent by `docker stop` and systemd.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c()
+        .await
+        .expect("Failed to listen for ctrl-c");
+}
+
 #[tokio::main]
 async fn main() -> Result<(), BotError> {
-    let cfg = BotCfg::read(&Args::parse().config)?;
+    let args = Args::parse();
+    let cfg = BotCfg::read(&args.config)?;
     tracing_subscriber::fmt()
         .with_env_filter(EnvFilter::from_default_env())
         .with_ansi(true)
@@ -44,9 +64,21 @@ async fn main() -> Result<(), BotError> {
 
     let intents = GatewayIntents::non_privileged() | GatewayIntents::privileged();
 
-    let db = BotDatabase::new(&Args::parse().db).await?;
+    let panic_file = args.db.with_extension("panic");
+    let panics = crash::capture_panics(panic_file.to_owned());
+
+    let db = BotDatabase::new(&args.db).await?;
+    let starts = match &cfg.crash_loop {
+        Some(crash_loop) => crash::record_start(&db, crash_loop.window).await?,
+        None => 0,
+    };
     let cfg = Arc::new(ArcSwap::from_pointee(cfg));
 
+    // Feed handlers have no events, their jobs are started by the module registry
+    MODULES.register(ReleaseHandler);
+    MODULES.register(PackageHandler);
+    MODULES.register(UploadHandler);
+
     let mut client = Client::builder(&cfg.load().token, intents)
         .cache_settings({
             let mut s = serenity::cache::Settings::default();
@@ -56,12 +88,62 @@ async fn main() -> Result<(), BotError> {
         .type_map_insert::<BotDatabase>(db.to_owned())
         .type_map_insert::<BotCfg>(cfg.to_owned())
         .event_handler(BootHandler)
+        .event_handler(ModulesHandler)
+        .event_handler(CrashLoopHandler::new(starts, panic_file))
+        .event_handler(PanicHandler::new(panics))
         .event_handler(CookieHandler)
         .event_handler(TreeHoleHandler::default())
         .event_handler(FlushHandler)
         .event_handler(ActiveHandler)
+        .event_handler(TriviaHandler::default())
+        .event_handler(PriceHandler::default())
+        .event_handler(GameTopicHandler::default())
+        .event_handler(KudosHandler::default())
+        .event_handler(MirrorHandler)
+        .event_handler(VoiceIdleHandler::default())
+        .event_handler(GoLiveHandler::default())
+        .event_handler(OfficeHoursHandler::default())
+        .event_handler(ChannelLabelHandler::default())
+        .event_handler(ApplicationHandler)
+        .event_handler(ChannelMuteHandler::default())
+        .event_handler(LinkScanHandler::default())
+        .event_handler(ReportHandler)
+        .event_handler(BanSyncHandler::default())
+        .event_handler_arc(MODULES.register(InviteFilterHandler))
+        .event_handler_arc(MODULES.register(LanguageHandler::default()))
+        .event_handler(ThreadNamingHandler::default())
+        .event_handler(ActivityRoleHandler::default())
+        .event_handler(DripHandler::default())
+        .event_handler(SuggestionHandler)
+        .event_handler(ReactionRuleHandler::default())
+        .event_handler(TopicRotationHandler::default())
+        .event_handler(TodoHandler::default())
+        .event_handler(GrowthHandler::default())
+        .event_handler(FaqHandler::default())
+        .event_handler(GameServerHandler::default())
+        .event_handler(BotStatusHandler::default())
+        .event_handler(DomainHandler::default())
+        .event_handler(UptimeHandler::default())
+        .event_handler(BackupHandler::default())
+        .event_handler(RatelimitHandler)
+        .event_handler(WelcomeHandler)
+        .event_handler(OnboardingHandler)
         .framework(framework(db, cfg))
         .await?;
+    client
+        .data
+        .write()
+        .await
+        .insert::<ShardManagerKey>(client.shard_manager.to_owned());
+
+    // Closing the shards makes `start` return, instead of the process dying mid-request. Tree
+    // hole deadlines are already in the database and are rescheduled on the next start.
+    let shard_manager = client.shard_manager.to_owned();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        info!("Shutting down");
+        shard_manager.shutdown_all().await;
+    });
 
     // Finally, start a single shard, and start listening to events.
     //
@@ -0,0 +1,138 @@
+use entities::applications::*;
+use sea_orm::{QueryOrder, Set, prelude::*};
+use serenity::all::*;
+use snafu::ResultExt;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type Application = Model;
+
+pub const PENDING: &str = "pending";
+pub const APPROVED: &str = "approved";
+pub const DENIED: &str = "denied";
+
+pub struct ApplicationRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the join applications
+    pub fn applications(&self) -> ApplicationRepo<'_> {
+        ApplicationRepo(self)
+    }
+}
+
+impl ApplicationRepo<'_> {
+    /// Record a pending application, returning its ID
+    pub async fn create(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        answers: &[(String, String)],
+    ) -> Result<i32, BotError> {
+        let application = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            user_id: Set(user_id.get() as i64),
+            answers: Set(serenity::json::to_string(answers)
+                .whatever_context::<&str, BotError>("Failed to serialize answers")?),
+            status: Set(PENDING.to_owned()),
+            created_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        };
+        Ok(Entity::insert(application)
+            .exec(self.0.inner())
+            .await?
+            .last_insert_id)
+    }
+
+    pub async fn get(&self, id: i32) -> Result<Option<Application>, BotError> {
+        Ok(Entity::find_by_id(id).one(self.0.inner()).await?)
+    }
+
+    /// Get the latest application of a user that is still awaiting review
+    pub async fn pending(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<Application>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .filter(Column::Status.eq(PENDING))
+            .order_by_desc(Column::Id)
+            .one(self.0.inner())
+            .await?)
+    }
+
+    /// Approve or deny a pending application. Returns false if it was already reviewed.
+    pub async fn review(
+        &self,
+        id: i32,
+        reviewer_id: UserId,
+        approved: bool,
+    ) -> Result<bool, BotError> {
+        let res = Entity::update_many()
+            .col_expr(
+                Column::Status,
+                Expr::value(if approved { APPROVED } else { DENIED }),
+            )
+            .col_expr(Column::ReviewerId, Expr::value(reviewer_id.get() as i64))
+            .col_expr(
+                Column::ReviewedAt,
+                Expr::value(DateTimeWithTimeZone::from(chrono::Utc::now())),
+            )
+            .filter(Column::Id.eq(id))
+            .filter(Column::Status.eq(PENDING))
+            .exec(self.0.inner())
+            .await?;
+        Ok(res.rows_affected == 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_review_once() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let guild_id = GuildId::new(456);
+        let user_id = UserId::new(1);
+        let answers = [("为什么加入?".to_string(), "学习".to_string())];
+        let id = db
+            .applications()
+            .create(guild_id, user_id, &answers)
+            .await
+            .unwrap();
+        let pending = db.applications().pending(guild_id, user_id).await.unwrap();
+        assert_eq!(pending.unwrap().answers(), answers);
+        assert!(
+            db.applications()
+                .review(id, UserId::new(2), true)
+                .await
+                .unwrap()
+        );
+        // A second moderator clicking at the same time loses
+        assert!(
+            !db.applications()
+                .review(id, UserId::new(3), false)
+                .await
+                .unwrap()
+        );
+        let application = db.applications().get(id).await.unwrap().unwrap();
+        assert_eq!(application.status, APPROVED);
+        assert_eq!(application.reviewer_id(), Some(UserId::new(2)));
+        assert!(
+            db.applications()
+                .pending(guild_id, user_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
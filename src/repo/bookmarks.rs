@@ -0,0 +1,110 @@
+use entities::bookmarks::*;
+use sea_orm::{PaginatorTrait, QueryOrder, QuerySelect, Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type Bookmark = Model;
+
+pub struct BookmarkRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the users' bookmarked messages
+    pub fn bookmarks(&self) -> BookmarkRepo<'_> {
+        BookmarkRepo(self)
+    }
+}
+
+impl BookmarkRepo<'_> {
+    /// Save a copy of a message for a user. Returns false if it was already bookmarked.
+    pub async fn add(&self, user_id: UserId, message: &Message) -> Result<bool, BotError> {
+        let bookmark = ActiveModel {
+            user_id: Set(user_id.get() as i64),
+            message_id: Set(message.id.get() as i64),
+            channel_id: Set(message.channel_id.get() as i64),
+            guild_id: Set(message.guild_id.map(|id| id.get() as i64)),
+            author_id: Set(message.author.id.get() as i64),
+            content: Set(message.content.to_owned()),
+            created_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        };
+        let res = Entity::insert(bookmark)
+            .on_conflict(
+                OnConflict::columns([Column::UserId, Column::MessageId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(self.0.inner())
+            .await?;
+        Ok(res == 1)
+    }
+
+    /// Get a page of a user's bookmarks, newest first
+    pub async fn list(
+        &self,
+        user_id: UserId,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<Bookmark>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .order_by_desc(Column::Id)
+            .limit(limit)
+            .offset(offset)
+            .all(self.0.inner())
+            .await?)
+    }
+
+    pub async fn count(&self, user_id: UserId) -> Result<u64, BotError> {
+        Ok(Entity::find()
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .count(self.0.inner())
+            .await?)
+    }
+
+    /// Delete one of a user's bookmarks. Returns false if they have no such bookmark.
+    pub async fn remove(&self, user_id: UserId, id: i32) -> Result<bool, BotError> {
+        let res = Entity::delete_many()
+            .filter(Column::Id.eq(id))
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .exec(self.0.inner())
+            .await?;
+        Ok(res.rows_affected == 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_bookmarks() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let (alice, bob) = (UserId::new(1), UserId::new(2));
+        let bookmarks = db.bookmarks();
+        let mut message = Message::default();
+        message.id = MessageId::new(100);
+        message.channel_id = ChannelId::new(10);
+        message.content = "hello".to_string();
+        assert!(bookmarks.add(alice, &message).await.unwrap());
+        assert!(!bookmarks.add(alice, &message).await.unwrap());
+        assert!(bookmarks.add(bob, &message).await.unwrap());
+        message.id = MessageId::new(101);
+        assert!(bookmarks.add(alice, &message).await.unwrap());
+        assert_eq!(bookmarks.count(alice).await.unwrap(), 2);
+        let page = bookmarks.list(alice, 1, 0).await.unwrap();
+        assert_eq!(page[0].message_id(), MessageId::new(101));
+        assert_eq!(page[0].content, "hello");
+        // Users can only remove their own bookmarks
+        assert!(!bookmarks.remove(bob, page[0].id).await.unwrap());
+        assert!(bookmarks.remove(alice, page[0].id).await.unwrap());
+        assert_eq!(bookmarks.count(alice).await.unwrap(), 1);
+    }
+}
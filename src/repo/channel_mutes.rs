@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use entities::channel_mutes::*;
+use sea_orm::{Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type ChannelMute = Model;
+
+pub struct ChannelMuteRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the timed channel mutes
+    pub fn channel_mutes(&self) -> ChannelMuteRepo<'_> {
+        ChannelMuteRepo(self)
+    }
+}
+
+impl ChannelMuteRepo<'_> {
+    /// Record a mute, replacing the expiry of an existing one
+    pub async fn add(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        user_id: UserId,
+        moderator_id: UserId,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), BotError> {
+        let mute = ActiveModel {
+            channel_id: Set(channel_id.get() as i64),
+            user_id: Set(user_id.get() as i64),
+            guild_id: Set(guild_id.get() as i64),
+            moderator_id: Set(moderator_id.get() as i64),
+            expires_at: Set(expires_at.into()),
+        };
+        Entity::insert(mute)
+            .on_conflict(
+                OnConflict::columns([Column::ChannelId, Column::UserId])
+                    .update_columns([Column::ModeratorId, Column::ExpiresAt])
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove(&self, channel_id: ChannelId, user_id: UserId) -> Result<(), BotError> {
+        Entity::delete_by_id((channel_id.get() as i64, user_id.get() as i64))
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Get the mutes that expired by `now`
+    pub async fn expired(&self, now: DateTime<Utc>) -> Result<Vec<ChannelMute>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::ExpiresAt.lte(DateTimeWithTimeZone::from(now)))
+            .all(self.0.inner())
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeDelta;
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_expired() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let (guild_id, channel_id, moderator) =
+            (GuildId::new(1), ChannelId::new(2), UserId::new(3));
+        let now = Utc::now();
+        let mutes = db.channel_mutes();
+        mutes
+            .add(
+                guild_id,
+                channel_id,
+                UserId::new(10),
+                moderator,
+                now - TimeDelta::minutes(1),
+            )
+            .await
+            .unwrap();
+        mutes
+            .add(
+                guild_id,
+                channel_id,
+                UserId::new(11),
+                moderator,
+                now + TimeDelta::hours(1),
+            )
+            .await
+            .unwrap();
+        let expired = mutes.expired(now).await.unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].user_id(), UserId::new(10));
+        // Muting again extends the mute
+        mutes
+            .add(
+                guild_id,
+                channel_id,
+                UserId::new(10),
+                moderator,
+                now + TimeDelta::hours(1),
+            )
+            .await
+            .unwrap();
+        assert!(mutes.expired(now).await.unwrap().is_empty());
+        mutes.remove(channel_id, UserId::new(11)).await.unwrap();
+        assert!(
+            mutes
+                .expired(now + TimeDelta::hours(2))
+                .await
+                .unwrap()
+                .len()
+                == 1
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use entities::drip_queue::*;
+use sea_orm::{QueryOrder, Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type DripMessage = Model;
+
+pub struct DripRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the scheduled onboarding DMs
+    pub fn drip(&self) -> DripRepo<'_> {
+        DripRepo(self)
+    }
+}
+
+impl DripRepo<'_> {
+    /// Queue the steps of a member's campaign, restarting it if they rejoin
+    pub async fn schedule(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        steps: impl IntoIterator<Item = DateTime<Utc>>,
+    ) -> Result<(), BotError> {
+        let messages = steps
+            .into_iter()
+            .enumerate()
+            .map(|(step, due_at)| ActiveModel {
+                guild_id: Set(guild_id.get() as i64),
+                user_id: Set(user_id.get() as i64),
+                step: Set(step as i32),
+                due_at: Set(due_at.into()),
+            })
+            .collect::<Vec<_>>();
+        if messages.is_empty() {
+            return Ok(());
+        }
+        Entity::insert_many(messages)
+            .on_conflict(
+                OnConflict::columns([Column::GuildId, Column::UserId, Column::Step])
+                    .update_column(Column::DueAt)
+                    .to_owned(),
+            )
+            .exec_without_returning(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Get the messages due by `now`, oldest first
+    pub async fn due(&self, now: DateTime<Utc>) -> Result<Vec<DripMessage>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::DueAt.lte(DateTimeWithTimeZone::from(now)))
+            .order_by_asc(Column::DueAt)
+            .all(self.0.inner())
+            .await?)
+    }
+
+    pub async fn done(&self, message: &DripMessage) -> Result<(), BotError> {
+        Entity::delete_by_id((message.guild_id, message.user_id, message.step))
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Drop the remaining messages of a member, returning how many were left
+    pub async fn cancel(&self, guild_id: GuildId, user_id: UserId) -> Result<u64, BotError> {
+        Ok(Entity::delete_many()
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .exec(self.0.inner())
+            .await?
+            .rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeDelta;
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_drip() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let guild_id = GuildId::new(456);
+        let (alice, bob) = (UserId::new(1), UserId::new(2));
+        let now = Utc::now();
+        let steps = [now, now + TimeDelta::days(1), now + TimeDelta::days(7)];
+        let drip = db.drip();
+        drip.schedule(guild_id, alice, steps).await.unwrap();
+        drip.schedule(guild_id, bob, steps).await.unwrap();
+        let due = drip.due(now).await.unwrap();
+        assert_eq!(due.len(), 2);
+        let first = due.iter().find(|m| m.user_id() == alice).unwrap();
+        drip.done(first).await.unwrap();
+        assert_eq!(drip.due(now + TimeDelta::days(1)).await.unwrap().len(), 3);
+        // Opting out drops the rest of the campaign
+        assert_eq!(drip.cancel(guild_id, alice).await.unwrap(), 2);
+        assert_eq!(drip.due(now + TimeDelta::days(7)).await.unwrap().len(), 3);
+    }
+}
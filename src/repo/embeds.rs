@@ -0,0 +1,95 @@
+use entities::posted_embeds::*;
+use sea_orm::{NotSet, QueryOrder, Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type PostedEmbed = Model;
+
+pub struct EmbedRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the embeds posted by the bot
+    pub fn embeds(&self) -> EmbedRepo<'_> {
+        EmbedRepo(self)
+    }
+}
+
+impl EmbedRepo<'_> {
+    /// Remember the source an embed message was built from
+    pub async fn track(
+        &self,
+        message: &Message,
+        guild_id: GuildId,
+        author_id: UserId,
+        source: String,
+    ) -> Result<(), BotError> {
+        let embed = ActiveModel {
+            message_id: Set(message.id.get() as i64),
+            channel_id: Set(message.channel_id.get() as i64),
+            guild_id: Set(guild_id.get() as i64),
+            author_id: Set(author_id.get() as i64),
+            source: Set(source),
+            updated_at: Set(chrono::Utc::now().into()),
+            board: NotSet,
+        };
+        Entity::insert(embed)
+            .on_conflict(
+                OnConflict::column(Column::MessageId)
+                    .update_columns([Column::AuthorId, Column::Source, Column::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Remember the message a board from `boards` was posted as
+    pub async fn track_board(
+        &self,
+        message: &Message,
+        guild_id: GuildId,
+        author_id: UserId,
+        board: &str,
+        source: String,
+    ) -> Result<(), BotError> {
+        let embed = ActiveModel {
+            message_id: Set(message.id.get() as i64),
+            channel_id: Set(message.channel_id.get() as i64),
+            guild_id: Set(guild_id.get() as i64),
+            author_id: Set(author_id.get() as i64),
+            source: Set(source),
+            updated_at: Set(chrono::Utc::now().into()),
+            board: Set(Some(board.to_owned())),
+        };
+        Entity::insert(embed)
+            .on_conflict(
+                OnConflict::column(Column::MessageId)
+                    .update_columns([
+                        Column::AuthorId,
+                        Column::Source,
+                        Column::UpdatedAt,
+                        Column::Board,
+                    ])
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Get the latest message a board was posted as
+    pub async fn board(&self, board: &str) -> Result<Option<PostedEmbed>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::Board.eq(board))
+            .order_by_desc(Column::UpdatedAt)
+            .one(self.0.inner())
+            .await?)
+    }
+
+    /// Get a tracked embed message
+    pub async fn get(&self, message_id: MessageId) -> Result<Option<PostedEmbed>, BotError> {
+        Ok(Entity::find_by_id(message_id.get() as i64)
+            .one(self.0.inner())
+            .await?)
+    }
+}
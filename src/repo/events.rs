@@ -0,0 +1,90 @@
+use entities::event_attendance::*;
+use sea_orm::{QuerySelect, Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub struct EventRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the attendance of scheduled events
+    pub fn events(&self) -> EventRepo<'_> {
+        EventRepo(self)
+    }
+}
+
+impl EventRepo<'_> {
+    /// Record that a user attended an event, once per event
+    pub async fn attend(
+        &self,
+        guild_id: GuildId,
+        event_id: ScheduledEventId,
+        user_id: UserId,
+    ) -> Result<(), BotError> {
+        let attendance = ActiveModel {
+            event_id: Set(event_id.get() as i64),
+            user_id: Set(user_id.get() as i64),
+            guild_id: Set(guild_id.get() as i64),
+            attended_at: Set(chrono::Utc::now().into()),
+        };
+        Entity::insert(attendance)
+            .on_conflict(
+                OnConflict::columns([Column::EventId, Column::UserId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Get the number of events each user of a guild attended
+    pub async fn attendance(&self, guild_id: GuildId) -> Result<Vec<(UserId, u64)>, BotError> {
+        Ok(Entity::find()
+            .select_only()
+            .column(Column::UserId)
+            .column_as(Column::EventId.count(), "events")
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .group_by(Column::UserId)
+            .into_tuple::<(i64, i64)>()
+            .all(self.0.inner())
+            .await?
+            .into_iter()
+            .map(|(user_id, count)| (UserId::new(user_id as u64), count as u64))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_attendance() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let guild_id = GuildId::new(456);
+        let (alice, bob) = (UserId::new(1), UserId::new(2));
+        let events = db.events();
+        for (event, user) in [(10, alice), (10, alice), (11, alice), (11, bob)] {
+            events
+                .attend(guild_id, ScheduledEventId::new(event), user)
+                .await
+                .unwrap();
+        }
+        events
+            .attend(GuildId::new(789), ScheduledEventId::new(12), bob)
+            .await
+            .unwrap();
+        let mut attendance = events.attendance(guild_id).await.unwrap();
+        attendance.sort();
+        // Rejoining the same event counts once
+        assert_eq!(attendance, [(alice, 2), (bob, 1)]);
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::Utc;
+use entities::{incident_notes, incidents::*};
+use sea_orm::{QueryOrder, Set, prelude::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type Incident = Model;
+pub type IncidentNote = incident_notes::Model;
+
+pub struct IncidentRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the incidents and their timelines
+    pub fn incidents(&self) -> IncidentRepo<'_> {
+        IncidentRepo(self)
+    }
+}
+
+impl IncidentRepo<'_> {
+    /// Record an open incident handled in `thread_id`, returning its ID
+    pub async fn create(
+        &self,
+        guild_id: GuildId,
+        thread_id: ChannelId,
+        title: &str,
+        opened_by: UserId,
+    ) -> Result<i32, BotError> {
+        let incident = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            thread_id: Set(thread_id.get() as i64),
+            title: Set(title.to_owned()),
+            opened_by: Set(opened_by.get() as i64),
+            created_at: Set(Utc::now().into()),
+            ..Default::default()
+        };
+        Ok(Entity::insert(incident)
+            .exec(self.0.inner())
+            .await?
+            .last_insert_id)
+    }
+
+    /// Remember the pinned status embed of an incident
+    pub async fn set_status_message(&self, id: i32, message_id: MessageId) -> Result<(), BotError> {
+        Entity::update_many()
+            .col_expr(
+                Column::StatusMessageId,
+                Expr::value(message_id.get() as i64),
+            )
+            .filter(Column::Id.eq(id))
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// The incident handled in a thread, if any
+    pub async fn by_thread(&self, thread_id: ChannelId) -> Result<Option<Incident>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::ThreadId.eq(thread_id.get() as i64))
+            .one(self.0.inner())
+            .await?)
+    }
+
+    /// Add an entry to the timeline of an incident
+    pub async fn add_note(
+        &self,
+        id: i32,
+        author_id: UserId,
+        content: &str,
+    ) -> Result<(), BotError> {
+        let note = incident_notes::ActiveModel {
+            incident_id: Set(id),
+            author_id: Set(author_id.get() as i64),
+            content: Set(content.to_owned()),
+            created_at: Set(Utc::now().into()),
+            ..Default::default()
+        };
+        incident_notes::Entity::insert(note)
+            .exec_without_returning(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// The timeline of an incident, oldest first
+    pub async fn notes(&self, id: i32) -> Result<Vec<IncidentNote>, BotError> {
+        Ok(incident_notes::Entity::find()
+            .filter(incident_notes::Column::IncidentId.eq(id))
+            .order_by_asc(incident_notes::Column::Id)
+            .all(self.0.inner())
+            .await?)
+    }
+
+    /// Close an open incident. Returns false if it was already closed.
+    pub async fn close(&self, id: i32) -> Result<bool, BotError> {
+        let res = Entity::update_many()
+            .col_expr(
+                Column::ClosedAt,
+                Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+            )
+            .filter(Column::Id.eq(id))
+            .filter(Column::ClosedAt.is_null())
+            .exec(self.0.inner())
+            .await?;
+        Ok(res.rows_affected == 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_incident_timeline() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let manager = SchemaManager::new(db.inner());
+        for migration in Migrator::migrations() {
+            migration.up(&manager).await.unwrap();
+        }
+        let thread_id = ChannelId::new(789);
+        let (alice, bob) = (UserId::new(1), UserId::new(2));
+        let incidents = db.incidents();
+        let id = incidents
+            .create(GuildId::new(456), thread_id, "Raid", alice)
+            .await
+            .unwrap();
+        incidents
+            .add_note(id, alice, "Locked channels")
+            .await
+            .unwrap();
+        incidents
+            .add_note(id, bob, "Banned 12 accounts")
+            .await
+            .unwrap();
+        let incident = incidents.by_thread(thread_id).await.unwrap().unwrap();
+        assert_eq!(incident.id, id);
+        let notes = incidents.notes(id).await.unwrap();
+        assert_eq!(
+            notes.iter().map(|n| n.author_id()).collect::<Vec<_>>(),
+            [alice, bob]
+        );
+        assert!(incidents.close(id).await.unwrap());
+        assert!(!incidents.close(id).await.unwrap());
+        assert!(
+            incidents
+                .by_thread(ChannelId::new(1))
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
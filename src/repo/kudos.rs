@@ -0,0 +1,133 @@
+use entities::kudos::*;
+use sea_orm::{QueryOrder, QuerySelect, Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type KudosCount = Model;
+
+pub struct KudosRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the monthly kudos counts
+    pub fn kudos(&self) -> KudosRepo<'_> {
+        KudosRepo(self)
+    }
+}
+
+impl KudosRepo<'_> {
+    /// Give a user one kudos in `month`, returning their new count
+    pub async fn give(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        month: &str,
+    ) -> Result<u64, BotError> {
+        let kudos = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            user_id: Set(user_id.get() as i64),
+            month: Set(month.to_owned()),
+            count: Set(1),
+        };
+        Entity::insert(kudos)
+            .on_conflict(
+                OnConflict::columns([Column::GuildId, Column::UserId, Column::Month])
+                    .value(Column::Count, Expr::col((Entity, Column::Count)).add(1))
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        self.count(guild_id, user_id, month).await
+    }
+
+    /// Take back one kudos, as when a reaction is removed
+    pub async fn take(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        month: &str,
+    ) -> Result<(), BotError> {
+        Entity::update_many()
+            .col_expr(Column::Count, Expr::col(Column::Count).sub(1))
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .filter(Column::UserId.eq(user_id.get() as i64))
+            .filter(Column::Month.eq(month))
+            .filter(Column::Count.gt(0))
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Get the kudos a user received in `month`
+    pub async fn count(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        month: &str,
+    ) -> Result<u64, BotError> {
+        Ok(Entity::find_by_id((
+            guild_id.get() as i64,
+            user_id.get() as i64,
+            month.to_owned(),
+        ))
+        .one(self.0.inner())
+        .await?
+        .map_or(0, |k| k.count()))
+    }
+
+    /// Get the users with the most kudos of a guild in `month`
+    pub async fn leaderboard(
+        &self,
+        guild_id: GuildId,
+        month: &str,
+        limit: u64,
+    ) -> Result<Vec<KudosCount>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .filter(Column::Month.eq(month))
+            .filter(Column::Count.gt(0))
+            .order_by_desc(Column::Count)
+            .limit(limit)
+            .all(self.0.inner())
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_kudos() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let guild_id = GuildId::new(456);
+        let alice = UserId::new(1);
+        let bob = UserId::new(2);
+        db.kudos().give(guild_id, alice, "2026-10").await.unwrap();
+        db.kudos().give(guild_id, bob, "2026-10").await.unwrap();
+        assert_eq!(
+            db.kudos().give(guild_id, alice, "2026-10").await.unwrap(),
+            2
+        );
+        db.kudos().give(guild_id, bob, "2026-09").await.unwrap();
+        db.kudos().take(guild_id, bob, "2026-10").await.unwrap();
+        // Counts never go below zero
+        db.kudos().take(guild_id, bob, "2026-10").await.unwrap();
+        let board = db
+            .kudos()
+            .leaderboard(guild_id, "2026-10", 10)
+            .await
+            .unwrap();
+        assert_eq!(board.len(), 1);
+        assert_eq!(board[0].user_id(), alice);
+        assert_eq!(board[0].count(), 2);
+        assert_eq!(db.kudos().count(guild_id, bob, "2026-09").await.unwrap(), 1);
+    }
+}
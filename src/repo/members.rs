@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use entities::member_snapshots::*;
+use sea_orm::{QueryOrder, Set, prelude::*, sea_query::*};
+use serenity::{all::*, json};
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type MemberSnapshot = Model;
+
+pub struct MembersRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the daily member counts and joins/leaves
+    pub fn members(&self) -> MembersRepo<'_> {
+        MembersRepo(self)
+    }
+}
+
+impl MembersRepo<'_> {
+    /// Count a member joining on `day`
+    pub async fn joined(&self, guild_id: GuildId, day: &str) -> Result<(), BotError> {
+        self.bump(guild_id, day, Column::Joins).await
+    }
+
+    /// Count a member leaving on `day`
+    pub async fn left(&self, guild_id: GuildId, day: &str) -> Result<(), BotError> {
+        self.bump(guild_id, day, Column::Leaves).await
+    }
+
+    async fn bump(&self, guild_id: GuildId, day: &str, column: Column) -> Result<(), BotError> {
+        let mut snapshot = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            day: Set(day.to_owned()),
+            joins: Set(0),
+            leaves: Set(0),
+            ..Default::default()
+        };
+        snapshot.set(column, 1i64.into());
+        Entity::insert(snapshot)
+            .on_conflict(
+                OnConflict::columns([Column::GuildId, Column::Day])
+                    .value(column, Expr::col((Entity, column)).add(1))
+                    .to_owned(),
+            )
+            .exec_without_returning(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Record the member count and role distribution of a guild at the end of `day`
+    pub async fn snapshot(
+        &self,
+        guild_id: GuildId,
+        day: &str,
+        member_count: u64,
+        roles: &HashMap<RoleId, u64>,
+    ) -> Result<(), BotError> {
+        let snapshot = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            day: Set(day.to_owned()),
+            joins: Set(0),
+            leaves: Set(0),
+            member_count: Set(Some(member_count as i64)),
+            roles: Set(Some(json::to_string(roles)?)),
+        };
+        Entity::insert(snapshot)
+            .on_conflict(
+                OnConflict::columns([Column::GuildId, Column::Day])
+                    .update_columns([Column::MemberCount, Column::Roles])
+                    .to_owned(),
+            )
+            .exec_without_returning(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Get the days of a guild from `since` on, oldest first
+    pub async fn since(
+        &self,
+        guild_id: GuildId,
+        since: &str,
+    ) -> Result<Vec<MemberSnapshot>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .filter(Column::Day.gte(since))
+            .order_by_asc(Column::Day)
+            .all(self.0.inner())
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_members() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let manager = SchemaManager::new(db.inner());
+        for migration in Migrator::migrations() {
+            migration.up(&manager).await.unwrap();
+        }
+        let members = db.members();
+        let guild_id = GuildId::new(1);
+        members.joined(guild_id, "2026-10-01").await.unwrap();
+        members.joined(guild_id, "2026-10-01").await.unwrap();
+        members.left(guild_id, "2026-10-01").await.unwrap();
+        let roles = HashMap::from([(RoleId::new(2), 5)]);
+        members
+            .snapshot(guild_id, "2026-10-01", 42, &roles)
+            .await
+            .unwrap();
+        members.left(guild_id, "2026-10-02").await.unwrap();
+        members.joined(guild_id, "2026-09-30").await.unwrap();
+
+        let days = members.since(guild_id, "2026-10-01").await.unwrap();
+        assert_eq!(days.len(), 2);
+        let (first, second) = (&days[0], &days[1]);
+        assert_eq!((first.joins, first.leaves), (2, 1));
+        assert_eq!(first.member_count(), Some(42));
+        assert_eq!(first.roles(), roles);
+        assert_eq!((second.joins, second.leaves), (0, 1));
+        assert_eq!(second.member_count(), None);
+    }
+}
@@ -114,6 +114,26 @@ impl MsgRepo<'_> {
             .collect())
     }
 
+    /// Get the author and time of every message in a guild since `from`
+    pub async fn get_activity(
+        &self,
+        guild_id: GuildId,
+        from: impl Into<DateTime<FixedOffset>>,
+    ) -> Result<Vec<(UserId, DateTime<FixedOffset>)>, BotError> {
+        Ok(Entity::find()
+            .select_only()
+            .column(Column::UserId)
+            .column(Column::Timestamp)
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .filter(Column::Timestamp.gte(from.into()))
+            .into_tuple::<(i64, DateTimeWithTimeZone)>()
+            .all(self.0.inner())
+            .await?
+            .into_iter()
+            .map(|(user_id, timestamp)| (UserId::new(user_id as u64), timestamp))
+            .collect())
+    }
+
     /// Get message records for a specific user in a guild
     pub async fn get_user_messages(
         &self,
@@ -1,2 +1,20 @@
+mod applications;
+mod bookmarks;
+mod channel_mutes;
+mod drip;
+mod embeds;
+mod events;
 mod flush;
+pub mod incidents;
+mod kudos;
+pub mod members;
 mod messages;
+pub mod prefs;
+mod quota;
+pub mod reports;
+mod store;
+pub mod suggestions;
+pub mod todos;
+mod tree_holes;
+mod trivia;
+mod watch;
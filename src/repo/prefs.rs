@@ -0,0 +1,162 @@
+use entities::user_prefs::*;
+use poise::ChoiceParameter;
+use sea_orm::{Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type UserPref = Model;
+
+/// Kinds of DMs the bot sends on its own, each of which users can turn off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ChoiceParameter)]
+pub enum DmKind {
+    #[name = "Onboarding messages"]
+    #[name_localized("zh-CN", "新成员引导消息")]
+    Drip,
+    #[name = "Suggestion updates"]
+    #[name_localized("zh-CN", "建议处理结果")]
+    Suggestions,
+}
+
+impl DmKind {
+    pub const ALL: [DmKind; 2] = [DmKind::Drip, DmKind::Suggestions];
+
+    /// The bit of this kind in `muted_dms`
+    fn bit(self) -> i64 {
+        1 << self as u8
+    }
+}
+
+pub struct PrefsRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the user preference table
+    pub fn prefs(&self) -> PrefsRepo<'_> {
+        PrefsRepo(self)
+    }
+}
+
+impl PrefsRepo<'_> {
+    /// Get the preferences of a user
+    pub async fn get(&self, user_id: UserId) -> Result<Option<UserPref>, BotError> {
+        Ok(Entity::find_by_id(user_id.get() as i64)
+            .one(self.0.inner())
+            .await?)
+    }
+
+    /// Set or clear the IANA timezone of a user
+    pub async fn set_timezone(
+        &self,
+        user_id: UserId,
+        timezone: Option<String>,
+    ) -> Result<(), BotError> {
+        let pref = ActiveModel {
+            user_id: Set(user_id.get() as i64),
+            timezone: Set(timezone),
+            ..Default::default()
+        };
+        Entity::insert(pref)
+            .on_conflict(
+                OnConflict::column(Column::UserId)
+                    .update_column(Column::Timezone)
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Set the default weather location of a user
+    pub async fn set_weather_location(
+        &self,
+        user_id: UserId,
+        location: Option<String>,
+    ) -> Result<(), BotError> {
+        let pref = ActiveModel {
+            user_id: Set(user_id.get() as i64),
+            weather_location: Set(location),
+            ..Default::default()
+        };
+        Entity::insert(pref)
+            .on_conflict(
+                OnConflict::column(Column::UserId)
+                    .update_column(Column::WeatherLocation)
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Whether a user accepts DMs of `kind`, which they do unless muted
+    pub async fn allows_dm(&self, user_id: UserId, kind: DmKind) -> Result<bool, BotError> {
+        Ok(self
+            .get(user_id)
+            .await?
+            .is_none_or(|p| p.muted_dms & kind.bit() == 0))
+    }
+
+    /// Mute or unmute DMs of `kind` for a user
+    pub async fn set_dm(
+        &self,
+        user_id: UserId,
+        kind: DmKind,
+        allowed: bool,
+    ) -> Result<(), BotError> {
+        let muted = self.get(user_id).await?.map_or(0, |p| p.muted_dms);
+        let muted = if allowed {
+            muted & !kind.bit()
+        } else {
+            muted | kind.bit()
+        };
+        let pref = ActiveModel {
+            user_id: Set(user_id.get() as i64),
+            muted_dms: Set(muted),
+            ..Default::default()
+        };
+        Entity::insert(pref)
+            .on_conflict(
+                OnConflict::column(Column::UserId)
+                    .update_column(Column::MutedDms)
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dm_prefs() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let manager = SchemaManager::new(db.inner());
+        for migration in Migrator::migrations() {
+            migration.up(&manager).await.unwrap();
+        }
+        let user_id = UserId::new(1);
+        let prefs = db.prefs();
+        assert!(prefs.allows_dm(user_id, DmKind::Drip).await.unwrap());
+        prefs.set_dm(user_id, DmKind::Drip, false).await.unwrap();
+        prefs
+            .set_dm(user_id, DmKind::Suggestions, false)
+            .await
+            .unwrap();
+        prefs
+            .set_dm(user_id, DmKind::Suggestions, true)
+            .await
+            .unwrap();
+        assert!(!prefs.allows_dm(user_id, DmKind::Drip).await.unwrap());
+        assert!(prefs.allows_dm(user_id, DmKind::Suggestions).await.unwrap());
+        // Other preferences leave the muted kinds alone
+        prefs
+            .set_timezone(user_id, Some("Asia/Shanghai".to_owned()))
+            .await
+            .unwrap();
+        assert!(!prefs.allows_dm(user_id, DmKind::Drip).await.unwrap());
+    }
+}
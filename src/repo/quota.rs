@@ -0,0 +1,92 @@
+use entities::quota_usage::*;
+use sea_orm::{Set, prelude::*, sea_query::*};
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub struct QuotaRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the quota usage counters
+    pub fn quota(&self) -> QuotaRepo<'_> {
+        QuotaRepo(self)
+    }
+}
+
+impl QuotaRepo<'_> {
+    /// Count one use of `module` by `subject` in the window starting at `window_start`,
+    /// unless `limit` uses were already counted. Returns whether the use is allowed.
+    pub async fn consume(
+        &self,
+        module: &str,
+        subject: &str,
+        window_start: i64,
+        limit: u64,
+    ) -> Result<bool, BotError> {
+        if limit == 0 {
+            return Ok(false);
+        }
+        let usage = ActiveModel {
+            module: Set(module.to_owned()),
+            subject: Set(subject.to_owned()),
+            window_start: Set(window_start),
+            count: Set(1),
+        };
+        // Checked and counted in one statement, so concurrent uses can't both take the last one
+        let counted = Entity::insert(usage)
+            .on_conflict(
+                OnConflict::columns([Column::Module, Column::Subject, Column::WindowStart])
+                    .value(Column::Count, Expr::col((Entity, Column::Count)).add(1))
+                    .action_and_where(Expr::col((Entity, Column::Count)).lt(limit as i64))
+                    .to_owned(),
+            )
+            .exec_without_returning(self.0.inner())
+            .await?;
+        if counted == 0 {
+            return Ok(false);
+        }
+        // Counters of windows that already ended are never read again
+        Entity::delete_many()
+            .filter(Column::Module.eq(module))
+            .filter(Column::Subject.eq(subject))
+            .filter(Column::WindowStart.lt(window_start))
+            .exec(self.0.inner())
+            .await?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_consume_quota() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let manager = SchemaManager::new(db.inner());
+        for migration in Migrator::migrations() {
+            migration.up(&manager).await.unwrap();
+        }
+        let quota = db.quota();
+        assert!(quota.consume("run", "user:1", 0, 2).await.unwrap());
+        assert!(quota.consume("run", "user:1", 0, 2).await.unwrap());
+        assert!(!quota.consume("run", "user:1", 0, 2).await.unwrap());
+        // Other subjects and later windows have their own budget
+        assert!(quota.consume("run", "user:2", 0, 2).await.unwrap());
+        assert!(quota.consume("run", "user:1", 3600, 2).await.unwrap());
+        assert!(!quota.consume("run", "user:3", 0, 0).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_consume_quota_concurrently() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let manager = SchemaManager::new(db.inner());
+        for migration in Migrator::migrations() {
+            migration.up(&manager).await.unwrap();
+        }
+        let quota = db.quota();
+        let uses =
+            futures::future::join_all((0..10).map(|_| quota.consume("run", "user:1", 0, 3))).await;
+        assert_eq!(uses.into_iter().filter(|u| *u.as_ref().unwrap()).count(), 3);
+    }
+}
@@ -0,0 +1,136 @@
+use entities::reports::*;
+use sea_orm::{Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type Report = Model;
+
+pub const OPEN: &str = "open";
+pub const DELETED: &str = "deleted";
+pub const WARNED: &str = "warned";
+pub const DISMISSED: &str = "dismissed";
+
+pub struct ReportRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the message reports
+    pub fn reports(&self) -> ReportRepo<'_> {
+        ReportRepo(self)
+    }
+}
+
+impl ReportRepo<'_> {
+    /// Record an open report of `message`, returning its ID, or None if the reporter has
+    /// already reported it
+    pub async fn create(
+        &self,
+        guild_id: GuildId,
+        message: &Message,
+        reporter_id: UserId,
+        reason: &str,
+    ) -> Result<Option<i32>, BotError> {
+        let report = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            channel_id: Set(message.channel_id.get() as i64),
+            message_id: Set(message.id.get() as i64),
+            author_id: Set(message.author.id.get() as i64),
+            reporter_id: Set(reporter_id.get() as i64),
+            reason: Set(reason.to_owned()),
+            status: Set(OPEN.to_owned()),
+            created_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        };
+        let res = Entity::insert(report)
+            .on_conflict(
+                OnConflict::columns([Column::MessageId, Column::ReporterId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await;
+        match res {
+            Ok(res) => Ok(Some(res.last_insert_id)),
+            Err(DbErr::RecordNotInserted) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn get(&self, id: i32) -> Result<Option<Report>, BotError> {
+        Ok(Entity::find_by_id(id).one(self.0.inner()).await?)
+    }
+
+    /// Close an open report with `status`. Returns false if it was already handled.
+    pub async fn resolve(
+        &self,
+        id: i32,
+        handler_id: UserId,
+        status: &str,
+    ) -> Result<bool, BotError> {
+        let res = Entity::update_many()
+            .col_expr(Column::Status, Expr::value(status))
+            .col_expr(Column::HandlerId, Expr::value(handler_id.get() as i64))
+            .col_expr(
+                Column::HandledAt,
+                Expr::value(DateTimeWithTimeZone::from(chrono::Utc::now())),
+            )
+            .filter(Column::Id.eq(id))
+            .filter(Column::Status.eq(OPEN))
+            .exec(self.0.inner())
+            .await?;
+        Ok(res.rows_affected == 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_report_once() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let guild_id = GuildId::new(456);
+        let mut message = Message::default();
+        message.id = MessageId::new(100);
+        message.author.id = UserId::new(9);
+        let reports = db.reports();
+        let id = reports
+            .create(guild_id, &message, UserId::new(1), "spam")
+            .await
+            .unwrap()
+            .unwrap();
+        // Reporting the same message twice is ignored, others may still report it
+        assert!(
+            reports
+                .create(guild_id, &message, UserId::new(1), "spam")
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            reports
+                .create(guild_id, &message, UserId::new(2), "ads")
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(reports.resolve(id, UserId::new(3), DELETED).await.unwrap());
+        assert!(
+            !reports
+                .resolve(id, UserId::new(4), DISMISSED)
+                .await
+                .unwrap()
+        );
+        let report = reports.get(id).await.unwrap().unwrap();
+        assert_eq!(report.status, DELETED);
+        assert_eq!(report.author_id(), UserId::new(9));
+        assert_eq!(report.handler_id(), Some(UserId::new(3)));
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::{DateTime, Utc};
+use entities::kv_store::*;
+use sea_orm::{Condition, Set, prelude::*, sea_query::*};
+use serde::{Serialize, de::DeserializeOwned};
+use serenity::json;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub struct StoreRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the key-value store shared by modules without tables of their own
+    pub fn store(&self) -> StoreRepo<'_> {
+        StoreRepo(self)
+    }
+}
+
+impl StoreRepo<'_> {
+    /// Get the value under `key` of a namespace, unless it has expired
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        namespace: &str,
+        key: &str,
+    ) -> Result<Option<T>, BotError> {
+        let Some(entry) = Entity::find_by_id((namespace.to_owned(), key.to_owned()))
+            .one(self.0.inner())
+            .await?
+        else {
+            return Ok(None);
+        };
+        if entry.expires_at.is_some_and(|at| at <= Utc::now()) {
+            return Ok(None);
+        }
+        Ok(Some(json::from_str(&entry.value)?))
+    }
+
+    /// Store `value` under `key` of a namespace, replacing what was there. Entries with an
+    /// expiry are dropped once it passes; expired entries of the namespace are purged on write.
+    pub async fn put<T: Serialize>(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: &T,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<(), BotError> {
+        self.purge_expired(namespace).await?;
+        let entry = ActiveModel {
+            namespace: Set(namespace.to_owned()),
+            key: Set(key.to_owned()),
+            value: Set(json::to_string(value)?),
+            expires_at: Set(expires_at.map(Into::into)),
+        };
+        Entity::insert(entry)
+            .on_conflict(
+                OnConflict::columns([Column::Namespace, Column::Key])
+                    .update_columns([Column::Value, Column::ExpiresAt])
+                    .to_owned(),
+            )
+            .exec_without_returning(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn remove(&self, namespace: &str, key: &str) -> Result<(), BotError> {
+        Entity::delete_by_id((namespace.to_owned(), key.to_owned()))
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Drop every entry of a namespace, returning how many there were
+    pub async fn purge(&self, namespace: &str) -> Result<u64, BotError> {
+        Ok(Entity::delete_many()
+            .filter(Column::Namespace.eq(namespace))
+            .exec(self.0.inner())
+            .await?
+            .rows_affected)
+    }
+
+    async fn purge_expired(&self, namespace: &str) -> Result<(), BotError> {
+        Entity::delete_many()
+            .filter(
+                Condition::all()
+                    .add(Column::Namespace.eq(namespace))
+                    .add(Column::ExpiresAt.lte(DateTimeWithTimeZone::from(Utc::now()))),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeDelta;
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_store() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let manager = SchemaManager::new(db.inner());
+        for migration in Migrator::migrations() {
+            migration.up(&manager).await.unwrap();
+        }
+        let store = db.store();
+        let past = Utc::now() - TimeDelta::seconds(1);
+        store.put("afk", "1", &"lunch", None).await.unwrap();
+        store.put("afk", "2", &"gone", Some(past)).await.unwrap();
+        store
+            .put("counter", "1", &vec![1u32, 2], None)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get::<String>("afk", "1").await.unwrap().as_deref(),
+            Some("lunch")
+        );
+        assert!(store.get::<String>("afk", "2").await.unwrap().is_none());
+        // Namespaces keep the same keys apart
+        assert_eq!(
+            store.get::<Vec<u32>>("counter", "1").await.unwrap(),
+            Some(vec![1, 2])
+        );
+        // The expired entry was purged by the last write to its namespace
+        store.put("afk", "3", &"back soon", None).await.unwrap();
+        assert_eq!(store.purge("afk").await.unwrap(), 2);
+        store.remove("counter", "1").await.unwrap();
+        assert!(
+            store
+                .get::<Vec<u32>>("counter", "1")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use entities::{suggestion_votes, suggestions::*};
+use sea_orm::{PaginatorTrait, Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type Suggestion = Model;
+
+pub const OPEN: &str = "open";
+pub const APPROVED: &str = "approved";
+pub const DENIED: &str = "denied";
+pub const IMPLEMENTED: &str = "implemented";
+
+pub struct SuggestionRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the suggestions and their votes
+    pub fn suggestions(&self) -> SuggestionRepo<'_> {
+        SuggestionRepo(self)
+    }
+}
+
+impl SuggestionRepo<'_> {
+    /// Record an open suggestion, returning its ID
+    pub async fn create(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        author_id: UserId,
+        content: &str,
+    ) -> Result<i32, BotError> {
+        let suggestion = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            channel_id: Set(channel_id.get() as i64),
+            author_id: Set(author_id.get() as i64),
+            content: Set(content.to_owned()),
+            status: Set(OPEN.to_owned()),
+            created_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        };
+        Ok(Entity::insert(suggestion)
+            .exec(self.0.inner())
+            .await?
+            .last_insert_id)
+    }
+
+    /// Remember the bot's message showing a suggestion
+    pub async fn set_message(&self, id: i32, message_id: MessageId) -> Result<(), BotError> {
+        Entity::update_many()
+            .col_expr(Column::MessageId, Expr::value(message_id.get() as i64))
+            .filter(Column::Id.eq(id))
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, id: i32) -> Result<Option<Suggestion>, BotError> {
+        Ok(Entity::find_by_id(id).one(self.0.inner()).await?)
+    }
+
+    /// Change the status of a suggestion. Returns false if there is no such suggestion in the guild.
+    pub async fn set_status(
+        &self,
+        guild_id: GuildId,
+        id: i32,
+        status: &str,
+        handler_id: UserId,
+        note: Option<&str>,
+    ) -> Result<bool, BotError> {
+        let res = Entity::update_many()
+            .col_expr(Column::Status, Expr::value(status))
+            .col_expr(Column::HandlerId, Expr::value(handler_id.get() as i64))
+            .col_expr(Column::Note, Expr::value(note.map(str::to_owned)))
+            .filter(Column::Id.eq(id))
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .exec(self.0.inner())
+            .await?;
+        Ok(res.rows_affected == 1)
+    }
+
+    /// Vote on a suggestion, taking the vote back if it is cast again. Returns the new tally.
+    pub async fn vote(&self, id: i32, user_id: UserId, up: bool) -> Result<(u64, u64), BotError> {
+        use suggestion_votes::{ActiveModel, Column, Entity};
+        let key = (id, user_id.get() as i64);
+        match Entity::find_by_id(key).one(self.0.inner()).await? {
+            Some(vote) if vote.up == up => {
+                Entity::delete_by_id(key).exec(self.0.inner()).await?;
+            }
+            _ => {
+                let vote = ActiveModel {
+                    suggestion_id: Set(id),
+                    user_id: Set(user_id.get() as i64),
+                    up: Set(up),
+                };
+                Entity::insert(vote)
+                    .on_conflict(
+                        OnConflict::columns([Column::SuggestionId, Column::UserId])
+                            .update_column(Column::Up)
+                            .to_owned(),
+                    )
+                    .exec_without_returning(self.0.inner())
+                    .await?;
+            }
+        }
+        self.tally(id).await
+    }
+
+    /// Get the up and down votes of a suggestion
+    pub async fn tally(&self, id: i32) -> Result<(u64, u64), BotError> {
+        use suggestion_votes::{Column, Entity};
+        let count = |up: bool| {
+            Entity::find()
+                .filter(Column::SuggestionId.eq(id))
+                .filter(Column::Up.eq(up))
+                .count(self.0.inner())
+        };
+        Ok((count(true).await?, count(false).await?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_votes() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let guild_id = GuildId::new(456);
+        let suggestions = db.suggestions();
+        let id = suggestions
+            .create(guild_id, ChannelId::new(1), UserId::new(1), "加个音乐频道")
+            .await
+            .unwrap();
+        assert_eq!(
+            suggestions.vote(id, UserId::new(2), true).await.unwrap(),
+            (1, 0)
+        );
+        assert_eq!(
+            suggestions.vote(id, UserId::new(3), false).await.unwrap(),
+            (1, 1)
+        );
+        // Switching sides moves the vote, voting again takes it back
+        assert_eq!(
+            suggestions.vote(id, UserId::new(3), true).await.unwrap(),
+            (2, 0)
+        );
+        assert_eq!(
+            suggestions.vote(id, UserId::new(2), true).await.unwrap(),
+            (1, 0)
+        );
+        assert!(
+            !suggestions
+                .set_status(GuildId::new(789), id, APPROVED, UserId::new(9), None)
+                .await
+                .unwrap()
+        );
+        assert!(
+            suggestions
+                .set_status(guild_id, id, APPROVED, UserId::new(9), Some("好主意"))
+                .await
+                .unwrap()
+        );
+        let suggestion = suggestions.get(id).await.unwrap().unwrap();
+        assert_eq!(suggestion.status, APPROVED);
+        assert_eq!(suggestion.note.as_deref(), Some("好主意"));
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use entities::todos::*;
+use sea_orm::{QueryOrder, Set, prelude::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type Todo = Model;
+
+pub struct TodoRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the task lists of the mod teams
+    pub fn todos(&self) -> TodoRepo<'_> {
+        TodoRepo(self)
+    }
+}
+
+impl TodoRepo<'_> {
+    /// Add an open task, returning its ID
+    pub async fn add(
+        &self,
+        guild_id: GuildId,
+        content: &str,
+        assignee_id: Option<UserId>,
+        created_by: UserId,
+        due_at: Option<DateTime<Utc>>,
+    ) -> Result<i32, BotError> {
+        let todo = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            content: Set(content.to_owned()),
+            assignee_id: Set(assignee_id.map(|id| id.get() as i64)),
+            created_by: Set(created_by.get() as i64),
+            due_at: Set(due_at.map(Into::into)),
+            created_at: Set(Utc::now().into()),
+            ..Default::default()
+        };
+        Ok(Entity::insert(todo)
+            .exec(self.0.inner())
+            .await?
+            .last_insert_id)
+    }
+
+    /// Close an open task of a guild. Returns false if there is no such open task.
+    pub async fn done(&self, guild_id: GuildId, id: i32) -> Result<bool, BotError> {
+        let res = Entity::update_many()
+            .col_expr(
+                Column::DoneAt,
+                Expr::value(DateTimeWithTimeZone::from(Utc::now())),
+            )
+            .filter(Column::Id.eq(id))
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .filter(Column::DoneAt.is_null())
+            .exec(self.0.inner())
+            .await?;
+        Ok(res.rows_affected == 1)
+    }
+
+    /// Open tasks of a guild, those due soonest first
+    pub async fn open(&self, guild_id: GuildId) -> Result<Vec<Todo>, BotError> {
+        let mut todos = Entity::find()
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .filter(Column::DoneAt.is_null())
+            .order_by_asc(Column::Id)
+            .all(self.0.inner())
+            .await?;
+        todos.sort_by_key(|t| (t.due_at.is_none(), t.due_at));
+        Ok(todos)
+    }
+
+    /// Open tasks due by `now` whose assignees were not reminded yet
+    pub async fn due(&self, now: DateTime<Utc>) -> Result<Vec<Todo>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::DoneAt.is_null())
+            .filter(Column::Reminded.eq(false))
+            .filter(Column::DueAt.lte(now))
+            .all(self.0.inner())
+            .await?)
+    }
+
+    pub async fn set_reminded(&self, id: i32) -> Result<(), BotError> {
+        Entity::update_many()
+            .col_expr(Column::Reminded, Expr::value(true))
+            .filter(Column::Id.eq(id))
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeDelta;
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_todos() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let manager = SchemaManager::new(db.inner());
+        for migration in Migrator::migrations() {
+            migration.up(&manager).await.unwrap();
+        }
+        let guild_id = GuildId::new(456);
+        let (alice, bob) = (UserId::new(1), UserId::new(2));
+        let now = Utc::now();
+        let todos = db.todos();
+        let later = todos
+            .add(guild_id, "Update rules", None, alice, None)
+            .await
+            .unwrap();
+        let soon = todos
+            .add(guild_id, "Plan event", Some(bob), alice, Some(now))
+            .await
+            .unwrap();
+        let open = todos.open(guild_id).await.unwrap();
+        assert_eq!(open.iter().map(|t| t.id).collect::<Vec<_>>(), [soon, later]);
+        let due = todos.due(now + TimeDelta::minutes(1)).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].assignee_id(), Some(bob));
+        todos.set_reminded(soon).await.unwrap();
+        assert!(todos.due(now).await.unwrap().is_empty());
+        assert!(todos.done(guild_id, later).await.unwrap());
+        assert!(!todos.done(guild_id, later).await.unwrap());
+        assert!(!todos.done(GuildId::new(1), soon).await.unwrap());
+        assert_eq!(todos.open(guild_id).await.unwrap().len(), 1);
+    }
+}
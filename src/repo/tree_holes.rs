@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use entities::tree_hole_deletions::*;
+use sea_orm::{Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type TreeHoleDeletion = Model;
+
+/// Message IDs removed per statement by [`TreeHoleRepo::remove_many`]
+const REMOVE_BATCH: usize = 500;
+
+pub struct TreeHoleRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the pending deletions of tree hole messages
+    pub fn tree_holes(&self) -> TreeHoleRepo<'_> {
+        TreeHoleRepo(self)
+    }
+}
+
+impl TreeHoleRepo<'_> {
+    /// Remember when a message is due for deletion, keeping the first time if already known
+    pub async fn schedule(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        delete_at: DateTime<Utc>,
+    ) -> Result<(), BotError> {
+        let deletion = ActiveModel {
+            message_id: Set(message_id.get() as i64),
+            channel_id: Set(channel_id.get() as i64),
+            delete_at: Set(delete_at.into()),
+        };
+        Entity::insert(deletion)
+            .on_conflict(
+                OnConflict::column(Column::MessageId)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, message_id: MessageId) -> Result<Option<TreeHoleDeletion>, BotError> {
+        Ok(Entity::find_by_id(message_id.get() as i64)
+            .one(self.0.inner())
+            .await?)
+    }
+
+    /// Move the deadline of a pending deletion, returning whether there was one
+    pub async fn postpone(
+        &self,
+        message_id: MessageId,
+        delete_at: DateTime<Utc>,
+    ) -> Result<bool, BotError> {
+        let updated = Entity::update_many()
+            .col_expr(
+                Column::DeleteAt,
+                Expr::value(DateTimeWithTimeZone::from(delete_at)),
+            )
+            .filter(Column::MessageId.eq(message_id.get() as i64))
+            .exec(self.0.inner())
+            .await?;
+        Ok(updated.rows_affected > 0)
+    }
+
+    /// All pending deletions, to be rescheduled after a restart
+    pub async fn pending(&self) -> Result<Vec<TreeHoleDeletion>, BotError> {
+        Ok(Entity::find().all(self.0.inner()).await?)
+    }
+
+    /// Forget a deletion because it happened or the message was pinned or removed by hand
+    pub async fn remove(&self, message_id: MessageId) -> Result<(), BotError> {
+        Entity::delete_by_id(message_id.get() as i64)
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Forget many deletions at once, e.g. of a purged channel
+    pub async fn remove_many(&self, message_ids: &[MessageId]) -> Result<(), BotError> {
+        // Stay well under SQLite's limit on bound parameters per statement
+        for chunk in message_ids.chunks(REMOVE_BATCH) {
+            Entity::delete_many()
+                .filter(Column::MessageId.is_in(chunk.iter().map(|id| id.get() as i64)))
+                .exec(self.0.inner())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeDelta;
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_tree_hole_deletions() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let manager = SchemaManager::new(db.inner());
+        for migration in Migrator::migrations() {
+            migration.up(&manager).await.unwrap();
+        }
+        let channel_id = ChannelId::new(456);
+        let (first, second) = (MessageId::new(1), MessageId::new(2));
+        let now = Utc::now();
+        let tree_holes = db.tree_holes();
+        tree_holes.schedule(channel_id, first, now).await.unwrap();
+        tree_holes
+            .schedule(channel_id, second, now + TimeDelta::hours(1))
+            .await
+            .unwrap();
+        // Rescans after a restart must not push an expiry back
+        tree_holes
+            .schedule(channel_id, first, now + TimeDelta::hours(2))
+            .await
+            .unwrap();
+        let pending = tree_holes.pending().await.unwrap();
+        assert_eq!(pending.len(), 2);
+        let deletion = pending.iter().find(|d| d.message_id() == first).unwrap();
+        assert_eq!(deletion.delete_at.timestamp(), now.timestamp());
+        let later = now + TimeDelta::days(7);
+        assert!(tree_holes.postpone(second, later).await.unwrap());
+        let deletion = tree_holes.get(second).await.unwrap().unwrap();
+        assert_eq!(deletion.delete_at.timestamp(), later.timestamp());
+        tree_holes.remove(first).await.unwrap();
+        assert!(!tree_holes.postpone(first, later).await.unwrap());
+        assert_eq!(tree_holes.pending().await.unwrap().len(), 1);
+        let many = (10..1200).map(MessageId::new).collect::<Vec<_>>();
+        for &message_id in &many {
+            tree_holes
+                .schedule(channel_id, message_id, now)
+                .await
+                .unwrap();
+        }
+        tree_holes.remove_many(&many).await.unwrap();
+        let pending = tree_holes.pending().await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].message_id(), second);
+    }
+}
@@ -0,0 +1,91 @@
+use entities::trivia_scores::*;
+use sea_orm::{QueryOrder, QuerySelect, Set, prelude::*, sea_query::*};
+use serenity::all::*;
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub type TriviaScore = Model;
+
+pub struct TriviaRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the trivia score table
+    pub fn trivia(&self) -> TriviaRepo<'_> {
+        TriviaRepo(self)
+    }
+}
+
+impl TriviaRepo<'_> {
+    /// Add points to a user's score in a guild
+    pub async fn add(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        points: u64,
+    ) -> Result<(), BotError> {
+        let score = ActiveModel {
+            guild_id: Set(guild_id.get() as i64),
+            user_id: Set(user_id.get() as i64),
+            score: Set(points as i64),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+        Entity::insert(score)
+            .on_conflict(
+                OnConflict::columns([Column::GuildId, Column::UserId])
+                    .value(
+                        Column::Score,
+                        Expr::col((Entity, Column::Score))
+                            .add(Expr::col((Alias::new("excluded"), Column::Score))),
+                    )
+                    .update_column(Column::UpdatedAt)
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+
+    /// Get the top scorers of a guild
+    pub async fn leaderboard(
+        &self,
+        guild_id: GuildId,
+        limit: u64,
+    ) -> Result<Vec<TriviaScore>, BotError> {
+        Ok(Entity::find()
+            .filter(Column::GuildId.eq(guild_id.get() as i64))
+            .order_by_desc(Column::Score)
+            .limit(limit)
+            .all(self.0.inner())
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use migration::{Migrator, MigratorTrait, SchemaManager};
+
+    use super::*;
+    use crate::database::BotDatabase;
+
+    #[tokio::test]
+    async fn test_add_score() {
+        let db = BotDatabase::new_memory().await.unwrap();
+        let migrations = Migrator::migrations();
+        let manager = SchemaManager::new(db.inner());
+        for migration in migrations {
+            migration.up(&manager).await.unwrap();
+        }
+        let guild_id = GuildId::new(456);
+        let alice = UserId::new(1);
+        let bob = UserId::new(2);
+        db.trivia().add(guild_id, alice, 2).await.unwrap();
+        db.trivia().add(guild_id, bob, 1).await.unwrap();
+        db.trivia().add(guild_id, alice, 3).await.unwrap();
+        db.trivia().add(GuildId::new(789), bob, 10).await.unwrap();
+        let board = db.trivia().leaderboard(guild_id, 10).await.unwrap();
+        assert_eq!(board.len(), 2);
+        assert_eq!(board[0].user_id(), alice);
+        assert_eq!(board[0].score(), 5);
+        assert_eq!(board[1].user_id(), bob);
+        assert_eq!(board[1].score(), 1);
+    }
+}
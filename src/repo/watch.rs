@@ -0,0 +1,40 @@
+use entities::watch_state::*;
+use sea_orm::{Set, prelude::*, sea_query::*};
+
+use crate::{database::BotDatabase, error::BotError};
+
+pub struct WatchRepo<'a>(&'a BotDatabase);
+impl BotDatabase {
+    /// Get a reference to the last seen values of the watchers
+    pub fn watch(&self) -> WatchRepo<'_> {
+        WatchRepo(self)
+    }
+}
+
+impl WatchRepo<'_> {
+    /// Get the last value seen under `key`
+    pub async fn get(&self, key: &str) -> Result<Option<String>, BotError> {
+        Ok(Entity::find_by_id(key)
+            .one(self.0.inner())
+            .await?
+            .map(|m| m.value))
+    }
+
+    /// Remember `value` as the last value seen under `key`
+    pub async fn set(&self, key: &str, value: &str) -> Result<(), BotError> {
+        let state = ActiveModel {
+            key: Set(key.to_owned()),
+            value: Set(value.to_owned()),
+            updated_at: Set(chrono::Utc::now().into()),
+        };
+        Entity::insert(state)
+            .on_conflict(
+                OnConflict::column(Column::Key)
+                    .update_columns([Column::Value, Column::UpdatedAt])
+                    .to_owned(),
+            )
+            .exec(self.0.inner())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use serenity::all::{
+    colours::{
+        branding::BLURPLE,
+        css::{DANGER, POSITIVE, WARNING},
+    },
+    *,
+};
+use tracing::warn;
+
+use crate::{config::GetCfg, error::BotError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Resolved,
+    Warning,
+    /// Also pings the ops role
+    Critical,
+}
+
+impl Severity {
+    fn style(self) -> (&'static str, Colour) {
+        match self {
+            Self::Info => ("ℹ️", BLURPLE),
+            Self::Resolved => ("✅", POSITIVE),
+            Self::Warning => ("⚠️", WARNING),
+            Self::Critical => ("🚨", DANGER),
+        }
+    }
+}
+
+/// Post an alert to the configured ops channel, logging it instead if there is none.
+pub async fn alert(
+    ctx: &Context,
+    severity: Severity,
+    title: impl AsRef<str>,
+    description: impl Into<String>,
+) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?.load_full();
+    let description = description.into();
+    let Some(channel_id) = cfg.ops_channel_id else {
+        warn!("{}: {description}", title.as_ref());
+        return Ok(());
+    };
+    let (emoji, colour) = severity.style();
+    let mut msg = CreateMessage::new().embed(
+        CreateEmbed::new()
+            .title(format!("{emoji} {}", title.as_ref()))
+            .description(description)
+            .colour(colour)
+            .timestamp(Timestamp::now()),
+    );
+    if severity == Severity::Critical
+        && let Some(role_id) = cfg.ops_role_id
+    {
+        msg = msg
+            .content(role_id.mention().to_string())
+            .allowed_mentions(CreateAllowedMentions::new().roles([role_id]));
+    }
+    channel_id.send_message(ctx, msg).await?;
+    Ok(())
+}
+
+/// Post an event to a guild's log channel, if it has one.
+pub async fn guild_log(
+    ctx: &Context,
+    guild_id: GuildId,
+    severity: Severity,
+    title: impl AsRef<str>,
+    description: impl Into<String>,
+) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?.load_full();
+    let Some(channel_id) = cfg.guilds.get(&guild_id).and_then(|g| g.log_channel_id) else {
+        return Ok(());
+    };
+    let (emoji, colour) = severity.style();
+    let msg = CreateMessage::new()
+        .embed(
+            CreateEmbed::new()
+                .title(format!("{emoji} {}", title.as_ref()))
+                .description(description)
+                .colour(colour)
+                .timestamp(Timestamp::now()),
+        )
+        .allowed_mentions(CreateAllowedMentions::new());
+    channel_id.send_message(ctx, msg).await?;
+    Ok(())
+}
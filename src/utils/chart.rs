@@ -0,0 +1,408 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Write,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use flate2::{Compression, write::ZlibEncoder};
+
+/// Colours shared by every chart, matching Discord's dark theme
+pub mod theme {
+    pub const BACKGROUND: [u8; 3] = [0x2B, 0x2D, 0x31];
+    pub const GRID: [u8; 3] = [0x3F, 0x41, 0x47];
+    pub const AXIS: [u8; 3] = [0x80, 0x84, 0x8E];
+    pub const TEXT: [u8; 3] = [0xDB, 0xDE, 0xE1];
+    pub const POSITIVE: [u8; 3] = [0x57, 0xF2, 0x87];
+    pub const NEGATIVE: [u8; 3] = [0xED, 0x42, 0x45];
+    pub const ACCENT: [u8; 3] = [0x58, 0x65, 0xF2];
+}
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 300;
+const PADDING: u32 = 20;
+/// Room left of the plot for the scale, above it for the legend and below it for the x labels
+const LEFT: u32 = 80;
+const TOP: u32 = 40;
+const BOTTOM: u32 = 36;
+/// At most this many x labels are drawn, spread evenly, so they never overlap
+const MAX_X_LABELS: usize = 6;
+
+/// How long a rendered chart is reused for the same data, e.g. when a command is run repeatedly
+const CACHE_TTL: Duration = Duration::from_secs(600);
+
+pub(crate) static CACHE: LazyLock<DashMap<u64, (Instant, Vec<u8>)>> = LazyLock::new(DashMap::new);
+
+/// Render a chart unless one was recently rendered from the same `key`.
+fn cached(key: impl Hash, render: impl FnOnce() -> Vec<u8>) -> Vec<u8> {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    let key = hasher.finish();
+    if let Some(entry) = CACHE.get(&key).filter(|e| e.0.elapsed() < CACHE_TTL) {
+        return entry.1.to_owned();
+    }
+    CACHE.retain(|_, e| e.0.elapsed() < CACHE_TTL);
+    let png = render();
+    CACHE.insert(key, (Instant::now(), png.to_owned()));
+    png
+}
+
+/// The plot area of a chart, with its legend, scale and x labels drawn around it
+struct Frame {
+    canvas: Canvas,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    slot: f64,
+    bar: u32,
+}
+
+impl Frame {
+    fn new(bars: usize, legend: &[(&str, [u8; 3])]) -> Self {
+        let mut canvas = Canvas::new(WIDTH, HEIGHT, theme::BACKGROUND);
+        let mut x = LEFT;
+        for &(name, colour) in legend {
+            canvas.fill(x, PADDING / 2 + 1, GLYPH_HEIGHT, GLYPH_HEIGHT, colour);
+            x += GLYPH_HEIGHT + GLYPH_SCALE * 2;
+            canvas.text(x, PADDING / 2 + 1, name, theme::TEXT);
+            x += Canvas::text_width(name) + PADDING;
+        }
+        let width = WIDTH - LEFT - PADDING;
+        let slot = width as f64 / bars.max(1) as f64;
+        Self {
+            canvas,
+            x: LEFT,
+            y: TOP,
+            width,
+            height: HEIGHT - TOP - BOTTOM,
+            slot,
+            bar: ((slot * 0.7) as u32).max(1),
+        }
+    }
+
+    /// Left edge of the `i`th bar
+    fn bar_x(&self, i: usize) -> u32 {
+        self.x + (i as f64 * self.slot) as u32
+    }
+
+    /// A gridline across the plot at `y` with its value on the scale to the left
+    fn mark(&mut self, y: u32, value: &str, colour: [u8; 3]) {
+        self.canvas.fill(self.x, y, self.width, 1, colour);
+        let x = (self.x - PADDING / 2).saturating_sub(Canvas::text_width(value));
+        let y = y
+            .saturating_sub(GLYPH_HEIGHT / 2)
+            .min(HEIGHT - GLYPH_HEIGHT);
+        self.canvas.text(x, y, value, theme::TEXT);
+    }
+
+    /// Labels under the bars they belong to, skipping some when there are too many
+    fn x_labels(&mut self, labels: &[String]) {
+        let y = self.y + self.height + PADDING / 2;
+        for i in label_indices(labels.len(), MAX_X_LABELS) {
+            let width = Canvas::text_width(&labels[i]);
+            let centre = self.bar_x(i) + self.bar / 2;
+            let x = centre
+                .saturating_sub(width / 2)
+                .min(WIDTH - PADDING / 2 - width);
+            self.canvas.text(x, y, &labels[i], theme::TEXT);
+        }
+    }
+}
+
+/// Up to `max` indices out of `len`, evenly spaced and always including the first and last
+fn label_indices(len: usize, max: usize) -> Vec<usize> {
+    match (len, max) {
+        (0, _) | (_, 0) => vec![],
+        (1, _) | (_, 1) => vec![0],
+        _ if len <= max => (0..len).collect(),
+        _ => (0..max).map(|i| i * (len - 1) / (max - 1)).collect(),
+    }
+}
+
+/// Pairs of counts as bars above and below an axis, with a marker at their difference, e.g.
+/// joins and leaves per day. `labels` name each pair along the x axis and `legend` names the
+/// upper bars, lower bars and difference markers.
+pub fn diverging_bars(series: &[(u64, u64)], labels: &[String], legend: [&str; 3]) -> Vec<u8> {
+    cached(("diverging_bars", series, labels, legend), || {
+        let mut frame = Frame::new(
+            series.len(),
+            &[
+                (legend[0], theme::POSITIVE),
+                (legend[1], theme::NEGATIVE),
+                (legend[2], theme::ACCENT),
+            ],
+        );
+        let max_up = series.iter().map(|d| d.0).max().unwrap_or(0);
+        let max_down = series.iter().map(|d| d.1).max().unwrap_or(0);
+        let scale = frame.height as f64 / (max_up + max_down).max(1) as f64;
+        let axis = frame.y + (max_up as f64 * scale) as u32;
+        if max_up > 0 {
+            frame.mark(frame.y, &max_up.to_string(), theme::GRID);
+        }
+        if max_down > 0 {
+            frame.mark(frame.y + frame.height, &format!("-{max_down}"), theme::GRID);
+        }
+        for (i, &(up, down)) in series.iter().enumerate() {
+            let x = frame.bar_x(i);
+            let above = (up as f64 * scale) as u32;
+            let bar = frame.bar;
+            frame
+                .canvas
+                .fill(x, axis - above, bar, above, theme::POSITIVE);
+            frame
+                .canvas
+                .fill(x, axis, bar, (down as f64 * scale) as u32, theme::NEGATIVE);
+            let net = (axis as f64 - (up as f64 - down as f64) * scale) as u32;
+            frame
+                .canvas
+                .fill(x, net.saturating_sub(1), bar, 3, theme::ACCENT);
+        }
+        frame.mark(axis, "0", theme::AXIS);
+        frame.x_labels(labels);
+        frame.canvas.png()
+    })
+}
+
+/// Values as columns scaled to the tallest, failed ones drawn full height in the warning
+/// colour, e.g. response times of health checks. `labels` name each value along the x axis,
+/// `unit` follows the values on the scale and `legend` names the ok and failed columns.
+pub fn columns(
+    series: &[(u64, bool)],
+    labels: &[String],
+    unit: &str,
+    legend: [&str; 2],
+) -> Vec<u8> {
+    cached(("columns", series, labels, unit, legend), || {
+        let mut frame = Frame::new(
+            series.len(),
+            &[(legend[0], theme::ACCENT), (legend[1], theme::NEGATIVE)],
+        );
+        let max = series.iter().map(|d| d.0).max().unwrap_or(0).max(1);
+        let (top, height) = (frame.y, frame.height);
+        let axis = top + height;
+        frame.mark(top, &format!("{max}{unit}"), theme::GRID);
+        frame.mark(top + height / 2, &format!("{}{unit}", max / 2), theme::GRID);
+        for (i, &(value, ok)) in series.iter().enumerate() {
+            let x = frame.bar_x(i);
+            let bar = frame.bar;
+            if ok {
+                let up = (value as f64 / max as f64 * height as f64) as u32;
+                frame.canvas.fill(x, axis - up, bar, up, theme::ACCENT);
+            } else {
+                frame.canvas.fill(x, top, bar, height, theme::NEGATIVE);
+            }
+        }
+        frame.mark(axis, &format!("0{unit}"), theme::AXIS);
+        frame.x_labels(labels);
+        frame.canvas.png()
+    })
+}
+
+/// Pixels per font pixel, so the 5x7 glyphs stay legible once Discord scales the image down
+const GLYPH_SCALE: u32 = 2;
+const GLYPH_WIDTH: u32 = 5 * GLYPH_SCALE;
+const GLYPH_HEIGHT: u32 = 7 * GLYPH_SCALE;
+
+/// Rows of a 5x7 bitmap glyph, most significant of the low five bits on the left. Only the
+/// characters charts need are covered, anything else is drawn as a blank.
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        '/' => [0x01, 0x01, 0x02, 0x04, 0x08, 0x10, 0x10],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        _ => [0; 7],
+    }
+}
+
+/// An RGB raster for simple charts, encoded as PNG to be sent as an attachment
+pub struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, background: [u8; 3]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Fill a rectangle, clipping whatever lies outside the canvas
+    pub fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, colour: [u8; 3]) {
+        for row in y..(y + height).min(self.height) {
+            for col in x..(x + width).min(self.width) {
+                self.pixels[(row * self.width + col) as usize] = colour;
+            }
+        }
+    }
+
+    /// Width of `text` as drawn by [`Canvas::text`]
+    pub fn text_width(text: &str) -> u32 {
+        (text.chars().count() as u32 * (GLYPH_WIDTH + GLYPH_SCALE)).saturating_sub(GLYPH_SCALE)
+    }
+
+    /// Draw `text` with its top left corner at `x`, `y` in the built-in bitmap font
+    pub fn text(&mut self, x: u32, y: u32, text: &str, colour: [u8; 3]) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i as u32 * (GLYPH_WIDTH + GLYPH_SCALE);
+            for (row, bits) in glyph(c).into_iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0x10 >> col) != 0 {
+                        self.fill(
+                            left + col * GLYPH_SCALE,
+                            y + row as u32 * GLYPH_SCALE,
+                            GLYPH_SCALE,
+                            GLYPH_SCALE,
+                            colour,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) as usize * self.height as usize);
+        for row in self.pixels.chunks(self.width as usize) {
+            raw.push(0); // No filter
+            raw.extend(row.iter().flatten());
+        }
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder
+            .write_all(&raw)
+            .expect("Writing to a Vec never fails");
+        let data = encoder.finish().expect("Writing to a Vec never fails");
+
+        let mut header = vec![];
+        header.extend(self.width.to_be_bytes());
+        header.extend(self.height.to_be_bytes());
+        // 8 bit RGB, default compression and filtering, no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut png, b"IHDR", &header);
+        chunk(&mut png, b"IDAT", &data);
+        chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32fast::hash(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use flate2::read::ZlibDecoder;
+
+    use super::*;
+
+    #[test]
+    fn test_png() {
+        let mut canvas = Canvas::new(3, 2, [0, 0, 0]);
+        canvas.fill(1, 1, 5, 5, [255, 0, 0]);
+        let png = canvas.png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 3, 0, 0, 0, 2]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+
+        let len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let mut raw = vec![];
+        ZlibDecoder::new(&png[41..41 + len])
+            .read_to_end(&mut raw)
+            .unwrap();
+        // Each row starts with its filter type
+        let rows = [[0; 10], [0, 0, 0, 0, 255, 0, 0, 255, 0, 0]];
+        assert_eq!(raw, rows.concat());
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(Canvas::text_width(""), 0);
+        assert_eq!(Canvas::text_width("10"), 2 * GLYPH_WIDTH + GLYPH_SCALE);
+        let mut canvas = Canvas::new(GLYPH_WIDTH, GLYPH_HEIGHT, [0, 0, 0]);
+        canvas.text(0, 0, "-", [255, 255, 255]);
+        let lit = canvas
+            .pixels
+            .iter()
+            .filter(|p| **p == [255, 255, 255])
+            .count();
+        assert_eq!(lit as u32, GLYPH_WIDTH * GLYPH_SCALE);
+        assert!(canvas.pixels[(3 * GLYPH_SCALE * GLYPH_WIDTH) as usize] == [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_label_indices() {
+        assert_eq!(label_indices(0, 6), Vec::<usize>::new());
+        assert_eq!(label_indices(1, 6), [0]);
+        assert_eq!(label_indices(4, 6), [0, 1, 2, 3]);
+        assert_eq!(label_indices(7, 6), [0, 1, 2, 3, 4, 6]);
+        assert_eq!(label_indices(30, 6), [0, 5, 11, 17, 23, 29]);
+    }
+
+    #[test]
+    fn test_cached() {
+        let mut renders = 0;
+        for _ in 0..2 {
+            cached(("test_cached", 1), || {
+                renders += 1;
+                vec![]
+            });
+        }
+        cached(("test_cached", 2), || {
+            renders += 1;
+            vec![]
+        });
+        assert_eq!(renders, 2);
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    backtrace::Backtrace,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+use crate::{database::BotDatabase, error::BotError};
+
+const NAMESPACE: &str = "crash_loop";
+const STARTS: &str = "starts";
+const PANICS: &str = "panics";
+/// How long recorded panics are kept
+const PANIC_RETENTION: TimeDelta = TimeDelta::days(30);
+
+tokio::task_local! {
+    /// Name of the task being polled, for panic reports
+    static TASK: &'static str;
+}
+
+/// A panic as captured by the hook of `capture_panics`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PanicReport {
+    pub at: DateTime<Utc>,
+    /// The task set by `named`, or else the thread that panicked
+    pub task: String,
+    /// Payload and location of the panic
+    pub message: String,
+    pub backtrace: String,
+}
+
+/// Panics waiting for the bot to record and report them
+static REPORTS: Mutex<Option<UnboundedSender<PanicReport>>> = Mutex::new(None);
+
+/// Run a future as a named task, so panics in it are reported with the name.
+pub async fn named<F: Future>(task: &'static str, fut: F) -> F::Output {
+    TASK.scope(task, fut).await
+}
+
+/// The message of a panic payload, which is a string unless the panic was raised with another
+/// value through `panic_any`
+fn payload_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
+/// Capture every panic before the default hook runs: write it to `path`, so the next start can
+/// report the last one even if the process died with it, and queue it for the returned receiver.
+pub fn capture_panics(path: PathBuf) -> UnboundedReceiver<PanicReport> {
+    let (sender, receiver) = unbounded_channel();
+    *REPORTS.lock().unwrap() = Some(sender);
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let at = Utc::now();
+        if let Err(e) = std::fs::write(&path, format!("{} {info}", at.to_rfc3339())) {
+            eprintln!("Failed to write panic to {}: {e}", path.display());
+        }
+        let task = TASK.try_with(|task| task.to_string()).unwrap_or_else(|_| {
+            let thread = std::thread::current();
+            thread.name().unwrap_or("unnamed thread").to_owned()
+        });
+        let mut message = payload_message(info.payload()).to_owned();
+        if let Some(location) = info.location() {
+            message = format!("{message} ({location})");
+        }
+        let report = PanicReport {
+            at,
+            task,
+            message,
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+        // Poisoned if the panic came from within this very lock, nothing to report to then
+        if let Ok(reports) = REPORTS.lock()
+            && let Some(sender) = reports.as_ref()
+        {
+            let _ = sender.send(report);
+        }
+        default(info);
+    }));
+    receiver
+}
+
+/// Keep a panic in the key-value store for a while.
+pub async fn record_panic(db: &BotDatabase, report: &PanicReport) -> Result<(), BotError> {
+    let key = report.at.to_rfc3339();
+    db.store()
+        .put(PANICS, &key, report, Some(report.at + PANIC_RETENTION))
+        .await
+}
+
+/// The last panic captured by `capture_panics`, from this or an earlier run, if it happened
+/// within `window` of now
+pub fn last_panic(path: &Path, window: Duration) -> Option<String> {
+    let panic = std::fs::read_to_string(path).ok()?;
+    is_recent(&panic, Utc::now(), window).then_some(panic)
+}
+
+/// Whether a panic as written by `capture_panics` happened within `window` of `now`
+fn is_recent(panic: &str, now: DateTime<Utc>, window: Duration) -> bool {
+    let at = panic.split_once(' ').map_or(panic, |(at, _)| at);
+    DateTime::parse_from_rfc3339(at)
+        .is_ok_and(|at| now - at.to_utc() < TimeDelta::from_std(window).unwrap())
+}
+
+/// Forget the last panic once it has been reported.
+pub fn clear_last_panic(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Starts within `window` of `now`, the current one included
+fn recent(
+    mut starts: Vec<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    window: Duration,
+) -> Vec<DateTime<Utc>> {
+    let window = TimeDelta::from_std(window).unwrap();
+    starts.retain(|start| now - *start < window);
+    starts.push(now);
+    starts
+}
+
+/// Persist the start of this process, returning how many starts there were within `window`.
+pub async fn record_start(db: &BotDatabase, window: Duration) -> Result<usize, BotError> {
+    let starts = db.store().get(NAMESPACE, STARTS).await?.unwrap_or_default();
+    let starts = recent(starts, Utc::now(), window);
+    db.store().put(NAMESPACE, STARTS, &starts, None).await?;
+    Ok(starts.len())
+}
+
+/// Forget the recorded starts once a crash loop has been reported.
+pub async fn clear_starts(db: &BotDatabase) -> Result<(), BotError> {
+    db.store().remove(NAMESPACE, STARTS).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_payload_message() {
+        assert_eq!(payload_message(&"static"), "static");
+        assert_eq!(payload_message(&"owned".to_owned()), "owned");
+        assert_eq!(payload_message(&42), "Box<dyn Any>");
+    }
+
+    #[test]
+    fn test_is_recent() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let panic = |at: DateTime<Utc>| format!("{} panicked at src/main.rs:1:1", at.to_rfc3339());
+        let window = Duration::from_secs(600);
+        assert!(is_recent(&panic(now - TimeDelta::minutes(5)), now, window));
+        assert!(!is_recent(&panic(now - TimeDelta::days(90)), now, window));
+        assert!(!is_recent("garbage", now, window));
+    }
+
+    #[test]
+    fn test_recent() {
+        let now = DateTime::from_timestamp(1_800_000_000, 0).unwrap();
+        let minutes = |m| now - TimeDelta::minutes(m);
+        let window = Duration::from_secs(600);
+        assert_eq!(
+            recent(vec![minutes(30), minutes(9), minutes(1)], now, window),
+            [minutes(9), minutes(1), now]
+        );
+        assert_eq!(recent(vec![], now, window), [now]);
+    }
+}
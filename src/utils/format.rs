@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+/// An integer with thousands separators, e.g. `1,234,567`
+pub fn number(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if n < 0 {
+        grouped.push('-');
+    }
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// A size in binary units, e.g. `1.5 GiB`
+pub fn bytes(n: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB"];
+    if n < 1024 {
+        return format!("{n} B");
+    }
+    let mut size = n as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    format!("{size:.1} {unit}")
+}
+
+/// `part` as a share of `whole`, e.g. `12.5%`
+pub fn percent(part: f64, whole: f64) -> String {
+    if whole == 0.0 {
+        return "0.0%".to_owned();
+    }
+    format!("{:.1}%", part / whole * 100.0)
+}
+
+/// A duration to the minute in its two largest units, e.g. `2 天 1 小时` or `3 小时 2 分钟`
+pub fn duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{days} 天 {hours} 小时")
+    } else if hours > 0 {
+        format!("{hours} 小时 {minutes} 分钟")
+    } else {
+        format!("{minutes} 分钟")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_number() {
+        assert_eq!(number(0), "0");
+        assert_eq!(number(999), "999");
+        assert_eq!(number(1000), "1,000");
+        assert_eq!(number(1_234_567), "1,234,567");
+        assert_eq!(number(-12_345), "-12,345");
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(bytes(512), "512 B");
+        assert_eq!(bytes(1536), "1.5 KiB");
+        assert_eq!(bytes(200 * 1024 * 1024), "200.0 MiB");
+        assert_eq!(bytes(3 * 1024 * 1024 * 1024 / 2), "1.5 GiB");
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration(Duration::from_secs(59)), "0 分钟");
+        assert_eq!(
+            duration(Duration::from_secs(3 * 3600 + 120)),
+            "3 小时 2 分钟"
+        );
+        assert_eq!(
+            duration(Duration::from_secs(2 * 86400 + 3600)),
+            "2 天 1 小时"
+        );
+    }
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(1.0, 8.0), "12.5%");
+        assert_eq!(percent(1.0, 0.0), "0.0%");
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    io::{self, ErrorKind},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+    time::timeout,
+};
+
+use crate::{
+    config::{GameServerCfg, ServerKind},
+    error::BotError,
+};
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What a game server reports about itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerInfo {
+    pub name: String,
+    pub players: u32,
+    pub max_players: u32,
+    /// Game version for Minecraft, current map for Source servers
+    pub detail: String,
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+async fn with_timeout<T>(fut: impl Future<Output = io::Result<T>>) -> io::Result<T> {
+    timeout(QUERY_TIMEOUT, fut)
+        .await
+        .map_err(|_| io::Error::new(ErrorKind::TimedOut, "Server did not answer in time"))?
+}
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7F | 0x80) as u8);
+        value >>= 7;
+    }
+}
+
+async fn read_varint(reader: &mut (impl AsyncRead + Unpin)) -> io::Result<i32> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let byte = reader.read_u8().await?;
+        value |= ((byte & 0x7F) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err(invalid("VarInt is too long"))
+}
+
+/// Query a Minecraft Java server through the Server List Ping protocol.
+pub async fn minecraft(address: &str) -> Result<ServerInfo, BotError> {
+    #[derive(Deserialize)]
+    struct Status {
+        version: Version,
+        players: Players,
+        description: Description,
+    }
+    #[derive(Deserialize)]
+    struct Version {
+        name: String,
+    }
+    #[derive(Deserialize)]
+    struct Players {
+        max: u32,
+        online: u32,
+    }
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Description {
+        Plain(String),
+        Chat {
+            #[serde(default)]
+            text: String,
+            #[serde(default)]
+            extra: Vec<Description>,
+        },
+    }
+    impl Description {
+        fn flatten(&self, out: &mut String) {
+            match self {
+                Self::Plain(text) => out.push_str(text),
+                Self::Chat { text, extra } => {
+                    out.push_str(text);
+                    extra.iter().for_each(|e| e.flatten(out));
+                }
+            }
+        }
+    }
+
+    let (host, port) = address.rsplit_once(':').unwrap_or((address, "25565"));
+    let port = port
+        .parse::<u16>()
+        .map_err(|_| invalid("Invalid server port"))?;
+    let json = with_timeout(async {
+        let mut stream = TcpStream::connect((host, port)).await?;
+        let mut handshake = vec![0x00];
+        write_varint(&mut handshake, -1); // Protocol version is irrelevant for status
+        write_varint(&mut handshake, host.len() as i32);
+        handshake.extend_from_slice(host.as_bytes());
+        handshake.extend_from_slice(&port.to_be_bytes());
+        write_varint(&mut handshake, 1); // Next state: status
+        let mut packet = vec![];
+        write_varint(&mut packet, handshake.len() as i32);
+        packet.extend(handshake);
+        packet.extend_from_slice(&[0x01, 0x00]); // Status request
+        stream.write_all(&packet).await?;
+
+        read_varint(&mut stream).await?; // Packet length
+        if read_varint(&mut stream).await? != 0x00 {
+            return Err(invalid("Unexpected status response packet"));
+        }
+        let len = read_varint(&mut stream).await?;
+        let mut json = vec![0; usize::try_from(len).map_err(|_| invalid("Negative length"))?];
+        stream.read_exact(&mut json).await?;
+        Ok(json)
+    })
+    .await?;
+    let status = serenity::json::from_slice::<Status>(&json)
+        .map_err(|_| invalid("Malformed status response"))?;
+    let mut name = String::new();
+    status.description.flatten(&mut name);
+    Ok(ServerInfo {
+        name: strip_formatting(&name),
+        players: status.players.online,
+        max_players: status.players.max,
+        detail: status.version.name,
+    })
+}
+
+/// Remove Minecraft `§` formatting codes from a message of the day.
+fn strip_formatting(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out.trim().to_string()
+}
+
+const A2S_INFO: &[u8] = b"\xFF\xFF\xFF\xFFTSource Engine Query\0";
+
+/// Query a Source engine server with `A2S_INFO`.
+pub async fn source(address: &str) -> Result<ServerInfo, BotError> {
+    let response = with_timeout(async {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(address).await?;
+        socket.send(A2S_INFO).await?;
+        let mut buf = vec![0; 1400];
+        let mut len = socket.recv(&mut buf).await?;
+        // Newer servers answer with a challenge that must be echoed back
+        if len == 9 && buf[4] == b'A' {
+            let mut request = A2S_INFO.to_vec();
+            request.extend_from_slice(&buf[5..9]);
+            socket.send(&request).await?;
+            len = socket.recv(&mut buf).await?;
+        }
+        buf.truncate(len);
+        Ok(buf)
+    })
+    .await?;
+    Ok(parse_a2s_info(&response)?)
+}
+
+fn parse_a2s_info(buf: &[u8]) -> io::Result<ServerInfo> {
+    let body = buf
+        .strip_prefix(b"\xFF\xFF\xFF\xFFI")
+        .ok_or_else(|| invalid("Unexpected A2S_INFO response"))?;
+    // Skip the protocol version byte
+    let mut fields = body
+        .get(1..)
+        .ok_or_else(|| invalid("Truncated A2S_INFO response"))?
+        .splitn(5, |&b| b == 0);
+    let mut next_str = || {
+        fields
+            .next()
+            .map(|s| String::from_utf8_lossy(s).into_owned())
+            .ok_or_else(|| invalid("Truncated A2S_INFO response"))
+    };
+    let name = next_str()?;
+    let map = next_str()?;
+    let _folder = next_str()?;
+    let _game = next_str()?;
+    let rest = fields
+        .next()
+        .ok_or_else(|| invalid("Truncated A2S_INFO response"))?;
+    // App id (u16), players, max players
+    let [_, _, players, max_players, ..] = rest else {
+        return Err(invalid("Truncated A2S_INFO response"));
+    };
+    Ok(ServerInfo {
+        name,
+        players: *players as u32,
+        max_players: *max_players as u32,
+        detail: map,
+    })
+}
+
+impl GameServerCfg {
+    pub async fn query(&self) -> Result<ServerInfo, BotError> {
+        match self.kind {
+            ServerKind::Minecraft => minecraft(&self.address).await,
+            ServerKind::Source => source(&self.address).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_varint_roundtrip() {
+        for value in [0, 1, 127, 128, 25565, i32::MAX, -1] {
+            let mut buf = vec![];
+            write_varint(&mut buf, value);
+            assert_eq!(read_varint(&mut buf.as_slice()).await.unwrap(), value);
+        }
+        let mut buf = vec![];
+        write_varint(&mut buf, -1);
+        assert_eq!(buf, [0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+    }
+
+    #[test]
+    fn test_parse_a2s_info() {
+        let response = b"\xFF\xFF\xFF\xFFI\x11My Server\0de_dust2\0cstrike\0Counter-Strike\0\x0A\x00\x05\x20\x00d";
+        assert_eq!(
+            parse_a2s_info(response).unwrap(),
+            ServerInfo {
+                name: "My Server".into(),
+                players: 5,
+                max_players: 32,
+                detail: "de_dust2".into(),
+            }
+        );
+        assert!(parse_a2s_info(b"\xFF\xFF\xFF\xFFI\x11short").is_err());
+        assert_eq!(strip_formatting("§aHello §lWorld "), "Hello World");
+    }
+}
@@ -1,3 +1,34 @@
+use std::collections::HashSet;
+
+use serenity::all::{Context, UserId};
+
+use crate::{config::GetCfg, error::BotError};
+
+pub mod alert;
+pub mod chart;
 mod children;
+pub mod crash;
+pub mod format;
+pub mod game_query;
+pub mod offenses;
+pub mod paste;
+mod ratelimit;
+pub mod s3;
+pub mod schedule;
+pub mod template;
+mod time;
+pub mod twitch;
 
 pub use children::get_all_children_channels;
+pub use ratelimit::RateLimiter;
+pub use time::{
+    DurationArg, InvalidDuration, MAX_DURATION, UserTz, parse_datetime, parse_duration,
+};
+
+/// The application owner and `extraOwners`
+pub async fn owners(ctx: &Context) -> Result<HashSet<UserId>, BotError> {
+    let mut owners = ctx.cfg().await?.load().extra_owners.to_owned();
+    let info = ctx.http.get_current_application_info().await?;
+    owners.extend(info.owner.map(|owner| owner.id));
+    Ok(owners)
+}
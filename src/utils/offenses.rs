@@ -0,0 +1,78 @@
+use chrono::{TimeDelta, Utc};
+use serenity::all::*;
+use sha2::{Digest, Sha256};
+
+use super::alert::{Severity, guild_log};
+use crate::{config::GetCfg, database::GetDb, error::BotError};
+
+const NAMESPACE: &str = "offenses";
+
+/// Hash of content that tells trivial variations in case and spacing apart from new content
+fn fingerprint(content: &str) -> String {
+    let normalized = content
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ");
+    hex::encode(&Sha256::digest(normalized.as_bytes())[..16])
+}
+
+/// Count the removal of a rule-breaking message towards its author's repeat offenses, reporting
+/// them to the guild's log channel once they reach the configured threshold.
+pub async fn record_removal(
+    ctx: &Context,
+    guild_id: GuildId,
+    msg: &Message,
+) -> Result<(), BotError> {
+    let cfg = ctx.cfg().await?.load_full();
+    let Some(offenses) = cfg
+        .guilds
+        .get(&guild_id)
+        .and_then(|g| g.repeat_offenses.as_ref())
+    else {
+        return Ok(());
+    };
+    if msg.content.trim().is_empty() {
+        return Ok(());
+    }
+    let key = format!("{guild_id}:{}:{}", msg.author.id, fingerprint(&msg.content));
+    let db = ctx.db().await?;
+    let count = db.store().get::<u32>(NAMESPACE, &key).await?.unwrap_or(0) + 1;
+    let expires_at = Utc::now() + TimeDelta::from_std(offenses.window).unwrap();
+    db.store()
+        .put(NAMESPACE, &key, &count, Some(expires_at))
+        .await?;
+    if count < offenses.threshold {
+        return Ok(());
+    }
+    guild_log(
+        ctx,
+        guild_id,
+        Severity::Warning,
+        "重复违规内容",
+        format!(
+            "{} 第 {count} 次发送已被删除的相同内容, 最近一次在 {}。",
+            msg.author.mention(),
+            msg.channel_id.mention()
+        ),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(
+            fingerprint("Free  NITRO\nhere"),
+            fingerprint("free nitro here")
+        );
+        assert_ne!(
+            fingerprint("free nitro here"),
+            fingerprint("free nitro there")
+        );
+        assert_eq!(fingerprint("spam").len(), 32);
+    }
+}
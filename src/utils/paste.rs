@@ -0,0 +1,33 @@
+use reqwest::Url;
+use serde::Deserialize;
+use snafu::ResultExt;
+
+use crate::error::BotError;
+
+#[derive(Deserialize)]
+struct HastebinResponse {
+    key: String,
+}
+
+/// Upload `content` to a paste service and return a link to it.
+///
+/// The content is POSTed as plain text. Services answering with the paste URL in the body
+/// (paste.rs, 0x0.st) and hastebin style ones answering `{"key": ...}` are both supported.
+pub async fn upload(endpoint: &Url, content: impl Into<String>) -> Result<Url, BotError> {
+    let body = reqwest::Client::new()
+        .post(endpoint.to_owned())
+        .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .body(content.into())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    if let Ok(HastebinResponse { key }) = serenity::json::from_str(&body) {
+        // hastebin serves pastes next to its `documents` endpoint
+        return endpoint
+            .join(&format!("/{key}"))
+            .whatever_context("Failed to construct paste URL");
+    }
+    Url::parse(body.trim()).whatever_context("Paste service returned an invalid URL")
+}
@@ -0,0 +1,142 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use reqwest::{Method, Url};
+use sha2::{Digest, Sha256};
+
+use crate::error::BotError;
+
+/// Characters escaped in SigV4 canonical query strings, everything but the unreserved set
+const QUERY: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// A bucket on an S3 compatible service, addressed path-style
+pub struct Bucket<'a> {
+    pub endpoint: &'a Url,
+    pub name: &'a str,
+    pub region: &'a str,
+    pub access_key: &'a str,
+    pub secret_key: &'a str,
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{secret}").as_bytes(), date);
+    let key = hmac(&key, region);
+    let key = hmac(&key, service);
+    hmac(&key, "aws4_request")
+}
+
+impl Bucket<'_> {
+    /// Send a request signed with AWS Signature Version 4.
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, BotError> {
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let mut url = self.endpoint.to_owned();
+        if key.is_empty() {
+            url.set_path(&format!("/{}", self.name));
+        } else {
+            url.set_path(&format!("/{}/{key}", self.name));
+        }
+        let mut query = query
+            .iter()
+            .map(|(k, v)| format!("{k}={}", utf8_percent_encode(v, QUERY)))
+            .collect::<Vec<_>>();
+        query.sort();
+        let query = query.join("&");
+        url.set_query(Some(&query).filter(|q| !q.is_empty()).map(|q| q.as_str()));
+        let host = url.authority().to_string();
+        let payload_hash = hex::encode(Sha256::digest(&body));
+
+        let canonical = format!(
+            "{method}\n{}\n{query}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\nhost;x-amz-content-sha256;x-amz-date\n{payload_hash}",
+            url.path()
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical.as_bytes()))
+        );
+        let signature = hex::encode(hmac(
+            &signing_key(self.secret_key, &date, self.region, "s3"),
+            &to_sign,
+        ));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={signature}",
+            self.access_key
+        );
+        Ok(reqwest::Client::new()
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .body(body)
+            .send()
+            .await?
+            .error_for_status()?)
+    }
+
+    pub async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), BotError> {
+        self.send(Method::PUT, key, &[], body).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), BotError> {
+        self.send(Method::DELETE, key, &[], vec![]).await?;
+        Ok(())
+    }
+
+    /// List the keys starting with `prefix`, at most the first thousand.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>, BotError> {
+        let xml = self
+            .send(
+                Method::GET,
+                "",
+                &[("list-type", "2"), ("prefix", prefix)],
+                vec![],
+            )
+            .await?
+            .text()
+            .await?;
+        Ok(xml
+            .split("<Key>")
+            .skip(1)
+            .filter_map(|s| s.split_once("</Key>"))
+            .map(|(key, _)| key.to_string())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_signing_key() {
+        // Example from the AWS Signature Version 4 documentation
+        assert_eq!(
+            hex::encode(signing_key(
+                "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+                "20120215",
+                "us-east-1",
+                "iam"
+            )),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+}
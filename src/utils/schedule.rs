@@ -0,0 +1,115 @@
+use std::{any::type_name, future::Future, panic::AssertUnwindSafe, time::Duration};
+
+use chrono::{DateTime, FixedOffset, NaiveTime, Utc};
+use futures::FutureExt;
+use tokio::{spawn, task::JoinHandle, time::MissedTickBehavior};
+use tracing::error;
+
+use super::crash;
+
+/// Run a job once, named after its type for panic reports. A panicking run is caught, so the
+/// job still runs at its next time.
+async fn run<F, Fut>(job: &mut F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let task = type_name::<F>();
+    if AssertUnwindSafe(crash::named(task, job()))
+        .catch_unwind()
+        .await
+        .is_err()
+    {
+        error!("Job {task} panicked, it runs again at its next time");
+    }
+}
+
+/// Spawn a job that runs every `period`, starting immediately.
+pub fn every<F, Fut>(period: Duration, mut job: F) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            run(&mut job).await;
+        }
+    })
+}
+
+/// Spawn a job that runs once a day at `at`, interpreted in `offset`.
+pub fn daily<F, Fut>(at: NaiveTime, offset: FixedOffset, mut job: F) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    spawn(async move {
+        loop {
+            let now = Utc::now();
+            let wait = (next_daily(now, at, offset) - now)
+                .to_std()
+                .unwrap_or_default();
+            tokio::time::sleep(wait).await;
+            run(&mut job).await;
+        }
+    })
+}
+
+/// Spawn a job that runs at every time matched by `schedule`, interpreted in `offset`.
+pub fn cron<F, Fut>(schedule: cron::Schedule, offset: FixedOffset, mut job: F) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    spawn(async move {
+        while let Some(next) = schedule.upcoming(offset).next() {
+            let wait = (next.to_utc() - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+            run(&mut job).await;
+        }
+    })
+}
+
+/// The first instant strictly after `now` whose local time in `offset` is `at`.
+pub fn next_daily(now: DateTime<Utc>, at: NaiveTime, offset: FixedOffset) -> DateTime<Utc> {
+    let local = now.with_timezone(&offset);
+    let today = local
+        .date_naive()
+        .and_time(at)
+        .and_local_timezone(offset)
+        .single()
+        .expect("Fixed offsets have no ambiguous local times");
+    if today > local {
+        today.to_utc()
+    } else {
+        (today + chrono::Duration::days(1)).to_utc()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_next_daily() {
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let at = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        // 00:30 UTC is 08:30 in UTC+8, so the job runs later today
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 0, 30, 0).unwrap();
+        assert_eq!(
+            next_daily(now, at, offset),
+            Utc.with_ymd_and_hms(2025, 1, 1, 1, 0, 0).unwrap()
+        );
+        // exactly on time rolls over to tomorrow
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 1, 0, 0).unwrap();
+        assert_eq!(
+            next_daily(now, at, offset),
+            Utc.with_ymd_and_hms(2025, 1, 2, 1, 0, 0).unwrap()
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Var(String),
+}
+
+/// A message template with `{name}` placeholders, `{{` and `}}` escape literal braces.
+///
+/// Templates are parsed when deserialized, so malformed ones are rejected at config load.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl FromStr for Template {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    if !closed {
+                        return Err("缺少 `}`".into());
+                    }
+                    let name = name.trim();
+                    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                        return Err(format!("无效的变量名 `{{{name}}}`"));
+                    }
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Var(name.to_string()));
+                }
+                '}' => return Err("多余的 `}`, 请使用 `}}`".into()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            parts,
+        })
+    }
+}
+
+impl TryFrom<String> for Template {
+    type Error = String;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        source.parse()
+    }
+}
+
+impl From<Template> for String {
+    fn from(template: Template) -> Self {
+        template.source
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Template {
+    /// Check that the template only uses variables from `allowed`.
+    pub fn validate(&self, allowed: &[&str]) -> Result<(), String> {
+        match self.parts.iter().find_map(|p| match p {
+            Part::Var(name) if !allowed.contains(&name.as_str()) => Some(name),
+            _ => None,
+        }) {
+            Some(name) => Err(format!(
+                "未知的变量 `{{{name}}}`, 可用变量: {}",
+                allowed
+                    .iter()
+                    .map(|v| format!("`{{{v}}}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the template refers to `var`, to skip computing unused expensive values.
+    pub fn uses(&self, var: &str) -> bool {
+        self.parts
+            .iter()
+            .any(|p| matches!(p, Part::Var(name) if name == var))
+    }
+
+    /// Substitute the variables, leaving unknown ones as written.
+    pub fn render(&self, vars: &[(&str, &dyn Display)]) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.to_owned(),
+                Part::Var(name) => vars
+                    .iter()
+                    .find(|(var, _)| var == name)
+                    .map_or_else(|| format!("{{{name}}}"), |(_, value)| value.to_string()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_template() {
+        let template = "欢迎 {user} 加入 {guild}, 你是第 {count} 位成员 {{!}}"
+            .parse::<Template>()
+            .unwrap();
+        assert_eq!(
+            template.render(&[("user", &"Alice"), ("guild", &"Dog"), ("count", &42)]),
+            "欢迎 Alice 加入 Dog, 你是第 42 位成员 {!}"
+        );
+        assert!(template.validate(&["user", "guild", "count"]).is_ok());
+        assert!(template.validate(&["user", "guild"]).is_err());
+        assert_eq!(
+            "{missing}".parse::<Template>().unwrap().render(&[]),
+            "{missing}"
+        );
+        assert!("{unclosed".parse::<Template>().is_err());
+        assert!("{}".parse::<Template>().is_err());
+        assert!("stray }".parse::<Template>().is_err());
+    }
+}
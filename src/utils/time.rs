@@ -0,0 +1,243 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d"];
+const TIME_FORMATS: &[&str] = &["%H:%M:%S", "%H:%M"];
+/// Longest duration accepted, about ten years. Deadlines any further would be useless, and far
+/// enough out they overflow the date they are added to.
+pub const MAX_DURATION: Duration = Duration::days(3653);
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveTime::parse_from_str(s, f).ok())
+}
+
+/// Parse a user supplied datetime relative to `now`, interpreting local times in `now`'s zone.
+///
+/// Accepts RFC 3339, unix timestamps, `YYYY-MM-DD [HH:MM[:SS]]`, a bare `HH:MM[:SS]` for today,
+/// and `now` / `today` / `tomorrow` / `yesterday` (or 现在 / 今天 / 明天 / 昨天) with an optional time.
+pub fn parse_datetime<Tz: TimeZone>(input: &str, now: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let input = input.trim();
+    let tz = now.timezone();
+    let local = |dt: NaiveDateTime| tz.from_local_datetime(&dt).earliest();
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Some(dt.with_timezone(&tz));
+    }
+    if input.len() >= 9
+        && input.chars().all(|c| c.is_ascii_digit())
+        && let Some(dt) = input
+            .parse()
+            .ok()
+            .and_then(|s| DateTime::from_timestamp(s, 0))
+    {
+        return Some(dt.with_timezone(&tz));
+    }
+    if let Some(dt) = DATETIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(input, f).ok())
+    {
+        return local(dt);
+    }
+    if let Some(date) = DATE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(input, f).ok())
+    {
+        return local(date.and_time(NaiveTime::MIN));
+    }
+    if let Some(time) = parse_time(input) {
+        return local(now.date_naive().and_time(time));
+    }
+
+    let lower = input.to_lowercase();
+    if matches!(lower.as_str(), "now" | "现在") {
+        return Some(now.to_owned());
+    }
+    let (day, rest) = lower
+        .split_once(char::is_whitespace)
+        .map(|(d, r)| (d, r.trim()))
+        .unwrap_or((lower.as_str(), ""));
+    let days = match day {
+        "today" | "今天" => 0,
+        "tomorrow" | "明天" => 1,
+        "yesterday" | "昨天" => -1,
+        _ => return None,
+    };
+    let time = if rest.is_empty() {
+        NaiveTime::MIN
+    } else {
+        parse_time(rest)?
+    };
+    local((now.date_naive() + Duration::days(days)).and_time(time))
+}
+
+/// The timezone a user works in: their stored IANA zone, or the bot's configured offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserTz {
+    Iana(Tz),
+    Fixed(FixedOffset),
+}
+
+impl UserTz {
+    /// Resolve a stored timezone name, falling back to `offset`.
+    pub fn new(timezone: Option<&str>, offset: FixedOffset) -> Self {
+        timezone
+            .and_then(|tz| tz.parse().ok())
+            .map_or(Self::Fixed(offset), Self::Iana)
+    }
+
+    /// Parse a datetime as written by the user, see [`parse_datetime`].
+    pub fn parse(&self, input: &str) -> Option<DateTime<FixedOffset>> {
+        let now = Utc::now();
+        match self {
+            Self::Iana(tz) => {
+                parse_datetime(input, &now.with_timezone(tz)).map(|t| t.fixed_offset())
+            }
+            Self::Fixed(offset) => parse_datetime(input, &now.with_timezone(offset)),
+        }
+    }
+
+    /// Convert an instant into the user's local time.
+    pub fn localize<T: TimeZone>(&self, instant: &DateTime<T>) -> DateTime<FixedOffset> {
+        match self {
+            Self::Iana(tz) => instant.with_timezone(tz).fixed_offset(),
+            Self::Fixed(offset) => instant.with_timezone(offset),
+        }
+    }
+}
+
+impl Display for UserTz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Iana(tz) => write!(f, "{}", tz.name()),
+            Self::Fixed(offset) => write!(f, "UTC{offset}"),
+        }
+    }
+}
+
+/// Parse a duration such as `90s`, `1h30m`, `2d` or `3天`, made of one or more number-unit pairs,
+/// up to [`MAX_DURATION`].
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    let mut total = Duration::zero();
+    let mut rest = input;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let value = rest[..digits].parse::<i64>().ok()?;
+        rest = rest[digits..].trim_start();
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let part = match &rest[..unit] {
+            "s" | "sec" | "秒" => Duration::try_seconds(value)?,
+            "m" | "min" | "分" | "分钟" => Duration::try_minutes(value)?,
+            "h" | "hr" | "小时" => Duration::try_hours(value)?,
+            "d" | "day" | "天" => Duration::try_days(value)?,
+            "w" | "week" | "周" => Duration::try_weeks(value)?,
+            _ => return None,
+        };
+        total = total.checked_add(&part)?;
+        rest = rest[unit..].trim_start();
+    }
+    (total > Duration::zero() && total <= MAX_DURATION).then_some(total)
+}
+
+/// A slash command argument for a duration in the form accepted by [`parse_duration`], which
+/// replies with an example instead of running the command when the input is malformed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationArg(pub Duration);
+
+#[derive(Debug)]
+pub struct InvalidDuration;
+
+impl Display for InvalidDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("无效的时长, 例如 `30m`, `2h30m`, `1d`, `3天`, 最长为 10 年")
+    }
+}
+
+impl std::error::Error for InvalidDuration {}
+
+impl FromStr for DurationArg {
+    type Err = InvalidDuration;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(Self).ok_or(InvalidDuration)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::FixedOffset;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_datetime() {
+        let tz = FixedOffset::east_opt(8 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2025, 7, 10, 15, 30, 0).unwrap();
+        let at = |y, m, d, h, min| tz.with_ymd_and_hms(y, m, d, h, min, 0).unwrap();
+
+        assert_eq!(
+            parse_datetime("2025-07-11T01:00:00Z", &now),
+            Some(at(2025, 7, 11, 9, 0))
+        );
+        assert_eq!(
+            parse_datetime("2025-07-11 09:00", &now),
+            Some(at(2025, 7, 11, 9, 0))
+        );
+        assert_eq!(
+            parse_datetime("2025/12/31", &now),
+            Some(at(2025, 12, 31, 0, 0))
+        );
+        assert_eq!(parse_datetime("20:15", &now), Some(at(2025, 7, 10, 20, 15)));
+        assert_eq!(
+            parse_datetime("tomorrow 08:00", &now),
+            Some(at(2025, 7, 11, 8, 0))
+        );
+        assert_eq!(parse_datetime("昨天", &now), Some(at(2025, 7, 9, 0, 0)));
+        assert_eq!(parse_datetime("now", &now), Some(now));
+        assert_eq!(
+            parse_datetime("1752132600", &now),
+            Some(at(2025, 7, 10, 15, 30))
+        );
+        assert_eq!(parse_datetime("next week", &now), None);
+        assert_eq!(parse_datetime("tomorrow noon", &now), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Duration::try_seconds(90));
+        assert_eq!(parse_duration("1h30m"), Duration::try_minutes(90));
+        assert_eq!(parse_duration("2d 3h"), Duration::try_hours(51));
+        assert_eq!(parse_duration("3天"), Duration::try_days(3));
+        assert_eq!(parse_duration("10分钟"), Duration::try_minutes(10));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("520w"), Duration::try_weeks(520));
+        assert_eq!(parse_duration("100000000w"), None);
+        assert_eq!(
+            "2小时".parse::<DurationArg>().ok(),
+            Duration::try_hours(2).map(DurationArg)
+        );
+        assert!("soon".parse::<DurationArg>().is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, de::DeserializeOwned};
+use tokio::sync::Mutex;
+
+use crate::{config::TwitchCfg, error::BotError};
+
+#[derive(Deserialize)]
+struct Token {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct Page<T> {
+    data: Vec<T>,
+}
+
+/// A Helix API client authenticated with an app access token
+pub struct Twitch {
+    client: reqwest::Client,
+    cfg: TwitchCfg,
+    token: Mutex<Option<String>>,
+}
+
+impl Twitch {
+    pub fn new(cfg: TwitchCfg) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cfg,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Fetch a Helix endpoint such as `streams`, refreshing the token once if it expired.
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        query: &[(&str, &str)],
+    ) -> Result<Vec<T>, BotError> {
+        let mut token = self.token.lock().await;
+        for _ in 0..2 {
+            let access_token = match token.as_ref() {
+                Some(access_token) => access_token.to_owned(),
+                None => {
+                    let access_token = self
+                        .client
+                        .post("https://id.twitch.tv/oauth2/token")
+                        .query(&[
+                            ("client_id", self.cfg.client_id.as_str()),
+                            ("client_secret", self.cfg.client_secret.as_str()),
+                            ("grant_type", "client_credentials"),
+                        ])
+                        .send()
+                        .await?
+                        .error_for_status()?
+                        .json::<Token>()
+                        .await?
+                        .access_token;
+                    token.insert(access_token).to_owned()
+                }
+            };
+            let res = self
+                .client
+                .get(format!("https://api.twitch.tv/helix/{endpoint}"))
+                .query(query)
+                .header("Client-Id", &self.cfg.client_id)
+                .bearer_auth(access_token)
+                .send()
+                .await?;
+            if res.status() == reqwest::StatusCode::UNAUTHORIZED {
+                *token = None;
+                continue;
+            }
+            return Ok(res.error_for_status()?.json::<Page<T>>().await?.data);
+        }
+        snafu::whatever!("Twitch rejected a freshly issued access token");
+    }
+}